@@ -16,7 +16,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::collections::{BTreeMap, BTreeSet};
 use std::net::SocketAddr;
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -137,6 +137,11 @@ pub struct NodeConfig {
     #[serde(default)]
     pub transaction_deny_config: TransactionDenyConfig,
 
+    /// If set, the transaction deny config is read from this file instead of
+    /// `transaction_deny_config`, and reloaded whenever the file changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_deny_config_path: Option<PathBuf>,
+
     #[serde(default)]
     pub certificate_deny_config: CertificateDenyConfig,
 
@@ -1019,6 +1024,10 @@ pub struct StateSnapshotConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_store_config: Option<ObjectStoreConfig>,
     pub concurrency: usize,
+    /// When set, a full state snapshot is only written every this many epochs, and the epochs in
+    /// between get delta snapshots against the snapshot of the previous epoch. Must be non-zero.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_snapshot_interval_epochs: Option<NonZeroU64>,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -1087,6 +1096,11 @@ pub struct AuthorityOverloadConfig {
     // is above the threshold.
     #[serde(default = "default_max_transaction_manager_per_object_queue_length")]
     pub max_transaction_manager_per_object_queue_length: usize,
+
+    // When set, load shedding rejects transactions from the senders and shared objects with the
+    // most transactions in flight first, instead of rejecting transactions at random.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fair_load_shedding: Option<FairLoadSheddingConfig>,
 }
 
 fn default_max_txn_age_in_queue() -> Duration {
@@ -1145,6 +1159,52 @@ impl Default for AuthorityOverloadConfig {
             max_transaction_manager_queue_length: default_max_transaction_manager_queue_length(),
             max_transaction_manager_per_object_queue_length:
                 default_max_transaction_manager_per_object_queue_length(),
+            fair_load_shedding: None,
+        }
+    }
+}
+
+/// Configuration for fairness-aware load shedding. Transactions admitted by the validator are
+/// considered in flight until they are sent for execution, and are counted against their sender
+/// and every shared object they use. In load shedding mode, the senders and shared objects with the
+/// most transactions in flight are capped first, and transactions are only shed at random if
+/// capping them does not shed enough load.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FairLoadSheddingConfig {
+    // How long an admitted transaction counts as in flight, if it is not sent for execution
+    // sooner, e.g. because its certificate was never submitted.
+    #[serde(default = "default_fair_load_shedding_inflight_timeout")]
+    pub inflight_timeout: Duration,
+
+    // Senders and shared objects are never capped below this many transactions in flight.
+    #[serde(default = "default_fair_load_shedding_min_inflight_limit")]
+    pub min_inflight_limit: usize,
+
+    // The most transactions tracked as in flight at once. Once this many are tracked, further
+    // transactions are still checked against the in-flight limit, but are not tracked themselves.
+    #[serde(default = "default_fair_load_shedding_max_tracked_transactions")]
+    pub max_tracked_transactions: usize,
+}
+
+fn default_fair_load_shedding_inflight_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_fair_load_shedding_min_inflight_limit() -> usize {
+    2
+}
+
+fn default_fair_load_shedding_max_tracked_transactions() -> usize {
+    100_000
+}
+
+impl Default for FairLoadSheddingConfig {
+    fn default() -> Self {
+        Self {
+            inflight_timeout: default_fair_load_shedding_inflight_timeout(),
+            min_inflight_limit: default_fair_load_shedding_min_inflight_limit(),
+            max_tracked_transactions: default_fair_load_shedding_max_tracked_transactions(),
         }
     }
 }
@@ -1349,7 +1409,7 @@ mod tests {
     use sui_keys::keypair_file::{write_authority_keypair_to_file, write_keypair_to_file};
     use sui_types::crypto::{get_key_pair_from_rng, AuthorityKeyPair, NetworkKeyPair, SuiKeyPair};
 
-    use super::Genesis;
+    use super::{Genesis, StateSnapshotConfig};
     use crate::NodeConfig;
 
     #[test]
@@ -1377,6 +1437,21 @@ mod tests {
         let _template: NodeConfig = serde_yaml::from_str(FILE).unwrap();
     }
 
+    #[test]
+    fn state_snapshot_config_rejects_zero_interval() {
+        let config: StateSnapshotConfig =
+            serde_yaml::from_str("concurrency: 5\nfull-snapshot-interval-epochs: 7\n").unwrap();
+        assert_eq!(
+            Some(7),
+            config.full_snapshot_interval_epochs.map(|i| i.get())
+        );
+
+        assert!(serde_yaml::from_str::<StateSnapshotConfig>(
+            "concurrency: 5\nfull-snapshot-interval-epochs: 0\n"
+        )
+        .is_err());
+    }
+
     #[test]
     fn load_key_pairs_to_node_config() {
         let protocol_key_pair: AuthorityKeyPair =
@@ -362,20 +362,21 @@ impl ValidatorService {
         traffic_controller_metrics: TrafficControllerMetrics,
         policy_config: Option<PolicyConfig>,
         firewall_config: Option<RemoteFirewallConfig>,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        let traffic_controller = policy_config
+            .clone()
+            .map(|policy| {
+                TrafficController::init(policy, traffic_controller_metrics, firewall_config)
+            })
+            .transpose()?
+            .map(Arc::new);
+        Ok(Self {
             state,
             consensus_adapter,
             metrics: validator_metrics,
-            traffic_controller: policy_config.clone().map(|policy| {
-                Arc::new(TrafficController::init(
-                    policy,
-                    traffic_controller_metrics,
-                    firewall_config,
-                ))
-            }),
+            traffic_controller,
             client_id_source: policy_config.map(|policy| policy.client_id_source),
-        }
+        })
     }
 
     pub fn new_for_tests(
@@ -396,6 +397,10 @@ impl ValidatorService {
         &self.state
     }
 
+    pub fn traffic_controller(&self) -> Option<Arc<TrafficController>> {
+        self.traffic_controller.clone()
+    }
+
     pub async fn execute_certificate_for_testing(
         &self,
         cert: CertifiedTransaction,
@@ -1317,6 +1322,7 @@ impl ValidatorService {
     fn handle_traffic_resp<T>(
         &self,
         client: Option<IpAddr>,
+        method: &str,
         wrapped_response: WrappedServiceResponse<T>,
     ) -> Result<tonic::Response<T>, tonic::Status> {
         let (error, spam_weight, unwrapped_response) = match wrapped_response {
@@ -1338,6 +1344,7 @@ impl ValidatorService {
                     (error_weight, error_type)
                 }),
                 spam_weight,
+                method: Some(method.to_string()),
                 timestamp: SystemTime::now(),
             })
         }
@@ -1390,7 +1397,8 @@ macro_rules! handle_with_decoration {
 
         // handle traffic tallying
         let wrapped_response = $self.$func_name($request).await;
-        $self.handle_traffic_resp(client, wrapped_response)
+        let method = stringify!($func_name).trim_end_matches("_impl");
+        $self.handle_traffic_resp(client, method, wrapped_response)
     }};
 }
 
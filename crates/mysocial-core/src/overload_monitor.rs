@@ -2,14 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::authority::AuthorityState;
+use dashmap::mapref::entry::Entry as DashMapEntry;
+use dashmap::DashMap;
 use mysten_metrics::monitored_scope;
 use std::cmp::{max, min};
 use std::hash::Hasher;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
 use std::sync::Weak;
 use std::time::Duration;
-use std::time::{SystemTime, UNIX_EPOCH};
-use sui_config::node::AuthorityOverloadConfig;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use sui_config::node::{AuthorityOverloadConfig, FairLoadSheddingConfig};
+use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::error::SuiError;
 use sui_types::error::SuiResult;
@@ -25,6 +28,18 @@ pub struct AuthorityOverloadInfo {
 
     /// The calculated percentage of transactions to drop.
     pub load_shedding_percentage: AtomicU32,
+
+    /// In fair load shedding mode, transactions are rejected if their sender or any of their
+    /// shared objects already has this many transactions in flight. Zero means no limit.
+    pub inflight_limit: AtomicUsize,
+
+    /// In fair load shedding mode, the percentage of transactions to drop at random, on top of
+    /// those rejected by `inflight_limit`.
+    pub random_load_shedding_percentage: AtomicU32,
+
+    /// In fair load shedding mode, the transactions that have been accepted but not yet sent for
+    /// execution.
+    pub inflight: InflightTransactions,
 }
 
 impl AuthorityOverloadInfo {
@@ -34,9 +49,122 @@ impl AuthorityOverloadInfo {
             .store(min(load_shedding_percentage, 100), Ordering::Relaxed);
     }
 
+    pub fn set_fair_load_shedding(
+        &self,
+        inflight_limit: usize,
+        random_load_shedding_percentage: u32,
+    ) {
+        self.inflight_limit.store(inflight_limit, Ordering::Relaxed);
+        self.random_load_shedding_percentage
+            .store(min(random_load_shedding_percentage, 100), Ordering::Relaxed);
+    }
+
     pub fn clear_overload(&self) {
         self.is_overload.store(false, Ordering::Relaxed);
         self.load_shedding_percentage.store(0, Ordering::Relaxed);
+        self.set_fair_load_shedding(0, 0);
+    }
+}
+
+/// Something that in-flight transactions are counted against in fair load shedding mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LoadSource {
+    Sender(SuiAddress),
+    SharedObject(ObjectID),
+}
+
+/// Tracks the transactions in flight for each sender and shared object. Both maps are sharded, so
+/// that admitting transactions from unrelated senders doesn't contend on a single lock. To avoid
+/// deadlocks, a `counts` shard is only ever locked while holding a `transactions` shard, never the
+/// other way around.
+#[derive(Default)]
+pub struct InflightTransactions {
+    transactions: DashMap<TransactionDigest, (Instant, Vec<LoadSource>)>,
+    counts: DashMap<LoadSource, usize>,
+}
+
+impl InflightTransactions {
+    /// Track the transaction with `tx_digest` as in flight, unless one of its `sources` already
+    /// has `limit` or more transactions in flight (a zero `limit` means no limit). Returns whether
+    /// the transaction is accepted. Transactions that are already in flight are not counted twice,
+    /// and once `max_tracked` transactions are in flight, new ones are accepted without being
+    /// tracked. Concurrent calls are not serialized, so both limits may be exceeded slightly.
+    pub fn try_admit(
+        &self,
+        tx_digest: TransactionDigest,
+        sources: Vec<LoadSource>,
+        limit: usize,
+        max_tracked: usize,
+        now: Instant,
+    ) -> bool {
+        if self.transactions.contains_key(&tx_digest) {
+            return true;
+        }
+
+        if limit > 0 && sources.iter().any(|source| self.count(source) >= limit) {
+            return false;
+        }
+
+        if self.transactions.len() >= max_tracked {
+            return true;
+        }
+
+        // Another request may have admitted the same transaction since it was looked up above.
+        let DashMapEntry::Vacant(entry) = self.transactions.entry(tx_digest) else {
+            return true;
+        };
+        for source in &sources {
+            *self.counts.entry(*source).or_default() += 1;
+        }
+        entry.insert((now, sources));
+        true
+    }
+
+    /// Stop tracking the transaction with `tx_digest`, e.g. once it is sent for execution.
+    pub fn remove(&self, tx_digest: &TransactionDigest) {
+        if let Some((_, (_, sources))) = self.transactions.remove(tx_digest) {
+            self.release(&sources);
+        }
+    }
+
+    /// Stop tracking transactions that were admitted before `deadline`.
+    pub fn expire(&self, deadline: Instant) {
+        let mut expired = vec![];
+        self.transactions.retain(|_, (admitted, sources)| {
+            if *admitted < deadline {
+                expired.push(std::mem::take(sources));
+                false
+            } else {
+                true
+            }
+        });
+
+        for sources in expired {
+            self.release(&sources);
+        }
+    }
+
+    /// The number of transactions in flight for each sender and shared object that has any.
+    pub fn counts(&self) -> Vec<usize> {
+        self.counts.iter().map(|count| *count.value()).collect()
+    }
+
+    pub fn count(&self, source: &LoadSource) -> usize {
+        self.counts
+            .get(source)
+            .map(|count| *count.value())
+            .unwrap_or_default()
+    }
+
+    fn release(&self, sources: &[LoadSource]) {
+        for source in sources {
+            if let DashMapEntry::Occupied(mut count) = self.counts.entry(*source) {
+                *count.get_mut() -= 1;
+                if *count.get() == 0 {
+                    count.remove();
+                }
+            }
+        }
     }
 }
 
@@ -47,6 +175,10 @@ const ADDITIONAL_LOAD_SHEDDING: f64 = 0.02;
 // The update interval of the random seed used to determine whether a txn should be rejected.
 const SEED_UPDATE_DURATION_SECS: u64 = 30;
 
+// How long to suggest waiting before retrying a txn that was rejected because its sender or shared
+// objects have too many txns in flight. The limit is recalculated every overload monitor interval.
+const INFLIGHT_LIMIT_RETRY_AFTER_SECS: u64 = 10;
+
 // Monitors the overload signals in `authority_state` periodically, and updates its `overload_info`
 // when the signals indicates overload.
 pub async fn overload_monitor(
@@ -113,6 +245,34 @@ fn check_authority_overload(
         authority.overload_info.clear_overload();
     }
 
+    if let Some(fair_config) = &config.fair_load_shedding {
+        let inflight = &authority.overload_info.inflight;
+        if let Some(deadline) = Instant::now().checked_sub(fair_config.inflight_timeout) {
+            inflight.expire(deadline);
+        }
+
+        if is_overload {
+            let (inflight_limit, random_load_shedding_percentage) = calculate_fair_load_shedding(
+                &inflight.counts(),
+                load_shedding_percentage,
+                fair_config.min_inflight_limit,
+            );
+            authority
+                .overload_info
+                .set_fair_load_shedding(inflight_limit, random_load_shedding_percentage);
+        }
+
+        authority
+            .metrics
+            .authority_load_shedding_inflight_limit
+            .set(
+                authority
+                    .overload_info
+                    .inflight_limit
+                    .load(Ordering::Relaxed) as i64,
+            );
+    }
+
     authority
         .metrics
         .authority_overload_status
@@ -216,6 +376,59 @@ fn check_overload_signals(
     (overload_status, load_shedding_percentage)
 }
 
+// Given the number of transactions in flight for each sender and shared object, and the percentage
+// of load to shed, returns the limit of transactions in flight per sender and shared object, and
+// the percentage of the remaining transactions to drop at random.
+// The limit is chosen so that the transactions in flight above it add up to the load to shed,
+// which only affects the heaviest senders and shared objects. The limit never goes below
+// `min_inflight_limit`, so if the load is spread too evenly to shed enough of it this way, the rest
+// is shed at random. A limit of 0 means no limit.
+fn calculate_fair_load_shedding(
+    inflight_counts: &[usize],
+    load_shedding_percentage: u32,
+    min_inflight_limit: usize,
+) -> (usize, u32) {
+    let min_inflight_limit = max(min_inflight_limit, 1);
+    let total: usize = inflight_counts.iter().sum();
+    let max_count = inflight_counts.iter().copied().max().unwrap_or_default();
+    if load_shedding_percentage == 0 || max_count <= min_inflight_limit {
+        return (0, load_shedding_percentage);
+    }
+
+    let target = (total * load_shedding_percentage as usize).div_ceil(100);
+    let excess = |limit: usize| -> usize {
+        inflight_counts
+            .iter()
+            .map(|count| count.saturating_sub(limit))
+            .sum()
+    };
+
+    // The excess shrinks as the limit grows, so binary search for the highest limit that sheds
+    // at least `target`.
+    let mut limit = min_inflight_limit;
+    if excess(limit) >= target {
+        let mut hi = max_count;
+        while hi - limit > 1 {
+            let mid = limit + (hi - limit) / 2;
+            if excess(mid) >= target {
+                limit = mid;
+            } else {
+                hi = mid;
+            }
+        }
+    }
+
+    let shed = excess(limit);
+    let remaining = target.saturating_sub(shed);
+    let random_load_shedding_percentage = if remaining == 0 {
+        0
+    } else {
+        min((remaining * 100).div_ceil(total - shed), 100) as u32
+    };
+
+    (limit, random_load_shedding_percentage)
+}
+
 // Return true if we should reject the txn with `tx_digest`.
 fn should_reject_tx(
     load_shedding_percentage: u32,
@@ -256,6 +469,39 @@ pub fn overload_monitor_accept_tx(
     Ok(())
 }
 
+// Checks if we can accept the transaction with `tx_digest` in fair load shedding mode. If so, it is
+// tracked as in flight against each of its `sources` until it is sent for execution.
+pub fn overload_monitor_accept_tx_fairly(
+    overload_info: &AuthorityOverloadInfo,
+    config: &FairLoadSheddingConfig,
+    tx_digest: TransactionDigest,
+    sources: Vec<LoadSource>,
+) -> SuiResult {
+    let mut inflight_limit = 0;
+    if overload_info.is_overload.load(Ordering::Relaxed) {
+        overload_monitor_accept_tx(
+            overload_info
+                .random_load_shedding_percentage
+                .load(Ordering::Relaxed),
+            tx_digest,
+        )?;
+        inflight_limit = overload_info.inflight_limit.load(Ordering::Relaxed);
+    }
+
+    if !overload_info.inflight.try_admit(
+        tx_digest,
+        sources,
+        inflight_limit,
+        config.max_tracked_transactions,
+        Instant::now(),
+    ) {
+        fp_bail!(SuiError::ValidatorOverloadedRetryAfter {
+            retry_after_secs: INFLIGHT_LIMIT_RETRY_AFTER_SECS
+        });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 #[allow(clippy::disallowed_methods)] // allow unbounded_channel() since tests are simulating txn manager execution driver interaction.
 mod tests {
@@ -409,6 +655,201 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_calculate_fair_load_shedding() {
+        let one_heavy_sender: Vec<usize> = std::iter::once(10).chain([1; 10]).collect();
+
+        // Nothing to shed.
+        assert_eq!(
+            calculate_fair_load_shedding(&one_heavy_sender, 0, 2),
+            (0, 0)
+        );
+
+        // Nothing in flight, or nothing above the minimum limit, so shed at random.
+        assert_eq!(calculate_fair_load_shedding(&[], 30, 2), (0, 30));
+        assert_eq!(calculate_fair_load_shedding(&[1; 20], 50, 2), (0, 50));
+
+        // Capping the heavy sender is enough to shed the load.
+        assert_eq!(
+            calculate_fair_load_shedding(&one_heavy_sender, 25, 2),
+            (5, 0)
+        );
+
+        // Capping the heavy sender at the minimum limit isn't enough to shed the load, so the
+        // rest is shed at random.
+        assert_eq!(
+            calculate_fair_load_shedding(&one_heavy_sender, 60, 2),
+            (2, 34)
+        );
+
+        // All heavy senders and shared objects are capped at the same limit.
+        assert_eq!(
+            calculate_fair_load_shedding(&[8, 6, 1, 1, 1, 1], 50, 2),
+            (2, 0)
+        );
+
+        // The limit is never zero, as that would mean no limit.
+        assert_eq!(calculate_fair_load_shedding(&[4, 1, 1], 50, 0), (1, 0));
+    }
+
+    #[test]
+    pub fn test_inflight_transactions() {
+        let inflight = InflightTransactions::default();
+        let sender = LoadSource::Sender(SuiAddress::random_for_testing_only());
+        let object = LoadSource::SharedObject(ObjectID::random());
+        let now = std::time::Instant::now();
+
+        let tx1 = TransactionDigest::random();
+        let tx2 = TransactionDigest::random();
+        assert!(inflight.try_admit(tx1, vec![sender, object], 0, 10, now));
+        assert!(inflight.try_admit(tx2, vec![sender], 0, 10, now + Duration::from_secs(1)));
+        assert_eq!(inflight.count(&sender), 2);
+        assert_eq!(inflight.count(&object), 1);
+
+        // Transactions that are already in flight are accepted, and not counted twice.
+        assert!(inflight.try_admit(tx1, vec![sender, object], 1, 10, now));
+        assert_eq!(inflight.count(&sender), 2);
+
+        // New transactions are rejected once any of their sources reaches the limit.
+        let tx3 = TransactionDigest::random();
+        assert!(!inflight.try_admit(tx3, vec![object], 1, 10, now));
+        assert!(inflight.try_admit(tx3, vec![object], 2, 10, now));
+        assert_eq!(inflight.count(&object), 2);
+
+        // Once the tracker is full, new transactions are still checked against the limit, but
+        // are accepted without being tracked.
+        assert!(!inflight.try_admit(TransactionDigest::random(), vec![object], 2, 3, now));
+        assert!(inflight.try_admit(TransactionDigest::random(), vec![sender], 3, 3, now));
+        assert_eq!(inflight.count(&sender), 2);
+
+        inflight.remove(&tx3);
+        assert_eq!(inflight.count(&object), 1);
+
+        // Only transactions admitted before the deadline expire.
+        inflight.expire(now + Duration::from_millis(500));
+        assert_eq!(inflight.count(&sender), 1);
+        assert_eq!(inflight.count(&object), 0);
+        assert_eq!(inflight.counts(), vec![1]);
+
+        inflight.remove(&tx2);
+        assert!(inflight.counts().is_empty());
+    }
+
+    #[test]
+    pub fn test_overload_monitor_accept_tx_fairly() {
+        let overload_info = AuthorityOverloadInfo::default();
+        let config = FairLoadSheddingConfig::default();
+        let heavy_sender = LoadSource::Sender(SuiAddress::random_for_testing_only());
+
+        // Transactions are tracked, but never rejected, while the authority isn't overloaded.
+        for _ in 0..10 {
+            assert!(overload_monitor_accept_tx_fairly(
+                &overload_info,
+                &config,
+                TransactionDigest::random(),
+                vec![heavy_sender],
+            )
+            .is_ok());
+        }
+        assert_eq!(overload_info.inflight.count(&heavy_sender), 10);
+
+        overload_info.set_overload(50);
+        overload_info.set_fair_load_shedding(2, 0);
+
+        // The heavy sender is over the limit, so all of its transactions are rejected, while
+        // other senders are unaffected.
+        for _ in 0..10 {
+            assert!(matches!(
+                overload_monitor_accept_tx_fairly(
+                    &overload_info,
+                    &config,
+                    TransactionDigest::random(),
+                    vec![heavy_sender],
+                ),
+                Err(SuiError::ValidatorOverloadedRetryAfter { .. })
+            ));
+
+            let light_sender = LoadSource::Sender(SuiAddress::random_for_testing_only());
+            assert!(overload_monitor_accept_tx_fairly(
+                &overload_info,
+                &config,
+                TransactionDigest::random(),
+                vec![light_sender],
+            )
+            .is_ok());
+        }
+        assert_eq!(overload_info.inflight.count(&heavy_sender), 10);
+
+        overload_info.clear_overload();
+        assert_eq!(overload_info.inflight_limit.load(Ordering::Relaxed), 0);
+        assert!(overload_monitor_accept_tx_fairly(
+            &overload_info,
+            &config,
+            TransactionDigest::random(),
+            vec![heavy_sender],
+        )
+        .is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    pub async fn test_check_authority_overload_fair_load_shedding() {
+        telemetry_subscribers::init_for_testing();
+
+        let config = AuthorityOverloadConfig {
+            safe_transaction_ready_rate: 0,
+            fair_load_shedding: Some(FairLoadSheddingConfig::default()),
+            ..Default::default()
+        };
+        let state = TestAuthorityBuilder::new()
+            .with_authority_overload_config(config.clone())
+            .build()
+            .await;
+
+        for _ in 0..1000 {
+            state
+                .metrics
+                .execution_queueing_latency
+                .report(Duration::from_secs(20));
+        }
+
+        // One sender has 10 transactions in flight, and 10 others have one each.
+        let now = std::time::Instant::now();
+        let heavy_sender = LoadSource::Sender(SuiAddress::random_for_testing_only());
+        for _ in 0..10 {
+            state.overload_info.inflight.try_admit(
+                TransactionDigest::random(),
+                vec![heavy_sender],
+                0,
+                usize::MAX,
+                now,
+            );
+            state.overload_info.inflight.try_admit(
+                TransactionDigest::random(),
+                vec![LoadSource::Sender(SuiAddress::random_for_testing_only())],
+                0,
+                usize::MAX,
+                now,
+            );
+        }
+
+        // Shedding 50% of the load caps the heavy sender at the minimum limit, and sheds the rest
+        // at random.
+        let authority = Arc::downgrade(&state);
+        assert!(check_authority_overload(&authority, &config));
+        assert!(state.overload_info.is_overload.load(Ordering::Relaxed));
+        assert_eq!(
+            state.overload_info.inflight_limit.load(Ordering::Relaxed),
+            2
+        );
+        assert_eq!(
+            state
+                .overload_info
+                .random_load_shedding_percentage
+                .load(Ordering::Relaxed),
+            17
+        );
+    }
+
     #[tokio::test(flavor = "current_thread")]
     pub async fn test_check_authority_overload() {
         telemetry_subscribers::init_for_testing();
@@ -33,6 +33,17 @@ pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 300;
 
 type Blocklist = Arc<DashMap<IpAddr, SystemTime>>;
 
+/// A client that is currently on one of the traffic controller's blocklists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlocklistEntry {
+    pub client: IpAddr,
+    /// Whether the client was blocked as a proxied client (i.e. by the IP
+    /// that a fullnode forwarded on its behalf), rather than as the
+    /// connecting client.
+    pub proxied: bool,
+    pub expiration: SystemTime,
+}
+
 #[derive(Clone)]
 struct Blocklists {
     clients: Blocklist,
@@ -82,8 +93,9 @@ impl TrafficController {
         policy_config: PolicyConfig,
         metrics: TrafficControllerMetrics,
         fw_config: Option<RemoteFirewallConfig>,
-    ) -> Self {
-        match policy_config.allow_list {
+    ) -> anyhow::Result<Self> {
+        TrafficControlPolicy::validate_config(&policy_config)?;
+        Ok(match policy_config.allow_list {
             Some(allow_list) => {
                 let allowlist = allow_list
                     .into_iter()
@@ -101,7 +113,7 @@ impl TrafficController {
                 }
             }
             None => Self::spawn(policy_config, metrics, fw_config),
-        }
+        })
     }
 
     fn spawn(
@@ -179,7 +191,7 @@ impl TrafficController {
         fw_config: Option<RemoteFirewallConfig>,
     ) -> Self {
         let metrics = TrafficControllerMetrics::new(&prometheus::Registry::new());
-        Self::init(policy_config, metrics, fw_config)
+        Self::init(policy_config, metrics, fw_config).expect("Invalid traffic control policy")
     }
 
     pub fn tally(&self, tally: TrafficTally) {
@@ -262,6 +274,63 @@ impl TrafficController {
         self.dry_run_mode
     }
 
+    /// The clients that are currently blocked, soonest to expire first.
+    /// Blocks that have been delegated to a remote firewall are not
+    /// included.
+    pub fn blocklist(&self) -> Vec<BlocklistEntry> {
+        let Acl::Blocklists(blocklists) = &self.acl else {
+            return vec![];
+        };
+        let now = SystemTime::now();
+        let entries = |blocklist: &Blocklist, proxied| {
+            blocklist
+                .iter()
+                .filter(|entry| now < *entry.value())
+                .map(|entry| BlocklistEntry {
+                    client: *entry.key(),
+                    proxied,
+                    expiration: *entry.value(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut blocklist = entries(&blocklists.clients, false);
+        blocklist.extend(entries(&blocklists.proxied_clients, true));
+        blocklist.sort_by_key(|entry| entry.expiration);
+        blocklist
+    }
+
+    /// Remove `client` from the blocklists, or clear the blocklists
+    /// entirely if no client is given. Returns the number of entries
+    /// removed. Note that this does not reset the state of the policies, so
+    /// a client that is unblocked and keeps misbehaving is treated as a
+    /// repeat offender.
+    pub fn clear_blocklist(&self, client: Option<IpAddr>) -> usize {
+        let Acl::Blocklists(blocklists) = &self.acl else {
+            return 0;
+        };
+        let clear = |blocklist: &Blocklist, gauge: &IntGauge| {
+            let removed = match client {
+                Some(client) => usize::from(blocklist.remove(&client).is_some()),
+                None => {
+                    let len = blocklist.len();
+                    blocklist.clear();
+                    len
+                }
+            };
+            gauge.set(blocklist.len() as i64);
+            removed
+        };
+        let removed = clear(
+            &blocklists.clients,
+            &self.metrics.connection_ip_blocklist_len,
+        ) + clear(
+            &blocklists.proxied_clients,
+            &self.metrics.proxy_ip_blocklist_len,
+        );
+        info!("Cleared {removed} entries from traffic control blocklists");
+        removed
+    }
+
     async fn check_and_clear_blocklist(
         &self,
         client: &Option<IpAddr>,
@@ -321,8 +390,13 @@ async fn run_tally_loop(
     metrics: Arc<TrafficControllerMetrics>,
     mut mem_drainfile_present: bool,
 ) {
-    let mut spam_policy = TrafficControlPolicy::from_spam_config(policy_config.clone()).await;
-    let mut error_policy = TrafficControlPolicy::from_error_config(policy_config.clone()).await;
+    // The policies have already been validated by `TrafficController::init`.
+    let mut spam_policy = TrafficControlPolicy::from_spam_config(policy_config.clone())
+        .await
+        .expect("Invalid spam policy");
+    let mut error_policy = TrafficControlPolicy::from_error_config(policy_config.clone())
+        .await
+        .expect("Invalid error policy");
     let spam_blocklists = Arc::new(blocklists.clone());
     let error_blocklists = Arc::new(blocklists);
     let node_fw_client = fw_config
@@ -443,28 +517,40 @@ async fn handle_error_tally(
     policy_config: &PolicyConfig,
     nodefw_client: &Option<NodeFWClient>,
     fw_config: &Option<RemoteFirewallConfig>,
-    tally: TrafficTally,
+    mut tally: TrafficTally,
     blocklists: Arc<Blocklists>,
     metrics: Arc<TrafficControllerMetrics>,
     mem_drainfile_present: bool,
 ) -> Result<(), reqwest::Error> {
-    let Some((error_weight, error_type)) = tally.clone().error_info else {
-        return Ok(());
-    };
-    if !error_weight.is_sampled() {
-        return Ok(());
+    match tally.clone().error_info {
+        Some((error_weight, _)) if !error_weight.is_sampled() => {
+            if !policy.tallies_successes() {
+                return Ok(());
+            }
+            // Policies that track the proportion of requests that fail count
+            // every request, so an error that isn't sampled is still counted,
+            // just not as a failure. Otherwise errors would be undercounted
+            // relative to successes.
+            tally.error_info = None;
+        }
+        Some((_, error_type)) => {
+            trace!(
+                "Handling error_type {:?} from client {:?}",
+                error_type,
+                tally.direct,
+            );
+            metrics
+                .tally_error_types
+                .with_label_values(&[error_type.as_str()])
+                .inc();
+            metrics.error_tally_handled.inc();
+        }
+        // Successful requests only matter to policies that track the
+        // proportion of requests that fail.
+        None if policy.tallies_successes() => {}
+        None => return Ok(()),
     }
-    trace!(
-        "Handling error_type {:?} from client {:?}",
-        error_type,
-        tally.direct,
-    );
-    metrics
-        .tally_error_types
-        .with_label_values(&[error_type.as_str()])
-        .inc();
     let resp = policy.handle_tally(tally);
-    metrics.error_tally_handled.inc();
     if let Some(fw_config) = fw_config {
         if fw_config.delegate_error_blocking && !mem_drainfile_present {
             let client = nodefw_client
@@ -527,6 +613,8 @@ async fn handle_policy_response(
     let PolicyResponse {
         block_client,
         block_proxied_client,
+        block_client_ttl,
+        block_proxied_client_ttl,
     } = response;
     let PolicyConfig {
         connection_blocklist_ttl_sec,
@@ -534,12 +622,10 @@ async fn handle_policy_response(
         ..
     } = policy_config;
     if let Some(client) = block_client {
+        let ttl = block_client_ttl.unwrap_or(Duration::from_secs(*connection_blocklist_ttl_sec));
         if blocklists
             .clients
-            .insert(
-                client,
-                SystemTime::now() + Duration::from_secs(*connection_blocklist_ttl_sec),
-            )
+            .insert(client, SystemTime::now() + ttl)
             .is_none()
         {
             // Only increment the metric if the client was not already blocked
@@ -549,12 +635,10 @@ async fn handle_policy_response(
         }
     }
     if let Some(client) = block_proxied_client {
+        let ttl = block_proxied_client_ttl.unwrap_or(Duration::from_secs(*proxy_blocklist_ttl_sec));
         if blocklists
             .proxied_clients
-            .insert(
-                client,
-                SystemTime::now() + Duration::from_secs(*proxy_blocklist_ttl_sec),
-            )
+            .insert(client, SystemTime::now() + ttl)
             .is_none()
         {
             // Only increment the metric if the client was not already blocked
@@ -575,6 +659,8 @@ async fn delegate_policy_response(
     let PolicyResponse {
         block_client,
         block_proxied_client,
+        block_client_ttl,
+        block_proxied_client_ttl,
     } = response;
     let PolicyConfig {
         connection_blocklist_ttl_sec,
@@ -587,7 +673,7 @@ async fn delegate_policy_response(
         addresses.push(BlockAddress {
             source_address: client_id.to_string(),
             destination_port,
-            ttl: *connection_blocklist_ttl_sec,
+            ttl: block_client_ttl.map_or(*connection_blocklist_ttl_sec, |ttl| ttl.as_secs()),
         });
     }
     if let Some(ip) = block_proxied_client {
@@ -595,7 +681,7 @@ async fn delegate_policy_response(
         addresses.push(BlockAddress {
             source_address: ip.to_string(),
             destination_port,
-            ttl: *proxy_blocklist_ttl_sec,
+            ttl: block_proxied_client_ttl.map_or(*proxy_blocklist_ttl_sec, |ttl| ttl.as_secs()),
         });
     }
     if addresses.is_empty() {
@@ -0,0 +1,309 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+use arc_swap::{ArcSwap, Guard};
+use fastcrypto_zkp::bn254::zk_login::OIDCProvider;
+use mysten_metrics::spawn_monitored_task;
+use notify::{RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use sui_config::transaction_deny_config::TransactionDenyConfig;
+use sui_transaction_checks::deny::DenyRule;
+use sui_types::is_system_package;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::authority::AuthorityMetrics;
+
+/// Source label for updates made through the admin server.
+pub const ADMIN_UPDATE_SOURCE: &str = "admin";
+
+/// Source label for updates made by editing the watched config file.
+pub const FILE_UPDATE_SOURCE: &str = "file";
+
+/// How long to wait after the config file changes before reloading it, so that a burst of
+/// events from a single write results in a single reload.
+const FILE_RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Holds the transaction deny config that transactions are checked against when they are signed,
+/// and allows it to be replaced while the node is running. Every accepted update is validated
+/// first, swapped in atomically, and logged along with the rules it changed.
+pub struct TransactionDenyConfigReloader {
+    config: ArcSwap<TransactionDenyConfig>,
+    updates: watch::Sender<Arc<TransactionDenyConfig>>,
+    /// Serializes updates, so that the changes logged for an update are relative to the config it
+    /// replaced.
+    update_lock: Mutex<()>,
+    metrics: Arc<AuthorityMetrics>,
+}
+
+impl TransactionDenyConfigReloader {
+    /// Fails if `config` is not valid, so that a node can't start with a config that it would
+    /// refuse to reload.
+    pub fn new(
+        config: TransactionDenyConfig,
+        metrics: Arc<AuthorityMetrics>,
+    ) -> anyhow::Result<Self> {
+        validate_transaction_deny_config(&config)?;
+        Ok(Self::new_unchecked(config, metrics))
+    }
+
+    /// Like `new`, but an invalid `config` is logged and put into effect anyway, rather than
+    /// rejected, so that nodes with configs that predate validation still start. Updates are still
+    /// validated.
+    pub fn new_lenient(config: TransactionDenyConfig, metrics: Arc<AuthorityMetrics>) -> Self {
+        if let Err(e) = validate_transaction_deny_config(&config) {
+            warn!("Transaction deny config is invalid, and would be rejected if reloaded: {e:#}");
+        }
+        Self::new_unchecked(config, metrics)
+    }
+
+    fn new_unchecked(config: TransactionDenyConfig, metrics: Arc<AuthorityMetrics>) -> Self {
+        let config = Arc::new(config);
+        let (updates, _) = watch::channel(config.clone());
+        Self {
+            config: ArcSwap::new(config),
+            updates,
+            update_lock: Mutex::new(()),
+            metrics,
+        }
+    }
+
+    /// The deny config currently in effect.
+    pub fn load(&self) -> Guard<Arc<TransactionDenyConfig>> {
+        self.config.load()
+    }
+
+    /// Receives the new deny config every time it is updated.
+    pub fn subscribe(&self) -> watch::Receiver<Arc<TransactionDenyConfig>> {
+        self.updates.subscribe()
+    }
+
+    /// Replace the deny config with `config`, if it is valid. `source` identifies where the update
+    /// came from, for the audit log and metrics. Returns a description of each change.
+    pub fn update(
+        &self,
+        config: TransactionDenyConfig,
+        source: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let result = self.try_update(config, source);
+
+        let status = if result.is_ok() { "success" } else { "failure" };
+        self.metrics
+            .transaction_deny_config_updates
+            .with_label_values(&[source, status])
+            .inc();
+
+        if let Err(e) = &result {
+            warn!(source, "Rejected transaction deny config update: {e:#}");
+        }
+
+        result
+    }
+
+    fn try_update(
+        &self,
+        config: TransactionDenyConfig,
+        source: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        validate_transaction_deny_config(&config)?;
+
+        let _guard = self.update_lock.lock();
+        let changes = describe_changes(&self.config.load(), &config);
+        if changes.is_empty() {
+            info!(source, "Transaction deny config is unchanged");
+            return Ok(changes);
+        }
+
+        let config = Arc::new(config);
+        self.config.store(config.clone());
+        self.updates.send_replace(config);
+
+        info!(source, ?changes, "Transaction deny config updated");
+        Ok(changes)
+    }
+
+    /// Load the deny config from the file at `path`, and spawn a task that reloads it every time
+    /// the file changes. Fails if the file can't be watched, or does not contain a valid config.
+    /// Invalid changes to the file are logged and ignored, leaving the previous config in effect.
+    pub fn watch_file(self: &Arc<Self>, path: PathBuf) -> anyhow::Result<JoinHandle<()>> {
+        self.update(read_transaction_deny_config(&path)?, FILE_UPDATE_SOURCE)?;
+
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("Not a file: {}", path.display()))?
+            .to_owned();
+
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                match event {
+                    Ok(event) => {
+                        if !event.kind.is_access()
+                            && event
+                                .paths
+                                .iter()
+                                .any(|p| p.file_name() == Some(file_name.as_os_str()))
+                        {
+                            // If a reload is already pending, it will pick up this change as well.
+                            let _ = tx.try_send(());
+                        }
+                    }
+                    Err(e) => warn!("Error watching transaction deny config: {e}"),
+                }
+            })?;
+
+        // Watch the directory rather than the file itself, so that the watch survives the file
+        // being replaced, rather than modified in place.
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", dir.display()))?;
+
+        let reloader = self.clone();
+        Ok(spawn_monitored_task!(async move {
+            let _watcher = watcher;
+            while rx.recv().await.is_some() {
+                tokio::time::sleep(FILE_RELOAD_DEBOUNCE).await;
+                while rx.try_recv().is_ok() {}
+
+                match read_transaction_deny_config(&path) {
+                    // Failures are already logged by `update`.
+                    Ok(config) => {
+                        let _ = reloader.update(config, FILE_UPDATE_SOURCE);
+                    }
+                    Err(e) => warn!(
+                        path = %path.display(),
+                        "Failed to reload transaction deny config: {e:#}"
+                    ),
+                }
+            }
+        }))
+    }
+}
+
+/// Read a deny config from a YAML (or JSON) file.
+pub fn read_transaction_deny_config(path: &Path) -> anyhow::Result<TransactionDenyConfig> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    serde_yaml::from_reader(file).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Check a deny config for mistakes that would make it silently ineffective, or deny far more
+/// than intended.
+pub fn validate_transaction_deny_config(config: &TransactionDenyConfig) -> anyhow::Result<()> {
+    for provider in config.zklogin_disabled_providers() {
+        // Providers are matched against the canonical name of the provider that issued the
+        // zkLogin signature, so any other spelling would never match.
+        let canonical = OIDCProvider::from_str(provider)
+            .map_err(|_| anyhow!("Unknown zkLogin provider: {provider:?}"))?
+            .to_string();
+        if &canonical != provider {
+            bail!("zkLogin provider {provider:?} should be spelled {canonical:?}");
+        }
+    }
+
+    for package in config.get_package_deny_set() {
+        if is_system_package(*package) {
+            bail!(
+                "Cannot deny system package {package}, set {} instead",
+                DenyRule::UserTransactionDisabled.as_str(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Describe the differences between two deny configs, one change per entry.
+fn describe_changes(old: &TransactionDenyConfig, new: &TransactionDenyConfig) -> Vec<String> {
+    let mut changes = vec![];
+
+    let flags = [
+        (
+            DenyRule::UserTransactionDisabled,
+            TransactionDenyConfig::user_transaction_disabled as fn(&TransactionDenyConfig) -> bool,
+        ),
+        (
+            DenyRule::SharedObjectDisabled,
+            TransactionDenyConfig::shared_object_disabled,
+        ),
+        (
+            DenyRule::PackagePublishDisabled,
+            TransactionDenyConfig::package_publish_disabled,
+        ),
+        (
+            DenyRule::PackageUpgradeDisabled,
+            TransactionDenyConfig::package_upgrade_disabled,
+        ),
+        (
+            DenyRule::ReceivingObjectsDisabled,
+            TransactionDenyConfig::receiving_objects_disabled,
+        ),
+        (
+            DenyRule::ZkLoginSigDisabled,
+            TransactionDenyConfig::zklogin_sig_disabled,
+        ),
+    ];
+
+    for (rule, flag) in flags {
+        if flag(old) != flag(new) {
+            changes.push(format!("{}: {} -> {}", rule.as_str(), flag(old), flag(new)));
+        }
+    }
+
+    describe_set_changes(
+        &mut changes,
+        DenyRule::ObjectDenied,
+        old.get_object_deny_set(),
+        new.get_object_deny_set(),
+    );
+    describe_set_changes(
+        &mut changes,
+        DenyRule::PackageDenied,
+        old.get_package_deny_set(),
+        new.get_package_deny_set(),
+    );
+    describe_set_changes(
+        &mut changes,
+        DenyRule::AddressDenied,
+        old.get_address_deny_set(),
+        new.get_address_deny_set(),
+    );
+    describe_set_changes(
+        &mut changes,
+        DenyRule::ZkLoginProviderDisabled,
+        old.zklogin_disabled_providers(),
+        new.zklogin_disabled_providers(),
+    );
+
+    changes
+}
+
+fn describe_set_changes<T: Eq + Hash + Display>(
+    changes: &mut Vec<String>,
+    rule: DenyRule,
+    old: &HashSet<T>,
+    new: &HashSet<T>,
+) {
+    let mut removed: Vec<_> = old.difference(new).map(|x| format!("-{x}")).collect();
+    let mut added: Vec<_> = new.difference(old).map(|x| format!("+{x}")).collect();
+    removed.sort();
+    added.sort();
+
+    for change in removed.into_iter().chain(added) {
+        changes.push(format!("{}: {change}", rule.as_str()));
+    }
+}
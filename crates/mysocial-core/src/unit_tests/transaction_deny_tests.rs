@@ -7,11 +7,13 @@ use crate::authority::auth_unit_test_utils::{
 use crate::authority::test_authority_builder::TestAuthorityBuilder;
 use crate::authority::AuthorityState;
 use crate::test_utils::make_transfer_sui_transaction;
+use crate::transaction_deny_config_reloader::{TransactionDenyConfigReloader, ADMIN_UPDATE_SOURCE};
 use fastcrypto::ed25519::Ed25519KeyPair;
 use fastcrypto::traits::KeyPair;
 use move_core_types::ident_str;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use sui_config::certificate_deny_config::CertificateDenyConfigBuilder;
 use sui_config::transaction_deny_config::{TransactionDenyConfig, TransactionDenyConfigBuilder};
 use sui_swarm_config::genesis_config::{AccountConfig, DEFAULT_GAS_AMOUNT};
@@ -481,3 +483,129 @@ async fn test_certificate_deny() {
         }
     ));
 }
+
+#[tokio::test]
+async fn test_update_deny_config() {
+    let (network_config, state) = setup_test(TransactionDenyConfig::default()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    let reloader = state.transaction_deny_config();
+
+    let changes = reloader
+        .update(
+            TransactionDenyConfigBuilder::new()
+                .disable_user_transaction()
+                .build(),
+            ADMIN_UPDATE_SOURCE,
+        )
+        .unwrap();
+    assert_eq!(changes, vec!["user-transaction-disabled: false -> true"]);
+    assert_denied(&transfer_with_account(&accounts[0], &accounts[0], &state).await);
+    assert_eq!(
+        state
+            .metrics
+            .transaction_deny_rejections
+            .with_label_values(&["user-transaction-disabled"])
+            .get(),
+        1
+    );
+
+    // Invalid configs are rejected, leaving the previous config in effect.
+    reloader
+        .update(
+            TransactionDenyConfigBuilder::new()
+                .add_zklogin_disabled_provider("NotAProvider".to_string())
+                .build(),
+            ADMIN_UPDATE_SOURCE,
+        )
+        .unwrap_err();
+    reloader
+        .update(
+            TransactionDenyConfigBuilder::new()
+                .add_denied_package(ObjectID::from_single_byte(2))
+                .build(),
+            ADMIN_UPDATE_SOURCE,
+        )
+        .unwrap_err();
+    assert!(reloader.load().user_transaction_disabled());
+    assert_denied(&transfer_with_account(&accounts[0], &accounts[0], &state).await);
+
+    // The same configs are rejected at startup.
+    assert!(TransactionDenyConfigReloader::new(
+        TransactionDenyConfigBuilder::new()
+            .add_denied_package(ObjectID::from_single_byte(2))
+            .build(),
+        state.metrics.clone(),
+    )
+    .is_err());
+    // ...unless the node is started leniently, in which case the config is used as is.
+    let lenient = TransactionDenyConfigReloader::new_lenient(
+        TransactionDenyConfigBuilder::new()
+            .add_denied_package(ObjectID::from_single_byte(2))
+            .build(),
+        state.metrics.clone(),
+    );
+    assert!(lenient
+        .load()
+        .get_package_deny_set()
+        .contains(&ObjectID::from_single_byte(2)));
+
+    let changes = reloader
+        .update(
+            TransactionDenyConfigBuilder::new()
+                .add_denied_address(accounts[1].0)
+                .build(),
+            ADMIN_UPDATE_SOURCE,
+        )
+        .unwrap();
+    assert_eq!(
+        changes,
+        vec![
+            "user-transaction-disabled: true -> false".to_string(),
+            format!("address-deny-list: +{}", accounts[1].0),
+        ]
+    );
+    assert!(transfer_with_account(&accounts[0], &accounts[0], &state)
+        .await
+        .is_ok());
+    assert_denied(&transfer_with_account(&accounts[1], &accounts[1], &state).await);
+}
+
+#[tokio::test]
+async fn test_watch_deny_config_file() {
+    let (_, state) = setup_test(TransactionDenyConfig::default()).await;
+    let reloader = state.transaction_deny_config();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("deny-config.yaml");
+    let write_config = |config: &TransactionDenyConfig| {
+        std::fs::write(&path, serde_yaml::to_string(config).unwrap()).unwrap()
+    };
+
+    // The config in the file takes effect as soon as it is watched.
+    write_config(
+        &TransactionDenyConfigBuilder::new()
+            .disable_package_publish()
+            .build(),
+    );
+    let _watcher = reloader.watch_file(path.clone()).unwrap();
+    assert!(reloader.load().package_publish_disabled());
+
+    // Invalid changes to the file are ignored.
+    std::fs::write(&path, "package-publish-disabled: maybe").unwrap();
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert!(reloader.load().package_publish_disabled());
+
+    write_config(
+        &TransactionDenyConfigBuilder::new()
+            .disable_package_upgrade()
+            .build(),
+    );
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while !reloader.load().package_upgrade_disabled() {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("deny config was not reloaded");
+    assert!(!reloader.load().package_publish_disabled());
+}
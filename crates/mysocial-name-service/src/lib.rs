@@ -15,6 +15,9 @@ use mysocial_types::dynamic_field::Field;
 use mysocial_types::id::{ID, UID};
 use mysocial_types::object::{MoveObject, Object};
 use mysocial_types::TypeTag;
+use unicode_normalization::UnicodeNormalization;
+use unicode_properties::{GeneralCategory, UnicodeGeneralCategory};
+use unicode_security::{GeneralSecurityProfile, MixedScript};
 
 const NAME_SERVICE_DOMAIN_MODULE: &IdentStr = ident_str!("domain");
 const NAME_SERVICE_DOMAIN_STRUCT: &IdentStr = ident_str!("Domain");
@@ -22,6 +25,8 @@ const LEAF_EXPIRATION_TIMESTAMP: u64 = 0;
 const DEFAULT_TLD: &str = "myso";
 const ACCEPTED_SEPARATORS: [char; 2] = ['.', '*'];
 const MYSO_NEW_FORMAT_SEPARATOR: char = '@';
+/// Prefix of labels that hold a punycode-encoded Unicode label (RFC 5890).
+const PUNYCODE_PREFIX: &str = "xn--";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Registry {
@@ -116,6 +121,18 @@ pub enum NameServiceError {
     LabelsEmpty,
     #[error("Name Service: Domain must include only one separator")]
     InvalidSeparator,
+    #[error("Name Service: Hyphens in the third and fourth positions are reserved")]
+    ReservedHyphens,
+    #[error("Name Service: Character {0:?} is not allowed in a handle")]
+    DisallowedCharacter(char),
+    #[error("Name Service: Handle mixes characters from more than one script")]
+    MixedScripts,
+    #[error("Name Service: Handle is confusable with {0:?}")]
+    ConfusableLabel(String),
+    #[error("Name Service: Invalid punycode label: {0}")]
+    InvalidPunycode(String),
+    #[error("Name Service: Label {0} is not in canonical form, expected {1}")]
+    NonCanonicalLabel(String, String),
 
     #[error("Name Service: Name has expired.")]
     NameExpired,
@@ -175,6 +192,60 @@ impl Domain {
 
         format!("{}{}{}", labels.join(sep), MYSO_NEW_FORMAT_SEPARATOR, sld)
     }
+
+    /// Parses a user-visible handle into a domain, normalizing each label into its canonical
+    /// on-chain form (see `normalize_label`). Unlike `Domain::from_str`, this accepts mixed case,
+    /// compatibility characters (e.g. full-width letters and separators) and Unicode labels.
+    /// E.g. `Café@Example` -> `xn--caf-dma.example.myso`
+    pub fn from_handle(handle: &str) -> Result<Self, NameServiceError> {
+        Self::parse(&fold(handle), normalize_label)
+    }
+
+    /// Formats a domain like `Domain::format`, but with punycode-encoded labels decoded back to
+    /// Unicode for display.
+    /// E.g. `xn--caf-dma.example.myso` -> `café@example`
+    pub fn format_unicode(&self, format: DomainFormat) -> String {
+        let labels = self
+            .labels
+            .iter()
+            .map(|label| label_to_unicode(label).unwrap_or_else(|_| label.clone()))
+            .collect();
+
+        Domain { labels }.format(format)
+    }
+
+    /// Splits `s` into labels in any of the accepted formats, checking each label with
+    /// `label_fn`.
+    fn parse(
+        s: &str,
+        label_fn: impl Fn(&str) -> Result<String, NameServiceError>,
+    ) -> Result<Self, NameServiceError> {
+        /// The maximum length of a full domain
+        const MAX_DOMAIN_LENGTH: usize = 200;
+
+        if s.len() > MAX_DOMAIN_LENGTH {
+            return Err(NameServiceError::ExceedsMaxLength(
+                s.len(),
+                MAX_DOMAIN_LENGTH,
+            ));
+        }
+        let separator = separator(s)?;
+
+        let formatted_string = convert_from_new_format(s, &separator)?;
+
+        let labels = formatted_string
+            .split(separator)
+            .rev()
+            .map(label_fn)
+            .collect::<Result<Vec<_>, NameServiceError>>()?;
+
+        // A valid domain in our system has at least a TLD and an SLD (len == 2).
+        if labels.len() < 2 {
+            return Err(NameServiceError::LabelsEmpty);
+        }
+
+        Ok(Domain { labels })
+    }
 }
 
 impl NameServiceConfig {
@@ -269,32 +340,7 @@ impl FromStr for Domain {
     type Err = NameServiceError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        /// The maximum length of a full domain
-        const MAX_DOMAIN_LENGTH: usize = 200;
-
-        if s.len() > MAX_DOMAIN_LENGTH {
-            return Err(NameServiceError::ExceedsMaxLength(
-                s.len(),
-                MAX_DOMAIN_LENGTH,
-            ));
-        }
-        let separator = separator(s)?;
-
-        let formatted_string = convert_from_new_format(s, &separator)?;
-
-        let labels = formatted_string
-            .split(separator)
-            .rev()
-            .map(validate_label)
-            .collect::<Result<Vec<_>, Self::Err>>()?;
-
-        // A valid domain in our system has at least a TLD and an SLD (len == 2).
-        if labels.len() < 2 {
-            return Err(NameServiceError::LabelsEmpty);
-        }
-
-        let labels = labels.into_iter().map(ToOwned::to_owned).collect();
-        Ok(Domain { labels })
+        Self::parse(s, |label| validate_label(label).map(ToOwned::to_owned))
     }
 }
 
@@ -420,6 +466,122 @@ pub fn validate_label(label: &str) -> Result<&str, NameServiceError> {
     Ok(label)
 }
 
+/// Validates that `label` is in the canonical form that `normalize_label` produces for new
+/// registrations. On top of `validate_label`, hyphens in the third and fourth positions are
+/// reserved for `xn--` labels, which must encode a canonical Unicode label. Names that are already
+/// registered are only held to `validate_label`, so that they can still be parsed.
+fn validate_canonical_label(label: &str) -> Result<(), NameServiceError> {
+    validate_label(label)?;
+
+    if let Some(encoded) = label.strip_prefix(PUNYCODE_PREFIX) {
+        let canonical = normalize_label(&decode_punycode(label, encoded)?)?;
+        if canonical != label {
+            return Err(NameServiceError::NonCanonicalLabel(
+                label.to_owned(),
+                canonical,
+            ));
+        }
+    } else if label.get(2..4) == Some("--") {
+        return Err(NameServiceError::ReservedHyphens);
+    }
+
+    Ok(())
+}
+
+/// Normalizes one label of a user-visible handle into its canonical on-chain form, so that each
+/// handle maps to exactly one label:
+///
+/// - Compatibility characters are folded (Unicode NFKC), and so is case (full Unicode case
+///   folding, which also maps `ß` to `ss`).
+/// - ASCII labels are then validated as they are (see `validate_canonical_label`).
+/// - Unicode labels may only contain letters, numbers, combining marks that follow them, and
+///   hyphens, from a single script, and must not be confusable with an ASCII label. They are
+///   punycode-encoded into an `xn--` label.
+///
+/// E.g. `Alice` -> `alice` | `Café` -> `xn--caf-dma` | `Straße` -> `strasse`
+pub fn normalize_label(handle: &str) -> Result<String, NameServiceError> {
+    let label = fold(handle);
+    if label.is_ascii() {
+        validate_canonical_label(&label)?;
+        return Ok(label);
+    }
+
+    validate_unicode_label(&label)?;
+
+    let encoded = idna::punycode::encode_str(&label)
+        .ok_or_else(|| NameServiceError::InvalidPunycode(label.clone()))?;
+    let encoded = format!("{PUNYCODE_PREFIX}{encoded}");
+    validate_label(&encoded)?;
+    Ok(encoded)
+}
+
+/// Decodes a canonical on-chain label into the Unicode form that is shown to users. Labels that
+/// are not punycode-encoded are returned unchanged.
+/// E.g. `xn--caf-dma` -> `café` | `alice` -> `alice`
+pub fn label_to_unicode(label: &str) -> Result<String, NameServiceError> {
+    validate_label(label)?;
+
+    match label.strip_prefix(PUNYCODE_PREFIX) {
+        Some(encoded) => decode_punycode(label, encoded),
+        None => Ok(label.to_owned()),
+    }
+}
+
+/// Case and compatibility folding applied to handles before they are validated.
+fn fold(s: &str) -> String {
+    // Case folding can produce unnormalized sequences, so normalize again after it.
+    let folded = caseless::default_case_fold_str(&s.nfkc().collect::<String>());
+    folded.nfkc().collect()
+}
+
+fn decode_punycode(label: &str, encoded: &str) -> Result<String, NameServiceError> {
+    idna::punycode::decode_to_string(encoded)
+        .ok_or_else(|| NameServiceError::InvalidPunycode(label.to_owned()))
+}
+
+/// Validates the characters of a folded, non-ASCII label. Its length is checked after it has been
+/// encoded.
+fn validate_unicode_label(label: &str) -> Result<(), NameServiceError> {
+    let len = label.chars().count();
+    let mut prev: Option<char> = None;
+    for (i, character) in label.chars().enumerate() {
+        match character {
+            '-' if i == 0 || i == len - 1 => return Err(NameServiceError::InvalidHyphens),
+            '-' => {}
+            c if c.is_alphanumeric() && c.identifier_allowed() => {}
+            // Combining marks (e.g. a Devanagari virama or a Thai tone mark) are part of the
+            // spelling of many names, but only after the character they modify.
+            c if is_combining_mark(c)
+                && c.identifier_allowed()
+                && prev.is_some_and(|p| p != '-') => {}
+            c => return Err(NameServiceError::DisallowedCharacter(c)),
+        }
+
+        prev = Some(character);
+    }
+
+    if !label.is_single_script() {
+        return Err(NameServiceError::MixedScripts);
+    }
+
+    // A Unicode label whose confusable skeleton is entirely ASCII can be used to impersonate the
+    // owner of that ASCII label (e.g. Cyrillic `сосо` and Latin `coco`).
+    let skeleton: String = unicode_security::skeleton(label).collect();
+    if skeleton.is_ascii() {
+        return Err(NameServiceError::ConfusableLabel(skeleton));
+    }
+
+    Ok(())
+}
+
+/// Whether `c` is a non-spacing (Mn) or spacing (Mc) combining mark.
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c.general_category(),
+        GeneralCategory::NonspacingMark | GeneralCategory::SpacingMark
+    )
+}
+
 // Backward compatibility aliases
 pub type SuinsRegistration = MysocialNameServiceRegistration;
 pub const SUI_NEW_FORMAT_SEPARATOR: char = MYSO_NEW_FORMAT_SEPARATOR;
@@ -531,4 +693,138 @@ mod tests {
         assert!(domain.format(DomainFormat::Dot) == "test.test.test.test.myso");
         assert!(domain.format(DomainFormat::At) == "test.test.test@test");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_normalize_ascii_handles() {
+        assert_eq!(normalize_label("alice").unwrap(), "alice");
+        assert_eq!(normalize_label("Alice").unwrap(), "alice");
+        assert_eq!(normalize_label("ＡＬＩＣＥ").unwrap(), "alice");
+        assert_eq!(normalize_label("alice-42").unwrap(), "alice-42");
+    }
+
+    #[test]
+    fn test_normalize_unicode_handles() {
+        assert_eq!(normalize_label("café").unwrap(), "xn--caf-dma");
+        assert_eq!(normalize_label("Café").unwrap(), "xn--caf-dma");
+        // Decomposed and precomposed forms map to the same label.
+        assert_eq!(normalize_label("cafe\u{301}").unwrap(), "xn--caf-dma");
+        assert_eq!(label_to_unicode("xn--caf-dma").unwrap(), "café");
+        assert_eq!(label_to_unicode("alice").unwrap(), "alice");
+    }
+
+    #[test]
+    fn test_handle_round_trip() {
+        for handle in ["alice", "café", "münchen", "ñandú", "日本語", "δοκιμή"] {
+            let label = normalize_label(handle).unwrap();
+            assert_eq!(validate_label(&label).unwrap(), label);
+            assert_eq!(label_to_unicode(&label).unwrap(), handle);
+            assert_eq!(normalize_label(&label).unwrap(), label);
+        }
+    }
+
+    #[test]
+    fn test_non_latin_handles() {
+        // Devanagari and Tamil viramas, Thai tone marks and vowel signs, Arabic, and Hangul.
+        for handle in ["हिन्दी", "தமிழ்", "แม่", "สมศักดิ์", "محمد", "한국어"]
+        {
+            let label = normalize_label(handle).unwrap();
+            assert!(label.starts_with(PUNYCODE_PREFIX), "{handle}: {label}");
+            assert_eq!(label_to_unicode(&label).unwrap(), handle);
+            assert_eq!(normalize_label(&label).unwrap(), label);
+        }
+
+        // Combining marks must follow the character they modify.
+        assert_eq!(
+            normalize_label("\u{94d}हिन्दी"),
+            Err(NameServiceError::DisallowedCharacter('\u{94d}'))
+        );
+        assert_eq!(
+            normalize_label("แม-\u{e48}"),
+            Err(NameServiceError::DisallowedCharacter('\u{e48}'))
+        );
+    }
+
+    #[test]
+    fn test_case_folding() {
+        // Full case folding, rather than lowercasing.
+        assert_eq!(normalize_label("Straße").unwrap(), "strasse");
+        assert_eq!(normalize_label("STRASSE").unwrap(), "strasse");
+        assert_eq!(fold("ΟΔΥΣΣΕΥΣ"), fold("οδυσσευς"));
+    }
+
+    #[test]
+    fn test_rejected_handles() {
+        // Latin `p`, `y`, `p`, `l` with Cyrillic `а`.
+        assert_eq!(
+            normalize_label("p\u{430}ypal"),
+            Err(NameServiceError::MixedScripts)
+        );
+        // Entirely Cyrillic, but reads as `coco`.
+        assert_eq!(
+            normalize_label("\u{441}\u{43e}\u{441}\u{43e}"),
+            Err(NameServiceError::ConfusableLabel("coco".to_string()))
+        );
+        assert_eq!(
+            normalize_label("café😀"),
+            Err(NameServiceError::DisallowedCharacter('😀'))
+        );
+        assert_eq!(
+            normalize_label("café_"),
+            Err(NameServiceError::DisallowedCharacter('_'))
+        );
+        assert_eq!(
+            normalize_label("-café"),
+            Err(NameServiceError::InvalidHyphens)
+        );
+        assert_eq!(
+            normalize_label("ab--cd"),
+            Err(NameServiceError::ReservedHyphens)
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_encoded_labels() {
+        // Encodes the decomposed form of `café`, which normalizes to a different label.
+        let label = format!(
+            "{PUNYCODE_PREFIX}{}",
+            idna::punycode::encode_str("cafe\u{301}").unwrap()
+        );
+
+        assert_eq!(
+            normalize_label(&label),
+            Err(NameServiceError::NonCanonicalLabel(
+                label.clone(),
+                "xn--caf-dma".to_string()
+            ))
+        );
+        assert!(Domain::from_handle(&format!("{label}.myso")).is_err());
+
+        // Names that may already be registered still parse.
+        assert_eq!(validate_label(&label), Ok(label.as_str()));
+        assert!(Domain::from_str(&format!("{label}.myso")).is_ok());
+        assert!(Domain::from_str("ab--cd.myso").is_ok());
+    }
+
+    #[test]
+    fn test_domain_from_handle() {
+        let domain = Domain::from_handle("Café@Example").unwrap();
+        assert_eq!(domain.to_string(), "xn--caf-dma.example.myso");
+        assert_eq!(domain.format_unicode(DomainFormat::At), "café@example");
+        assert_eq!(
+            domain.format_unicode(DomainFormat::Dot),
+            "café.example.myso"
+        );
+        assert_eq!(Domain::from_str(&domain.to_string()).unwrap(), domain);
+
+        // Full-width separators are folded too.
+        assert_eq!(
+            Domain::from_handle("＠ＡＬＩＣＥ").unwrap().to_string(),
+            "alice.myso"
+        );
+        assert_eq!(
+            Domain::from_handle("Test.Test.MYSO").unwrap(),
+            Domain::from_str("test.test.myso").unwrap()
+        );
+        assert!(Domain::from_handle("p\u{430}ypal@myso").is_err());
+    }
+}
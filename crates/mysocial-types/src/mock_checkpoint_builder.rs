@@ -22,7 +22,7 @@ pub trait ValidatorKeypairProvider {
 
 /// A utility to build consecutive checkpoints by adding transactions to the checkpoint builder.
 /// It's mostly used by simulations, tests and benchmarks.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct MockCheckpointBuilder {
     previous_checkpoint: Option<VerifiedCheckpoint>,
     transactions: Vec<VerifiedExecutionData>,
@@ -32,9 +32,17 @@ pub struct MockCheckpointBuilder {
 
 impl MockCheckpointBuilder {
     pub fn new(previous_checkpoint: VerifiedCheckpoint) -> Self {
-        let epoch_rolling_gas_cost_summary =
-            previous_checkpoint.epoch_rolling_gas_cost_summary.clone();
-        let epoch = previous_checkpoint.epoch;
+        // The checkpoint after the last checkpoint of an epoch starts the next epoch, with a fresh
+        // rolling gas cost summary.
+        let (epoch, epoch_rolling_gas_cost_summary) =
+            if previous_checkpoint.end_of_epoch_data.is_some() {
+                (previous_checkpoint.epoch + 1, GasCostSummary::default())
+            } else {
+                (
+                    previous_checkpoint.epoch,
+                    previous_checkpoint.epoch_rolling_gas_cost_summary.clone(),
+                )
+            };
 
         Self {
             previous_checkpoint: Some(previous_checkpoint),
@@ -3,6 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 // These values set to loosely attempt to limit
@@ -202,6 +203,144 @@ fn default_sketch_tolerance() -> f64 {
     DEFAULT_SKETCH_TOLERANCE
 }
 
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TokenBucketConfig {
+    /// Maximum number of tokens a direct client can accumulate, i.e. the
+    /// largest burst of (unit cost) requests it can make before being blocked.
+    #[serde(default = "default_bucket_capacity")]
+    pub client_capacity: u64,
+    /// Tokens per second returned to a direct client's bucket.
+    #[serde(default = "default_bucket_refill_rate")]
+    pub client_refill_rate: f64,
+    #[serde(default = "default_proxied_bucket_capacity")]
+    pub proxied_client_capacity: u64,
+    #[serde(default = "default_proxied_bucket_refill_rate")]
+    pub proxied_client_refill_rate: f64,
+    /// Cost of a request to a method that is not listed in `method_costs`.
+    #[serde(default = "default_method_cost")]
+    pub default_method_cost: u64,
+    /// Per-method request cost, keyed by method name, e.g.
+    /// `sui_executeTransactionBlock` for json rpc or `transaction`
+    /// for the validator grpc service.
+    #[serde(default)]
+    pub method_costs: BTreeMap<String, u64>,
+    /// Additional cost of a request that resulted in an error whose type
+    /// is not listed in `error_costs`.
+    #[serde(default = "default_error_cost")]
+    pub default_error_cost: u64,
+    /// Additional per-error cost of a request that resulted in an error,
+    /// keyed by error type.
+    #[serde(default)]
+    pub error_costs: BTreeMap<String, u64>,
+    /// List of String which should all parse to either an IpAddr or a CIDR
+    /// block. Clients matching any entry are never tallied by this policy.
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+}
+
+impl Default for TokenBucketConfig {
+    fn default() -> Self {
+        Self {
+            client_capacity: default_bucket_capacity(),
+            client_refill_rate: default_bucket_refill_rate(),
+            proxied_client_capacity: default_proxied_bucket_capacity(),
+            proxied_client_refill_rate: default_proxied_bucket_refill_rate(),
+            default_method_cost: default_method_cost(),
+            method_costs: BTreeMap::new(),
+            default_error_cost: default_error_cost(),
+            error_costs: BTreeMap::new(),
+            allowlist: vec![],
+        }
+    }
+}
+
+fn default_bucket_capacity() -> u64 {
+    // as with the frequency threshold, a direct client may be a fullnode
+    // proxying traffic for many clients, so by default only block clients
+    // with unreasonably high request rates
+    1_000_000
+}
+
+fn default_bucket_refill_rate() -> f64 {
+    100_000.0
+}
+
+fn default_proxied_bucket_capacity() -> u64 {
+    100
+}
+
+fn default_proxied_bucket_refill_rate() -> f64 {
+    10.0
+}
+
+fn default_method_cost() -> u64 {
+    1
+}
+
+fn default_error_cost() -> u64 {
+    1
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ErrorRatioConfig {
+    /// Fraction of a client's requests over the window that must have
+    /// failed for the client to be blocked.
+    #[serde(default = "default_error_ratio_threshold")]
+    pub error_ratio_threshold: f64,
+    /// Number of requests a client must have made over the window before
+    /// its error ratio is considered, so that a handful of failures from an
+    /// otherwise quiet client does not get it blocked.
+    #[serde(default = "default_error_ratio_min_requests")]
+    pub min_requests: u64,
+    #[serde(default = "default_window_size_secs")]
+    pub window_size_secs: u64,
+    #[serde(default = "default_update_interval_secs")]
+    pub update_interval_secs: u64,
+    /// Upper bound on the blocklist TTL of repeat offenders. Each time a
+    /// client is blocked again, its TTL is doubled, starting from the
+    /// configured `connection_blocklist_ttl_sec` (or `proxy_blocklist_ttl_sec`
+    /// for proxied clients).
+    #[serde(default = "default_max_blocklist_ttl_sec")]
+    pub max_blocklist_ttl_sec: u64,
+    /// A client's previous offenses are forgotten once it has not been
+    /// blocked for this long, and its TTL starts from the base TTL again.
+    #[serde(default = "default_offense_decay_sec")]
+    pub offense_decay_sec: u64,
+}
+
+impl Default for ErrorRatioConfig {
+    fn default() -> Self {
+        Self {
+            error_ratio_threshold: default_error_ratio_threshold(),
+            min_requests: default_error_ratio_min_requests(),
+            window_size_secs: default_window_size_secs(),
+            update_interval_secs: default_update_interval_secs(),
+            max_blocklist_ttl_sec: default_max_blocklist_ttl_sec(),
+            offense_decay_sec: default_offense_decay_sec(),
+        }
+    }
+}
+
+fn default_error_ratio_threshold() -> f64 {
+    0.5
+}
+
+fn default_error_ratio_min_requests() -> u64 {
+    20
+}
+
+fn default_max_blocklist_ttl_sec() -> u64 {
+    3_600
+}
+
+fn default_offense_decay_sec() -> u64 {
+    3_600
+}
+
 // Serializable representation of policy types, used in config
 // in order to easily change in tests or to killswitch
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
@@ -215,6 +354,20 @@ pub enum PolicyType {
     /// with granularity of `update_interval_secs`
     FreqThreshold(FreqThresholdConfig),
 
+    /// Blocks connection_ip once its token bucket is exhausted. Each client's
+    /// bucket holds up to `capacity` tokens and is refilled at `refill_rate`
+    /// tokens per second, and each tally consumes tokens according to the cost
+    /// of the method called and of the error returned, if any.
+    TokenBucket(TokenBucketConfig),
+
+    /// Blocks connection_ip once the fraction of its requests that failed,
+    /// as calculated over a sliding window of `window_size_secs` with
+    /// granularity of `update_interval_secs`, reaches `error_ratio_threshold`.
+    /// Clients that are blocked repeatedly are blocked for exponentially
+    /// longer, up to `max_blocklist_ttl_sec`. Intended to be used as an error
+    /// policy, as it needs to see successful requests as well as errors.
+    ErrorRatio(ErrorRatioConfig),
+
     /* Below this point are test policies, and thus should not be used in production */
     ///
     /// Simple policy that adds connection_ip to blocklist when the same connection_ip
@@ -25,8 +25,9 @@ use sui_types::{
 
 use crate::SimulatorStore;
 
+#[derive(Clone)]
 pub struct EpochState {
-    epoch_start_state: EpochStartSystemState,
+    epoch_start_state: Arc<EpochStartSystemState>,
     committee: Committee,
     protocol_config: ProtocolConfig,
     limits_metrics: Arc<LimitsMetrics>,
@@ -35,11 +36,17 @@ pub struct EpochState {
     /// A counter that advances each time we advance the clock in order to ensure that each update
     /// txn has a unique digest. This is reset on epoch changes
     next_consensus_round: u64,
+    /// Whether `committee` replaces the one in the system state, and carries over to later epochs.
+    fixed_committee: bool,
 }
 
 impl EpochState {
     pub fn new(system_state: SuiSystemState) -> Self {
-        let epoch_start_state = system_state.into_epoch_start_state();
+        Self::from_parts(system_state.into_epoch_start_state(), 0)
+    }
+
+    /// Recreate an `EpochState` from the parts returned by [`EpochState::to_parts`].
+    pub fn from_parts(epoch_start_state: EpochStartSystemState, next_consensus_round: u64) -> Self {
         let committee = epoch_start_state.get_sui_committee();
         let protocol_config =
             ProtocolConfig::get_for_version(epoch_start_state.protocol_version(), Chain::Unknown);
@@ -49,14 +56,44 @@ impl EpochState {
         let executor = sui_execution::executor(&protocol_config, true, None).unwrap();
 
         Self {
-            epoch_start_state,
+            epoch_start_state: Arc::new(epoch_start_state),
             committee,
             protocol_config,
             limits_metrics,
             bytecode_verifier_metrics,
             executor,
-            next_consensus_round: 0,
+            next_consensus_round,
+            fixed_committee: false,
+        }
+    }
+
+    /// Sign checkpoints with `committee` instead of the committee in the system state, in this
+    /// epoch and all the epochs after it. This is how forks of a live network, whose validator
+    /// keys are not available, produce checkpoints.
+    pub fn with_committee(mut self, committee: Committee) -> Self {
+        self.committee = committee;
+        self.fixed_committee = true;
+        self
+    }
+
+    /// Create the `EpochState` for the epoch after this one, from the system state at its start.
+    pub fn next_epoch(&self, system_state: SuiSystemState) -> Self {
+        let next = Self::new(system_state);
+        if !self.fixed_committee {
+            return next;
         }
+
+        let committee = Committee::new(
+            next.epoch(),
+            self.committee.voting_rights.iter().cloned().collect(),
+        );
+        next.with_committee(committee)
+    }
+
+    /// The epoch start state and the next consensus round, which are all that is needed to
+    /// recreate this `EpochState`.
+    pub fn to_parts(&self) -> (&EpochStartSystemState, u64) {
+        (&self.epoch_start_state, self.next_consensus_round)
     }
 
     pub fn epoch(&self) -> EpochId {
@@ -11,12 +11,13 @@
 //! [`Simulacrum`]: crate::Simulacrum
 
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{anyhow, ensure, Context, Result};
 use fastcrypto::traits::Signer;
 use rand::rngs::OsRng;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sui_config::verifier_signing_config::VerifierSigningConfig;
 use sui_config::{genesis, transaction_deny_config::TransactionDenyConfig};
 use sui_protocol_config::ProtocolVersion;
@@ -46,6 +47,7 @@ use sui_types::{
 };
 
 use self::epoch_state::EpochState;
+pub use self::store::forked_store::{ForkSource, ForkedStore};
 pub use self::store::in_mem_store::InMemoryStore;
 use self::store::in_mem_store::KeyStore;
 pub use self::store::SimulatorStore;
@@ -143,6 +145,72 @@ where
     }
 }
 
+impl<R> Simulacrum<R, ForkedStore>
+where
+    R: rand::RngCore + rand::CryptoRng,
+{
+    /// Create a Simulacrum that forks the network `source` reads from, at the source's
+    /// checkpoint, to execute transactions against the network's state.
+    ///
+    /// The network's validators are replaced by a local committee that signs the fork's
+    /// checkpoints, and the accounts in the Simulacrum's keystore are given fresh gas coins, for
+    /// [`Simulacrum::request_gas`] to draw from. Objects owned by the network's own accounts can be
+    /// read, but can only be used by transactions signed by their owners.
+    pub fn new_forked(mut rng: R, source: impl ForkSource + 'static) -> Self {
+        let config = ConfigBuilder::new_with_temp_dir()
+            .rng(&mut rng)
+            .deterministic_committee_size(NonZeroUsize::new(1).unwrap())
+            .build();
+        let keystore = KeyStore::from_network_config(&config);
+
+        let mut store = ForkedStore::new(Arc::new(source));
+        let checkpoint = store.fork_checkpoint().clone();
+
+        let epoch_state = EpochState::new(store.get_system_state());
+        let committee = Committee::new(
+            epoch_state.epoch(),
+            config
+                .genesis
+                .committee()
+                .unwrap()
+                .voting_rights
+                .into_iter()
+                .collect(),
+        );
+        let epoch_state = epoch_state.with_committee(committee);
+
+        // The local committee goes in first, so that if the forked checkpoint is the last one in
+        // its epoch, the network's committee for the next epoch does not take its place.
+        store.insert_committee(epoch_state.committee().clone());
+        store.insert_checkpoint(checkpoint.clone());
+
+        let gas_coins = config
+            .genesis
+            .objects()
+            .iter()
+            .filter(|object| {
+                object.is_gas_coin()
+                    && matches!(object.owner, Owner::AddressOwner(owner)
+                        if keystore.accounts().any(|(address, _)| *address == owner))
+            })
+            .map(|object| (object.id(), object.clone()))
+            .collect();
+        store.update_objects(gas_coins, vec![]);
+
+        Self {
+            rng,
+            keystore,
+            genesis: config.genesis,
+            store,
+            checkpoint_builder: MockCheckpointBuilder::new(checkpoint),
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+            verifier_signing_config: VerifierSigningConfig::default(),
+            data_ingestion_path: None,
+        }
+    }
+}
+
 impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
     pub fn new_with_network_config_store(config: &NetworkConfig, rng: R, store: S) -> Self {
         let keystore = KeyStore::from_network_config(config);
@@ -284,7 +352,7 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
         self.execute_transaction(tx.into())
             .expect("advancing the epoch cannot fail");
 
-        let new_epoch_state = EpochState::new(self.store.get_system_state());
+        let new_epoch_state = self.epoch_state.next_epoch(self.store.get_system_state());
         let end_of_epoch_data = EndOfEpochData {
             next_epoch_committee: new_epoch_state.committee().voting_rights.clone(),
             next_epoch_protocol_version,
@@ -450,6 +518,159 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
     }
 }
 
+/// A copy of the state of a [`Simulacrum`] at some point, created by [`Simulacrum::snapshot`] and
+/// returned to by [`Simulacrum::restore`].
+pub struct SimulacrumSnapshot<S = InMemoryStore> {
+    store: S,
+    checkpoint_builder: MockCheckpointBuilder,
+    epoch_state: EpochState,
+}
+
+impl<R, S: store::SimulatorStore + Clone> Simulacrum<R, S> {
+    /// Take a snapshot of the chain state, which can later be returned to with
+    /// [`Simulacrum::restore`].
+    ///
+    /// ```
+    /// use simulacrum::Simulacrum;
+    /// use sui_types::base_types::SuiAddress;
+    /// use sui_types::gas_coin::MIST_PER_SUI;
+    ///
+    /// # fn main() {
+    /// let mut simulacrum = Simulacrum::new();
+    /// let snapshot = simulacrum.snapshot();
+    ///
+    /// let address = SuiAddress::generate(simulacrum.rng());
+    /// simulacrum.request_gas(address, MIST_PER_SUI).unwrap();
+    ///
+    /// // `address` no longer owns the coin it was sent.
+    /// simulacrum.restore(&snapshot);
+    /// assert!(simulacrum.store().owned_objects(address).next().is_none());
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> SimulacrumSnapshot<S> {
+        SimulacrumSnapshot {
+            store: self.store.clone(),
+            checkpoint_builder: self.checkpoint_builder.clone(),
+            epoch_state: self.epoch_state.clone(),
+        }
+    }
+
+    /// Return the chain state to what it was when `snapshot` was taken. Transactions executed and
+    /// checkpoints created since then are forgotten. The same snapshot can be restored any number
+    /// of times.
+    pub fn restore(&mut self, snapshot: &SimulacrumSnapshot<S>) {
+        self.store = snapshot.store.clone();
+        self.checkpoint_builder = snapshot.checkpoint_builder.clone();
+        self.epoch_state = snapshot.epoch_state.clone();
+    }
+
+    /// Create an independent copy of this Simulacrum, so that alternative sequences of
+    /// transactions can be explored from a common starting point.
+    ///
+    /// The fork starts with a copy of this Simulacrum's RNG, and does not write checkpoints to the
+    /// data ingestion path, if one was set.
+    pub fn fork(&self) -> Self
+    where
+        R: Clone,
+    {
+        Self {
+            rng: self.rng.clone(),
+            keystore: self.keystore.clone(),
+            genesis: self.genesis.clone(),
+            store: self.store.clone(),
+            checkpoint_builder: self.checkpoint_builder.clone(),
+            epoch_state: self.epoch_state.clone(),
+            deny_config: self.deny_config.clone(),
+            data_ingestion_path: None,
+            verifier_signing_config: self.verifier_signing_config.clone(),
+        }
+    }
+}
+
+/// The state of a [`Simulacrum`] that is written to disk by [`Simulacrum::save`].
+#[derive(Serialize)]
+struct SavedSimulacrum<'a, S> {
+    genesis: &'a genesis::Genesis,
+    keystore: &'a KeyStore,
+    store: &'a S,
+    epoch_start_state: &'a EpochStartSystemState,
+    next_consensus_round: u64,
+}
+
+#[derive(Deserialize)]
+struct LoadedSimulacrum<S> {
+    genesis: genesis::Genesis,
+    keystore: KeyStore,
+    store: S,
+    epoch_start_state: EpochStartSystemState,
+    next_consensus_round: u64,
+}
+
+impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
+    /// Write the chain state to `path`, so that it can be loaded again with [`Simulacrum::load`],
+    /// for example to reuse a chain state that is expensive to set up across test runs.
+    ///
+    /// All executed transactions must have been included in a checkpoint before saving.
+    pub fn save(&self, path: &Path) -> Result<()>
+    where
+        S: Serialize,
+    {
+        ensure!(
+            self.checkpoint_builder.size() == 0,
+            "Cannot save a Simulacrum with transactions that are not in a checkpoint yet"
+        );
+
+        let (epoch_start_state, next_consensus_round) = self.epoch_state.to_parts();
+        let bytes = bcs::to_bytes(&SavedSimulacrum {
+            genesis: &self.genesis,
+            keystore: &self.keystore,
+            store: &self.store,
+            epoch_start_state,
+            next_consensus_round,
+        })?;
+
+        std::fs::write(path, bytes)
+            .with_context(|| format!("Unable to save Simulacrum to {}", path.display()))
+    }
+
+    /// Load a chain state written to `path` by [`Simulacrum::save`], using `rng` as the source of
+    /// randomness.
+    ///
+    /// The transaction deny config, verifier signing config and data ingestion path are not saved,
+    /// and start out with their defaults.
+    pub fn load(path: &Path, rng: R) -> Result<Self>
+    where
+        S: DeserializeOwned,
+    {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Unable to load Simulacrum from {}", path.display()))?;
+        let LoadedSimulacrum {
+            genesis,
+            keystore,
+            store,
+            epoch_start_state,
+            next_consensus_round,
+        } = bcs::from_bytes(&bytes)
+            .with_context(|| format!("Unable to parse Simulacrum from {}", path.display()))?;
+
+        let highest_checkpoint = store
+            .get_highest_checkpint()
+            .context("Saved Simulacrum has no checkpoints")?;
+
+        Ok(Self {
+            rng,
+            keystore,
+            genesis,
+            store,
+            checkpoint_builder: MockCheckpointBuilder::new(highest_checkpoint),
+            epoch_state: EpochState::from_parts(epoch_start_state, next_consensus_round),
+            deny_config: TransactionDenyConfig::default(),
+            data_ingestion_path: None,
+            verifier_signing_config: VerifierSigningConfig::default(),
+        })
+    }
+}
+
 pub struct CommitteeWithKeys<'a> {
     keystore: &'a KeyStore,
     committee: &'a Committee,
@@ -653,10 +874,24 @@ mod tests {
     use rand::{rngs::StdRng, SeedableRng};
     use sui_types::{
         base_types::SuiAddress, effects::TransactionEffectsAPI, gas_coin::GasCoin,
+        sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait,
         transaction::TransactionDataAPI,
     };
 
+    use fastcrypto::hash::MultisetHash;
+    use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
+    use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+    use sui_snapshot::writer::StateSnapshotWriterV1;
+    use sui_storage::FileCompression;
+    use sui_types::{
+        accumulator::Accumulator,
+        digests::ChainIdentifier,
+        messages_checkpoint::ECMHLiveObjectSetDigest,
+        storage::{BackingPackageStore, ChildObjectResolver},
+    };
+
     use super::*;
+    use crate::store::snapshot_source::SnapshotForkSource;
 
     #[test]
     fn deterministic_genesis() {
@@ -758,4 +993,262 @@ mod tests {
         assert_eq!(&checkpoint.epoch_rolling_gas_cost_summary, gas_summary);
         assert_eq!(checkpoint.network_total_transactions, 2); // genesis + 1 txn
     }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut sim = Simulacrum::new();
+        sim.create_checkpoint();
+        let snapshot = sim.snapshot();
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let (tx, _) = sim.transfer_txn(recipient);
+        sim.execute_transaction(tx.clone()).unwrap();
+        sim.create_checkpoint();
+        assert!(sim.store().owned_objects(recipient).next().is_some());
+
+        // The transfer is forgotten, so it can be executed again.
+        sim.restore(&snapshot);
+        assert!(sim.store().owned_objects(recipient).next().is_none());
+        assert_eq!(
+            sim.store().get_highest_checkpint().unwrap().sequence_number,
+            1
+        );
+
+        sim.execute_transaction(tx).unwrap();
+        let checkpoint = sim.create_checkpoint();
+        assert_eq!(checkpoint.sequence_number, 2);
+        assert!(sim.store().owned_objects(recipient).next().is_some());
+    }
+
+    #[test]
+    fn fork() {
+        let mut sim = Simulacrum::new_with_rng(StdRng::from_seed([1; 32]));
+        let mut fork = sim.fork();
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let (tx, _) = sim.transfer_txn(recipient);
+        sim.execute_transaction(tx).unwrap();
+        sim.create_checkpoint();
+
+        fork.advance_clock(Duration::from_millis(1));
+        fork.advance_epoch(/* create_random_state */ false);
+
+        assert!(sim.store().owned_objects(recipient).next().is_some());
+        assert!(fork.store().owned_objects(recipient).next().is_none());
+        assert_eq!(sim.epoch_start_state().epoch(), 0);
+        assert_eq!(fork.epoch_start_state().epoch(), 1);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("simulacrum.bcs");
+
+        let mut sim = Simulacrum::new();
+        sim.advance_epoch(/* create_random_state */ false);
+        let recipient = SuiAddress::random_for_testing_only();
+        let (tx, _) = sim.transfer_txn(recipient);
+        sim.execute_transaction(tx).unwrap();
+        assert!(sim.save(&path).is_err());
+
+        sim.create_checkpoint();
+        sim.save(&path).unwrap();
+
+        let mut loaded: Simulacrum = Simulacrum::load(&path, OsRng).unwrap();
+        assert_eq!(
+            loaded.store().get_highest_checkpint(),
+            sim.store().get_highest_checkpint(),
+        );
+        assert_eq!(loaded.epoch_start_state().epoch(), 1);
+        assert!(loaded.store().owned_objects(recipient).next().is_some());
+
+        // The loaded chain can carry on where the saved one left off.
+        let (tx, _) = loaded.transfer_txn(recipient);
+        loaded.execute_transaction(tx).unwrap();
+        let checkpoint = loaded.create_checkpoint();
+        assert_eq!(checkpoint.sequence_number, 3);
+        assert_eq!(checkpoint.epoch, 1);
+        assert_eq!(
+            checkpoint.previous_digest,
+            Some(*sim.store().get_highest_checkpint().unwrap().digest()),
+        );
+    }
+
+    /// Reads the state of another Simulacrum, as a stand-in for a live network.
+    struct StoreSource {
+        store: InMemoryStore,
+        checkpoint: VerifiedCheckpoint,
+    }
+
+    impl ForkSource for StoreSource {
+        fn checkpoint(&self) -> &VerifiedCheckpoint {
+            &self.checkpoint
+        }
+
+        fn get_object(&self, id: &ObjectID) -> Result<Option<Object>> {
+            Ok(self.store.get_object(id).cloned())
+        }
+
+        fn get_object_at_version(
+            &self,
+            id: &ObjectID,
+            version: VersionNumber,
+        ) -> Result<Option<Object>> {
+            Ok(self.store.get_object_at_version(id, version).cloned())
+        }
+    }
+
+    #[test]
+    fn fork_from_source() {
+        let mut chain = Simulacrum::new_with_rng(StdRng::from_seed([1; 32]));
+        let address = SuiAddress::generate(chain.rng());
+        chain.request_gas(address, MIST_PER_SUI).unwrap();
+        let checkpoint = chain.create_checkpoint();
+        let coin = chain.store().owned_objects(address).next().unwrap();
+
+        let source = StoreSource {
+            store: chain.store.clone(),
+            checkpoint: checkpoint.clone(),
+        };
+        let mut fork = Simulacrum::new_forked(StdRng::from_seed([2; 32]), source);
+
+        // Objects are read from the source, and the fork's checkpoints follow on from its.
+        assert_eq!(
+            fork.store()
+                .get_object(&coin.id())
+                .map(|o| o.compute_object_reference()),
+            Some(coin.compute_object_reference()),
+        );
+
+        let recipient = SuiAddress::generate(fork.rng());
+        let effects = fork.request_gas(recipient, MIST_PER_SUI).unwrap();
+        assert!(effects.status().is_ok());
+        let fork_checkpoint = fork.create_checkpoint();
+        assert_eq!(
+            fork_checkpoint.sequence_number,
+            checkpoint.sequence_number + 1
+        );
+        assert_eq!(fork_checkpoint.previous_digest, Some(*checkpoint.digest()));
+
+        // The fork's committee carries over into the next epoch.
+        fork.advance_epoch(/* create_random_state */ false);
+        assert_eq!(fork.epoch_start_state().epoch(), checkpoint.epoch + 1);
+        fork.advance_clock(Duration::from_millis(1));
+        fork.create_checkpoint();
+
+        // The forked chain is unaffected by the fork.
+        assert!(chain.store().owned_objects(recipient).next().is_none());
+        assert_eq!(chain.epoch_start_state().epoch(), checkpoint.epoch);
+    }
+
+    /// A source that fails every read, like a fullnode that can't be reached.
+    struct FailingSource {
+        checkpoint: VerifiedCheckpoint,
+    }
+
+    impl ForkSource for FailingSource {
+        fn checkpoint(&self) -> &VerifiedCheckpoint {
+            &self.checkpoint
+        }
+
+        fn get_object(&self, _id: &ObjectID) -> Result<Option<Object>> {
+            Err(anyhow!("source unavailable"))
+        }
+
+        fn get_object_at_version(
+            &self,
+            _id: &ObjectID,
+            _version: VersionNumber,
+        ) -> Result<Option<Object>> {
+            Err(anyhow!("source unavailable"))
+        }
+    }
+
+    #[test]
+    fn fork_source_errors() {
+        let mut chain = Simulacrum::new_with_rng(StdRng::from_seed([1; 32]));
+        let checkpoint = chain.create_checkpoint();
+        let store = ForkedStore::new(Arc::new(FailingSource { checkpoint }));
+
+        // Errors are surfaced where the store can return them, and are not cached.
+        let id = ObjectID::random();
+        assert!(store.try_get_object(&id).is_err());
+        assert!(store.try_get_object(&id).is_err());
+        assert!(store
+            .read_child_object(&ObjectID::random(), &id, VersionNumber::MAX)
+            .is_err());
+        assert!(store.get_package_object(&id).is_err());
+
+        // Elsewhere, the object is treated as missing.
+        assert!(ForkedStore::get_object(&store, &id).is_none());
+    }
+
+    #[tokio::test]
+    async fn fork_from_snapshot() {
+        let mut chain = Simulacrum::new_with_rng(StdRng::from_seed([1; 32]));
+        let address = SuiAddress::generate(chain.rng());
+        chain.request_gas(address, MIST_PER_SUI).unwrap();
+        chain.create_checkpoint();
+        chain.advance_epoch(/* create_random_state */ false);
+        let checkpoint = chain.store().get_highest_checkpint().unwrap();
+        let coin = chain.store().owned_objects(address).next().unwrap();
+
+        // Write a snapshot of the chain's live objects at the end of its first epoch, the same way
+        // that nodes do.
+        let dir = tempfile::tempdir().unwrap();
+        let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&dir.path().join("db"), None));
+        for object in chain.store.live_objects() {
+            perpetual_db
+                .insert_object_test_only(object.clone())
+                .unwrap();
+        }
+
+        let store_config = |name: &str| ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(dir.path().join(name)),
+            ..Default::default()
+        };
+        StateSnapshotWriterV1::new(
+            &store_config("staging"),
+            &store_config("snapshots"),
+            FileCompression::Zstd,
+            NonZeroUsize::new(1).unwrap(),
+        )
+        .await
+        .unwrap()
+        .write(
+            checkpoint.epoch(),
+            perpetual_db,
+            ECMHLiveObjectSetDigest::from(Accumulator::default().digest()),
+            ChainIdentifier::from(*checkpoint.digest()),
+        )
+        .await
+        .unwrap();
+
+        let source = SnapshotForkSource::open(
+            &dir.path().join("snapshots"),
+            checkpoint.epoch(),
+            checkpoint.clone(),
+        )
+        .await
+        .unwrap();
+        let mut fork = Simulacrum::new_forked(StdRng::from_seed([2; 32]), source);
+
+        // Objects are read from the snapshot, and the fork's checkpoints follow on from its.
+        assert_eq!(
+            fork.store()
+                .get_object(&coin.id())
+                .map(|o| o.compute_object_reference()),
+            Some(coin.compute_object_reference()),
+        );
+        assert!(fork.store().get_object(&ObjectID::random()).is_none());
+
+        let recipient = SuiAddress::generate(fork.rng());
+        let effects = fork.request_gas(recipient, MIST_PER_SUI).unwrap();
+        assert!(effects.status().is_ok());
+        assert_eq!(
+            fork.create_checkpoint().sequence_number,
+            checkpoint.sequence_number + 1
+        );
+    }
 }
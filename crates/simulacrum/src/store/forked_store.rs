@@ -0,0 +1,443 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use sui_types::storage::PackageObject;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    committee::{Committee, EpochId},
+    digests::{ObjectDigest, TransactionDigest, TransactionEventsDigest},
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    error::{SuiError, SuiResult},
+    messages_checkpoint::{
+        CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
+        VerifiedCheckpoint,
+    },
+    object::{Object, Owner},
+    storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync},
+    transaction::VerifiedTransaction,
+};
+
+use tracing::{error, warn};
+
+use super::in_mem_store::InMemoryStore;
+use super::SimulatorStore;
+
+/// How many times a read from the fork source is attempted before its error is surfaced.
+const FORK_SOURCE_ATTEMPTS: usize = 3;
+
+/// How long to wait before retrying a failed read from the fork source, multiplied by the number
+/// of attempts so far.
+const FORK_SOURCE_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// The state of a network at a checkpoint, that a [`ForkedStore`] reads objects from.
+pub trait ForkSource: Send + Sync {
+    /// The checkpoint the network is forked at. The fork's own checkpoints follow on from it.
+    fn checkpoint(&self) -> &VerifiedCheckpoint;
+
+    /// The version of object `id` that was live as of the forked checkpoint, or `None` if there
+    /// was no such object, or it was wrapped.
+    fn get_object(&self, id: &ObjectID) -> anyhow::Result<Option<Object>>;
+
+    /// Object `id` at `version`, or `None` if the source does not know of that version.
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> anyhow::Result<Option<Object>>;
+}
+
+/// A store for a fork of a live network, that reads objects from a [`ForkSource`] the first time
+/// they are needed, and keeps everything written after the fork in memory.
+///
+/// Only the state of objects is forked: transactions, effects and events from before the fork are
+/// not available, and [`SimulatorStore::owned_objects`] only finds objects that were written after
+/// the fork.
+#[derive(Clone)]
+pub struct ForkedStore {
+    source: Arc<dyn ForkSource>,
+
+    /// Checkpoints, transactions and objects created since the fork.
+    local: InMemoryStore,
+    /// Objects deleted or wrapped since the fork, which must not be read from the source again.
+    removed: HashSet<ObjectID>,
+
+    /// Objects read from the source. The source does not change, so this is shared between clones
+    /// of the store.
+    fetched: Arc<RwLock<FetchedObjects>>,
+}
+
+#[derive(Default)]
+struct FetchedObjects {
+    live: HashMap<ObjectID, Option<Object>>,
+    versions: HashMap<(ObjectID, SequenceNumber), Option<Object>>,
+}
+
+impl ForkedStore {
+    pub fn new(source: Arc<dyn ForkSource>) -> Self {
+        Self {
+            source,
+            local: InMemoryStore::default(),
+            removed: HashSet::new(),
+            fetched: Arc::default(),
+        }
+    }
+
+    /// The checkpoint the network was forked at.
+    pub fn fork_checkpoint(&self) -> &VerifiedCheckpoint {
+        self.source.checkpoint()
+    }
+
+    /// The live version of object `id`, reading it from the source if it has not been read or
+    /// written since the fork. Reads from the source are retried, and fail with
+    /// [`SuiError::Storage`] if the source keeps failing.
+    pub fn try_get_object(&self, id: &ObjectID) -> SuiResult<Option<Object>> {
+        if let Some(object) = self.local.get_object(id) {
+            return Ok(Some(object.clone()));
+        }
+
+        if self.removed.contains(id) {
+            return Ok(None);
+        }
+
+        if let Some(object) = self.fetched.read().unwrap().live.get(id) {
+            return Ok(object.clone());
+        }
+
+        let object = with_retries(|| self.source.get_object(id)).map_err(|e| {
+            SuiError::Storage(format!(
+                "Failed to read object {id} from fork source: {e:#}"
+            ))
+        })?;
+        self.fetched
+            .write()
+            .unwrap()
+            .live
+            .insert(*id, object.clone());
+        Ok(object)
+    }
+
+    /// Object `id` at `version`, reading it from the source if it was not written since the
+    /// fork. Fails like [`Self::try_get_object`].
+    pub fn try_get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        if let Some(object) = self.local.get_object_at_version(id, version) {
+            return Ok(Some(object.clone()));
+        }
+
+        {
+            let fetched = self.fetched.read().unwrap();
+            if let Some(Some(object)) = fetched.live.get(id) {
+                if object.version() == version {
+                    return Ok(Some(object.clone()));
+                }
+            }
+
+            if let Some(object) = fetched.versions.get(&(*id, version)) {
+                return Ok(object.clone());
+            }
+        }
+
+        let object =
+            with_retries(|| self.source.get_object_at_version(id, version)).map_err(|e| {
+                SuiError::Storage(format!(
+                    "Failed to read object {id} at version {version} from fork source: {e:#}"
+                ))
+            })?;
+        self.fetched
+            .write()
+            .unwrap()
+            .versions
+            .insert((*id, version), object.clone());
+        Ok(object)
+    }
+
+    /// Like [`Self::try_get_object`], for callers that can not handle errors, such as
+    /// [`ObjectStore`]. Errors are logged, and the object is treated as missing.
+    pub fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        self.try_get_object(id).unwrap_or_else(|e| {
+            error!("{e}");
+            None
+        })
+    }
+
+    /// Like [`Self::try_get_object_at_version`], for callers that can not handle errors. Errors
+    /// are logged, and the object is treated as missing.
+    pub fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.try_get_object_at_version(id, version)
+            .unwrap_or_else(|e| {
+                error!("{e}");
+                None
+            })
+    }
+
+    pub fn update_objects(
+        &mut self,
+        written_objects: BTreeMap<ObjectID, Object>,
+        deleted_objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    ) {
+        self.removed
+            .extend(deleted_objects.iter().map(|(id, _, _)| *id));
+        for id in written_objects.keys() {
+            self.removed.remove(id);
+        }
+        self.local.update_objects(written_objects, deleted_objects)
+    }
+}
+
+/// Run `read` against the fork source, retrying it a few times if it fails, as sources like
+/// [`super::rpc_source::RpcForkSource`] can fail transiently.
+fn with_retries<T>(mut read: impl FnMut() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    let mut attempt = 1;
+    loop {
+        match read() {
+            Ok(result) => return Ok(result),
+            Err(e) if attempt < FORK_SOURCE_ATTEMPTS => {
+                warn!("Read from fork source failed, retrying (attempt {attempt}): {e:#}");
+                std::thread::sleep(FORK_SOURCE_RETRY_DELAY * attempt as u32);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+impl BackingPackageStore for ForkedStore {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<PackageObject>> {
+        let Some(object) = self.try_get_object(package_id)? else {
+            return Ok(None);
+        };
+
+        if !object.is_package() {
+            return Err(SuiError::BadObjectType {
+                error: format!("Package expected, Move object found: {package_id}"),
+            });
+        }
+
+        Ok(Some(PackageObject::new(object)))
+    }
+}
+
+impl ChildObjectResolver for ForkedStore {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        let child_object = match self.try_get_object(child)? {
+            None => return Ok(None),
+            Some(obj) => obj,
+        };
+
+        let parent = *parent;
+        if child_object.owner != Owner::ObjectOwner(parent.into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: parent,
+                actual_owner: child_object.owner.clone(),
+            });
+        }
+
+        if child_object.version() > child_version_upper_bound {
+            return Err(SuiError::UnsupportedFeatureError {
+                error: "TODO ForkedStore::read_child_object does not yet support bounded reads"
+                    .to_owned(),
+            });
+        }
+
+        Ok(Some(child_object))
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        _epoch_id: EpochId,
+        // TODO: Delete this parameter once table migration is complete.
+        _use_object_per_epoch_marker_table_v2: bool,
+    ) -> SuiResult<Option<Object>> {
+        let recv_object = match self.try_get_object(receiving_object_id)? {
+            None => return Ok(None),
+            Some(obj) => obj,
+        };
+        if recv_object.owner != Owner::AddressOwner((*owner).into()) {
+            return Ok(None);
+        }
+
+        if recv_object.version() != receive_object_at_version {
+            return Ok(None);
+        }
+        Ok(Some(recv_object))
+    }
+}
+
+impl ObjectStore for ForkedStore {
+    fn get_object(&self, object_id: &ObjectID) -> Option<Object> {
+        self.get_object(object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: sui_types::base_types::VersionNumber,
+    ) -> Option<Object> {
+        self.get_object_at_version(object_id, version)
+    }
+}
+
+impl ParentSync for ForkedStore {
+    fn get_latest_parent_entry_ref_deprecated(
+        &self,
+        _object_id: ObjectID,
+    ) -> Option<sui_types::base_types::ObjectRef> {
+        panic!("Never called in newer protocol versions")
+    }
+}
+
+impl SimulatorStore for ForkedStore {
+    fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Option<VerifiedCheckpoint> {
+        self.local
+            .get_checkpoint_by_sequence_number(sequence_number)
+            .cloned()
+    }
+
+    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<VerifiedCheckpoint> {
+        self.local.get_checkpoint_by_digest(digest).cloned()
+    }
+
+    fn get_highest_checkpint(&self) -> Option<VerifiedCheckpoint> {
+        self.local.get_highest_checkpint().cloned()
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Option<CheckpointContents> {
+        self.local.get_checkpoint_contents(digest).cloned()
+    }
+
+    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<Committee> {
+        self.local.get_committee_by_epoch(epoch).cloned()
+    }
+
+    fn get_transaction(&self, digest: &TransactionDigest) -> Option<VerifiedTransaction> {
+        self.local.get_transaction(digest).cloned()
+    }
+
+    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<TransactionEffects> {
+        self.local.get_transaction_effects(digest).cloned()
+    }
+
+    fn get_transaction_events(
+        &self,
+        digest: &TransactionEventsDigest,
+    ) -> Option<TransactionEvents> {
+        self.local.get_transaction_events(digest).cloned()
+    }
+
+    fn get_transaction_events_by_tx_digest(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Option<TransactionEvents> {
+        SimulatorStore::get_transaction_events_by_tx_digest(&self.local, tx_digest)
+    }
+
+    fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        self.get_object(id)
+    }
+
+    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.get_object_at_version(id, version)
+    }
+
+    fn try_get_object(&self, id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.try_get_object(id)
+    }
+
+    fn try_get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        self.try_get_object_at_version(id, version)
+    }
+
+    fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
+        sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
+    }
+
+    fn get_clock(&self) -> sui_types::clock::Clock {
+        self.get_object(&sui_types::SUI_CLOCK_OBJECT_ID)
+            .expect("clock should exist")
+            .to_rust()
+            .expect("clock object should deserialize")
+    }
+
+    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = Object> + '_> {
+        Box::new(self.local.owned_objects(owner).cloned())
+    }
+
+    fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
+        self.local.insert_checkpoint(checkpoint)
+    }
+
+    fn insert_checkpoint_contents(&mut self, contents: CheckpointContents) {
+        self.local.insert_checkpoint_contents(contents)
+    }
+
+    fn insert_committee(&mut self, committee: Committee) {
+        self.local.insert_committee(committee)
+    }
+
+    fn insert_executed_transaction(
+        &mut self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+    ) {
+        // Wrapped objects are removed too, so that they are not read from the source again.
+        let mut deleted_objects = effects.deleted();
+        deleted_objects.extend(effects.wrapped());
+        let tx_digest = *effects.transaction_digest();
+        self.insert_transaction(transaction);
+        self.insert_transaction_effects(effects);
+        self.insert_events(&tx_digest, events);
+        self.update_objects(written_objects, deleted_objects);
+    }
+
+    fn insert_transaction(&mut self, transaction: VerifiedTransaction) {
+        self.local.insert_transaction(transaction)
+    }
+
+    fn insert_transaction_effects(&mut self, effects: TransactionEffects) {
+        self.local.insert_transaction_effects(effects)
+    }
+
+    fn insert_events(&mut self, tx_digest: &TransactionDigest, events: TransactionEvents) {
+        self.local.insert_events(tx_digest, events)
+    }
+
+    fn update_objects(
+        &mut self,
+        written_objects: BTreeMap<ObjectID, Object>,
+        deleted_objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    ) {
+        self.update_objects(written_objects, deleted_objects)
+    }
+
+    fn backing_store(&self) -> &dyn sui_types::storage::BackingStore {
+        self
+    }
+}
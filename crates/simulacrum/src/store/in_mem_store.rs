@@ -1,9 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::encoding::EncodeDecodeBase64;
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{BTreeMap, HashMap};
 use sui_config::genesis;
 use sui_types::storage::{get_module, load_package_object_from_object_store, PackageObject};
@@ -16,16 +18,16 @@ use sui_types::{
     error::SuiError,
     messages_checkpoint::{
         CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
-        VerifiedCheckpoint,
+        TrustedCheckpoint, VerifiedCheckpoint,
     },
     object::{Object, Owner},
     storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync},
-    transaction::VerifiedTransaction,
+    transaction::{TrustedTransaction, VerifiedTransaction},
 };
 
 use super::SimulatorStore;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct InMemoryStore {
     // Checkpoint data
     checkpoints: BTreeMap<CheckpointSequenceNumber, VerifiedCheckpoint>,
@@ -40,7 +42,7 @@ pub struct InMemoryStore {
     events_tx_digest_index: HashMap<TransactionDigest, TransactionEventsDigest>,
 
     // Committee data
-    epoch_to_committee: Vec<Committee>,
+    epoch_to_committee: BTreeMap<EpochId, Committee>,
 
     // Object data
     live_objects: HashMap<ObjectID, SequenceNumber>,
@@ -84,7 +86,7 @@ impl InMemoryStore {
     }
 
     pub fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<&Committee> {
-        self.epoch_to_committee.get(epoch as usize)
+        self.epoch_to_committee.get(&epoch)
     }
     pub fn get_transaction(&self, digest: &TransactionDigest) -> Option<&VerifiedTransaction> {
         self.transactions.get(digest)
@@ -126,13 +128,17 @@ impl InMemoryStore {
             .expect("clock object should deserialize")
     }
 
-    pub fn owned_objects(&self, owner: SuiAddress) -> impl Iterator<Item = &Object> {
+    /// The live version of every object in the store.
+    pub fn live_objects(&self) -> impl Iterator<Item = &Object> {
         self.live_objects
             .iter()
             .flat_map(|(id, version)| self.get_object_at_version(id, *version))
-            .filter(
-                move |object| matches!(object.owner, Owner::AddressOwner(addr) if addr == owner),
-            )
+    }
+
+    pub fn owned_objects(&self, owner: SuiAddress) -> impl Iterator<Item = &Object> {
+        self.live_objects().filter(
+            move |object| matches!(object.owner, Owner::AddressOwner(addr) if addr == owner),
+        )
     }
 }
 
@@ -161,16 +167,21 @@ impl InMemoryStore {
     }
 
     pub fn insert_committee(&mut self, committee: Committee) {
-        let epoch = committee.epoch as usize;
+        let epoch = committee.epoch;
 
-        if self.epoch_to_committee.get(epoch).is_some() {
+        if self.epoch_to_committee.contains_key(&epoch) {
             return;
         }
 
-        if self.epoch_to_committee.len() == epoch {
-            self.epoch_to_committee.push(committee);
-        } else {
-            panic!("committee was inserted into EpochCommitteeMap out of order");
+        // The first committee can be for any epoch, so that stores forked from a live network can
+        // start in the middle of its history.
+        match self.epoch_to_committee.last_key_value() {
+            Some((last_epoch, _)) if last_epoch + 1 != epoch => {
+                panic!("committee was inserted into EpochCommitteeMap out of order");
+            }
+            _ => {
+                self.epoch_to_committee.insert(epoch, committee);
+            }
         }
     }
 
@@ -223,6 +234,126 @@ impl InMemoryStore {
     }
 }
 
+/// The contents of an [`InMemoryStore`] when it is serialized. Lookup tables that can be derived
+/// from other fields are left out.
+#[derive(Serialize)]
+struct SerializedStore<'a> {
+    checkpoints: Vec<&'a TrustedCheckpoint>,
+    checkpoint_contents: Vec<&'a CheckpointContents>,
+    transactions: Vec<&'a TrustedTransaction>,
+    effects: Vec<&'a TransactionEffects>,
+    events: Vec<(&'a TransactionDigest, &'a TransactionEvents)>,
+    epoch_to_committee: Vec<&'a Committee>,
+    live_objects: Vec<(&'a ObjectID, &'a SequenceNumber)>,
+    objects: Vec<&'a Object>,
+}
+
+#[derive(Deserialize)]
+struct DeserializedStore {
+    checkpoints: Vec<TrustedCheckpoint>,
+    checkpoint_contents: Vec<CheckpointContents>,
+    transactions: Vec<TrustedTransaction>,
+    effects: Vec<TransactionEffects>,
+    events: Vec<(TransactionDigest, TransactionEvents)>,
+    epoch_to_committee: Vec<Committee>,
+    live_objects: Vec<(ObjectID, SequenceNumber)>,
+    objects: Vec<Object>,
+}
+
+impl Serialize for InMemoryStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedStore {
+            checkpoints: self
+                .checkpoints
+                .values()
+                .map(|checkpoint| checkpoint.serializable_ref())
+                .collect(),
+            checkpoint_contents: self.checkpoint_contents.values().collect(),
+            transactions: self
+                .transactions
+                .values()
+                .map(|transaction| transaction.serializable_ref())
+                .collect(),
+            effects: self.effects.values().collect(),
+            events: self
+                .events_tx_digest_index
+                .iter()
+                .filter_map(|(tx_digest, events_digest)| {
+                    Some((tx_digest, self.events.get(events_digest)?))
+                })
+                .collect(),
+            epoch_to_committee: self.epoch_to_committee.values().collect(),
+            live_objects: self.live_objects.iter().collect(),
+            objects: self
+                .objects
+                .values()
+                .flat_map(|versions| versions.values())
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InMemoryStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let DeserializedStore {
+            checkpoints,
+            checkpoint_contents,
+            transactions,
+            effects,
+            events,
+            epoch_to_committee,
+            live_objects,
+            objects,
+        } = DeserializedStore::deserialize(deserializer)?;
+
+        let mut store = Self {
+            epoch_to_committee: epoch_to_committee
+                .into_iter()
+                .map(|committee| (committee.epoch, committee))
+                .collect(),
+            live_objects: live_objects.into_iter().collect(),
+            ..Default::default()
+        };
+
+        for checkpoint in checkpoints {
+            let checkpoint = VerifiedCheckpoint::from(checkpoint);
+            store
+                .checkpoint_digest_to_sequence_number
+                .insert(*checkpoint.digest(), *checkpoint.sequence_number());
+            store
+                .checkpoints
+                .insert(*checkpoint.sequence_number(), checkpoint);
+        }
+
+        for contents in checkpoint_contents {
+            store.insert_checkpoint_contents(contents);
+        }
+
+        for transaction in transactions {
+            store.insert_transaction(transaction.into());
+        }
+
+        for effects in effects {
+            store.insert_transaction_effects(effects);
+        }
+
+        for (tx_digest, events) in events {
+            store.insert_events(&tx_digest, events);
+        }
+
+        for object in objects {
+            store
+                .objects
+                .entry(object.id())
+                .or_default()
+                .insert(object.version(), object);
+        }
+
+        Ok(store)
+    }
+}
+
 impl BackingPackageStore for InMemoryStore {
     fn get_package_object(
         &self,
@@ -374,6 +505,84 @@ impl KeyStore {
     }
 }
 
+impl Clone for KeyStore {
+    fn clone(&self) -> Self {
+        use fastcrypto::traits::KeyPair;
+
+        Self {
+            validator_keys: self
+                .validator_keys
+                .iter()
+                .map(|(name, key)| (*name, key.copy()))
+                .collect(),
+            account_keys: self
+                .account_keys
+                .iter()
+                .map(|(address, key)| (*address, key.copy()))
+                .collect(),
+        }
+    }
+}
+
+/// Keys are serialized as Base64, and their names and addresses derived from them again when they
+/// are deserialized.
+#[derive(Serialize, Deserialize)]
+struct SerializedKeyStore {
+    validator_keys: Vec<String>,
+    account_keys: Vec<String>,
+}
+
+impl Serialize for KeyStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedKeyStore {
+            validator_keys: self
+                .validator_keys
+                .values()
+                .map(|key| key.encode_base64())
+                .collect(),
+            account_keys: self
+                .account_keys
+                .values()
+                .map(|key| key.encode_base64())
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use fastcrypto::traits::KeyPair;
+        use serde::de::Error;
+
+        let SerializedKeyStore {
+            validator_keys,
+            account_keys,
+        } = SerializedKeyStore::deserialize(deserializer)?;
+
+        let validator_keys = validator_keys
+            .iter()
+            .map(|key| {
+                let key = AuthorityKeyPair::decode_base64(key).map_err(D::Error::custom)?;
+                Ok((key.public().into(), key))
+            })
+            .collect::<Result<_, D::Error>>()?;
+
+        let account_keys = account_keys
+            .iter()
+            .map(|key| {
+                let key = AccountKeyPair::decode_base64(key).map_err(D::Error::custom)?;
+                Ok((key.public().into(), key))
+            })
+            .collect::<Result<_, D::Error>>()?;
+
+        Ok(Self {
+            validator_keys,
+            account_keys,
+        })
+    }
+}
+
 impl SimulatorStore for InMemoryStore {
     fn get_checkpoint_by_sequence_number(
         &self,
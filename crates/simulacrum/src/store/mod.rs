@@ -23,7 +23,10 @@ use sui_types::{
     storage::{BackingStore, ChildObjectResolver, ParentSync},
     transaction::{InputObjectKind, VerifiedTransaction},
 };
+pub mod forked_store;
 pub mod in_mem_store;
+pub mod rpc_source;
+pub mod snapshot_source;
 
 pub trait SimulatorStore:
     sui_types::storage::BackingPackageStore
@@ -86,6 +89,20 @@ pub trait SimulatorStore:
 
     fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object>;
 
+    /// Like [`SimulatorStore::get_object`], for stores that can fail to read objects.
+    fn try_get_object(&self, id: &ObjectID) -> SuiResult<Option<Object>> {
+        Ok(SimulatorStore::get_object(self, id))
+    }
+
+    /// Like [`SimulatorStore::get_object_at_version`], for stores that can fail to read objects.
+    fn try_get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        Ok(SimulatorStore::get_object_at_version(self, id, version))
+    }
+
     fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState;
 
     fn get_clock(&self) -> sui_types::clock::Clock;
@@ -133,16 +150,12 @@ pub trait SimulatorStore:
         let mut input_objects = Vec::new();
         for kind in input_object_kinds {
             let obj = match kind {
-                InputObjectKind::MovePackage(id) => {
-                    crate::store::SimulatorStore::get_object(self, id)
-                }
+                InputObjectKind::MovePackage(id) => self.try_get_object(id)?,
                 InputObjectKind::ImmOrOwnedMoveObject(objref) => {
-                    self.get_object_by_key(&objref.0, objref.1)
+                    self.try_get_object_at_version(&objref.0, objref.1)?
                 }
 
-                InputObjectKind::SharedMoveObject { id, .. } => {
-                    crate::store::SimulatorStore::get_object(self, id)
-                }
+                InputObjectKind::SharedMoveObject { id, .. } => self.try_get_object(id)?,
             };
 
             input_objects.push(ObjectReadResult::new(
@@ -154,7 +167,7 @@ pub trait SimulatorStore:
         let mut receiving_objects = Vec::new();
         for objref in receiving_object_refs {
             // no need for marker table check in simulacrum
-            let Some(obj) = crate::store::SimulatorStore::get_object(self, &objref.0) else {
+            let Some(obj) = self.try_get_object(&objref.0)? else {
                 return Err(UserInputError::ObjectNotFound {
                     object_id: objref.0,
                     version: Some(objref.1),
@@ -0,0 +1,166 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::future::Future;
+
+use anyhow::{Context, Result};
+use sui_rpc_api::Client;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    effects::TransactionEffectsAPI,
+    messages_checkpoint::{CheckpointSequenceNumber, VerifiedCheckpoint},
+    object::Object,
+};
+use tokio::runtime::Runtime;
+
+use super::forked_store::ForkSource;
+
+/// A [`ForkSource`] that reads the state of a network from a fullnode's gRPC API.
+///
+/// Fullnodes only serve the latest version of objects, so to find the version that was live at
+/// the forked checkpoint, objects modified since then are walked back through the transactions
+/// that modified them. Objects that were deleted after the forked checkpoint can not be found this
+/// way, and are treated as if they did not exist.
+///
+/// Walking an object back costs two requests for every version written since the forked
+/// checkpoint: one for the effects of the transaction that wrote it, and one for the version it
+/// was modified from. Shared objects that are written often, like the clock, which is written in
+/// every checkpoint, can take thousands of requests to read when forking far behind the latest
+/// checkpoint.
+pub struct RpcForkSource {
+    client: Client,
+    checkpoint: VerifiedCheckpoint,
+
+    /// Runs requests to the fullnode, which are made from synchronous store methods, that might
+    /// themselves be called from inside another runtime.
+    runtime: Option<Runtime>,
+}
+
+impl RpcForkSource {
+    /// Fork the network that the fullnode at `url` belongs to, at `checkpoint`, or its latest
+    /// checkpoint if none is given.
+    pub async fn new(url: &str, checkpoint: Option<CheckpointSequenceNumber>) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("rpc-fork-source")
+            .enable_all()
+            .build()?;
+
+        let client = {
+            let _guard = runtime.enter();
+            Client::new(url)?
+        };
+
+        let summary = {
+            let client = client.clone();
+            runtime
+                .spawn(async move {
+                    match checkpoint {
+                        Some(sequence_number) => {
+                            client.get_checkpoint_summary(sequence_number).await
+                        }
+                        None => client.get_latest_checkpoint().await,
+                    }
+                })
+                .await?
+                .context("Failed to fetch the checkpoint to fork at")?
+        };
+
+        Ok(Self {
+            client,
+            checkpoint: VerifiedCheckpoint::new_unchecked(summary),
+            runtime: Some(runtime),
+        })
+    }
+
+    /// Run `request` against the fullnode, blocking until it completes.
+    fn block_on<T, F>(&self, request: impl FnOnce(Client) -> F) -> T
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        let runtime = self
+            .runtime
+            .as_ref()
+            .expect("runtime is only taken on drop");
+        let handle = runtime.spawn(request(self.client.clone()));
+        futures::executor::block_on(handle).expect("fork source request panicked")
+    }
+
+    /// Fetch `id` at `version`, or its latest version if none is given.
+    fn fetch_object(
+        &self,
+        id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<Option<Object>> {
+        let result = self.block_on(|client| async move {
+            match version {
+                Some(version) => client.get_object_with_version(id, version).await,
+                None => client.get_object(id).await,
+            }
+        });
+
+        match result {
+            Ok(object) => Ok(Some(object)),
+            Err(status) if status.code() == tonic::Code::NotFound => Ok(None),
+            Err(status) => Err(status).with_context(|| format!("Failed to fetch object {id}")),
+        }
+    }
+}
+
+impl ForkSource for RpcForkSource {
+    fn checkpoint(&self) -> &VerifiedCheckpoint {
+        &self.checkpoint
+    }
+
+    fn get_object(&self, id: &ObjectID) -> Result<Option<Object>> {
+        let Some(mut object) = self.fetch_object(*id, None)? else {
+            return Ok(None);
+        };
+
+        loop {
+            let digest = object.previous_transaction;
+            let (effects, checkpoint) = self
+                .block_on(|client| async move { client.get_transaction_effects(digest).await })
+                .with_context(|| format!("Failed to fetch effects of transaction {digest}"))?;
+            let checkpoint =
+                checkpoint.with_context(|| format!("Transaction {digest} is not checkpointed"))?;
+
+            if checkpoint <= *self.checkpoint.sequence_number() {
+                return Ok(Some(object));
+            }
+
+            // The object was written after the fork, so look for the version it was modified
+            // from. If it was not modified, it was created or unwrapped after the fork, and was
+            // not live then.
+            let Some((_, version)) = effects
+                .modified_at_versions()
+                .into_iter()
+                .find(|(modified, _)| modified == id)
+            else {
+                return Ok(None);
+            };
+
+            object = self.fetch_object(*id, Some(version))?.with_context(|| {
+                format!("Object {id} at version {version} is no longer available")
+            })?;
+        }
+    }
+
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>> {
+        self.fetch_object(*id, Some(version))
+    }
+}
+
+impl Drop for RpcForkSource {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks, which is not allowed inside another runtime.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use anyhow::{ensure, Result};
+use indicatif::MultiProgress;
+use sui_snapshot::reader::StateSnapshotReaderV1;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    messages_checkpoint::VerifiedCheckpoint,
+    object::Object,
+};
+
+use super::forked_store::ForkSource;
+
+/// A [`ForkSource`] that reads the state of a network from a formal snapshot in a local directory,
+/// forking it at the end of the snapshot's epoch.
+///
+/// Only full snapshots are supported, not delta snapshots, and only the live version of each
+/// object is available.
+pub struct SnapshotForkSource {
+    reader: StateSnapshotReaderV1,
+    checkpoint: VerifiedCheckpoint,
+
+    /// The smallest and largest object IDs in each partition of the snapshot, by bucket and part
+    /// number, to narrow down the partitions that an object could be in.
+    partitions: Vec<(u32, u32, ObjectID, ObjectID)>,
+}
+
+impl SnapshotForkSource {
+    /// Open the snapshot for `epoch` in `snapshot_dir`, a directory laid out like a snapshot
+    /// store. Snapshots do not include checkpoints, so the last checkpoint of `epoch` has to be
+    /// provided as `checkpoint`.
+    pub async fn open(
+        snapshot_dir: &Path,
+        epoch: u64,
+        checkpoint: VerifiedCheckpoint,
+    ) -> Result<Self> {
+        ensure!(
+            checkpoint.epoch() == epoch && checkpoint.end_of_epoch_data.is_some(),
+            "Checkpoint {} is not the last checkpoint of epoch {epoch}",
+            checkpoint.sequence_number(),
+        );
+
+        let concurrency = NonZeroUsize::new(num_cpus::get()).unwrap();
+        let reader = StateSnapshotReaderV1::new_from_dir(
+            epoch,
+            snapshot_dir,
+            concurrency,
+            MultiProgress::new(),
+        )
+        .await?;
+        ensure!(
+            reader.base_epoch().is_none(),
+            "Snapshot for epoch {epoch} is a delta snapshot, which can not be forked from",
+        );
+
+        let mut partitions = vec![];
+        for (bucket_num, part_num) in reader.partitions() {
+            let mut ids = reader.ref_iter(bucket_num, part_num)?.map(|(id, _, _)| id);
+            let Some(first) = ids.next() else {
+                continue;
+            };
+
+            let (min, max) = ids.fold((first, first), |(min, max), id| (min.min(id), max.max(id)));
+            partitions.push((bucket_num, part_num, min, max));
+        }
+
+        Ok(Self {
+            reader,
+            checkpoint,
+            partitions,
+        })
+    }
+}
+
+impl ForkSource for SnapshotForkSource {
+    fn checkpoint(&self) -> &VerifiedCheckpoint {
+        &self.checkpoint
+    }
+
+    fn get_object(&self, id: &ObjectID) -> Result<Option<Object>> {
+        let candidates = self
+            .partitions
+            .iter()
+            .filter(|(_, _, min, max)| min <= id && id <= max);
+
+        for (bucket_num, part_num, _, _) in candidates {
+            // Reference files are much smaller than object files, so check them first.
+            if !self
+                .reader
+                .ref_iter(*bucket_num, *part_num)?
+                .any(|(ref_id, _, _)| ref_id == *id)
+            {
+                continue;
+            }
+
+            return Ok(self
+                .reader
+                .object_iter(*bucket_num, *part_num)?
+                .find(|object| object.object_id() == *id)
+                .and_then(|object| object.to_normal()));
+        }
+
+        Ok(None)
+    }
+
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>> {
+        Ok(self
+            .get_object(id)?
+            .filter(|object| object.version() == version))
+    }
+}
@@ -44,7 +44,9 @@ use crate::tables::{
     MovePackageEntry, ObjectEntry, ObjectStatus, OwnerType, TransactionEntry,
     TransactionObjectEntry, WrappedObjectEntry,
 };
+use crate::writers::arrow_writer::ArrowWriter;
 use crate::writers::csv_writer::CSVWriter;
+use crate::writers::json_writer::JsonWriter;
 use crate::writers::parquet_writer::ParquetWriter;
 use crate::writers::AnalyticsWriter;
 use gcp_bigquery_client::model::query_response::ResultSet;
@@ -106,7 +108,7 @@ pub struct AnalyticsIndexerConfig {
     // Remote object store path prefix to use while writing
     #[clap(long, default_value = None, global = true)]
     pub remote_store_path_prefix: Option<Path>,
-    // File format to store data in i.e. csv, parquet, etc
+    // File format to store data in i.e. csv, parquet, arrow (IPC file), jsonl, etc
     #[clap(long, value_enum, default_value = "csv", global = true)]
     pub file_format: FileFormat,
     // Type of data to write i.e. checkpoint, object, transaction, etc
@@ -296,6 +298,8 @@ impl MaxCheckpointReader for NoOpCheckpointReader {
 pub enum FileFormat {
     CSV = 0,
     PARQUET = 1,
+    ARROW = 2,
+    JSONL = 3,
 }
 
 impl FileFormat {
@@ -303,6 +307,8 @@ impl FileFormat {
         match self {
             FileFormat::CSV => "csv",
             FileFormat::PARQUET => "parquet",
+            FileFormat::ARROW => "arrow",
+            FileFormat::JSONL => "jsonl",
         }
     }
 }
@@ -880,6 +886,16 @@ pub fn make_writer<S: Serialize + ParquetSchema>(
             file_type,
             starting_checkpoint_seq_num,
         )?),
+        FileFormat::ARROW => Box::new(ArrowWriter::new(
+            &config.checkpoint_dir,
+            file_type,
+            starting_checkpoint_seq_num,
+        )?),
+        FileFormat::JSONL => Box::new(JsonWriter::new(
+            &config.checkpoint_dir,
+            file_type,
+            starting_checkpoint_seq_num,
+        )?),
     })
 }
 
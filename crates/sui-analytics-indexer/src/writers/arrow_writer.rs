@@ -0,0 +1,100 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::writers::record_batch;
+use crate::{AnalyticsWriter, FileFormat, FileType};
+use crate::{ParquetSchema, ParquetValue};
+use anyhow::{anyhow, Result};
+use arrow::ipc::writer::FileWriter;
+use mysocial_types::base_types::EpochId;
+use serde::Serialize;
+use std::fs::File;
+use std::fs::{create_dir_all, remove_file};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use sui_storage::object_store::util::path_to_filesystem;
+
+// Save table entries to arrow IPC (feather v2) files.
+pub(crate) struct ArrowWriter {
+    root_dir_path: PathBuf,
+    file_type: FileType,
+    epoch: EpochId,
+    checkpoint_range: Range<u64>,
+    data: Vec<Vec<ParquetValue>>,
+}
+
+impl ArrowWriter {
+    pub(crate) fn new(
+        root_dir_path: &Path,
+        file_type: FileType,
+        start_checkpoint_seq_num: u64,
+    ) -> Result<Self> {
+        let checkpoint_range = start_checkpoint_seq_num..u64::MAX;
+        Ok(Self {
+            root_dir_path: root_dir_path.to_path_buf(),
+            file_type,
+            epoch: 0,
+            checkpoint_range,
+            data: vec![],
+        })
+    }
+
+    fn file(&self) -> Result<File> {
+        let file_path = path_to_filesystem(
+            self.root_dir_path.clone(),
+            &self
+                .file_type
+                .file_path(FileFormat::ARROW, self.epoch, self.checkpoint_range.clone()),
+        )?;
+        create_dir_all(file_path.parent().ok_or(anyhow!("Bad directory path"))?)?;
+        if file_path.exists() {
+            remove_file(&file_path)?;
+        }
+        Ok(File::create(&file_path)?)
+    }
+}
+
+impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ArrowWriter {
+    fn file_format(&self) -> Result<FileFormat> {
+        Ok(FileFormat::ARROW)
+    }
+
+    fn write(&mut self, rows: &[S]) -> Result<()> {
+        for row in rows {
+            for col_idx in 0..S::schema().len() {
+                if col_idx == self.data.len() {
+                    self.data.push(vec![]);
+                }
+                self.data[col_idx].push(row.get_column(col_idx));
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, end_checkpoint_seq_num: u64) -> Result<bool> {
+        if self.data.is_empty() {
+            return Ok(false);
+        }
+        self.checkpoint_range.end = end_checkpoint_seq_num;
+        let batch = record_batch::<S>(std::mem::take(&mut self.data))?;
+
+        let mut writer = FileWriter::try_new(self.file()?, &batch.schema())?;
+        writer.write(&batch)?;
+        writer.finish()?;
+        Ok(true)
+    }
+
+    fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()> {
+        self.checkpoint_range.start = start_checkpoint_seq_num;
+        self.checkpoint_range.end = u64::MAX;
+        self.epoch = epoch_num;
+        self.data = vec![];
+        Ok(())
+    }
+
+    fn file_size(&self) -> Result<Option<u64>> {
+        // like the parquet writer, records are only serialized into a file when flush is invoked
+        Ok(None)
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs::{create_dir_all, remove_file};
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::{fs, fs::File, path::PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use mysocial_types::base_types::EpochId;
+use sui_storage::object_store::util::path_to_filesystem;
+
+use crate::writers::AnalyticsWriter;
+use crate::{FileFormat, FileType, ParquetSchema};
+
+// Save table entries to newline-delimited json files, one object per table entry.
+pub(crate) struct JsonWriter {
+    root_dir_path: PathBuf,
+    file_type: FileType,
+    writer: BufWriter<File>,
+    epoch: EpochId,
+    checkpoint_range: Range<u64>,
+}
+
+impl JsonWriter {
+    pub(crate) fn new(
+        root_dir_path: &Path,
+        file_type: FileType,
+        start_checkpoint_seq_num: u64,
+    ) -> Result<Self> {
+        let checkpoint_range = start_checkpoint_seq_num..u64::MAX;
+        let writer = Self::make_writer(
+            root_dir_path.to_path_buf(),
+            file_type,
+            0,
+            checkpoint_range.clone(),
+        )?;
+        Ok(JsonWriter {
+            root_dir_path: root_dir_path.to_path_buf(),
+            file_type,
+            writer,
+            epoch: 0,
+            checkpoint_range,
+        })
+    }
+
+    fn make_writer(
+        root_dir_path: PathBuf,
+        file_type: FileType,
+        epoch_num: EpochId,
+        checkpoint_range: Range<u64>,
+    ) -> Result<BufWriter<File>> {
+        let file_path = path_to_filesystem(
+            root_dir_path,
+            &file_type.file_path(FileFormat::JSONL, epoch_num, checkpoint_range),
+        )?;
+        create_dir_all(file_path.parent().ok_or(anyhow!("Bad directory path"))?)?;
+        if file_path.exists() {
+            remove_file(&file_path)?;
+        }
+        Ok(BufWriter::new(File::create(file_path)?))
+    }
+
+    fn file_path(&self, epoch: EpochId, range: Range<u64>) -> Result<PathBuf> {
+        path_to_filesystem(
+            self.root_dir_path.clone(),
+            &self.file_type.file_path(FileFormat::JSONL, epoch, range),
+        )
+    }
+}
+
+impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for JsonWriter {
+    fn file_format(&self) -> Result<FileFormat> {
+        Ok(FileFormat::JSONL)
+    }
+
+    fn write(&mut self, rows: &[S]) -> Result<()> {
+        for row in rows {
+            serde_json::to_writer(&mut self.writer, row)?;
+            self.writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, end_checkpoint_seq_num: u64) -> Result<bool> {
+        self.writer.flush()?;
+        let old_file_path = self.file_path(self.epoch, self.checkpoint_range.clone())?;
+        let new_file_path = self.file_path(
+            self.epoch,
+            self.checkpoint_range.start..end_checkpoint_seq_num,
+        )?;
+        fs::rename(old_file_path, new_file_path)?;
+        Ok(true)
+    }
+
+    fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()> {
+        self.checkpoint_range.start = start_checkpoint_seq_num;
+        self.checkpoint_range.end = u64::MAX;
+        self.epoch = epoch_num;
+        self.writer = JsonWriter::make_writer(
+            self.root_dir_path.clone(),
+            self.file_type,
+            self.epoch,
+            self.checkpoint_range.clone(),
+        )?;
+        Ok(())
+    }
+
+    fn file_size(&self) -> Result<Option<u64>> {
+        let file_path = self.file_path(self.epoch, self.checkpoint_range.clone())?;
+        let len = fs::metadata(file_path)?.len();
+        Ok(Some(len))
+    }
+}
@@ -9,6 +9,7 @@ pub mod writer;
 mod tests;
 
 use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
+use crate::writer::ArchiveWriter;
 use anyhow::{anyhow, Result};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use bytes::Bytes;
@@ -32,7 +33,10 @@ use mysocial_config::object_storage_config::ObjectStoreConfig;
 use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::util::{get, put};
 use sui_storage::object_store::{ObjectStoreGetExt, ObjectStorePutExt};
-use sui_storage::{compute_sha3_checksum, compute_sha3_checksum_for_bytes, SHA3_BYTES};
+use sui_storage::{
+    compute_sha3_checksum, compute_sha3_checksum_for_bytes, FileCompression, StorageFormat,
+    SHA3_BYTES,
+};
 use mysocial_types::base_types::ExecutionData;
 use mysocial_types::messages_checkpoint::{FullCheckpointContents, VerifiedCheckpointContents};
 use mysocial_types::storage::{SingleCheckpointSharedInMemoryStore, WriteStore};
@@ -439,6 +443,44 @@ pub async fn verify_archive_with_checksums(
     Ok(())
 }
 
+/// Rewrite the archive in `source_store_config` into `dest_store_config`, which must be empty,
+/// cutting files at `commit_file_size` bytes and compressing them with `file_compression`. Files
+/// are staged in the directory of `local_store_config` before being uploaded, and the new archive
+/// is verified against its manifest once it has been written.
+pub async fn rechunk_archive(
+    source_store_config: ObjectStoreConfig,
+    local_store_config: ObjectStoreConfig,
+    dest_store_config: ObjectStoreConfig,
+    file_compression: FileCompression,
+    commit_file_size: usize,
+    concurrency: usize,
+) -> Result<()> {
+    let metrics = ArchiveReaderMetrics::new(&Registry::default());
+    let config = ArchiveReaderConfig {
+        remote_store_config: source_store_config,
+        download_concurrency: NonZeroUsize::new(concurrency).unwrap(),
+        use_for_pruning_watermark: false,
+    };
+    let archive_reader = ArchiveReader::new(config, &metrics)?;
+    let archive_writer = ArchiveWriter::new(
+        local_store_config,
+        dest_store_config.clone(),
+        file_compression,
+        StorageFormat::Blob,
+        Duration::MAX,
+        commit_file_size,
+        &Registry::default(),
+    )
+    .await?;
+    let manifest = archive_writer.rewrite_archive(&archive_reader).await?;
+    info!(
+        "Rewrote {} checkpoints into {} files",
+        manifest.next_checkpoint_seq_num(),
+        manifest.files().len()
+    );
+    verify_archive_with_checksums(dest_store_config, concurrency).await
+}
+
 pub async fn verify_archive_with_local_store<S>(
     store: S,
     remote_store_config: ObjectStoreConfig,
@@ -27,7 +27,7 @@ use mysocial_types::messages_checkpoint::{
 };
 use mysocial_types::storage::WriteStore;
 use tokio::sync::oneshot::Sender;
-use tokio::sync::{oneshot, Mutex};
+use tokio::sync::{mpsc, oneshot, Mutex};
 use tracing::info;
 
 #[derive(Debug)]
@@ -414,35 +414,8 @@ impl ArchiveReader {
     where
         S: WriteStore + Clone,
     {
-        let manifest = self.manifest.lock().await.clone();
-
-        let latest_available_checkpoint = manifest
-            .next_checkpoint_seq_num()
-            .checked_sub(1)
-            .context("Checkpoint seq num underflow")?;
-
-        if checkpoint_range.start > latest_available_checkpoint {
-            return Err(anyhow!(
-                "Latest available checkpoint is: {}",
-                latest_available_checkpoint
-            ));
-        }
-
-        let files: Vec<(FileMetadata, FileMetadata)> = self.verify_manifest(manifest).await?;
-
-        let start_index = match files.binary_search_by_key(&checkpoint_range.start, |(s, _c)| {
-            s.checkpoint_seq_range.start
-        }) {
-            Ok(index) => index,
-            Err(index) => index - 1,
-        };
-
-        let end_index = match files.binary_search_by_key(&checkpoint_range.end, |(s, _c)| {
-            s.checkpoint_seq_range.start
-        }) {
-            Ok(index) => index,
-            Err(index) => index,
-        };
+        let (files, start_index, end_index) =
+            self.get_files_for_range(checkpoint_range.clone()).await?;
 
         let remote_object_store = self.remote_object_store.clone();
         futures::stream::iter(files.iter())
@@ -515,6 +488,73 @@ impl ArchiveReader {
             .await
     }
 
+    /// Send checkpoints and their contents from archive to `sender` for the given checkpoint
+    /// range, in checkpoint order, without inserting them into a store. Contents are checked
+    /// against the digest in their summary, but summaries are not verified.
+    pub async fn stream_checkpoints_no_verify(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+        sender: mpsc::Sender<(CertifiedCheckpointSummary, CheckpointContents)>,
+    ) -> Result<()> {
+        let (files, start_index, end_index) =
+            self.get_files_for_range(checkpoint_range.clone()).await?;
+
+        let remote_object_store = self.remote_object_store.clone();
+        futures::stream::iter(files.iter())
+            .enumerate()
+            .filter(|(index, (_s, _c))| future::ready(*index >= start_index && *index < end_index))
+            .map(|(_, (summary_metadata, content_metadata))| {
+                let remote_object_store = remote_object_store.clone();
+                async move {
+                    let summary_data =
+                        get(&remote_object_store, &summary_metadata.file_path()).await?;
+                    let content_data =
+                        get(&remote_object_store, &content_metadata.file_path()).await?;
+                    Ok::<(Bytes, Bytes), anyhow::Error>((summary_data, content_data))
+                }
+            })
+            .boxed()
+            .buffered(self.concurrency)
+            .try_for_each(|(summary_data, content_data)| {
+                let sender = sender.clone();
+                let checkpoint_range = checkpoint_range.clone();
+                async move {
+                    let summary_iter = make_iterator::<CertifiedCheckpointSummary, Reader<Bytes>>(
+                        SUMMARY_FILE_MAGIC,
+                        summary_data.reader(),
+                    )?;
+                    let content_iter = make_iterator::<CheckpointContents, Reader<Bytes>>(
+                        CHECKPOINT_FILE_MAGIC,
+                        content_data.reader(),
+                    )?;
+                    let checkpoints: Vec<_> = summary_iter
+                        .zip(content_iter)
+                        .filter(|(s, _c)| {
+                            s.sequence_number >= checkpoint_range.start
+                                && s.sequence_number < checkpoint_range.end
+                        })
+                        .collect();
+                    for (summary, contents) in checkpoints {
+                        contents.verify_digests(summary.content_digest)?;
+                        self.archive_reader_metrics
+                            .archive_txns_read
+                            .with_label_values(&[&self.bucket])
+                            .inc_by(contents.size() as u64);
+                        self.archive_reader_metrics
+                            .archive_checkpoints_read
+                            .with_label_values(&[&self.bucket])
+                            .inc_by(1);
+                        sender
+                            .send((summary, contents))
+                            .await
+                            .map_err(|_| anyhow!("Checkpoint receiver dropped"))?;
+                    }
+                    Ok::<(), anyhow::Error>(())
+                }
+            })
+            .await
+    }
+
     /// Return latest available checkpoint in archive
     pub async fn latest_available_checkpoint(&self) -> Result<CheckpointSequenceNumber> {
         let manifest = self.manifest.lock().await.clone();
@@ -608,6 +648,43 @@ impl ArchiveReader {
             .map_err(|e| anyhow!("Failed to get verified checkpoint: {:?}", e))
     }
 
+    async fn get_files_for_range(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+    ) -> Result<(Vec<(FileMetadata, FileMetadata)>, usize, usize)> {
+        let manifest = self.manifest.lock().await.clone();
+
+        let latest_available_checkpoint = manifest
+            .next_checkpoint_seq_num()
+            .checked_sub(1)
+            .context("Checkpoint seq num underflow")?;
+
+        if checkpoint_range.start > latest_available_checkpoint {
+            return Err(anyhow!(
+                "Latest available checkpoint is: {}",
+                latest_available_checkpoint
+            ));
+        }
+
+        let files: Vec<(FileMetadata, FileMetadata)> = self.verify_manifest(manifest).await?;
+
+        let start_index = match files.binary_search_by_key(&checkpoint_range.start, |(s, _c)| {
+            s.checkpoint_seq_range.start
+        }) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+
+        let end_index = match files.binary_search_by_key(&checkpoint_range.end, |(s, _c)| {
+            s.checkpoint_seq_range.start
+        }) {
+            Ok(index) => index,
+            Err(index) => index,
+        };
+
+        Ok((files, start_index, end_index))
+    }
+
     async fn get_summary_files_for_range(
         &self,
         checkpoint_range: Range<CheckpointSequenceNumber>,
@@ -3,7 +3,10 @@
 
 use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
 use crate::writer::ArchiveWriter;
-use crate::{read_manifest, verify_archive_with_local_store, write_manifest, Manifest};
+use crate::{
+    read_manifest, rechunk_archive, verify_archive_with_checksums, verify_archive_with_local_store,
+    write_manifest, FileType, Manifest,
+};
 use anyhow::{anyhow, Context, Result};
 use more_asserts as ma;
 use object_store::DynObjectStore;
@@ -372,3 +375,95 @@ async fn test_verify_archive_with_oneshot_store_bad_data() -> Result<(), anyhow:
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_rechunk_archive() -> Result<(), anyhow::Error> {
+    let test_store = SharedInMemoryStore::default();
+    let test_state = setup_test_state(temp_dir()).await?;
+    let kill = test_state.archive_writer.start(test_store.clone()).await?;
+    let mut latest_archived_checkpoint_seq_num = 0;
+    while latest_archived_checkpoint_seq_num < 10 {
+        insert_checkpoints_and_verify_manifest(&test_state, test_store.clone(), None).await?;
+        latest_archived_checkpoint_seq_num = test_state
+            .archive_reader
+            .latest_available_checkpoint()
+            .await?;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    kill.send(())?;
+    let source_manifest = read_manifest(test_state.remote_store.clone()).await?;
+
+    // Rewrite the archive into uncompressed files large enough to hold every checkpoint of an
+    // epoch in a single file
+    let rechunk_dir = temp_dir();
+    let dest_path = rechunk_dir.join("dest_dir");
+    let dest_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(dest_path.clone()),
+        ..Default::default()
+    };
+    let staging_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(rechunk_dir.join("staging_dir")),
+        ..Default::default()
+    };
+    rechunk_archive(
+        test_state.remote_store_config.clone(),
+        staging_store_config.clone(),
+        dest_store_config.clone(),
+        FileCompression::None,
+        1024 * 1024,
+        2,
+    )
+    .await?;
+
+    let dest_manifest = read_manifest(dest_store_config.make()?).await?;
+    assert_eq!(
+        dest_manifest.next_checkpoint_seq_num(),
+        source_manifest.next_checkpoint_seq_num()
+    );
+    assert_eq!(dest_manifest.epoch_num(), source_manifest.epoch_num());
+    let num_summary_files = |manifest: &Manifest| {
+        manifest
+            .files()
+            .iter()
+            .filter(|f| f.file_type == FileType::CheckpointSummary)
+            .count()
+    };
+    ma::assert_lt!(
+        num_summary_files(&dest_manifest),
+        num_summary_files(&source_manifest)
+    );
+    for file in dest_manifest.files() {
+        assert!(path_to_filesystem(dest_path.clone(), &file.file_path())?.exists());
+    }
+    verify_archive_with_checksums(dest_store_config.clone(), 2).await?;
+
+    // The rewritten archive can be read back and verified checkpoint by checkpoint
+    let genesis_checkpoint = test_store
+        .get_checkpoint_by_sequence_number(0)
+        .context("Missing genesis checkpoint")?;
+    let genesis_checkpoint_content = test_store
+        .get_full_checkpoint_contents_by_sequence_number(0)
+        .context("Missing genesis checkpoint")?;
+    let mut read_store = SingleCheckpointSharedInMemoryStore::default();
+    read_store.insert_genesis_state(
+        genesis_checkpoint,
+        VerifiedCheckpointContents::new_unchecked(genesis_checkpoint_content),
+        test_state.committee.committee().to_owned(),
+    );
+    verify_archive_with_local_store(read_store, dest_store_config.clone(), 1, false).await?;
+
+    // Rewriting into an archive that is not empty fails
+    assert!(rechunk_archive(
+        test_state.remote_store_config.clone(),
+        staging_store_config,
+        dest_store_config,
+        FileCompression::Zstd,
+        1024,
+        2,
+    )
+    .await
+    .is_err());
+    Ok(())
+}
@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(dead_code)]
 
+use crate::reader::ArchiveReader;
 use crate::{
     create_file_metadata, read_manifest, write_manifest, CheckpointUpdates, FileMetadata, FileType,
     Manifest, CHECKPOINT_FILE_MAGIC, CHECKPOINT_FILE_SUFFIX, EPOCH_DIR_PREFIX, MAGIC_BYTES,
     SUMMARY_FILE_MAGIC, SUMMARY_FILE_SUFFIX,
 };
-use anyhow::Context;
 use anyhow::Result;
+use anyhow::{anyhow, Context};
 use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
 use object_store::DynObjectStore;
 use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
@@ -395,6 +396,81 @@ impl ArchiveWriter {
         Ok(kill_sender)
     }
 
+    /// Rewrite all checkpoints in the archive read by `archive_reader` into this writer's remote
+    /// store, which must be empty. Files are cut by this writer's file size and compressed with its
+    /// file compression, regardless of how the source archive was written. Returns the manifest of
+    /// the new archive.
+    pub async fn rewrite_archive(&self, archive_reader: &ArchiveReader) -> Result<Manifest> {
+        let remote_archive_is_empty = self
+            .remote_object_store
+            .list_with_delimiter(None)
+            .await?
+            .common_prefixes
+            .is_empty();
+        if !remote_archive_is_empty {
+            return Err(anyhow!(
+                "Remote archive dir is not empty: {}",
+                self.remote_object_store
+            ));
+        }
+        archive_reader.sync_manifest_once().await?;
+        let source_manifest = archive_reader.get_manifest().await?;
+        let next_checkpoint_seq_num = source_manifest.next_checkpoint_seq_num();
+        info!(
+            "Rewriting archive up to checkpoint sequence number: {}",
+            next_checkpoint_seq_num
+        );
+
+        let (sender, receiver) = mpsc::channel::<CheckpointUpdates>(100);
+        // Files are only cut by size, as the whole archive is available to read upfront
+        let mut checkpoint_writer = CheckpointWriter::new(
+            self.local_staging_dir_root.clone(),
+            self.file_compression,
+            self.storage_format,
+            sender,
+            Manifest::new(0, 0),
+            Duration::MAX,
+            self.commit_file_size,
+        )?;
+        let (kill_sender, kill_receiver) = tokio::sync::broadcast::channel::<()>(1);
+        let sync_handle = tokio::spawn(Self::start_syncing_with_remote(
+            self.remote_object_store.clone(),
+            self.local_object_store.clone(),
+            self.local_staging_dir_root.clone(),
+            receiver,
+            kill_receiver,
+            self.archive_metrics.clone(),
+        ));
+        let (checkpoint_sender, mut checkpoint_receiver) = mpsc::channel(100);
+        let write_handle = tokio::task::spawn_blocking(move || {
+            while let Some((checkpoint_summary, checkpoint_contents)) =
+                checkpoint_receiver.blocking_recv()
+            {
+                checkpoint_writer.write(checkpoint_contents, checkpoint_summary)?;
+            }
+            checkpoint_writer.cut()
+        });
+
+        archive_reader
+            .stream_checkpoints_no_verify(0..next_checkpoint_seq_num, checkpoint_sender)
+            .await?;
+        write_handle.await??;
+        // The sync task stops once the checkpoint writer is dropped and all its updates have
+        // been uploaded.
+        sync_handle.await??;
+        drop(kill_sender);
+
+        let manifest = read_manifest(self.remote_object_store.clone()).await?;
+        if manifest.next_checkpoint_seq_num() != next_checkpoint_seq_num {
+            return Err(anyhow!(
+                "Rewritten archive ends at checkpoint: {}, expected: {}",
+                manifest.next_checkpoint_seq_num(),
+                next_checkpoint_seq_num
+            ));
+        }
+        Ok(manifest)
+    }
+
     fn start_tailing_checkpoints<S>(
         start_checkpoint_sequence_number: CheckpointSequenceNumber,
         mut checkpoint_writer: CheckpointWriter,
@@ -62,6 +62,7 @@ use sui_types::layout_resolver::into_struct_layout;
 use sui_types::layout_resolver::LayoutResolver;
 use sui_types::messages_consensus::{AuthorityCapabilitiesV1, AuthorityCapabilitiesV2};
 use sui_types::object::bounded_visitor::BoundedVisitor;
+use sui_types::signature::GenericSignature;
 use sui_types::transaction_executor::SimulateTransactionResult;
 use tap::TapFallible;
 use tokio::sync::mpsc::unbounded_channel;
@@ -160,10 +161,14 @@ use crate::execution_driver::execution_process;
 use crate::metrics::LatencyObserver;
 use crate::metrics::RateTracker;
 use crate::module_cache_metrics::ResolverMetrics;
-use crate::overload_monitor::{overload_monitor_accept_tx, AuthorityOverloadInfo};
+use crate::overload_monitor::{
+    overload_monitor_accept_tx, overload_monitor_accept_tx_fairly, AuthorityOverloadInfo,
+    LoadSource,
+};
 use crate::stake_aggregator::StakeAggregator;
 use crate::state_accumulator::{AccumulatorStore, StateAccumulator, WrappedObject};
 use crate::subscription_handler::SubscriptionHandler;
+use crate::transaction_deny_config_reloader::TransactionDenyConfigReloader;
 use crate::transaction_input_loader::TransactionInputLoader;
 use crate::transaction_manager::TransactionManager;
 
@@ -282,9 +287,13 @@ pub struct AuthorityMetrics {
 
     pub(crate) authority_overload_status: IntGauge,
     pub(crate) authority_load_shedding_percentage: IntGauge,
+    pub(crate) authority_load_shedding_inflight_limit: IntGauge,
 
     pub(crate) transaction_overload_sources: IntCounterVec,
 
+    pub(crate) transaction_deny_rejections: IntCounterVec,
+    pub(crate) transaction_deny_config_updates: IntCounterVec,
+
     /// Post processing metrics
     post_processing_total_events_emitted: IntCounter,
     post_processing_total_tx_indexed: IntCounter,
@@ -554,6 +563,11 @@ impl AuthorityMetrics {
                 "The percentage of transactions is shed when the authority is in load shedding mode.",
                 registry)
             .unwrap(),
+            authority_load_shedding_inflight_limit: register_int_gauge_with_registry!(
+                "authority_load_shedding_inflight_limit",
+                "The number of transactions each sender and shared object can have in flight when the authority is in fair load shedding mode. Zero means no limit.",
+                registry)
+            .unwrap(),
             transaction_manager_object_cache_misses: register_int_counter_with_registry!(
                 "transaction_manager_object_cache_misses",
                 "Number of object-availability cache misses in TransactionManager",
@@ -603,6 +617,20 @@ impl AuthorityMetrics {
                 &["source"],
                 registry)
             .unwrap(),
+            transaction_deny_rejections: register_int_counter_vec_with_registry!(
+                "transaction_deny_rejections",
+                "Number of transactions rejected by each rule of the transaction deny config",
+                &["rule"],
+                registry,
+            )
+            .unwrap(),
+            transaction_deny_config_updates: register_int_counter_vec_with_registry!(
+                "transaction_deny_config_updates",
+                "Number of attempts to update the transaction deny config, by source and result",
+                &["source", "result"],
+                registry,
+            )
+            .unwrap(),
             execution_driver_executed_transactions: register_int_counter_with_registry!(
                 "execution_driver_executed_transactions",
                 "Cumulative number of transaction executed by execution driver",
@@ -828,6 +856,10 @@ pub struct AuthorityState {
 
     pub config: NodeConfig,
 
+    /// The transaction deny config in effect, which starts out as the one in `config`, but can be
+    /// updated while the node is running.
+    transaction_deny_config: Arc<TransactionDenyConfigReloader>,
+
     /// Current overload status in this authority. Updated periodically.
     pub overload_info: AuthorityOverloadInfo,
 
@@ -866,6 +898,38 @@ impl AuthorityState {
         &self.config.authority_overload_config
     }
 
+    pub fn transaction_deny_config(&self) -> &Arc<TransactionDenyConfigReloader> {
+        &self.transaction_deny_config
+    }
+
+    /// Check that the transaction is allowed to be signed according to the deny config currently
+    /// in effect, counting rejections by the rule that rejected them.
+    fn check_transaction_deny_config(
+        &self,
+        tx_data: &TransactionData,
+        tx_signatures: &[GenericSignature],
+        input_object_kinds: &[InputObjectKind],
+        receiving_objects: &[ObjectRef],
+    ) -> SuiResult {
+        sui_transaction_checks::deny::check_transaction_for_signing_with_rule(
+            tx_data,
+            tx_signatures,
+            input_object_kinds,
+            receiving_objects,
+            &self.transaction_deny_config.load(),
+            self.get_backing_package_store().as_ref(),
+        )
+        .map_err(|e| {
+            if let Some(rule) = e.rule() {
+                self.metrics
+                    .transaction_deny_rejections
+                    .with_label_values(&[rule.as_str()])
+                    .inc();
+            }
+            e.into()
+        })
+    }
+
     pub fn get_epoch_state_commitments(
         &self,
         epoch: EpochId,
@@ -887,13 +951,11 @@ impl AuthorityState {
         // Note: the deny checks may do redundant package loads but:
         // - they only load packages when there is an active package deny map
         // - the loads are cached anyway
-        sui_transaction_checks::deny::check_transaction_for_signing(
+        self.check_transaction_deny_config(
             tx_data,
             transaction.tx_signatures(),
             &input_object_kinds,
             &receiving_objects_refs,
-            &self.config.transaction_deny_config,
-            self.get_backing_package_store().as_ref(),
         )?;
 
         let (input_objects, receiving_objects) = self.input_loader.read_objects_for_signing(
@@ -1125,6 +1187,24 @@ impl AuthorityState {
     }
 
     fn check_authority_overload(&self, tx_data: &SenderSignedData) -> SuiResult {
+        if let Some(fair_config) = &self.overload_config().fair_load_shedding {
+            let transaction_data = tx_data.transaction_data();
+            let sources = std::iter::once(LoadSource::Sender(transaction_data.sender()))
+                .chain(
+                    transaction_data
+                        .shared_input_objects()
+                        .into_iter()
+                        .map(|object| LoadSource::SharedObject(object.id)),
+                )
+                .collect();
+            return overload_monitor_accept_tx_fairly(
+                &self.overload_info,
+                fair_config,
+                tx_data.digest(),
+                sources,
+            );
+        }
+
         if !self.overload_info.is_overload.load(Ordering::Relaxed) {
             return Ok(());
         }
@@ -1791,13 +1871,11 @@ impl AuthorityState {
         let input_object_kinds = transaction.input_objects()?;
         let receiving_object_refs = transaction.receiving_objects();
 
-        sui_transaction_checks::deny::check_transaction_for_signing(
+        self.check_transaction_deny_config(
             &transaction,
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
-            self.get_backing_package_store().as_ref(),
         )?;
 
         let (input_objects, receiving_objects) = self.input_loader.read_objects_for_signing(
@@ -1982,13 +2060,11 @@ impl AuthorityState {
         let input_object_kinds = transaction.input_objects()?;
         let receiving_object_refs = transaction.receiving_objects();
 
-        sui_transaction_checks::deny::check_transaction_for_signing(
+        self.check_transaction_deny_config(
             &transaction,
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
-            self.get_backing_package_store().as_ref(),
         )?;
 
         let (input_objects, receiving_objects) = self.input_loader.read_objects_for_signing(
@@ -2149,13 +2225,11 @@ impl AuthorityState {
         let input_object_kinds = transaction.input_objects()?;
         let receiving_object_refs = transaction.receiving_objects();
 
-        sui_transaction_checks::deny::check_transaction_for_signing(
+        self.check_transaction_deny_config(
             &transaction,
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
-            self.get_backing_package_store().as_ref(),
         )?;
 
         let (mut input_objects, receiving_objects) = self.input_loader.read_objects_for_signing(
@@ -2918,6 +2992,10 @@ impl AuthorityState {
         );
         let input_loader =
             TransactionInputLoader::new(execution_cache_trait_pointers.object_cache_reader.clone());
+        let transaction_deny_config = Arc::new(TransactionDenyConfigReloader::new_lenient(
+            config.transaction_deny_config.clone(),
+            metrics.clone(),
+        ));
         let epoch = epoch_store.epoch();
         let state = Arc::new(AuthorityState {
             name,
@@ -2938,6 +3016,7 @@ impl AuthorityState {
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
             config,
+            transaction_deny_config,
             overload_info: AuthorityOverloadInfo::default(),
             validator_tx_finalizer,
             chain_identifier,
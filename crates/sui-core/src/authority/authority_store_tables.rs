@@ -210,6 +210,17 @@ impl AuthorityPerpetualTables {
         )
     }
 
+    /// Opens the tables as a secondary instance, for tools that read the database of a node that
+    /// may be running.
+    pub fn open_secondary(parent_path: &Path) -> Self {
+        Self::open_tables_secondary(
+            Self::path(parent_path),
+            None,
+            MetricConf::new("perpetual_secondary"),
+            None,
+        )
+    }
+
     // This is used by indexer to find the correct version of dynamic field child object.
     // We do not store the version of the child object, but because of lamport timestamp,
     // we know the child must have version number less then or eq to the parent.
@@ -328,6 +339,10 @@ impl AuthorityPerpetualTables {
         Ok(None)
     }
 
+    pub fn get_epoch_start_configuration(&self) -> SuiResult<Option<EpochStartConfiguration>> {
+        Ok(self.epoch_start_configuration.get(&())?)
+    }
+
     pub fn get_recovery_epoch_at_restart(&self) -> SuiResult<EpochId> {
         Ok(self
             .epoch_start_configuration
@@ -545,6 +560,58 @@ impl ObjectStore for AuthorityPerpetualTables {
     }
 }
 
+impl AuthorityPerpetualTablesReadOnly {
+    // Constructs `sui_types::object::Object` from `StoreObjectWrapper`, for tools reading a
+    // database opened in read only mode. Returns `None` if object was deleted/wrapped
+    pub fn object(
+        &self,
+        object_key: &ObjectKey,
+        store_object: StoreObjectWrapper,
+    ) -> Result<Option<Object>, SuiError> {
+        let StoreObject::Value(store_object) = store_object.migrate().into_inner() else {
+            return Ok(None);
+        };
+        Ok(Some(try_construct_object(object_key, store_object)?))
+    }
+
+    pub fn get_object_fallible(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        let obj_entry = self
+            .objects
+            .unbounded_iter()
+            .skip_prior_to(&ObjectKey::max_for_id(object_id))?
+            .next();
+
+        match obj_entry {
+            Some((ObjectKey(obj_id, version), obj)) if obj_id == *object_id => {
+                Ok(self.object(&ObjectKey(obj_id, version), obj)?)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn get_object_by_key_fallible(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> SuiResult<Option<Object>> {
+        match self.objects.get(&ObjectKey(*object_id, version))? {
+            Some(object) => self.object(&ObjectKey(*object_id, version), object),
+            None => Ok(None),
+        }
+    }
+}
+
+impl ObjectStore for AuthorityPerpetualTablesReadOnly {
+    fn get_object(&self, object_id: &ObjectID) -> Option<Object> {
+        self.get_object_fallible(object_id).expect("db error")
+    }
+
+    fn get_object_by_key(&self, object_id: &ObjectID, version: VersionNumber) -> Option<Object> {
+        self.get_object_by_key_fallible(object_id, version)
+            .expect("db error")
+    }
+}
+
 pub struct LiveSetIter<'a> {
     iter:
         <DBMap<ObjectKey, StoreObjectWrapper> as Map<'a, ObjectKey, StoreObjectWrapper>>::Iterator,
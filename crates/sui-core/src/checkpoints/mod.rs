@@ -199,6 +199,15 @@ impl CheckpointStoreTables {
             MetricConf::new("checkpoint_readonly"),
         )
     }
+
+    pub fn open_secondary(path: &Path) -> Self {
+        Self::open_tables_secondary(
+            path.to_path_buf(),
+            None,
+            MetricConf::new("checkpoint_secondary"),
+            None,
+        )
+    }
 }
 
 pub struct CheckpointStore {
@@ -235,6 +244,17 @@ impl CheckpointStore {
         CheckpointStoreTables::open_readonly(path)
     }
 
+    /// Opens the store as a secondary instance, for tools that read the database of a node that
+    /// may be running.
+    pub fn open_secondary(path: &Path) -> Arc<Self> {
+        let tables = CheckpointStoreTables::open_secondary(path);
+        Arc::new(Self {
+            tables,
+            synced_checkpoint_notify_read: NotifyRead::new(),
+            executed_checkpoint_notify_read: NotifyRead::new(),
+        })
+    }
+
     #[instrument(level = "info", skip_all)]
     pub fn insert_genesis_checkpoint(
         &self,
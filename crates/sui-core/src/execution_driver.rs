@@ -108,6 +108,12 @@ pub async fn execution_process(
 
         authority.metrics.execution_rate_tracker.lock().record();
 
+        // The transaction no longer counts towards its sender's and shared objects' in-flight
+        // transactions once it is sent for execution.
+        if authority.overload_config().fair_load_shedding.is_some() {
+            authority.overload_info.inflight.remove(&digest);
+        }
+
         // Certificate execution can take significant time, so run it in a separate task.
         let epoch_store_clone = epoch_store.clone();
         spawn_monitored_task!(epoch_store.within_alive_epoch(async move {
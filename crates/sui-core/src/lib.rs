@@ -40,6 +40,7 @@ pub mod streamer;
 pub mod subscription_handler;
 pub mod test_utils;
 pub mod traffic_controller;
+pub mod transaction_deny_config_reloader;
 mod transaction_input_loader;
 mod transaction_manager;
 pub mod transaction_orchestrator;
@@ -159,6 +159,15 @@ impl IndexStoreTables {
         )
     }
 
+    fn open_secondary<P: Into<PathBuf>>(path: P) -> Self {
+        IndexStoreTables::open_tables_secondary(
+            path.into(),
+            None,
+            MetricConf::new("rpc-index-secondary"),
+            None,
+        )
+    }
+
     fn needs_to_do_initialization(&self) -> bool {
         match self.meta.get(&()) {
             Ok(Some(metadata)) => metadata.version != CURRENT_DB_VERSION,
@@ -520,6 +529,18 @@ impl RpcIndexStore {
         }
     }
 
+    /// Opens the index as a secondary instance, for tools that read the index of a node that may
+    /// be running.
+    pub fn open_secondary(dir: &Path) -> Self {
+        let path = Self::db_path(dir);
+        let tables = IndexStoreTables::open_secondary(path);
+
+        Self {
+            tables,
+            pending_updates: Default::default(),
+        }
+    }
+
     pub fn prune(
         &self,
         checkpoint_contents_to_prune: &[CheckpointContents],
@@ -4,7 +4,10 @@
 
 use std::{collections::HashMap, net::IpAddr, sync::Arc};
 
+use anyhow::ensure;
+
 use count_min_sketch::CountMinSketch32;
+use ipnetwork::IpNetwork;
 use mysten_metrics::spawn_monitored_task;
 use parking_lot::RwLock;
 use std::cmp::Reverse;
@@ -13,11 +16,23 @@ use std::fmt::Debug;
 use std::hash::Hash;
 use std::time::Duration;
 use std::time::{Instant, SystemTime};
-use sui_types::traffic_control::{FreqThresholdConfig, PolicyConfig, PolicyType, Weight};
+use sui_types::traffic_control::{
+    ErrorRatioConfig, FreqThresholdConfig, PolicyConfig, PolicyType, TokenBucketConfig, Weight,
+};
 use tracing::{info, trace};
 
 const HIGHEST_RATES_CAPACITY: usize = 20;
 
+/// How often the token bucket policy drops the state of clients whose
+/// buckets have refilled completely (and are therefore indistinguishable
+/// from clients that have not been seen at all).
+const TOKEN_BUCKET_GC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the error ratio policy drops the state of clients that have
+/// not made any requests within the window, and forgets offenses that have
+/// decayed.
+const ERROR_RATIO_GC_INTERVAL: Duration = Duration::from_secs(60);
+
 /// The type of request client.
 #[derive(Hash, Eq, PartialEq, Debug)]
 enum ClientType {
@@ -228,6 +243,9 @@ pub struct TrafficTally {
     pub through_fullnode: Option<IpAddr>,
     pub error_info: Option<(Weight, String)>,
     pub spam_weight: Weight,
+    /// Name of the method that was called, if known. Used by policies that
+    /// weigh requests by the method being served.
+    pub method: Option<String>,
     pub timestamp: SystemTime,
 }
 
@@ -243,15 +261,29 @@ impl TrafficTally {
             through_fullnode,
             error_info,
             spam_weight,
+            method: None,
             timestamp: SystemTime::now(),
         }
     }
+
+    pub fn with_method(mut self, method: impl Into<String>) -> Self {
+        self.method = Some(method.into());
+        self
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct PolicyResponse {
     pub block_client: Option<IpAddr>,
     pub block_proxied_client: Option<IpAddr>,
+    /// How long to block `block_client` for, if the policy decides this
+    /// itself. Otherwise the `connection_blocklist_ttl_sec` from the policy
+    /// config is used.
+    pub block_client_ttl: Option<Duration>,
+    /// How long to block `block_proxied_client` for, if the policy decides
+    /// this itself. Otherwise the `proxy_blocklist_ttl_sec` from the policy
+    /// config is used.
+    pub block_proxied_client_ttl: Option<Duration>,
 }
 
 pub trait Policy {
@@ -265,6 +297,8 @@ pub trait Policy {
 // not object safe, so we can't use a trait object instead
 pub enum TrafficControlPolicy {
     FreqThreshold(FreqThresholdPolicy),
+    TokenBucket(TokenBucketPolicy),
+    ErrorRatio(ErrorRatioPolicy),
     NoOp(NoOpPolicy),
     // Test policies below this point
     TestNConnIP(TestNConnIPPolicy),
@@ -276,6 +310,8 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::FreqThreshold(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::TokenBucket(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::ErrorRatio(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestNConnIP(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.handle_tally(tally),
         }
@@ -285,6 +321,8 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.policy_config(),
             TrafficControlPolicy::FreqThreshold(policy) => policy.policy_config(),
+            TrafficControlPolicy::TokenBucket(policy) => policy.policy_config(),
+            TrafficControlPolicy::ErrorRatio(policy) => policy.policy_config(),
             TrafficControlPolicy::TestNConnIP(policy) => policy.policy_config(),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.policy_config(),
         }
@@ -292,25 +330,55 @@ impl Policy for TrafficControlPolicy {
 }
 
 impl TrafficControlPolicy {
-    pub async fn from_spam_config(policy_config: PolicyConfig) -> Self {
+    pub async fn from_spam_config(policy_config: PolicyConfig) -> anyhow::Result<Self> {
         Self::from_config(policy_config.clone().spam_policy_type, policy_config).await
     }
-    pub async fn from_error_config(policy_config: PolicyConfig) -> Self {
+    pub async fn from_error_config(policy_config: PolicyConfig) -> anyhow::Result<Self> {
         Self::from_config(policy_config.clone().error_policy_type, policy_config).await
     }
-    pub async fn from_config(policy_type: PolicyType, policy_config: PolicyConfig) -> Self {
-        match policy_type {
+    pub async fn from_config(
+        policy_type: PolicyType,
+        policy_config: PolicyConfig,
+    ) -> anyhow::Result<Self> {
+        Ok(match policy_type {
             PolicyType::NoOp => Self::NoOp(NoOpPolicy::new(policy_config)),
             PolicyType::FreqThreshold(freq_threshold_config) => Self::FreqThreshold(
                 FreqThresholdPolicy::new(policy_config, freq_threshold_config),
             ),
+            PolicyType::TokenBucket(token_bucket_config) => {
+                Self::TokenBucket(TokenBucketPolicy::new(policy_config, token_bucket_config))
+            }
+            PolicyType::ErrorRatio(error_ratio_config) => {
+                Self::ErrorRatio(ErrorRatioPolicy::new(policy_config, error_ratio_config)?)
+            }
             PolicyType::TestNConnIP(n) => {
                 Self::TestNConnIP(TestNConnIPPolicy::new(policy_config, n).await)
             }
             PolicyType::TestPanicOnInvocation => {
                 Self::TestPanicOnInvocation(TestPanicOnInvocationPolicy::new(policy_config))
             }
+        })
+    }
+
+    /// Check that the spam and error policies in `policy_config` can be
+    /// constructed, so that a bad config is rejected before any traffic is
+    /// tallied.
+    pub fn validate_config(policy_config: &PolicyConfig) -> anyhow::Result<()> {
+        for policy_type in [
+            &policy_config.spam_policy_type,
+            &policy_config.error_policy_type,
+        ] {
+            if let PolicyType::ErrorRatio(error_ratio_config) = policy_type {
+                ErrorRatioPolicy::validate_config(error_ratio_config)?;
+            }
         }
+        Ok(())
+    }
+
+    /// Whether the policy needs to see tallies for successful requests, in
+    /// addition to errors, when used as an error policy.
+    pub fn tallies_successes(&self) -> bool {
+        matches!(self, Self::ErrorRatio(_))
     }
 }
 
@@ -408,7 +476,437 @@ impl FreqThresholdPolicy {
         PolicyResponse {
             block_client,
             block_proxied_client,
+            ..Default::default()
+        }
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(capacity: f64, now: Instant) -> Self {
+        Self {
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, capacity: f64, refill_rate: f64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * refill_rate).min(capacity);
+        self.last_refill = now;
+    }
+
+    /// Attempt to take `cost` tokens from the bucket, returning false
+    /// (and leaving the bucket empty) if there were not enough tokens.
+    fn try_consume(&mut self, cost: f64, capacity: f64, refill_rate: f64, now: Instant) -> bool {
+        self.refill(capacity, refill_rate, now);
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            true
+        } else {
+            self.tokens = 0.0;
+            false
+        }
+    }
+}
+
+struct TokenBuckets {
+    buckets: HashMap<IpAddr, TokenBucket>,
+    capacity: f64,
+    refill_rate: f64,
+}
+
+impl TokenBuckets {
+    fn new(capacity: u64, refill_rate: f64) -> Self {
+        Self {
+            buckets: HashMap::new(),
+            capacity: capacity as f64,
+            refill_rate,
+        }
+    }
+
+    /// Charge `cost` to `client`, returning true if the client has
+    /// exhausted its bucket and should be blocked.
+    fn charge(&mut self, client: IpAddr, cost: f64, now: Instant) -> bool {
+        let (capacity, refill_rate) = (self.capacity, self.refill_rate);
+        !self
+            .buckets
+            .entry(client)
+            .or_insert_with(|| TokenBucket::full(capacity, now))
+            .try_consume(cost, capacity, refill_rate, now)
+    }
+
+    fn remove_full(&mut self, now: Instant) {
+        let (capacity, refill_rate) = (self.capacity, self.refill_rate);
+        self.buckets.retain(|_, bucket| {
+            bucket.refill(capacity, refill_rate, now);
+            bucket.tokens < capacity
+        });
+    }
+}
+
+pub struct TokenBucketPolicy {
+    config: PolicyConfig,
+    clients: TokenBuckets,
+    proxied_clients: TokenBuckets,
+    default_method_cost: u64,
+    method_costs: HashMap<String, u64>,
+    default_error_cost: u64,
+    error_costs: HashMap<String, u64>,
+    allowlist: Vec<IpNetwork>,
+    last_gc: Instant,
+}
+
+impl TokenBucketPolicy {
+    pub fn new(
+        config: PolicyConfig,
+        TokenBucketConfig {
+            client_capacity,
+            client_refill_rate,
+            proxied_client_capacity,
+            proxied_client_refill_rate,
+            default_method_cost,
+            method_costs,
+            default_error_cost,
+            error_costs,
+            allowlist,
+        }: TokenBucketConfig,
+    ) -> Self {
+        let allowlist = allowlist
+            .into_iter()
+            .map(|entry| {
+                entry.parse::<IpNetwork>().unwrap_or_else(|_| {
+                    panic!(
+                        "Failed to parse token bucket allowlist entry as IP address or CIDR: {:?}",
+                        entry
+                    )
+                })
+            })
+            .collect();
+        Self {
+            config,
+            clients: TokenBuckets::new(client_capacity, client_refill_rate),
+            proxied_clients: TokenBuckets::new(proxied_client_capacity, proxied_client_refill_rate),
+            default_method_cost,
+            method_costs: method_costs.into_iter().collect(),
+            default_error_cost,
+            error_costs: error_costs.into_iter().collect(),
+            allowlist,
+            last_gc: Instant::now(),
+        }
+    }
+
+    /// The number of tokens that `tally` consumes: the cost of the method
+    /// called, plus the cost of the error returned, if any.
+    pub fn cost(&self, tally: &TrafficTally) -> u64 {
+        let method_cost = tally
+            .method
+            .as_ref()
+            .and_then(|method| self.method_costs.get(method))
+            .copied()
+            .unwrap_or(self.default_method_cost);
+        let error_cost = tally.error_info.as_ref().map_or(0, |(_, error_type)| {
+            self.error_costs
+                .get(error_type)
+                .copied()
+                .unwrap_or(self.default_error_cost)
+        });
+        method_cost.saturating_add(error_cost)
+    }
+
+    pub fn is_allowlisted(&self, client: &IpAddr) -> bool {
+        self.allowlist
+            .iter()
+            .any(|network| network.contains(*client))
+    }
+
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let now = Instant::now();
+        if now.saturating_duration_since(self.last_gc) >= TOKEN_BUCKET_GC_INTERVAL {
+            self.clients.remove_full(now);
+            self.proxied_clients.remove_full(now);
+            self.last_gc = now;
+        }
+
+        let cost = self.cost(&tally);
+        if cost == 0 {
+            return PolicyResponse::default();
+        }
+        let cost = cost as f64;
+
+        let block_client = tally
+            .direct
+            .filter(|client| !self.is_allowlisted(client))
+            .filter(|client| self.clients.charge(*client, cost, now));
+        let block_proxied_client = tally
+            .through_fullnode
+            .filter(|client| !self.is_allowlisted(client))
+            .filter(|client| self.proxied_clients.charge(*client, cost, now));
+        trace!(
+            "TokenBucketPolicy handling tally -- cost: {:?}, method: {:?}, block_client: {:?}, block_proxied_client: {:?}",
+            cost,
+            tally.method,
+            block_client,
+            block_proxied_client,
+        );
+        PolicyResponse {
+            block_client,
+            block_proxied_client,
+            ..Default::default()
+        }
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
+/// Outcomes of a client's requests, bucketed by update interval so that
+/// outcomes that have left the sliding window can be dropped.
+#[derive(Default)]
+struct RequestOutcomes {
+    /// (update interval, successes, errors), oldest interval first.
+    buckets: VecDeque<(u64, u64, u64)>,
+}
+
+impl RequestOutcomes {
+    fn record(&mut self, interval: u64, is_error: bool) {
+        match self.buckets.back_mut() {
+            Some((last, successes, errors)) if *last == interval => {
+                if is_error {
+                    *errors += 1;
+                } else {
+                    *successes += 1;
+                }
+            }
+            _ => self
+                .buckets
+                .push_back((interval, !is_error as u64, is_error as u64)),
+        }
+    }
+
+    /// Drop all outcomes from before `oldest_interval`.
+    fn expire(&mut self, oldest_interval: u64) {
+        while self
+            .buckets
+            .front()
+            .is_some_and(|(interval, _, _)| *interval < oldest_interval)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Total number of requests and errors in the window.
+    fn totals(&self) -> (u64, u64) {
+        self.buckets
+            .iter()
+            .fold((0, 0), |(requests, errors), (_, s, e)| {
+                (requests + s + e, errors + e)
+            })
+    }
+}
+
+struct Offenses {
+    count: u32,
+    last_blocked: Instant,
+}
+
+#[derive(Default)]
+struct ErrorRatioTracker {
+    outcomes: HashMap<IpAddr, RequestOutcomes>,
+    offenses: HashMap<IpAddr, Offenses>,
+}
+
+pub struct ErrorRatioPolicy {
+    config: PolicyConfig,
+    clients: ErrorRatioTracker,
+    proxied_clients: ErrorRatioTracker,
+    error_ratio_threshold: f64,
+    min_requests: u64,
+    update_interval: Duration,
+    /// Number of update intervals in the sliding window.
+    num_intervals: u64,
+    max_blocklist_ttl: Duration,
+    offense_decay: Duration,
+    /// Update intervals are counted from this instant.
+    start: Instant,
+    last_gc: Instant,
+}
+
+impl ErrorRatioPolicy {
+    pub fn new(config: PolicyConfig, error_ratio_config: ErrorRatioConfig) -> anyhow::Result<Self> {
+        Self::validate_config(&error_ratio_config)?;
+        let ErrorRatioConfig {
+            error_ratio_threshold,
+            min_requests,
+            window_size_secs,
+            update_interval_secs,
+            max_blocklist_ttl_sec,
+            offense_decay_sec,
+        } = error_ratio_config;
+        let now = Instant::now();
+        Ok(Self {
+            config,
+            clients: ErrorRatioTracker::default(),
+            proxied_clients: ErrorRatioTracker::default(),
+            error_ratio_threshold,
+            min_requests,
+            update_interval: Duration::from_secs(update_interval_secs),
+            num_intervals: window_size_secs / update_interval_secs,
+            max_blocklist_ttl: Duration::from_secs(max_blocklist_ttl_sec),
+            offense_decay: Duration::from_secs(offense_decay_sec),
+            start: now,
+            last_gc: now,
+        })
+    }
+
+    pub fn validate_config(
+        ErrorRatioConfig {
+            error_ratio_threshold,
+            window_size_secs,
+            update_interval_secs,
+            ..
+        }: &ErrorRatioConfig,
+    ) -> anyhow::Result<()> {
+        ensure!(
+            *error_ratio_threshold > 0.0 && *error_ratio_threshold <= 1.0,
+            "Error ratio threshold must be in (0, 1], got {error_ratio_threshold}"
+        );
+        ensure!(
+            *update_interval_secs >= 1,
+            "Update interval too short, must be at least 1 second"
+        );
+        ensure!(
+            update_interval_secs <= window_size_secs,
+            "Update interval may not be larger than window size"
+        );
+        Ok(())
+    }
+
+    /// The ratio of errors to requests from `client` over the current window,
+    /// if it has made any requests.
+    pub fn error_ratio(&self, client: &IpAddr) -> Option<f64> {
+        let (requests, errors) = self.clients.outcomes.get(client)?.totals();
+        (requests > 0).then(|| errors as f64 / requests as f64)
+    }
+
+    /// The number of times `client` has been blocked without its offenses
+    /// decaying in between.
+    pub fn offenses(&self, client: &IpAddr) -> u32 {
+        self.clients
+            .offenses
+            .get(client)
+            .map_or(0, |offenses| offenses.count)
+    }
+
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let now = Instant::now();
+        let interval = self.interval(now);
+        let oldest_interval = (interval + 1).saturating_sub(self.num_intervals);
+        if now.saturating_duration_since(self.last_gc) >= ERROR_RATIO_GC_INTERVAL {
+            self.gc(oldest_interval, now);
+        }
+
+        let is_error = tally.error_info.is_some();
+        let block_client_ttl = tally
+            .direct
+            .and_then(|client| self.record(client, ClientType::Direct, is_error, interval, now));
+        let block_proxied_client_ttl = tally.through_fullnode.and_then(|client| {
+            self.record(client, ClientType::ThroughFullnode, is_error, interval, now)
+        });
+        trace!(
+            "ErrorRatioPolicy handling tally -- is_error: {:?}, block_client_ttl: {:?}, block_proxied_client_ttl: {:?}",
+            is_error,
+            block_client_ttl,
+            block_proxied_client_ttl,
+        );
+        PolicyResponse {
+            block_client: tally.direct.filter(|_| block_client_ttl.is_some()),
+            block_proxied_client: tally
+                .through_fullnode
+                .filter(|_| block_proxied_client_ttl.is_some()),
+            block_client_ttl,
+            block_proxied_client_ttl,
+        }
+    }
+
+    fn interval(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.start).as_secs() / self.update_interval.as_secs()
+    }
+
+    /// Record the outcome of a request from `client`, returning how long to
+    /// block it for if its error ratio has reached the threshold.
+    fn record(
+        &mut self,
+        client: IpAddr,
+        client_type: ClientType,
+        is_error: bool,
+        interval: u64,
+        now: Instant,
+    ) -> Option<Duration> {
+        let oldest_interval = (interval + 1).saturating_sub(self.num_intervals);
+        let (tracker, base_ttl_sec) = match client_type {
+            ClientType::Direct => (&mut self.clients, self.config.connection_blocklist_ttl_sec),
+            ClientType::ThroughFullnode => (
+                &mut self.proxied_clients,
+                self.config.proxy_blocklist_ttl_sec,
+            ),
+        };
+
+        let outcomes = tracker.outcomes.entry(client).or_default();
+        outcomes.expire(oldest_interval);
+        outcomes.record(interval, is_error);
+        let (requests, errors) = outcomes.totals();
+        if requests < self.min_requests
+            || (errors as f64) < self.error_ratio_threshold * requests as f64
+        {
+            return None;
+        }
+
+        // Start from a clean slate once blocked, so that the client has to
+        // reach the threshold over another `min_requests` requests before it
+        // is blocked (for longer) again.
+        tracker.outcomes.remove(&client);
+
+        let offenses = tracker.offenses.entry(client).or_insert(Offenses {
+            count: 0,
+            last_blocked: now,
+        });
+        if now.saturating_duration_since(offenses.last_blocked) >= self.offense_decay {
+            offenses.count = 0;
         }
+        offenses.count += 1;
+        offenses.last_blocked = now;
+
+        // Double the TTL for every previous offense.
+        let backoff = 1u32.checked_shl(offenses.count - 1).unwrap_or(u32::MAX);
+        Some(
+            Duration::from_secs(base_ttl_sec)
+                .saturating_mul(backoff)
+                .min(self.max_blocklist_ttl),
+        )
+    }
+
+    fn gc(&mut self, oldest_interval: u64, now: Instant) {
+        let offense_decay = self.offense_decay;
+        for tracker in [&mut self.clients, &mut self.proxied_clients] {
+            tracker.outcomes.retain(|_, outcomes| {
+                outcomes.expire(oldest_interval);
+                !outcomes.buckets.is_empty()
+            });
+            tracker.offenses.retain(|_, offenses| {
+                now.saturating_duration_since(offenses.last_blocked) < offense_decay
+            });
+        }
+        self.last_gc = now;
     }
 
     fn policy_config(&self) -> &PolicyConfig {
@@ -476,7 +974,7 @@ impl TestNConnIPPolicy {
             } else {
                 None
             },
-            block_proxied_client: None,
+            ..Default::default()
         }
     }
 
@@ -543,6 +1041,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))),
             error_info: None,
             spam_weight: Weight::one(),
+            method: None,
             timestamp: SystemTime::now(),
         };
         let bob = TrafficTally {
@@ -550,6 +1049,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1))),
             error_info: None,
             spam_weight: Weight::one(),
+            method: None,
             timestamp: SystemTime::now(),
         };
         let charlie = TrafficTally {
@@ -557,6 +1057,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8))),
             error_info: None,
             spam_weight: Weight::one(),
+            method: None,
             timestamp: SystemTime::now(),
         };
 
@@ -674,6 +1175,250 @@ mod tests {
         assert_eq!(proxied_rate, 1);
     }
 
+    #[sim_test]
+    async fn test_token_bucket_policy() {
+        // Direct clients can burst up to 5 unit cost requests, and regain
+        // one token per second. Proxied clients are tracked in separate
+        // buckets, with a burst of 2.
+        let mut policy = TokenBucketPolicy::new(
+            PolicyConfig::default(),
+            TokenBucketConfig {
+                client_capacity: 5,
+                client_refill_rate: 1.0,
+                proxied_client_capacity: 2,
+                proxied_client_refill_rate: 1.0,
+                method_costs: [("sui_executeTransactionBlock".to_string(), 3)].into(),
+                ..Default::default()
+            },
+        );
+        let alice = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5))),
+            None,
+            None,
+            Weight::one(),
+        );
+        let bob = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))),
+            None,
+            None,
+            Weight::one(),
+        )
+        .with_method("sui_executeTransactionBlock");
+
+        // alice can burst up to capacity before being blocked
+        for i in 0..5 {
+            let response = policy.handle_tally(alice.clone());
+            assert_eq!(response.block_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(alice.clone());
+        assert_eq!(response.block_client, alice.direct);
+
+        // bob's method is more expensive, so he is blocked on his second request
+        let response = policy.handle_tally(bob.clone());
+        assert_eq!(response.block_client, None);
+        let response = policy.handle_tally(bob.clone());
+        assert_eq!(response.block_client, bob.direct);
+
+        // after waiting, alice's bucket has refilled enough for a few more requests
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        for i in 0..3 {
+            let response = policy.handle_tally(alice.clone());
+            assert_eq!(response.block_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(alice.clone());
+        assert_eq!(response.block_client, alice.direct);
+
+        // proxied clients are charged against their own bucket
+        let charlie = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(9, 9, 9, 9))),
+            Some(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8))),
+            None,
+            Weight::one(),
+        );
+        for _ in 0..2 {
+            let response = policy.handle_tally(charlie.clone());
+            assert_eq!(response.block_client, None);
+            assert_eq!(response.block_proxied_client, None);
+        }
+        let response = policy.handle_tally(charlie.clone());
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, charlie.through_fullnode);
+    }
+
+    #[sim_test]
+    async fn test_token_bucket_policy_error_costs_and_allowlist() {
+        let mut policy = TokenBucketPolicy::new(
+            PolicyConfig::default(),
+            TokenBucketConfig {
+                client_capacity: 10,
+                client_refill_rate: 0.1,
+                default_method_cost: 0,
+                default_error_cost: 1,
+                error_costs: [("InvalidSignature".to_string(), 5)].into(),
+                allowlist: vec!["10.0.0.0/8".to_string(), "192.168.1.1".to_string()],
+                ..Default::default()
+            },
+        );
+        let client = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+
+        // successful requests are free
+        let ok = TrafficTally::new(client, None, None, Weight::one());
+        assert_eq!(policy.cost(&ok), 0);
+        for _ in 0..20 {
+            assert_eq!(policy.handle_tally(ok.clone()).block_client, None);
+        }
+
+        // errors carry a cost depending on their type
+        let other_error = TrafficTally::new(
+            client,
+            None,
+            Some((Weight::one(), "Other".to_string())),
+            Weight::one(),
+        );
+        let bad_signature = TrafficTally::new(
+            client,
+            None,
+            Some((Weight::one(), "InvalidSignature".to_string())),
+            Weight::one(),
+        );
+        assert_eq!(policy.cost(&other_error), 1);
+        assert_eq!(policy.cost(&bad_signature), 5);
+        assert_eq!(
+            policy.handle_tally(bad_signature.clone()).block_client,
+            None
+        );
+        assert_eq!(policy.handle_tally(other_error.clone()).block_client, None);
+        assert_eq!(
+            policy.handle_tally(bad_signature.clone()).block_client,
+            client
+        );
+
+        // allowlisted clients, by address or CIDR, are never blocked
+        for ip in [Ipv4Addr::new(10, 1, 2, 3), Ipv4Addr::new(192, 168, 1, 1)] {
+            let allowlisted = Some(IpAddr::V4(ip));
+            for _ in 0..10 {
+                let tally = TrafficTally::new(
+                    allowlisted,
+                    None,
+                    Some((Weight::one(), "InvalidSignature".to_string())),
+                    Weight::one(),
+                );
+                assert_eq!(policy.handle_tally(tally).block_client, None);
+            }
+        }
+    }
+
+    #[sim_test]
+    async fn test_error_ratio_policy() {
+        // Block clients once at least half of their requests over a 4 second
+        // window have failed, provided they have made at least 4 requests.
+        let mut policy = ErrorRatioPolicy::new(
+            PolicyConfig {
+                connection_blocklist_ttl_sec: 10,
+                ..Default::default()
+            },
+            ErrorRatioConfig {
+                error_ratio_threshold: 0.5,
+                min_requests: 4,
+                window_size_secs: 4,
+                update_interval_secs: 1,
+                max_blocklist_ttl_sec: 30,
+                offense_decay_sec: 600,
+            },
+        )
+        .unwrap();
+        let alice = IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5));
+        let ok = TrafficTally::new(Some(alice), None, None, Weight::one());
+        let error = TrafficTally::new(
+            Some(alice),
+            None,
+            Some((Weight::one(), "InvalidSignature".to_string())),
+            Weight::one(),
+        );
+
+        for _ in 0..3 {
+            assert_eq!(policy.handle_tally(ok.clone()).block_client, None);
+        }
+        for _ in 0..2 {
+            assert_eq!(policy.handle_tally(error.clone()).block_client, None);
+        }
+        assert_eq!(policy.error_ratio(&alice), Some(0.4));
+
+        // half of alice's requests have now failed
+        let response = policy.handle_tally(error.clone());
+        assert_eq!(response.block_client, Some(alice));
+        assert_eq!(response.block_client_ttl, Some(Duration::from_secs(10)));
+        assert_eq!(policy.offenses(&alice), 1);
+        assert_eq!(policy.error_ratio(&alice), None);
+
+        // repeat offenses are blocked for exponentially longer, up to the max
+        for expected_ttl in [20, 30, 30] {
+            for _ in 0..3 {
+                assert_eq!(policy.handle_tally(error.clone()).block_client, None);
+            }
+            let response = policy.handle_tally(error.clone());
+            assert_eq!(response.block_client, Some(alice));
+            assert_eq!(
+                response.block_client_ttl,
+                Some(Duration::from_secs(expected_ttl))
+            );
+        }
+        assert_eq!(policy.offenses(&alice), 4);
+
+        // errors that have left the window no longer count towards the ratio
+        let bob = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let bob_error = TrafficTally::new(
+            Some(bob),
+            None,
+            Some((Weight::one(), "InvalidSignature".to_string())),
+            Weight::one(),
+        );
+        for _ in 0..3 {
+            assert_eq!(policy.handle_tally(bob_error.clone()).block_client, None);
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+        assert_eq!(policy.handle_tally(bob_error.clone()).block_client, None);
+        let bob_ok = TrafficTally::new(Some(bob), None, None, Weight::one());
+        for _ in 0..3 {
+            assert_eq!(policy.handle_tally(bob_ok.clone()).block_client, None);
+        }
+        assert_eq!(policy.error_ratio(&bob), Some(0.25));
+        assert_eq!(policy.offenses(&bob), 0);
+    }
+
+    #[sim_test]
+    async fn test_error_ratio_policy_invalid_config() {
+        for config in [
+            ErrorRatioConfig {
+                error_ratio_threshold: 0.0,
+                ..Default::default()
+            },
+            ErrorRatioConfig {
+                error_ratio_threshold: 1.5,
+                ..Default::default()
+            },
+            ErrorRatioConfig {
+                update_interval_secs: 0,
+                ..Default::default()
+            },
+            ErrorRatioConfig {
+                window_size_secs: 1,
+                update_interval_secs: 2,
+                ..Default::default()
+            },
+        ] {
+            assert!(ErrorRatioPolicy::new(PolicyConfig::default(), config.clone()).is_err());
+            let policy_config = PolicyConfig {
+                error_policy_type: PolicyType::ErrorRatio(config),
+                ..Default::default()
+            };
+            assert!(TrafficControlPolicy::validate_config(&policy_config).is_err());
+        }
+        assert!(
+            ErrorRatioPolicy::new(PolicyConfig::default(), ErrorRatioConfig::default()).is_ok()
+        );
+    }
+
     #[sim_test]
     async fn test_traffic_sketch_mem_estimate() {
         // Test for getting a rough estimate of memory usage for the traffic sketch
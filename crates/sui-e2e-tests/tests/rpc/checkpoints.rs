@@ -426,6 +426,8 @@ async fn subscribe_checkpoint() {
         read_mask: Some(prost_types::FieldMask {
             paths: vec!["sequence_number".to_owned()],
         }),
+        start_cursor: None,
+        filter: None,
     };
 
     let mut stream = client
@@ -459,3 +461,123 @@ async fn subscribe_checkpoint() {
 
     assert!(count >= 50);
 }
+
+#[sim_test]
+async fn subscribe_checkpoint_with_cursor_and_filter() {
+    use sui_rpc_api::proto::node::v2alpha::subscription_service_client::SubscriptionServiceClient;
+    use sui_rpc_api::proto::node::v2alpha::{
+        CheckpointTransactionFilter, SubscribeCheckpointsRequest,
+    };
+    use tokio_stream::StreamExt;
+
+    let test_cluster = TestClusterBuilder::new().build().await;
+
+    let sender = test_cluster
+        .wallet
+        .get_all_accounts_and_gas_objects()
+        .await
+        .unwrap()[0]
+        .0;
+    let transaction_digest = transfer_coin(&test_cluster.wallet).await;
+
+    let mut client = SubscriptionServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    // Replay from genesis, only including transactions sent by `sender`.
+    let request = SubscribeCheckpointsRequest {
+        read_mask: Some(prost_types::FieldMask {
+            paths: vec![
+                "sequence_number".to_owned(),
+                "transactions.digest".to_owned(),
+            ],
+        }),
+        start_cursor: Some(0),
+        filter: Some(CheckpointTransactionFilter {
+            senders: vec![sui_sdk_types::Address::from(sender).into()],
+            ..Default::default()
+        }),
+    };
+
+    let mut stream = client
+        .subscribe_checkpoints(request)
+        .await
+        .unwrap()
+        .into_inner();
+
+    let expected: sui_rpc_api::proto::types::Digest =
+        sui_sdk_types::Digest::from(transaction_digest).into();
+
+    let mut next = 0;
+    let mut found = false;
+    while let Some(item) = stream.next().await {
+        let response = item.unwrap();
+        let cursor = response.cursor.unwrap();
+        let checkpoint = response.checkpoint.unwrap();
+
+        // Checkpoints are returned in order, without gaps, starting from the cursor.
+        assert_eq!(cursor, next);
+        assert_eq!(cursor, checkpoint.sequence_number.unwrap());
+        next += 1;
+
+        // Genesis is not sent by `sender`, so it should be filtered out.
+        if cursor == 0 {
+            assert!(checkpoint.transactions.is_empty());
+        }
+
+        if checkpoint
+            .transactions
+            .iter()
+            .any(|tx| tx.digest.as_ref() == Some(&expected))
+        {
+            found = true;
+            break;
+        }
+    }
+
+    assert!(found);
+
+    // Subscribing from a checkpoint that has not been executed yet waits for it.
+    let request = SubscribeCheckpointsRequest {
+        read_mask: Some(prost_types::FieldMask {
+            paths: vec!["sequence_number".to_owned()],
+        }),
+        start_cursor: Some(next + 5),
+        filter: None,
+    };
+
+    let mut stream = client
+        .subscribe_checkpoints(request)
+        .await
+        .unwrap()
+        .into_inner();
+
+    let response = stream.next().await.unwrap().unwrap();
+    assert_eq!(response.cursor.unwrap(), next + 5);
+}
+
+#[sim_test]
+async fn subscribe_checkpoint_invalid_filter() {
+    use sui_rpc_api::proto::node::v2alpha::subscription_service_client::SubscriptionServiceClient;
+    use sui_rpc_api::proto::node::v2alpha::{
+        CheckpointTransactionFilter, SubscribeCheckpointsRequest,
+    };
+
+    let test_cluster = TestClusterBuilder::new().build().await;
+
+    let mut client = SubscriptionServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    let request = SubscribeCheckpointsRequest {
+        read_mask: None,
+        start_cursor: None,
+        filter: Some(CheckpointTransactionFilter {
+            event_types: vec!["0x2::coin::Coin::value".to_owned()],
+            ..Default::default()
+        }),
+    };
+
+    let status = client.subscribe_checkpoints(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
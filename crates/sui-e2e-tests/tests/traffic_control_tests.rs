@@ -8,12 +8,13 @@ use core::panic;
 use fastcrypto::encoding::Base64;
 use jsonrpsee::{core::client::ClientT, rpc_params};
 use std::fs::File;
+use std::net::{IpAddr, Ipv4Addr};
 use std::num::NonZeroUsize;
 use std::time::Duration;
 use sui_core::authority_client::make_network_authority_clients_with_network_config;
 use sui_core::authority_client::AuthorityAPI;
 use sui_core::traffic_controller::{
-    nodefw_test_server::NodeFwTestServer, TrafficController, TrafficSim,
+    nodefw_test_server::NodeFwTestServer, policies::TrafficTally, TrafficController, TrafficSim,
 };
 use sui_json_rpc_types::{
     SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
@@ -27,7 +28,8 @@ use sui_types::{
     quorum_driver_types::ExecuteTransactionRequestType,
     signature::GenericSignature,
     traffic_control::{
-        FreqThresholdConfig, PolicyConfig, PolicyType, RemoteFirewallConfig, Weight,
+        ErrorRatioConfig, FreqThresholdConfig, PolicyConfig, PolicyType, RemoteFirewallConfig,
+        TokenBucketConfig, Weight,
     },
 };
 use test_cluster::{TestCluster, TestClusterBuilder};
@@ -584,6 +586,106 @@ async fn test_traffic_control_dead_mans_switch() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_traffic_control_error_ratio_blocklist() -> Result<(), anyhow::Error> {
+    let policy_config = PolicyConfig {
+        connection_blocklist_ttl_sec: 60,
+        error_policy_type: PolicyType::ErrorRatio(ErrorRatioConfig {
+            error_ratio_threshold: 0.5,
+            min_requests: 10,
+            ..Default::default()
+        }),
+        dry_run: false,
+        ..Default::default()
+    };
+    let tc = TrafficController::init_for_test(policy_config, None);
+
+    // one client's requests all succeed, while the other's all fail
+    let good = Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+    let bad = Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)));
+    for _ in 0..10 {
+        tc.tally(TrafficTally::new(good, None, None, Weight::one()));
+        tc.tally(TrafficTally::new(
+            bad,
+            None,
+            Some((Weight::one(), "InvalidSignature".to_string())),
+            Weight::one(),
+        ));
+    }
+
+    let mut blocked = false;
+    for _ in 0..10 {
+        if !tc.check(&bad, &None).await {
+            blocked = true;
+            break;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+    assert!(blocked, "Expected failing client to be blocked");
+    assert!(tc.check(&good, &None).await);
+
+    let blocklist = tc.blocklist();
+    assert_eq!(blocklist.len(), 1);
+    assert_eq!(Some(blocklist[0].client), bad);
+    assert!(!blocklist[0].proxied);
+
+    // operators can manually unblock clients
+    assert_eq!(tc.clear_blocklist(bad), 1);
+    assert!(tc.check(&bad, &None).await);
+    assert!(tc.blocklist().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_traffic_control_error_ratio_unsampled_errors() -> Result<(), anyhow::Error> {
+    let policy_config = PolicyConfig {
+        connection_blocklist_ttl_sec: 60,
+        error_policy_type: PolicyType::ErrorRatio(ErrorRatioConfig {
+            error_ratio_threshold: 0.5,
+            min_requests: 10,
+            ..Default::default()
+        }),
+        dry_run: false,
+        ..Default::default()
+    };
+    let tc = TrafficController::init_for_test(policy_config, None);
+    let client = Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+    let error = |weight| {
+        TrafficTally::new(
+            client,
+            None,
+            Some((weight, "InvalidSignature".to_string())),
+            Weight::one(),
+        )
+    };
+
+    // Errors that aren't sampled still count as requests, so a third of the
+    // client's requests have failed, which is below the threshold.
+    for _ in 0..20 {
+        tc.tally(error(Weight::zero()));
+    }
+    for _ in 0..10 {
+        tc.tally(error(Weight::one()));
+    }
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    assert!(tc.check(&client, &None).await);
+
+    // Once half of its requests have failed, the client is blocked.
+    for _ in 0..10 {
+        tc.tally(error(Weight::one()));
+    }
+    let mut blocked = false;
+    for _ in 0..10 {
+        if !tc.check(&client, &None).await {
+            blocked = true;
+            break;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+    assert!(blocked, "Expected failing client to be blocked");
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_traffic_control_manual_set_dead_mans_switch() -> Result<(), anyhow::Error> {
     let drain_path = tempfile::tempdir().unwrap().into_path().join("drain");
@@ -737,6 +839,44 @@ async fn test_traffic_sketch_allowlist_mode() {
     assert!(metrics.num_requests < expected_requests + 200);
 }
 
+#[sim_test]
+async fn test_traffic_token_bucket_with_allowlisted_client() {
+    let token_bucket_config = TokenBucketConfig {
+        client_capacity: 5_000,
+        client_refill_rate: 2_000.0,
+        // first client allowlisted, second is rate limited
+        allowlist: vec![String::from("127.0.0.0/32")],
+        ..Default::default()
+    };
+    let policy = PolicyConfig {
+        connection_blocklist_ttl_sec: 1,
+        proxy_blocklist_ttl_sec: 1,
+        spam_policy_type: PolicyType::TokenBucket(token_bucket_config),
+        spam_sample_rate: Weight::one(),
+        dry_run: false,
+        ..Default::default()
+    };
+    let metrics = TrafficSim::run(
+        policy,
+        2,      // num_clients
+        10_000, // per_client_tps
+        Duration::from_secs(10),
+        true, // report
+    )
+    .await;
+
+    let expected_requests = 10_000 * 10 * 2;
+    assert!(metrics.num_requests > expected_requests - 1_000);
+    assert!(metrics.num_requests < expected_requests + 200);
+    // The rate limited client exhausts its burst capacity well within the
+    // first second, and then spends most of its time blocked, as each
+    // refill is consumed faster than it is replenished.
+    assert!(metrics.abs_time_to_first_block.unwrap() < Duration::from_secs(2));
+    assert!(metrics.num_blocked > expected_requests / 4);
+    // The allowlisted client is never blocked.
+    assert!(metrics.num_blocked < expected_requests / 2);
+}
+
 async fn assert_traffic_control_ok(mut test_cluster: TestCluster) -> Result<(), anyhow::Error> {
     let context = &mut test_cluster.wallet;
     let jsonrpc_client = &test_cluster.fullnode_handle.rpc_client;
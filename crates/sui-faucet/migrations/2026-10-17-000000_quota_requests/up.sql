@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS faucet_quota_requests
+(
+    id                          BIGSERIAL        PRIMARY KEY,
+    -- The IP address or recipient address that made the request.
+    client                      TEXT             NOT NULL,
+    -- When the request was served, in milliseconds since the Unix epoch.
+    requested_at_ms             BIGINT           NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS faucet_quota_requests_client
+ON faucet_quota_requests (client, requested_at_ms);
+
+CREATE INDEX IF NOT EXISTS faucet_quota_requests_requested_at
+ON faucet_quota_requests (requested_at_ms);
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -11,6 +12,12 @@ pub enum FaucetError {
     #[error("Request limit exceeded. {0}")]
     TooManyRequests(String),
 
+    #[error("Request limit exceeded for {key}. You can request a new token in {retry_after}")]
+    QuotaExhausted {
+        key: String,
+        retry_after: RetryAfter,
+    },
+
     #[error("Faucet cannot read objects from fullnode: {0}")]
     FullnodeReadingError(String),
 
@@ -31,6 +38,12 @@ pub enum FaucetError {
     #[error("Timed out waiting for a coin from the gas coin pool")]
     NoGasCoinAvailable,
 
+    #[error("Timed out waiting for a coin from the `{0}` coin pool")]
+    NoCoinAvailable(String),
+
+    #[error("Coin type `{0}` is not dispensed by this faucet")]
+    UnsupportedCoinType(String),
+
     #[error("Wallet Error: `{0}`")]
     Wallet(String),
 
@@ -51,6 +64,9 @@ pub enum FaucetError {
 
     #[error("Invalid user agent: {0}")]
     InvalidUserAgent(String),
+
+    #[error("Missing or invalid admin token")]
+    InvalidAdminToken,
 }
 
 impl FaucetError {
@@ -58,3 +74,52 @@ impl FaucetError {
         FaucetError::Internal(e.to_string())
     }
 }
+
+/// Number of seconds a client has to wait before its quota allows another request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryAfter(pub u64);
+
+impl fmt::Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", secs_to_human_readable(self.0))
+    }
+}
+
+/// Format seconds to human readable format.
+fn secs_to_human_readable(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let seconds = seconds % 60;
+
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secs_to_human_readable() {
+        // Test seconds only
+        assert_eq!(secs_to_human_readable(45), "45s");
+        assert_eq!(secs_to_human_readable(1), "1s");
+
+        // Test minutes and seconds
+        assert_eq!(secs_to_human_readable(65), "1m 5s");
+        assert_eq!(secs_to_human_readable(3599), "59m 59s");
+
+        // Test hours, minutes, and seconds
+        assert_eq!(secs_to_human_readable(3600), "1h 0m 0s");
+        assert_eq!(secs_to_human_readable(3661), "1h 1m 1s");
+        assert_eq!(secs_to_human_readable(7384), "2h 3m 4s");
+
+        // Test edge case
+        assert_eq!(secs_to_human_readable(0), "0s");
+    }
+}
@@ -1,16 +1,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::FaucetError;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::gas_coin::GAS;
+use sui_types::{parse_sui_type_tag, TypeTag};
 use uuid::Uuid;
 
 mod simple_faucet;
 mod write_ahead_log;
 pub use self::simple_faucet::SimpleFaucet;
 use clap::Parser;
-use std::{net::Ipv4Addr, path::PathBuf, sync::Arc};
+use std::{net::Ipv4Addr, path::PathBuf, str::FromStr, sync::Arc};
+use url::Url;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FaucetReceipt {
@@ -28,6 +32,10 @@ pub struct CoinInfo {
     pub amount: u64,
     pub id: ObjectID,
     pub transfer_tx_digest: TransactionDigest,
+    /// Canonical type of the coin's balance, e.g. `0x...02::sui::SUI`. Defaults to SUI when
+    /// reading receipts from faucets that predate multi-coin support.
+    #[serde(default = "sui_coin_type")]
+    pub coin_type: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -55,6 +63,20 @@ impl<F> AppState<F> {
     }
 }
 
+/// A coin type other than SUI that the faucet dispenses, configured on the command line as
+/// `<coin type>=<amount>[@<treasury cap id>]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispensableCoin {
+    pub coin_type: TypeTag,
+
+    /// Amount of each coin sent per request.
+    pub amount: u64,
+
+    /// `TreasuryCap` owned by the faucet to mint coins of this type with. If not set, coins are
+    /// split off the coins of this type that the faucet owns.
+    pub treasury_cap: Option<ObjectID>,
+}
+
 #[async_trait]
 pub trait Faucet {
     /// Send `Coin<SUI>` of the specified amount to the recipient
@@ -63,7 +85,10 @@ pub trait Faucet {
         id: Uuid,
         recipient: SuiAddress,
         amounts: &[u64],
-    ) -> Result<FaucetReceipt, FaucetError>;
+    ) -> Result<FaucetReceipt, FaucetError> {
+        self.send_coin(id, recipient, &GAS::type_tag(), amounts)
+            .await
+    }
 
     /// Send `Coin<SUI>` of the specified amount to the recipient in a batch request
     async fn batch_send(
@@ -71,6 +96,28 @@ pub trait Faucet {
         id: Uuid,
         recipient: SuiAddress,
         amounts: &[u64],
+    ) -> Result<BatchFaucetReceipt, FaucetError> {
+        self.batch_send_coin(id, recipient, &GAS::type_tag(), amounts)
+            .await
+    }
+
+    /// Send coins of type `coin_type` (SUI, or one of the faucet's dispensable coin types) of the
+    /// specified amount to the recipient
+    async fn send_coin(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        coin_type: &TypeTag,
+        amounts: &[u64],
+    ) -> Result<FaucetReceipt, FaucetError>;
+
+    /// Send coins of type `coin_type` of the specified amount to the recipient in a batch request
+    async fn batch_send_coin(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        coin_type: &TypeTag,
+        amounts: &[u64],
     ) -> Result<BatchFaucetReceipt, FaucetError>;
 
     /// Get the status of a batch_send request
@@ -135,6 +182,16 @@ pub struct FaucetConfig {
     #[clap(long, default_value_t = 3)]
     pub max_requests_per_ip: u64,
 
+    /// Maximum number of requests per recipient address. This is used for the authenticated mode.
+    #[clap(long, default_value_t = 3)]
+    pub max_requests_per_address: u64,
+
+    /// URL of the Postgres database that records requests per IP address and recipient, so that
+    /// quotas are shared between faucet replicas and survive restarts. If not set, requests are
+    /// only recorded in memory. This is used for authenticated mode.
+    #[clap(long)]
+    pub quota_database_url: Option<Url>,
+
     /// This is the amount of time to wait before adding one more quota to the rate limiter. Basically,
     /// it ensures that we're not allowing too many requests all at once. This is very specific to
     /// governor and tower-governor crates. This is used primarily for authenticated mode. A small
@@ -142,15 +199,21 @@ pub struct FaucetConfig {
     #[clap(long, default_value_t = 10)]
     pub replenish_quota_interval_ms: u64,
 
-    /// The amount of seconds to wait before resetting the request count for the IP addresses recorded
-    /// by the rate limit layer. Default is 12 hours. This is used for authenticated mode.
+    /// The length of the rolling window, in seconds, over which requests count against the quotas
+    /// of IP addresses and recipients. Default is 12 hours. This is used for authenticated mode.
     #[clap(long, default_value_t = 3600*12)]
     pub reset_time_interval_secs: u64,
 
-    /// Interval time to run the task to clear the banned IP addresses by the rate limiter. This is
-    /// used for authenticated mode.
+    /// Interval time to run the task to prune IP addresses and recipients whose requests have all
+    /// left the window from the quota ledger. This is used for authenticated mode.
     #[clap(long, default_value_t = 60)]
     pub rate_limiter_cleanup_interval_secs: u64,
+
+    /// Coin types other than SUI to dispense, each as `<coin type>=<amount>[@<treasury cap id>]`.
+    /// Requests for these types receive `num_coins` coins of `amount` each, minted with the
+    /// treasury cap if one is given, or split off the faucet's own coins of that type otherwise.
+    #[clap(long = "coin")]
+    pub coins: Vec<DispensableCoin>,
 }
 
 impl Default for FaucetConfig {
@@ -171,9 +234,134 @@ impl Default for FaucetConfig {
             batch_enabled: false,
             authenticated: false,
             max_requests_per_ip: 3,
+            max_requests_per_address: 3,
+            quota_database_url: None,
             replenish_quota_interval_ms: 10,
             reset_time_interval_secs: 3600 * 12,
             rate_limiter_cleanup_interval_secs: 60,
+            coins: vec![],
         }
     }
 }
+
+impl FaucetConfig {
+    /// The type and amounts of coins to send in response to a request for `coin_type`, or for SUI
+    /// if the request does not specify a type.
+    pub fn amounts_for(&self, coin_type: Option<&str>) -> Result<(TypeTag, Vec<u64>), FaucetError> {
+        let Some(coin_type) = coin_type else {
+            return Ok((GAS::type_tag(), vec![self.amount; self.num_coins]));
+        };
+
+        let type_ = parse_sui_type_tag(coin_type)
+            .map_err(|e| FaucetError::UnsupportedCoinType(format!("{coin_type} ({e})")))?;
+
+        if type_ == GAS::type_tag() {
+            return Ok((type_, vec![self.amount; self.num_coins]));
+        }
+
+        let coin = self
+            .coins
+            .iter()
+            .find(|coin| coin.coin_type == type_)
+            .ok_or_else(|| FaucetError::UnsupportedCoinType(coin_type.to_string()))?;
+
+        Ok((type_, vec![coin.amount; self.num_coins]))
+    }
+}
+
+impl FromStr for DispensableCoin {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (coin_type, rest) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected <coin type>=<amount>[@<treasury cap id>]"))?;
+
+        let (amount, treasury_cap) = match rest.split_once('@') {
+            Some((amount, cap)) => (amount, Some(cap)),
+            None => (rest, None),
+        };
+
+        let coin_type = parse_sui_type_tag(coin_type.trim())
+            .with_context(|| format!("Invalid coin type: {coin_type}"))?;
+
+        if coin_type == GAS::type_tag() {
+            return Err(anyhow!("SUI is configured with --amount, not --coin"));
+        }
+
+        let amount = amount
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid amount: {amount}"))?;
+
+        let treasury_cap = treasury_cap
+            .map(|cap| ObjectID::from_str(cap.trim()))
+            .transpose()
+            .with_context(|| format!("Invalid treasury cap: {s}"))?;
+
+        Ok(Self {
+            coin_type,
+            amount,
+            treasury_cap,
+        })
+    }
+}
+
+fn sui_coin_type() -> String {
+    GAS::type_tag().to_canonical_string(/* with_prefix */ true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dispensable_coin() {
+        let coin: DispensableCoin = "0x42::social::SOCIAL=1000".parse().unwrap();
+        assert_eq!(
+            coin.coin_type,
+            parse_sui_type_tag("0x42::social::SOCIAL").unwrap()
+        );
+        assert_eq!(coin.amount, 1000);
+        assert_eq!(coin.treasury_cap, None);
+
+        let coin: DispensableCoin = "0x42::social::SOCIAL=5@0x7".parse().unwrap();
+        assert_eq!(coin.amount, 5);
+        assert_eq!(coin.treasury_cap, Some(ObjectID::from_str("0x7").unwrap()));
+
+        assert!("0x42::social::SOCIAL".parse::<DispensableCoin>().is_err());
+        assert!("0x42::social::SOCIAL=lots"
+            .parse::<DispensableCoin>()
+            .is_err());
+        assert!("0x42::social::SOCIAL=5@cap"
+            .parse::<DispensableCoin>()
+            .is_err());
+        assert!("0x2::sui::SUI=5".parse::<DispensableCoin>().is_err());
+    }
+
+    #[test]
+    fn amounts_for_coin_types() {
+        let config = FaucetConfig {
+            coins: vec!["0x42::social::SOCIAL=7".parse().unwrap()],
+            num_coins: 2,
+            ..Default::default()
+        };
+
+        let (type_, amounts) = config.amounts_for(None).unwrap();
+        assert_eq!(type_, GAS::type_tag());
+        assert_eq!(amounts, vec![config.amount; 2]);
+
+        let (type_, amounts) = config.amounts_for(Some("0x2::sui::SUI")).unwrap();
+        assert_eq!(type_, GAS::type_tag());
+        assert_eq!(amounts, vec![config.amount; 2]);
+
+        let (type_, amounts) = config.amounts_for(Some("0x0042::social::SOCIAL")).unwrap();
+        assert_eq!(type_, parse_sui_type_tag("0x42::social::SOCIAL").unwrap());
+        assert_eq!(amounts, vec![7; 2]);
+
+        assert!(matches!(
+            config.amounts_for(Some("0x43::social::SOCIAL")),
+            Err(FaucetError::UnsupportedCoinType(_)),
+        ));
+    }
+}
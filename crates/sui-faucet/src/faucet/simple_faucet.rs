@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::faucet::write_ahead_log;
+use crate::faucet::write_ahead_log::{self, TokenSource};
 use crate::metrics::FaucetMetrics;
 use async_recursion::async_recursion;
 use async_trait::async_trait;
@@ -26,12 +26,14 @@ use sui_json_rpc_types::{
 };
 use sui_keys::keystore::AccountKeystore;
 use sui_sdk::wallet_context::WalletContext;
-use sui_types::object::Owner;
+use sui_types::coin::COIN_MODULE_NAME;
+use sui_types::object::{Object, Owner};
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::{
-    base_types::{ObjectID, SuiAddress, TransactionDigest},
-    gas_coin::GasCoin,
-    transaction::{Transaction, TransactionData},
+    base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest},
+    gas_coin::{GasCoin, GAS},
+    transaction::{ObjectArg, Transaction, TransactionData},
+    Identifier, TypeTag, SUI_FRAMEWORK_PACKAGE_ID,
 };
 use tokio::sync::{
     mpsc::{self, Receiver, Sender},
@@ -43,10 +45,12 @@ use uuid::Uuid;
 
 use super::write_ahead_log::WriteAheadLog;
 use crate::{
-    BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo, Faucet, FaucetConfig,
-    FaucetError, FaucetReceipt,
+    BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo, DispensableCoin, Faucet,
+    FaucetConfig, FaucetError, FaucetReceipt,
 };
 
+type TokenRequest = (Uuid, SuiAddress, TypeTag, Vec<u64>);
+
 pub struct SimpleFaucet {
     wallet: WalletContext,
     active_address: SuiAddress,
@@ -57,6 +61,11 @@ pub struct SimpleFaucet {
     pub metrics: FaucetMetrics,
     pub wal: Mutex<WriteAheadLog>,
     request_producer: Sender<(Uuid, SuiAddress, Vec<u64>)>,
+    /// Pools for the coin types other than SUI that the faucet dispenses, keyed by coin type.
+    token_pools: HashMap<TypeTag, TokenPool>,
+    /// Batch requests for coin types other than SUI, which are served individually, in the
+    /// background.
+    token_request_producer: Sender<TokenRequest>,
     batch_request_size: u64,
     task_id_cache: Mutex<TtlCache<Uuid, BatchSendStatus>>,
     ttl_expiration: u64,
@@ -81,6 +90,14 @@ impl fmt::Debug for SimpleFaucet {
     }
 }
 
+/// Objects that coins of a type other than SUI are dispensed from: the faucet's coins of that
+/// type, or the type's treasury cap.
+struct TokenPool {
+    config: DispensableCoin,
+    producer: Mutex<Sender<ObjectID>>,
+    consumer: Mutex<Receiver<ObjectID>>,
+}
+
 enum GasCoinResponse {
     GasCoinWithInsufficientBalance(ObjectID),
     InvalidGasCoin(ObjectID),
@@ -162,6 +179,44 @@ impl SimpleFaucet {
                     .unwrap();
             }
         }
+
+        // Objects from token pools that are spent by pending transactions are recycled once
+        // those transactions are retried, so they are kept out of their pools for now.
+        let mut token_pools = HashMap::new();
+        for coin in &config.coins {
+            let sources =
+                find_token_sources(&wallet, active_address, coin, config.num_coins).await?;
+            let in_flight = wal
+                .tokens_in_flight(&coin.coin_type)
+                .map_err(FaucetError::internal)?;
+
+            let (producer, consumer) = mpsc::channel(sources.len().max(1));
+            for id in sources {
+                if in_flight.contains(&id) {
+                    continue;
+                }
+
+                producer
+                    .send(id)
+                    .await
+                    .tap_ok(|_| info!(?id, coin_type = %coin.coin_type, "Adding to coin pool"))
+                    .tap_err(|e| error!(?id, "Failed to add to coin pool: {e:?}"))
+                    .unwrap();
+            }
+
+            token_pools.insert(
+                coin.coin_type.clone(),
+                TokenPool {
+                    config: coin.clone(),
+                    producer: Mutex::new(producer),
+                    consumer: Mutex::new(consumer),
+                },
+            );
+        }
+
+        let (token_request_producer, mut token_request_consumer) =
+            mpsc::channel::<TokenRequest>(config.max_request_queue_length as usize);
+
         let (batch_transfer_shutdown, mut rx_batch_transfer_shutdown) = oneshot::channel();
 
         let faucet = Self {
@@ -174,6 +229,8 @@ impl SimpleFaucet {
             metrics,
             wal: Mutex::new(wal),
             request_producer: sender,
+            token_pools,
+            token_request_producer,
             batch_request_size: config.batch_request_size,
             // Max faucet requests times 10 minutes worth of requests to hold onto at max.
             // Note that the cache holds onto a Uuid for [ttl_expiration] in from every update in status with both INPROGRESS and SUCCEEDED
@@ -211,6 +268,39 @@ impl SimpleFaucet {
                 }
             }
         });
+
+        let token_clone = Arc::downgrade(&arc_faucet);
+        spawn_monitored_task!(async move {
+            info!("Starting task to handle batch faucet requests for other coin types.");
+            while let Some((uuid, recipient, coin_type, amounts)) =
+                token_request_consumer.recv().await
+            {
+                let Some(faucet) = token_clone.upgrade() else {
+                    info!("Faucet has shut down already. Exiting ...");
+                    return;
+                };
+
+                spawn_monitored_task!(async move {
+                    let result = faucet
+                        .send_coin(uuid, recipient, &coin_type, &amounts)
+                        .await;
+
+                    // Successful sends record their own status.
+                    if let Err(err) = result {
+                        error!(?uuid, %coin_type, "Batch transfer failed: {:?}", err);
+                        faucet.task_id_cache.lock().await.insert(
+                            uuid,
+                            BatchSendStatus {
+                                status: BatchSendStatusType::DISCARDED,
+                                transferred_gas_objects: None,
+                            },
+                            Duration::from_secs(faucet.ttl_expiration),
+                        );
+                    }
+                });
+            }
+        });
+
         // Retrying all the pending transactions from the WAL, before continuing.  Ignore return
         // values -- if the executions failed, the pending coins will simply remain in the WAL, and
         // not recycled.
@@ -275,6 +365,87 @@ impl SimpleFaucet {
         Some(coin)
     }
 
+    /// Take the consumer lock for `pool` and pull an object from it, without checking whether it
+    /// is valid or not.
+    async fn pop_token_source(&self, pool: &TokenPool, uuid: Uuid) -> Option<ObjectID> {
+        let coin_type = &pool.config.coin_type;
+        let Ok(mut consumer) = tokio::time::timeout(LOCK_TIMEOUT, pool.consumer.lock()).await
+        else {
+            error!(?uuid, %coin_type, "Timeout when getting consumer lock");
+            return None;
+        };
+
+        let Ok(id) = tokio::time::timeout(RECV_TIMEOUT, consumer.recv()).await else {
+            error!(?uuid, %coin_type, "Timeout when getting coin from the queue");
+            return None;
+        };
+
+        let Some(id) = id else {
+            unreachable!("channel is closed");
+        };
+
+        Some(id)
+    }
+
+    /// Pulls objects from `pool` until it finds one that can dispense `total_amount`: it belongs
+    /// to the faucet, and it is either the pool's treasury cap, or a coin of the pool's type with
+    /// sufficient balance. Objects that are unfit are removed from the pool.
+    async fn prepare_token_source(
+        &self,
+        pool: &TokenPool,
+        total_amount: u64,
+        uuid: Uuid,
+    ) -> Result<ObjectRef, FaucetError> {
+        let coin_type = &pool.config.coin_type;
+        loop {
+            let Some(id) = self.pop_token_source(pool, uuid).await else {
+                warn!(%coin_type, "Failed getting coin, try later!");
+                return Err(FaucetError::NoCoinAvailable(coin_type.to_string()));
+            };
+
+            let object = match self.get_object(id).await {
+                Ok(Some(object)) if object.owner == Owner::AddressOwner(self.active_address) => {
+                    object
+                }
+
+                Ok(_) => {
+                    warn!(?uuid, ?id, %coin_type, "Invalid, removing from pool");
+                    continue;
+                }
+
+                Err(e) => {
+                    error!(?uuid, ?id, "Fullnode read error: {e:?}");
+                    self.recycle_token_source(pool, id, uuid).await;
+                    return Err(FaucetError::FullnodeReadingError(format!(
+                        "unknown {coin_type} coin {id:?}"
+                    )));
+                }
+            };
+
+            if pool.config.treasury_cap.is_some() {
+                return Ok(object.compute_object_reference());
+            }
+
+            match object.as_coin_maybe() {
+                Some(coin)
+                    if object.coin_type_maybe().as_ref() == Some(coin_type)
+                        && coin.value() >= total_amount =>
+                {
+                    info!(?uuid, ?id, %coin_type, "balance: {}", coin.value());
+                    return Ok(object.compute_object_reference());
+                }
+
+                Some(_) => {
+                    warn!(?uuid, ?id, %coin_type, "Insufficient balance, removing from pool");
+                }
+
+                None => {
+                    warn!(?uuid, ?id, %coin_type, "Not a coin, removing from pool");
+                }
+            }
+        }
+    }
+
     /// Pulls a coin from the queue and makes sure it is fit for use (belongs to the faucet, has
     /// sufficient balance).
     async fn prepare_gas_coin(
@@ -347,6 +518,29 @@ impl SimpleFaucet {
         }
     }
 
+    /// Read the latest version of the object with ID `id`, if it exists.
+    async fn get_object(&self, id: ObjectID) -> anyhow::Result<Option<Object>> {
+        let client = self.wallet.get_client().await?;
+        let response = client
+            .read_api()
+            .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
+            .await?;
+        response.data.map(TryInto::try_into).transpose()
+    }
+
+    /// The balance of coin `coin_id`, if it exists, reading it as a gas coin if `is_gas`, or as a
+    /// coin of any type otherwise.
+    async fn get_coin_value(&self, coin_id: ObjectID, is_gas: bool) -> anyhow::Result<Option<u64>> {
+        if is_gas {
+            Ok(self.get_coin(coin_id).await?.map(|(_, coin)| coin.value()))
+        } else {
+            let object = self.get_object(coin_id).await?;
+            Ok(object
+                .and_then(|o| o.as_coin_maybe())
+                .map(|coin| coin.value()))
+        }
+    }
+
     /// Similar to get_coin but checks that the owner is the active
     /// faucet address. If the coin exists, but does not have the correct owner,
     /// returns None
@@ -466,8 +660,18 @@ impl SimpleFaucet {
                 // race with the next request served with this coin.  If this operation fails, log
                 // it and continue so we don't lose access to the coin -- the worst that can happen
                 // is that the WAL contains a stale entry.
-                if self.wal.lock().await.commit(coin_id).is_err() {
-                    error!(?coin_id, "Failed to remove coin from WAL");
+                let token = {
+                    let mut wal = self.wal.lock().await;
+                    let token = wal.token(coin_id).ok().flatten();
+                    if wal.commit(coin_id).is_err() {
+                        error!(?coin_id, "Failed to remove coin from WAL");
+                    }
+                    token
+                };
+                if let Some(TokenSource { coin_type, id }) = token {
+                    if let Some(pool) = self.token_pools.get(&coin_type) {
+                        self.recycle_token_source(pool, id, uuid).await;
+                    }
                 }
                 if for_batch {
                     self.recycle_gas_coin_for_batch(coin_id, uuid).await;
@@ -556,6 +760,86 @@ impl SimpleFaucet {
         }
     }
 
+    /// Send coins of the type in `pool` to `recipient`, dispensed from an object in `pool`, and
+    /// paying for gas from the SUI gas coin pool.
+    async fn transfer_tokens(
+        &self,
+        pool: &TokenPool,
+        amounts: &[u64],
+        recipient: SuiAddress,
+        uuid: Uuid,
+    ) -> Result<(TransactionDigest, Vec<ObjectID>), FaucetError> {
+        let number_of_coins = amounts.len();
+        let total_amount: u64 = amounts.iter().sum();
+        let gas_cost = self.get_gas_cost().await?;
+
+        let source = self.prepare_token_source(pool, total_amount, uuid).await?;
+        let coin_id = loop {
+            match self.prepare_gas_coin(gas_cost, uuid, false).await {
+                GasCoinResponse::ValidGasCoin(coin_id) => break coin_id,
+
+                GasCoinResponse::UnknownGasCoin(coin_id) => {
+                    self.recycle_gas_coin(coin_id, uuid).await;
+                    self.recycle_token_source(pool, source.0, uuid).await;
+                    return Err(FaucetError::FullnodeReadingError(format!(
+                        "unknown gas coin {coin_id:?}"
+                    )));
+                }
+
+                GasCoinResponse::GasCoinWithInsufficientBalance(coin_id)
+                | GasCoinResponse::InvalidGasCoin(coin_id) => {
+                    warn!(?uuid, ?coin_id, "Unfit for gas, removing from pool");
+                    self.metrics.total_discarded_coins.inc();
+                }
+
+                GasCoinResponse::NoGasCoinAvailable => {
+                    self.recycle_token_source(pool, source.0, uuid).await;
+                    return Err(FaucetError::NoGasCoinAvailable);
+                }
+            }
+        };
+
+        let tx_data = match self
+            .build_token_txn(pool, source, coin_id, recipient, amounts, gas_cost)
+            .await
+        {
+            Ok(tx_data) => tx_data,
+            Err(e) => {
+                self.recycle_gas_coin(coin_id, uuid).await;
+                self.recycle_token_source(pool, source.0, uuid).await;
+                return Err(FaucetError::internal(e));
+            }
+        };
+
+        {
+            // Register the intention to send this transaction before we send it, so that if
+            // faucet fails or we give up before we get a definite response, we have a chance
+            // to retry later.
+            let token = TokenSource {
+                coin_type: pool.config.coin_type.clone(),
+                id: source.0,
+            };
+            let mut wal = self.wal.lock().await;
+            wal.reserve_with_token(uuid, coin_id, Some(token), recipient, tx_data.clone())
+                .map_err(FaucetError::internal)?;
+        }
+
+        let response = self
+            .sign_and_execute_txn(uuid, recipient, coin_id, tx_data, false)
+            .await?;
+        self.metrics.total_coin_requests_succeeded.inc();
+        self.check_and_map_transfer_gas_result(response, number_of_coins, recipient)
+            .await
+    }
+
+    async fn recycle_token_source(&self, pool: &TokenPool, id: ObjectID, uuid: Uuid) {
+        let producer = pool.producer.lock().await;
+        producer
+            .try_send(id)
+            .expect("unexpected - queue is large enough to hold all coins");
+        info!(?uuid, ?id, coin_type = %pool.config.coin_type, "Recycled coin");
+    }
+
     async fn recycle_gas_coin(&self, coin_id: ObjectID, uuid: Uuid) {
         // Once transactions are done, in despite of success or failure,
         // we put back the coins. The producer should never wait indefinitely,
@@ -690,6 +974,53 @@ impl SimpleFaucet {
             })
     }
 
+    /// Build a transaction sending coins of the type in `pool` to `recipient`: minting them with
+    /// `source` if it is the type's treasury cap, or splitting them off `source` otherwise.
+    async fn build_token_txn(
+        &self,
+        pool: &TokenPool,
+        source: ObjectRef,
+        coin_id: ObjectID,
+        recipient: SuiAddress,
+        amounts: &[u64],
+        budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let gas_payment = self.wallet.get_object_ref(coin_id).await?;
+        let gas_price = self.wallet.get_reference_gas_price().await?;
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            if pool.config.treasury_cap.is_some() {
+                let cap = builder.obj(ObjectArg::ImmOrOwnedObject(source))?;
+                let recipient = builder.pure(recipient)?;
+                for amount in amounts {
+                    let amount = builder.pure(*amount)?;
+                    builder.programmable_move_call(
+                        SUI_FRAMEWORK_PACKAGE_ID,
+                        COIN_MODULE_NAME.to_owned(),
+                        Identifier::new("mint_and_transfer")?,
+                        vec![pool.config.coin_type.clone()],
+                        vec![cap, amount, recipient],
+                    );
+                }
+            } else {
+                builder.pay(
+                    vec![source],
+                    vec![recipient; amounts.len()],
+                    amounts.to_vec(),
+                )?;
+            }
+            builder.finish()
+        };
+
+        Ok(TransactionData::new_programmable(
+            self.active_address,
+            vec![gas_payment],
+            pt,
+            budget,
+            gas_price,
+        ))
+    }
+
     async fn check_and_map_transfer_gas_result(
         &self,
         res: SuiTransactionBlockResponse,
@@ -813,6 +1144,7 @@ impl SimpleFaucet {
                     id: coin.object_id(),
                     transfer_tx_digest: res.digest,
                     amount: self.coin_amount,
+                    coin_type: GAS::type_tag().to_canonical_string(/* with_prefix */ true),
                 })
                 .collect();
 
@@ -875,23 +1207,37 @@ impl SimpleFaucet {
 
 #[async_trait]
 impl Faucet for SimpleFaucet {
-    async fn send(
+    async fn send_coin(
         &self,
         id: Uuid,
         recipient: SuiAddress,
+        coin_type: &TypeTag,
         amounts: &[u64],
     ) -> Result<FaucetReceipt, FaucetError> {
-        info!(?recipient, uuid = ?id, ?amounts, "Getting faucet requests");
+        info!(?recipient, uuid = ?id, %coin_type, ?amounts, "Getting faucet requests");
 
-        let (digest, coin_ids) = self.transfer_gases(amounts, recipient, id).await?;
+        let is_gas = *coin_type == GAS::type_tag();
+        let (digest, coin_ids) = if is_gas {
+            self.transfer_gases(amounts, recipient, id).await?
+        } else {
+            let pool = self
+                .token_pools
+                .get(coin_type)
+                .ok_or_else(|| FaucetError::UnsupportedCoinType(coin_type.to_string()))?;
+            self.transfer_tokens(pool, amounts, recipient, id).await?
+        };
 
         info!(uuid = ?id, ?recipient, ?digest, "PaySui txn succeeded");
         let mut sent = Vec::with_capacity(coin_ids.len());
-        let coin_results =
-            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_coin(*coin_id))).await;
+        let coin_results = futures::future::join_all(
+            coin_ids
+                .iter()
+                .map(|coin_id| self.get_coin_value(*coin_id, is_gas)),
+        )
+        .await;
         for (coin_id, res) in coin_ids.into_iter().zip(coin_results) {
-            let amount = if let Ok(Some((_, coin))) = res {
-                coin.value()
+            let amount = if let Ok(Some(value)) = res {
+                value
             } else {
                 info!(
                     ?recipient,
@@ -906,6 +1252,7 @@ impl Faucet for SimpleFaucet {
                 transfer_tx_digest: digest,
                 amount,
                 id: coin_id,
+                coin_type: coin_type.to_canonical_string(/* with_prefix */ true),
             });
         }
 
@@ -924,18 +1271,27 @@ impl Faucet for SimpleFaucet {
         Ok(faucet_receipt)
     }
 
-    async fn batch_send(
+    async fn batch_send_coin(
         &self,
         id: Uuid,
         recipient: SuiAddress,
+        coin_type: &TypeTag,
         amounts: &[u64],
     ) -> Result<BatchFaucetReceipt, FaucetError> {
-        info!(?recipient, uuid = ?id, "Getting faucet request");
-        if self
-            .request_producer
-            .try_send((id, recipient, amounts.to_vec()))
-            .is_err()
-        {
+        info!(?recipient, uuid = ?id, %coin_type, "Getting faucet request");
+        let queued = if *coin_type == GAS::type_tag() {
+            self.request_producer
+                .try_send((id, recipient, amounts.to_vec()))
+                .is_ok()
+        } else if self.token_pools.contains_key(coin_type) {
+            self.token_request_producer
+                .try_send((id, recipient, coin_type.clone(), amounts.to_vec()))
+                .is_ok()
+        } else {
+            return Err(FaucetError::UnsupportedCoinType(coin_type.to_string()));
+        };
+
+        if !queued {
             return Err(FaucetError::BatchSendQueueFull);
         }
         let mut task_map = self.task_id_cache.lock().await;
@@ -1135,14 +1491,75 @@ async fn find_gas_coins_and_address(
     ))
 }
 
+/// Finds the objects that the faucet dispenses `coin` from: its treasury cap if it has one, or
+/// otherwise `address`'s coins of that type with enough balance to serve a request of `num_coins`
+/// coins.
+async fn find_token_sources(
+    wallet: &WalletContext,
+    address: SuiAddress,
+    coin: &DispensableCoin,
+    num_coins: usize,
+) -> Result<Vec<ObjectID>, FaucetError> {
+    if let Some(treasury_cap) = coin.treasury_cap {
+        return Ok(vec![treasury_cap]);
+    }
+
+    let coin_type = coin.coin_type.to_canonical_string(/* with_prefix */ true);
+    let min_balance = coin.amount.checked_mul(num_coins as u64).ok_or_else(|| {
+        FaucetError::Internal(format!(
+            "A request for {num_coins} coins of {} {coin_type} exceeds the maximum coin balance",
+            coin.amount,
+        ))
+    })?;
+
+    let client = wallet
+        .get_client()
+        .await
+        .map_err(|e| FaucetError::Wallet(e.to_string()))?;
+
+    let mut sources = vec![];
+    let mut cursor = None;
+    loop {
+        let page = client
+            .coin_read_api()
+            .get_coins(address, Some(coin_type.clone()), cursor, None)
+            .await
+            .map_err(|e| FaucetError::FullnodeReadingError(e.to_string()))?;
+
+        sources.extend(
+            page.data
+                .into_iter()
+                .filter(|c| c.balance >= min_balance)
+                .map(|c| c.coin_object_id),
+        );
+
+        if !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+
+    if sources.is_empty() {
+        return Err(FaucetError::Wallet(format!(
+            "No coins of type {coin_type} with sufficient balance found for {address}"
+        )));
+    }
+
+    Ok(sources)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use anyhow::*;
     use shared_crypto::intent::Intent;
+    use sui_json_rpc_types::get_new_package_obj_from_response;
+    use sui_json_rpc_types::ObjectChange;
     use sui_json_rpc_types::SuiExecutionStatus;
     use sui_json_rpc_types::SuiTransactionBlockEffects;
     use sui_sdk::wallet_context::WalletContext;
+    use sui_test_transaction_builder::TestTransactionBuilder;
+    use sui_types::coin::TreasuryCap;
     use sui_types::transaction::SenderSignedData;
     use sui_types::transaction::TransactionDataAPI;
     use test_cluster::TestClusterBuilder;
@@ -2003,6 +2420,131 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_transfer_tokens_with_treasury_cap() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let address = test_cluster.get_address_0();
+        let context = test_cluster.wallet;
+        let (coin_type, treasury_cap) = publish_coin(&context, address).await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let config = FaucetConfig {
+            coins: vec![DispensableCoin {
+                coin_type: coin_type.clone(),
+                amount: 10,
+                treasury_cap: Some(treasury_cap),
+            }],
+            ..Default::default()
+        };
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+
+        // The treasury cap goes back to its pool after each request, so it can serve another.
+        let recipient = SuiAddress::random_for_testing_only();
+        for _ in 0..2 {
+            let FaucetReceipt { sent } = faucet
+                .send_coin(Uuid::new_v4(), recipient, &coin_type, &[10, 20])
+                .await
+                .unwrap();
+
+            let mut actual_amounts: Vec<u64> = sent.iter().map(|c| c.amount).collect();
+            actual_amounts.sort_unstable();
+            assert_eq!(actual_amounts, vec![10, 20]);
+            assert!(sent
+                .iter()
+                .all(|c| c.coin_type == coin_type.to_canonical_string(true)));
+        }
+
+        let wal = faucet.wal.lock().await;
+        assert!(wal.log.is_empty());
+        assert!(wal.tokens.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transfer_tokens_from_coins() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let address = test_cluster.get_address_0();
+        let context = test_cluster.wallet;
+        let (coin_type, treasury_cap) = publish_coin(&context, address).await;
+
+        // Only the second coin can serve a request for `num_coins` coins of `amount` each.
+        let tiny_coin = mint_coin(&context, address, &coin_type, treasury_cap, 15).await;
+        let coin = mint_coin(&context, address, &coin_type, treasury_cap, 1000).await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let config = FaucetConfig {
+            amount: 10,
+            num_coins: 2,
+            coins: vec![DispensableCoin {
+                coin_type: coin_type.clone(),
+                amount: 10,
+                treasury_cap: None,
+            }],
+            ..Default::default()
+        };
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+
+        let pool = &faucet.token_pools[&coin_type];
+        {
+            let mut consumer = pool.consumer.lock().await;
+            assert_eq!(consumer.try_recv().unwrap(), coin);
+            assert!(consumer.try_recv().is_err());
+        }
+        faucet
+            .recycle_token_source(pool, coin, Uuid::new_v4())
+            .await;
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let FaucetReceipt { sent } = faucet
+            .send_coin(Uuid::new_v4(), recipient, &coin_type, &[10, 10])
+            .await
+            .unwrap();
+        assert_eq!(
+            sent.iter().map(|c| c.amount).collect::<Vec<_>>(),
+            vec![10, 10]
+        );
+        assert!(sent.iter().all(|c| c.id != tiny_coin));
+
+        // The coins were split off the source, which went back to its pool.
+        assert_eq!(faucet.get_coin_value(coin, false).await.unwrap(), Some(980));
+        assert_eq!(pool.consumer.lock().await.try_recv().unwrap(), coin);
+
+        let wal = faucet.wal.lock().await;
+        assert!(wal.log.is_empty());
+        assert!(wal.tokens.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_token_sources_overflow() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let address = test_cluster.get_address_0();
+        let coin = DispensableCoin {
+            coin_type: GAS::type_tag(),
+            amount: u64::MAX / 2 + 1,
+            treasury_cap: None,
+        };
+
+        let err = find_token_sources(&test_cluster.wallet, address, &coin, 2)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FaucetError::Internal(_)), "{err}");
+    }
+
     async fn test_send_interface_has_success_status(faucet: &impl Faucet) {
         let recipient = SuiAddress::random_for_testing_only();
         let amounts = vec![1, 2, 3];
@@ -2036,4 +2578,83 @@ mod tests {
         actual_amounts.sort_unstable();
         assert_eq!(actual_amounts, amounts);
     }
+
+    /// Publishes the `coin` example package from `address`, returning the type of the coin it
+    /// creates and its treasury cap.
+    async fn publish_coin(context: &WalletContext, address: SuiAddress) -> (TypeTag, ObjectID) {
+        let gas = context
+            .get_one_gas_object_owned_by_address(address)
+            .await
+            .unwrap()
+            .unwrap();
+        let gas_price = context.get_reference_gas_price().await.unwrap();
+        let txn = context.sign_transaction(
+            &TestTransactionBuilder::new(address, gas, gas_price)
+                .publish_examples("coin")
+                .build(),
+        );
+        let resp = context.execute_transaction_must_succeed(txn).await;
+
+        let (package, _, _) = get_new_package_obj_from_response(&resp).unwrap();
+        let coin_type =
+            sui_types::parse_sui_type_tag(&format!("{package}::my_coin::MY_COIN")).unwrap();
+        let treasury_cap = resp
+            .object_changes
+            .unwrap()
+            .into_iter()
+            .find_map(|change| match change {
+                ObjectChange::Created {
+                    object_type,
+                    object_id,
+                    ..
+                } if TreasuryCap::is_treasury_type(&object_type)
+                    && object_type.type_params == vec![coin_type.clone()] =>
+                {
+                    Some(object_id)
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        (coin_type, treasury_cap)
+    }
+
+    /// Mints a coin of `coin_type` worth `amount` to `address`, using `treasury_cap`.
+    async fn mint_coin(
+        context: &WalletContext,
+        address: SuiAddress,
+        coin_type: &TypeTag,
+        treasury_cap: ObjectID,
+        amount: u64,
+    ) -> ObjectID {
+        let cap = context.get_object_ref(treasury_cap).await.unwrap();
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            let cap = builder.obj(ObjectArg::ImmOrOwnedObject(cap)).unwrap();
+            let amount = builder.pure(amount).unwrap();
+            let recipient = builder.pure(address).unwrap();
+            builder.programmable_move_call(
+                SUI_FRAMEWORK_PACKAGE_ID,
+                COIN_MODULE_NAME.to_owned(),
+                Identifier::new("mint_and_transfer").unwrap(),
+                vec![coin_type.clone()],
+                vec![cap, amount, recipient],
+            );
+            builder.finish()
+        };
+
+        let gas = context
+            .get_one_gas_object_owned_by_address(address)
+            .await
+            .unwrap()
+            .unwrap();
+        let gas_price = context.get_reference_gas_price().await.unwrap();
+        let txn = context.sign_transaction(
+            &TestTransactionBuilder::new(address, gas, gas_price)
+                .programmable(pt)
+                .build(),
+        );
+        let resp = context.execute_transaction_must_succeed(txn).await;
+        resp.effects.unwrap().created()[0].reference.object_id
+    }
 }
@@ -1,10 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashSet;
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::SuiAddress;
+use sui_types::TypeTag;
 use sui_types::{base_types::ObjectID, transaction::TransactionData};
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store::Map;
@@ -21,9 +23,15 @@ use uuid::Uuid;
 ///
 /// This allows the faucet to go down and back up, and not forget which requests were in-flight that
 /// it needs to confirm succeeded or failed.
+///
+/// Transactions paying out a coin type other than SUI also spend an object from that type's pool
+/// (see [`TokenSource`]). It is recorded in a separate table under the same gas coin, so that it is
+/// kept out of its pool until the transaction is confirmed, and so that entries written before
+/// token pools existed can still be read.
 #[derive(DBMapUtils, Clone)]
 pub struct WriteAheadLog {
     pub log: DBMap<ObjectID, Entry>,
+    pub tokens: DBMap<ObjectID, TokenSource>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -36,6 +44,14 @@ pub struct Entry {
     pub in_flight: bool,
 }
 
+/// An object in the faucet's pool for a coin type other than SUI, that a transaction dispenses
+/// coins of that type from: either a coin of that type, or its treasury cap.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct TokenSource {
+    pub coin_type: TypeTag,
+    pub id: ObjectID,
+}
+
 impl WriteAheadLog {
     pub(crate) fn open(path: &Path) -> Self {
         Self::open_tables_read_write(
@@ -54,6 +70,19 @@ impl WriteAheadLog {
         coin: ObjectID,
         recipient: SuiAddress,
         tx: TransactionData,
+    ) -> Result<(), TypedStoreError> {
+        self.reserve_with_token(uuid, coin, None, recipient, tx)
+    }
+
+    /// Like `reserve`, but for a transaction that also spends `token` from the pool of its coin
+    /// type. Fails if either `coin` or `token` is already reserved by an existing transaction.
+    pub(crate) fn reserve_with_token(
+        &mut self,
+        uuid: Uuid,
+        coin: ObjectID,
+        token: Option<TokenSource>,
+        recipient: SuiAddress,
+        tx: TransactionData,
     ) -> Result<(), TypedStoreError> {
         if self.log.contains_key(&coin)? {
             // Don't permit multiple writes against the same coin
@@ -63,17 +92,50 @@ impl WriteAheadLog {
             )));
         }
 
+        if let Some(token) = &token {
+            if self.tokens_in_flight(&token.coin_type)?.contains(&token.id) {
+                return Err(TypedStoreError::SerializationError(format!(
+                    "Duplicate WAL entry for {} source {:?}",
+                    token.coin_type, token.id,
+                )));
+            }
+        }
+
         let uuid = *uuid.as_bytes();
-        self.log.insert(
-            &coin,
-            &Entry {
-                uuid,
-                recipient,
-                tx,
-                retry_count: 0,
-                in_flight: true,
-            },
-        )
+        let entry = Entry {
+            uuid,
+            recipient,
+            tx,
+            retry_count: 0,
+            in_flight: true,
+        };
+
+        let mut batch = self.log.batch();
+        batch.insert_batch(&self.log, [(coin, entry)])?;
+        if let Some(token) = token {
+            batch.insert_batch(&self.tokens, [(coin, token)])?;
+        }
+        batch.write()
+    }
+
+    /// The object from a token pool that the transaction in flight for `coin` spends, if any.
+    pub(crate) fn token(&self, coin: ObjectID) -> Result<Option<TokenSource>, TypedStoreError> {
+        self.tokens.get(&coin)
+    }
+
+    /// The objects from the pool of `coin_type` that are spent by transactions in the WAL.
+    pub(crate) fn tokens_in_flight(
+        &self,
+        coin_type: &TypeTag,
+    ) -> Result<HashSet<ObjectID>, TypedStoreError> {
+        let mut tokens = HashSet::new();
+        for item in self.tokens.safe_iter() {
+            let (_, token) = item?;
+            if &token.coin_type == coin_type {
+                tokens.insert(token.id);
+            }
+        }
+        Ok(tokens)
     }
 
     /// Check whether `coin` has a pending transaction in the WAL.  Returns `Ok(Some(entry))` if a
@@ -97,7 +159,10 @@ impl WriteAheadLog {
     /// Indicate that the transaction in flight for `coin` has landed, and the entry in the WAL can
     /// be removed.
     pub(crate) fn commit(&mut self, coin: ObjectID) -> Result<(), TypedStoreError> {
-        self.log.remove(&coin)
+        let mut batch = self.log.batch();
+        batch.delete_batch(&self.log, [coin])?;
+        batch.delete_batch(&self.tokens, [coin])?;
+        batch.write()
     }
 
     pub(crate) fn increment_retry_count(&mut self, coin: ObjectID) -> Result<(), TypedStoreError> {
@@ -252,6 +317,49 @@ mod tests {
         wal.reserve(uuid, coin.0, recv1, tx1).unwrap();
     }
 
+    #[tokio::test]
+    async fn reserve_with_token() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut wal = WriteAheadLog::open(&tmp.path().join("wal"));
+
+        let uuid = Uuid::new_v4();
+        let coin0 = random_object_ref();
+        let coin1 = random_object_ref();
+        let (recv0, tx0) = random_request(coin0);
+        let (recv1, tx1) = random_request(coin1);
+
+        let social = sui_types::parse_sui_type_tag("0x42::social::SOCIAL").unwrap();
+        let token = TokenSource {
+            coin_type: social.clone(),
+            id: ObjectID::random(),
+        };
+
+        wal.reserve_with_token(uuid, coin0.0, Some(token.clone()), recv0, tx0)
+            .unwrap();
+
+        assert!(wal.reclaim(coin0.0).unwrap().is_some());
+        assert_eq!(Some(token.clone()), wal.token(coin0.0).unwrap());
+        assert_eq!(
+            HashSet::from([token.id]),
+            wal.tokens_in_flight(&social).unwrap()
+        );
+        assert!(wal
+            .tokens_in_flight(&sui_types::gas_coin::GAS::type_tag())
+            .unwrap()
+            .is_empty());
+
+        // The token is already reserved, even though the gas coin is different.
+        assert!(matches!(
+            wal.reserve_with_token(uuid, coin1.0, Some(token.clone()), recv1, tx1),
+            Err(TypedStoreError::SerializationError(_)),
+        ));
+
+        // Committing the transaction releases the token.
+        wal.commit(coin0.0).unwrap();
+        assert_eq!(None, wal.token(coin0.0).unwrap());
+        assert!(wal.tokens_in_flight(&social).unwrap().is_empty());
+    }
+
     fn random_request(coin: ObjectRef) -> (SuiAddress, TransactionData) {
         let gas_price = 1;
         let send = SuiAddress::random_for_testing_only();
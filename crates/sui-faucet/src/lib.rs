@@ -4,6 +4,7 @@
 mod errors;
 mod faucet;
 mod metrics;
+mod quota;
 mod requests;
 mod responses;
 mod server;
@@ -11,8 +12,9 @@ mod server;
 pub mod metrics_layer;
 pub use metrics_layer::*;
 
-pub use errors::FaucetError;
+pub use errors::{FaucetError, RetryAfter};
 pub use faucet::*;
+pub use quota::{QuotaKey, QuotaStatus};
 pub use requests::*;
 pub use responses::*;
 pub use server::{create_wallet_context, start_faucet};
@@ -0,0 +1,489 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use diesel::sql_types::Text;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations};
+use serde::{Deserialize, Serialize};
+use sui_pg_db::{Db, DbArgs};
+use sui_types::base_types::SuiAddress;
+
+use crate::{FaucetError, RetryAfter};
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+diesel::table! {
+    /// One row per request served to a client (an IP address or a recipient address).
+    faucet_quota_requests (id) {
+        id -> Int8,
+        client -> Text,
+        requested_at_ms -> Int8,
+    }
+}
+
+/// Record of the requests served to each client IP and recipient address, used to enforce
+/// per-client quotas over a rolling window.
+pub(crate) struct QuotaLedger {
+    store: Store,
+
+    /// Requests older than this no longer count against a client's quota.
+    window: Duration,
+    max_requests_per_ip: u64,
+    max_requests_per_address: u64,
+}
+
+enum Store {
+    /// Shared by all faucet replicas that point at the same database, and survives restarts.
+    Postgres(Db),
+
+    /// Timestamps (milliseconds since the Unix epoch) of the requests served to each client
+    /// within the current window, oldest first. Only visible to this process.
+    Memory(parking_lot::Mutex<BTreeMap<QuotaKey, Vec<u64>>>),
+}
+
+/// A client that a quota is tracked for.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum QuotaKey {
+    Ip(IpAddr),
+    Address(SuiAddress),
+}
+
+/// A client's usage of its quota, as reported by the admin API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaStatus {
+    pub key: String,
+    pub requests_used: u64,
+    pub max_requests: u64,
+    /// Seconds until the client's oldest request leaves the window, if it has made any requests.
+    pub resets_in_secs: Option<u64>,
+}
+
+impl QuotaLedger {
+    /// A ledger backed by the Postgres database at `database_url`, running any pending migrations
+    /// for the ledger's table.
+    pub(crate) async fn postgres(
+        database_url: url::Url,
+        window: Duration,
+        max_requests_per_ip: u64,
+        max_requests_per_address: u64,
+    ) -> anyhow::Result<Self> {
+        let db = Db::for_write(DbArgs {
+            database_url,
+            ..Default::default()
+        })
+        .await?;
+
+        db.run_migrations(MIGRATIONS).await?;
+
+        Ok(Self {
+            store: Store::Postgres(db),
+            window,
+            max_requests_per_ip,
+            max_requests_per_address,
+        })
+    }
+
+    /// A ledger that is local to this process, and forgets all requests when it is dropped.
+    pub(crate) fn in_memory(
+        window: Duration,
+        max_requests_per_ip: u64,
+        max_requests_per_address: u64,
+    ) -> Self {
+        Self {
+            store: Store::Memory(parking_lot::Mutex::new(BTreeMap::new())),
+            window,
+            max_requests_per_ip,
+            max_requests_per_address,
+        }
+    }
+
+    /// Record a request against the quotas of all `keys`, if none of them are exhausted. If any
+    /// are, nothing is recorded, and the error says when the request can be retried.
+    pub(crate) async fn acquire(&self, keys: &[QuotaKey]) -> Result<(), FaucetError> {
+        self.acquire_at(keys, now_ms()).await
+    }
+
+    async fn acquire_at(&self, keys: &[QuotaKey], now: u64) -> Result<(), FaucetError> {
+        let db = match &self.store {
+            Store::Postgres(db) => db,
+            Store::Memory(requests) => {
+                let mut requests = requests.lock();
+                for key in keys {
+                    let live = live(requests.get(key), self.cutoff(now));
+                    self.check(key, &live, now)?;
+                }
+
+                for key in keys {
+                    let entry = requests.entry(*key).or_default();
+                    entry.retain(|t| *t > self.cutoff(now));
+                    entry.push(now);
+                }
+
+                return Ok(());
+            }
+        };
+
+        // Lock the clients in a consistent order, so that concurrent requests from different
+        // replicas that share some clients cannot deadlock.
+        let mut keys = keys.to_vec();
+        keys.sort();
+        keys.dedup();
+
+        let mut conn = db.connect().await.map_err(FaucetError::internal)?;
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            async move {
+                use faucet_quota_requests::dsl as q;
+
+                for key in &keys {
+                    diesel::sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+                        .bind::<Text, _>(key.to_string())
+                        .execute(conn)
+                        .await?;
+
+                    let live: Vec<i64> = q::faucet_quota_requests
+                        .select(q::requested_at_ms)
+                        .filter(q::client.eq(key.to_string()))
+                        .filter(q::requested_at_ms.gt(self.cutoff(now) as i64))
+                        .order_by(q::requested_at_ms)
+                        .load(conn)
+                        .await?;
+
+                    let live: Vec<_> = live.into_iter().map(|t| t as u64).collect();
+                    self.check(key, &live, now)?;
+                }
+
+                let rows: Vec<_> = keys
+                    .iter()
+                    .map(|key| {
+                        (
+                            q::client.eq(key.to_string()),
+                            q::requested_at_ms.eq(now as i64),
+                        )
+                    })
+                    .collect();
+
+                diesel::insert_into(q::faucet_quota_requests)
+                    .values(rows)
+                    .execute(conn)
+                    .await?;
+
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+        .map_err(|e| e.downcast().unwrap_or_else(FaucetError::internal))
+    }
+
+    /// The usage of `key`'s quota in the current window.
+    pub(crate) async fn status(&self, key: &QuotaKey) -> Result<QuotaStatus, FaucetError> {
+        self.status_at(key, now_ms()).await
+    }
+
+    async fn status_at(&self, key: &QuotaKey, now: u64) -> Result<QuotaStatus, FaucetError> {
+        let requests = self.live_requests(key, now).await?;
+        Ok(QuotaStatus {
+            key: key.to_string(),
+            requests_used: requests.len() as u64,
+            max_requests: self.max_requests(key),
+            resets_in_secs: self.resets_in_secs(&requests, now),
+        })
+    }
+
+    /// Forget all requests made by `key`, restoring its full quota. Returns whether there was
+    /// anything to forget.
+    pub(crate) async fn reset(&self, key: &QuotaKey) -> Result<bool, FaucetError> {
+        let db = match &self.store {
+            Store::Postgres(db) => db,
+            Store::Memory(requests) => return Ok(requests.lock().remove(key).is_some()),
+        };
+
+        use faucet_quota_requests::dsl as q;
+        let mut conn = db.connect().await.map_err(FaucetError::internal)?;
+        let deleted = diesel::delete(q::faucet_quota_requests)
+            .filter(q::client.eq(key.to_string()))
+            .execute(&mut conn)
+            .await
+            .map_err(FaucetError::internal)?;
+
+        Ok(deleted > 0)
+    }
+
+    /// Remove requests that have left the window, so the ledger does not grow without bound.
+    pub(crate) async fn prune(&self) -> Result<(), FaucetError> {
+        self.prune_at(now_ms()).await
+    }
+
+    async fn prune_at(&self, now: u64) -> Result<(), FaucetError> {
+        let cutoff = self.cutoff(now);
+        let db = match &self.store {
+            Store::Postgres(db) => db,
+            Store::Memory(requests) => {
+                requests.lock().retain(|_, requests| {
+                    requests.retain(|t| *t > cutoff);
+                    !requests.is_empty()
+                });
+                return Ok(());
+            }
+        };
+
+        use faucet_quota_requests::dsl as q;
+        let mut conn = db.connect().await.map_err(FaucetError::internal)?;
+        diesel::delete(q::faucet_quota_requests)
+            .filter(q::requested_at_ms.le(cutoff as i64))
+            .execute(&mut conn)
+            .await
+            .map_err(FaucetError::internal)?;
+
+        Ok(())
+    }
+
+    fn max_requests(&self, key: &QuotaKey) -> u64 {
+        match key {
+            QuotaKey::Ip(_) => self.max_requests_per_ip,
+            QuotaKey::Address(_) => self.max_requests_per_address,
+        }
+    }
+
+    /// Requests made at or before this time have left the window at `now`.
+    fn cutoff(&self, now: u64) -> u64 {
+        now.saturating_sub(self.window.as_millis() as u64)
+    }
+
+    /// Fails if `key`, having made the `requests` still within the window at `now`, has no
+    /// requests left.
+    fn check(&self, key: &QuotaKey, requests: &[u64], now: u64) -> Result<(), FaucetError> {
+        if (requests.len() as u64) < self.max_requests(key) {
+            return Ok(());
+        }
+
+        Err(FaucetError::QuotaExhausted {
+            key: key.to_string(),
+            retry_after: RetryAfter(self.resets_in_secs(requests, now).unwrap_or_default()),
+        })
+    }
+
+    /// The timestamps of `key`'s requests that are still within the window at `now`, oldest
+    /// first.
+    async fn live_requests(&self, key: &QuotaKey, now: u64) -> Result<Vec<u64>, FaucetError> {
+        let cutoff = self.cutoff(now);
+        let db = match &self.store {
+            Store::Postgres(db) => db,
+            Store::Memory(requests) => return Ok(live(requests.lock().get(key), cutoff)),
+        };
+
+        use faucet_quota_requests::dsl as q;
+        let mut conn = db.connect().await.map_err(FaucetError::internal)?;
+        let requests: Vec<i64> = q::faucet_quota_requests
+            .select(q::requested_at_ms)
+            .filter(q::client.eq(key.to_string()))
+            .filter(q::requested_at_ms.gt(cutoff as i64))
+            .order_by(q::requested_at_ms)
+            .load(&mut conn)
+            .await
+            .map_err(FaucetError::internal)?;
+
+        Ok(requests.into_iter().map(|t| t as u64).collect())
+    }
+
+    fn resets_in_secs(&self, requests: &[u64], now: u64) -> Option<u64> {
+        let oldest = requests.first()?;
+        let resets_at = oldest + self.window.as_millis() as u64;
+        Some(resets_at.saturating_sub(now).div_ceil(1000))
+    }
+}
+
+impl fmt::Display for QuotaKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaKey::Ip(ip) => write!(f, "{ip}"),
+            QuotaKey::Address(address) => write!(f, "{address}"),
+        }
+    }
+}
+
+impl FromStr for QuotaKey {
+    type Err = anyhow::Error;
+
+    /// Parse a client IP address, or a recipient Sui address.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(ip) = IpAddr::from_str(s) {
+            return Ok(QuotaKey::Ip(ip));
+        }
+
+        SuiAddress::from_str(s)
+            .map(QuotaKey::Address)
+            .map_err(|_| anyhow!("Expected an IP address or a Sui address, got {s:?}"))
+    }
+}
+
+/// The timestamps in `requests` that are after `cutoff`.
+fn live(requests: Option<&Vec<u64>>, cutoff: u64) -> Vec<u64> {
+    requests
+        .into_iter()
+        .flatten()
+        .copied()
+        .filter(|t| *t > cutoff)
+        .collect()
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is before the Unix epoch")
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use sui_pg_db::temp::TempDb;
+
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    fn ledger() -> QuotaLedger {
+        QuotaLedger::in_memory(WINDOW, 2, 1)
+    }
+
+    async fn pg_ledger(db: &TempDb) -> QuotaLedger {
+        QuotaLedger::postgres(db.database().url().clone(), WINDOW, 2, 1)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn quota_exhausted_until_window_passes() {
+        let ledger = ledger();
+        let ip = QuotaKey::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        ledger.acquire_at(&[ip], 1_000).await.unwrap();
+        ledger.acquire_at(&[ip], 2_000).await.unwrap();
+
+        let err = ledger.acquire_at(&[ip], 3_000).await.unwrap_err();
+        assert_eq!(
+            err,
+            FaucetError::QuotaExhausted {
+                key: ip.to_string(),
+                retry_after: RetryAfter(58),
+            }
+        );
+
+        // The first request has left the window, freeing up one request.
+        ledger.acquire_at(&[ip], 61_000).await.unwrap();
+        assert!(ledger.acquire_at(&[ip], 61_500).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn exhausted_key_blocks_all_keys() {
+        let ledger = ledger();
+        let ip = QuotaKey::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let address = QuotaKey::Address(SuiAddress::random_for_testing_only());
+
+        ledger.acquire_at(&[ip, address], 1_000).await.unwrap();
+
+        // The address has exhausted its quota, so the IP's request is not recorded either.
+        assert!(ledger.acquire_at(&[ip, address], 2_000).await.is_err());
+        assert_eq!(ledger.status_at(&ip, 2_000).await.unwrap().requests_used, 1);
+
+        assert!(ledger.reset(&address).await.unwrap());
+        assert!(!ledger.reset(&address).await.unwrap());
+        ledger.acquire_at(&[ip, address], 3_000).await.unwrap();
+        assert_eq!(ledger.status_at(&ip, 3_000).await.unwrap().requests_used, 2);
+    }
+
+    #[tokio::test]
+    async fn prune_removes_expired_clients() {
+        let ledger = ledger();
+        let old = QuotaKey::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let new = QuotaKey::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)));
+
+        ledger.acquire_at(&[old], 1_000).await.unwrap();
+        ledger.acquire_at(&[new], 30_000).await.unwrap();
+        ledger.prune_at(70_000).await.unwrap();
+
+        // Only the client with requests left in the window has anything to reset.
+        assert!(!ledger.reset(&old).await.unwrap());
+        assert!(ledger.reset(&new).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn quotas_shared_between_replicas() {
+        let db = TempDb::new().unwrap();
+        let replica_a = pg_ledger(&db).await;
+        let replica_b = pg_ledger(&db).await;
+        let ip = QuotaKey::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let address = QuotaKey::Address(SuiAddress::random_for_testing_only());
+
+        replica_a.acquire_at(&[ip, address], 1_000).await.unwrap();
+        assert_eq!(
+            replica_b.status_at(&address, 2_000).await.unwrap(),
+            QuotaStatus {
+                key: address.to_string(),
+                requests_used: 1,
+                max_requests: 1,
+                resets_in_secs: Some(59),
+            }
+        );
+
+        // The address was used up through the other replica, so nothing is recorded for the IP.
+        let err = replica_b
+            .acquire_at(&[ip, address], 2_000)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FaucetError::QuotaExhausted { .. }));
+        assert_eq!(
+            replica_a.status_at(&ip, 2_000).await.unwrap().requests_used,
+            1
+        );
+
+        assert!(replica_b.reset(&address).await.unwrap());
+        replica_a.acquire_at(&[ip, address], 3_000).await.unwrap();
+
+        // Pruning through one replica forgets the requests for all of them.
+        replica_b.prune_at(70_000).await.unwrap();
+        assert!(!replica_a.reset(&ip).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn concurrent_replicas_respect_quota() {
+        let db = TempDb::new().unwrap();
+        let replicas = [pg_ledger(&db).await, pg_ledger(&db).await];
+        let ip = QuotaKey::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        let results = futures::future::join_all(
+            (0..10).map(|i| replicas[i % 2].acquire_at(&[ip], 1_000 + i as u64)),
+        )
+        .await;
+
+        let granted = results.iter().filter(|r| r.is_ok()).count();
+        assert_eq!(granted, 2);
+    }
+
+    #[test]
+    fn parse_quota_key() {
+        assert_eq!(
+            QuotaKey::from_str("127.0.0.1").unwrap(),
+            QuotaKey::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        );
+
+        let address = SuiAddress::random_for_testing_only();
+        assert_eq!(
+            QuotaKey::from_str(&address.to_string()).unwrap(),
+            QuotaKey::Address(address),
+        );
+
+        assert!(QuotaKey::from_str("not a key").is_err());
+    }
+}
@@ -90,3 +90,28 @@ impl From<BatchSendStatus> for BatchStatusFaucetResponse {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaResponse {
+    pub quota: Option<QuotaStatus>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for QuotaResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            error: Some(e.to_string()),
+            quota: None,
+        }
+    }
+}
+
+impl From<QuotaStatus> for QuotaResponse {
+    fn from(v: QuotaStatus) -> Self {
+        Self {
+            quota: Some(v),
+            error: None,
+        }
+    }
+}
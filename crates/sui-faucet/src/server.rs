@@ -1,14 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::quota::QuotaLedger;
 use crate::{
     AppState, BatchFaucetResponse, BatchStatusFaucetResponse, FaucetConfig, FaucetError,
-    FaucetRequest, FaucetResponse, FixedAmountRequest, RequestMetricsLayer,
+    FaucetRequest, FaucetResponse, FixedAmountRequest, QuotaKey, QuotaResponse,
+    RequestMetricsLayer, RetryAfter,
 };
 use axum::{
     error_handling::HandleErrorLayer,
     extract::{ConnectInfo, Host, Path},
-    http::{header::HeaderMap, StatusCode},
+    http::{
+        header::{HeaderMap, AUTHORIZATION, RETRY_AFTER},
+        HeaderValue, StatusCode,
+    },
     response::{IntoResponse, Redirect, Response},
     routing::{get, post},
     BoxError, Extension, Json, Router,
@@ -20,11 +25,13 @@ use std::{
     borrow::Cow,
     net::{IpAddr, SocketAddr},
     path::PathBuf,
+    str::FromStr,
     sync::Arc,
-    time::{Duration, Instant},
+    time::Duration,
 };
 use sui_config::SUI_CLIENT_CONFIG;
 use sui_sdk::wallet_context::WalletContext;
+use sui_types::base_types::SuiAddress;
 use tower::ServiceBuilder;
 use tower_governor::{
     governor::GovernorConfigBuilder, key_extractor::GlobalKeyExtractor, GovernorLayer,
@@ -34,8 +41,7 @@ use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::faucet::Faucet;
-use dashmap::{mapref::entry::Entry, DashMap};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use anyhow::ensure;
 use once_cell::sync::Lazy;
@@ -57,25 +63,19 @@ static TURNSTILE_SECRET_KEY: Lazy<Option<String>> =
 static DISCORD_BOT_PWD: Lazy<String> =
     Lazy::new(|| std::env::var("DISCORD_BOT_PWD").unwrap_or_else(|_| "".to_string()));
 
-/// Keep track of every IP address' requests.
-#[derive(Debug)]
+/// Token that requests to the admin routes must present as `Authorization: Bearer <token>`. The
+/// admin routes reject all requests if it is not set.
+static FAUCET_ADMIN_TOKEN: Lazy<Option<String>> =
+    Lazy::new(|| std::env::var("FAUCET_ADMIN_TOKEN").ok());
+
+/// Validates requests from the web app, and keeps track of every IP address' and recipient's
+/// requests in the quota ledger.
 struct RequestsManager {
-    data: Arc<DashMap<IpAddr, RequestInfo>>,
-    reset_time_interval: Duration,
-    max_requests_per_ip: u64,
+    ledger: Arc<QuotaLedger>,
     cloudflare_turnstile_url: String,
     turnstile_secret_key: String,
 }
 
-/// Request's metadata
-#[derive(Debug, Clone)]
-struct RequestInfo {
-    /// When the first request from this IP address was made. In case of resetting the IP addresses
-    /// metadata, this field will be updated with the new current time.
-    timestamp: Instant,
-    requests_used: u64,
-}
-
 /// Struct to deserialize token verification response from Cloudflare
 #[derive(Deserialize, Debug)]
 struct TurnstileValidationResponse {
@@ -87,15 +87,12 @@ struct TurnstileValidationResponse {
 impl RequestsManager {
     /// Initialize a new RequestsManager
     fn new(
-        max_requests_per_ip: u64,
-        reset_time_interval_secs: Duration,
+        ledger: Arc<QuotaLedger>,
         cloudflare_turnstile_url: String,
         turnstile_secret_key: String,
     ) -> Self {
         Self {
-            data: Arc::new(DashMap::new()),
-            reset_time_interval: reset_time_interval_secs,
-            max_requests_per_ip,
+            ledger,
             cloudflare_turnstile_url,
             turnstile_secret_key,
         }
@@ -103,11 +100,12 @@ impl RequestsManager {
 
     /// Validates a turnstile token
     /// - against Cloudflare turnstile's server to ensure token was issued by turnstile
-    /// - against the IP address' request count
+    /// - against the IP address' and the recipient's quotas
     async fn validate_turnstile_token(
         &self,
         addr: SocketAddr,
         token: &str,
+        recipient: SuiAddress,
     ) -> Result<(), (StatusCode, FaucetError)> {
         let ip = addr.ip();
         let req = reqwest::Client::new();
@@ -160,44 +158,23 @@ impl RequestsManager {
             ));
         }
 
-        match self.data.entry(ip) {
-            Entry::Vacant(entry) => {
-                entry.insert(RequestInfo {
-                    timestamp: Instant::now(),
-                    requests_used: 1,
-                });
-            }
-
-            Entry::Occupied(mut entry) => {
-                let token = entry.get_mut();
-                let elapsed = token.timestamp.elapsed();
-
-                if elapsed >= self.reset_time_interval {
-                    token.timestamp = Instant::now();
-                    token.requests_used = 1;
-                } else if token.requests_used >= self.max_requests_per_ip {
-                    return Err((
-                        StatusCode::TOO_MANY_REQUESTS,
-                        FaucetError::TooManyRequests(format!(
-                            "You can request a new token in {}",
-                            secs_to_human_readable((self.reset_time_interval - elapsed).as_secs())
-                        )),
-                    ));
-                } else {
-                    token.requests_used += 1;
-                }
-            }
-        }
+        self.acquire_quota(&[QuotaKey::Ip(ip), QuotaKey::Address(recipient)])
+            .await
+    }
 
-        Ok(())
+    /// Records a request against the quotas of all `keys`, failing if any of them are exhausted.
+    async fn acquire_quota(&self, keys: &[QuotaKey]) -> Result<(), (StatusCode, FaucetError)> {
+        self.ledger.acquire(keys).await.map_err(|e| match e {
+            FaucetError::QuotaExhausted { .. } => (StatusCode::TOO_MANY_REQUESTS, e),
+            e => (StatusCode::INTERNAL_SERVER_ERROR, e),
+        })
     }
 
-    /// This function iterates through the stored IPs and removes those IP addresses which are now
-    /// eligible to make new requests.
-    fn cleanup_expired_tokens(&self) {
-        // keep only those IP addresses that are still under time limit.
-        self.data
-            .retain(|_, info| info.timestamp.elapsed() < self.reset_time_interval);
+    /// Removes the requests that have left the quota window from the ledger.
+    async fn cleanup_expired_tokens(&self) {
+        if let Err(e) = self.ledger.prune().await {
+            error!("Failed to prune quota ledger: {:?}", e);
+        }
     }
 }
 
@@ -234,12 +211,37 @@ pub async fn start_faucet(
         reset_time_interval_secs,
         rate_limiter_cleanup_interval_secs,
         max_requests_per_ip,
+        max_requests_per_address,
+        authenticated,
+        ref quota_database_url,
         ..
     } = app_state.config;
 
+    let window = Duration::from_secs(reset_time_interval_secs);
+    let ledger = Arc::new(match quota_database_url {
+        Some(url) => {
+            QuotaLedger::postgres(
+                url.clone(),
+                window,
+                max_requests_per_ip,
+                max_requests_per_address,
+            )
+            .await?
+        }
+        None => {
+            if authenticated {
+                warn!(
+                    "No quota database configured, quotas are not shared between replicas and \
+                     will be reset on restart"
+                );
+            }
+
+            QuotaLedger::in_memory(window, max_requests_per_ip, max_requests_per_address)
+        }
+    });
+
     let token_manager = Arc::new(RequestsManager::new(
-        max_requests_per_ip,
-        Duration::from_secs(reset_time_interval_secs),
+        ledger.clone(),
         cloudflare_turnstile_url,
         turnstile_secret_key,
     ));
@@ -262,7 +264,8 @@ pub async fn start_faucet(
             config: governor_cfg.clone(),
         });
 
-    // This has its own rate limiter via the RequestManager
+    // Requests to this route are checked against the quotas via the RequestManager, as are
+    // requests to all other routes that dispense coins, in authenticated mode.
     let faucet_web_routes = Router::new().route("/v1/faucet_web_gas", post(batch_faucet_web_gas));
     // Routes with no rate limit
     let unrestricted_routes = Router::new()
@@ -271,11 +274,17 @@ pub async fn start_faucet(
         .route("/v1/faucet_discord", post(batch_faucet_discord))
         .route("/v1/status/:task_id", get(request_status));
 
+    // These routes require the admin token
+    let admin_routes = Router::new()
+        .route("/admin/quota/:key", get(get_quota))
+        .route("/admin/quota/:key/reset", post(reset_quota));
+
     // Combine all routes
     let app = Router::new()
         .merge(global_limited_routes)
         .merge(unrestricted_routes)
         .merge(faucet_web_routes)
+        .merge(admin_routes)
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(handle_error))
@@ -285,6 +294,7 @@ pub async fn start_faucet(
                 .concurrency_limit(concurrency_limit)
                 .layer(Extension(app_state.clone()))
                 .layer(Extension(token_manager.clone()))
+                .layer(Extension(ledger))
                 .layer(cors)
                 .into_inner(),
         );
@@ -299,10 +309,10 @@ pub async fn start_faucet(
     });
 
     spawn_monitored_task!(async move {
-        info!("Starting task to clear banned ip addresses.");
+        info!("Starting task to prune the quota ledger.");
         loop {
             tokio::time::sleep(Duration::from_secs(rate_limiter_cleanup_interval_secs)).await;
-            token_manager.cleanup_expired_tokens();
+            token_manager.cleanup_expired_tokens().await;
         }
     });
 
@@ -340,42 +350,50 @@ async fn redirect(Host(host): Host) -> Response {
 /// A route for requests coming from the discord bot.
 async fn batch_faucet_discord(
     headers: HeaderMap,
+    Extension(token_manager): Extension<Arc<RequestsManager>>,
     Extension(state): Extension<Arc<AppState>>,
     Json(payload): Json<FaucetRequest>,
-) -> impl IntoResponse {
+) -> Response {
     if state.config.authenticated {
         let Some(agent_value) = headers
             .get(reqwest::header::USER_AGENT)
             .and_then(|v| v.to_str().ok())
         else {
-            return (
+            return reject::<BatchFaucetResponse>(
                 StatusCode::BAD_REQUEST,
-                Json(BatchFaucetResponse::from(FaucetError::InvalidUserAgent(
-                    "Invalid user agent for this route".to_string(),
-                ))),
+                FaucetError::InvalidUserAgent("Invalid user agent for this route".to_string()),
             );
         };
 
         if agent_value != *DISCORD_BOT_PWD {
-            return (
+            return reject::<BatchFaucetResponse>(
                 StatusCode::BAD_REQUEST,
-                Json(BatchFaucetResponse::from(FaucetError::InvalidUserAgent(
-                    "Invalid user agent for this route".to_string(),
-                ))),
+                FaucetError::InvalidUserAgent("Invalid user agent for this route".to_string()),
             );
         }
     }
 
     let FaucetRequest::FixedAmountRequest(request) = payload else {
-        return (
+        return reject::<BatchFaucetResponse>(
             StatusCode::BAD_REQUEST,
-            Json(BatchFaucetResponse::from(FaucetError::Internal(
-                "Input Error.".to_string(),
-            ))),
+            FaucetError::Internal("Input Error.".to_string()),
         );
     };
 
-    batch_request_spawn_task(request, state).await
+    // All requests on this route come from the bot, so only the recipient's quota applies.
+    if state.config.authenticated {
+        let quota = token_manager
+            .acquire_quota(&[QuotaKey::Address(request.recipient)])
+            .await;
+
+        if let Err((status_code, faucet_error)) = quota {
+            return reject::<BatchFaucetResponse>(status_code, faucet_error);
+        }
+    }
+
+    batch_request_spawn_task(request, state)
+        .await
+        .into_response()
 }
 
 /// Handler for requests coming from the frontend faucet web app.
@@ -385,37 +403,37 @@ async fn batch_faucet_web_gas(
     Extension(token_manager): Extension<Arc<RequestsManager>>,
     Extension(state): Extension<Arc<AppState>>,
     Json(payload): Json<FaucetRequest>,
-) -> impl IntoResponse {
+) -> Response {
+    let FaucetRequest::FixedAmountRequest(request) = payload else {
+        return reject::<BatchFaucetResponse>(
+            StatusCode::BAD_REQUEST,
+            FaucetError::Internal("Input Error.".to_string()),
+        );
+    };
+
     if state.config.authenticated {
         let Some(token) = headers
             .get("X-Turnstile-Token")
             .and_then(|v| v.to_str().ok())
         else {
-            return (
+            return reject::<BatchFaucetResponse>(
                 StatusCode::BAD_REQUEST,
-                Json(BatchFaucetResponse::from(
-                    FaucetError::MissingTurnstileTokenHeader,
-                )),
+                FaucetError::MissingTurnstileTokenHeader,
             );
         };
 
-        let validation = token_manager.validate_turnstile_token(addr, token).await;
+        let validation = token_manager
+            .validate_turnstile_token(addr, token, request.recipient)
+            .await;
 
         if let Err((status_code, faucet_error)) = validation {
-            return (status_code, Json(BatchFaucetResponse::from(faucet_error)));
+            return reject::<BatchFaucetResponse>(status_code, faucet_error);
         }
     }
 
-    let FaucetRequest::FixedAmountRequest(request) = payload else {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(BatchFaucetResponse::from(FaucetError::Internal(
-                "Input Error.".to_string(),
-            ))),
-        );
-    };
-
-    batch_request_spawn_task(request, state).await
+    batch_request_spawn_task(request, state)
+        .await
+        .into_response()
 }
 
 // helper method
@@ -423,14 +441,15 @@ async fn batch_request_spawn_task(
     request: FixedAmountRequest,
     state: Arc<AppState>,
 ) -> (StatusCode, Json<BatchFaucetResponse>) {
+    let (coin_type, amounts) = match state.config.amounts_for(request.coin_type.as_deref()) {
+        Ok(v) => v,
+        Err(v) => return (StatusCode::BAD_REQUEST, Json(BatchFaucetResponse::from(v))),
+    };
+
     let result = spawn_monitored_task!(async move {
         state
             .faucet
-            .batch_send(
-                Uuid::new_v4(),
-                request.recipient,
-                &vec![state.config.amount; state.config.num_coins],
-            )
+            .batch_send_coin(Uuid::new_v4(), request.recipient, &coin_type, &amounts)
             .await
     })
     .await
@@ -446,35 +465,60 @@ async fn batch_request_spawn_task(
 
 /// handler for batch_request_gas requests
 async fn batch_request_gas(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Extension(token_manager): Extension<Arc<RequestsManager>>,
     Extension(state): Extension<Arc<AppState>>,
     Json(payload): Json<FaucetRequest>,
-) -> impl IntoResponse {
+) -> Response {
     let id = Uuid::new_v4();
     // ID for traceability
     info!(uuid = ?id, "Got new gas request.");
 
     let FaucetRequest::FixedAmountRequest(request) = payload else {
-        return (
+        return reject::<BatchFaucetResponse>(
             StatusCode::BAD_REQUEST,
-            Json(BatchFaucetResponse::from(FaucetError::Internal(
-                "Input Error.".to_string(),
-            ))),
+            FaucetError::Internal("Input Error.".to_string()),
         );
     };
 
+    if state.config.authenticated {
+        let quota = token_manager
+            .acquire_quota(&[
+                QuotaKey::Ip(addr.ip()),
+                QuotaKey::Address(request.recipient),
+            ])
+            .await;
+
+        if let Err((status_code, faucet_error)) = quota {
+            return reject::<BatchFaucetResponse>(status_code, faucet_error);
+        }
+    }
+
+    batch_request_gas_impl(id, request, state)
+        .await
+        .into_response()
+}
+
+/// Serves a request to `batch_request_gas` that has been admitted by the quotas.
+async fn batch_request_gas_impl(
+    id: Uuid,
+    request: FixedAmountRequest,
+    state: Arc<AppState>,
+) -> (StatusCode, Json<BatchFaucetResponse>) {
     if state.config.batch_enabled {
         batch_request_spawn_task(request, state).await
     } else {
         // TODO (jian): remove this feature gate when batch has proven to be baked long enough
         info!(uuid = ?id, "Falling back to v1 implementation");
+        let (coin_type, amounts) = match state.config.amounts_for(request.coin_type.as_deref()) {
+            Ok(v) => v,
+            Err(v) => return (StatusCode::BAD_REQUEST, Json(BatchFaucetResponse::from(v))),
+        };
+
         let result = spawn_monitored_task!(async move {
             state
                 .faucet
-                .send(
-                    id,
-                    request.recipient,
-                    &vec![state.config.amount; state.config.num_coins],
-                )
+                .send_coin(id, request.recipient, &coin_type, &amounts)
                 .await
         })
         .await
@@ -526,54 +570,158 @@ async fn request_status(
 
 /// handler for all the request_gas requests
 async fn request_gas(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Extension(token_manager): Extension<Arc<RequestsManager>>,
     Extension(state): Extension<Arc<AppState>>,
     Json(payload): Json<FaucetRequest>,
-) -> impl IntoResponse {
+) -> Response {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
 
     let result = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
+            let requested = requests.coin_type.as_deref();
+            let (coin_type, amounts) = match state.config.amounts_for(requested) {
+                Ok(v) => v,
+                Err(v) => return reject::<FaucetResponse>(StatusCode::BAD_REQUEST, v),
+            };
+
+            if state.config.authenticated {
+                let quota = token_manager
+                    .acquire_quota(&[
+                        QuotaKey::Ip(addr.ip()),
+                        QuotaKey::Address(requests.recipient),
+                    ])
+                    .await;
+
+                if let Err((status_code, faucet_error)) = quota {
+                    return reject::<FaucetResponse>(status_code, faucet_error);
+                }
+            }
+
             // We spawn a tokio task for this such that connection drop will not interrupt
             // it and impact the recycling of coins
             spawn_monitored_task!(async move {
                 state
                     .faucet
-                    .send(
-                        id,
-                        requests.recipient,
-                        &vec![state.config.amount; state.config.num_coins],
-                    )
+                    .send_coin(id, requests.recipient, &coin_type, &amounts)
                     .await
             })
             .await
             .unwrap()
         }
         _ => {
-            return (
+            return reject::<FaucetResponse>(
                 StatusCode::BAD_REQUEST,
-                Json(FaucetResponse::from(FaucetError::Internal(
-                    "Input Error.".to_string(),
-                ))),
+                FaucetError::Internal("Input Error.".to_string()),
             )
         }
     };
     match result {
         Ok(v) => {
             info!(uuid =?id, "Request is successfully served");
-            (StatusCode::CREATED, Json(FaucetResponse::from(v)))
+            (StatusCode::CREATED, Json(FaucetResponse::from(v))).into_response()
         }
         Err(v) => {
             warn!(uuid =?id, "Failed to request gas: {:?}", v);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(FaucetResponse::from(v)),
-            )
+            reject::<FaucetResponse>(StatusCode::INTERNAL_SERVER_ERROR, v)
+        }
+    }
+}
+
+/// Admin handler to query the quota of a client, identified by IP address or recipient address.
+async fn get_quota(
+    headers: HeaderMap,
+    Extension(ledger): Extension<Arc<QuotaLedger>>,
+    Path(key): Path<String>,
+) -> Response {
+    if let Err(e) = check_admin_token(&headers) {
+        return reject::<QuotaResponse>(StatusCode::UNAUTHORIZED, e);
+    }
+
+    let key = match QuotaKey::from_str(&key) {
+        Ok(key) => key,
+        Err(e) => {
+            return reject::<QuotaResponse>(StatusCode::BAD_REQUEST, FaucetError::internal(e))
         }
+    };
+
+    match ledger.status(&key).await {
+        Ok(status) => (StatusCode::OK, Json(QuotaResponse::from(status))).into_response(),
+        Err(e) => reject::<QuotaResponse>(StatusCode::INTERNAL_SERVER_ERROR, e),
     }
 }
 
+/// Admin handler to restore the full quota of a client, identified by IP address or recipient
+/// address.
+async fn reset_quota(
+    headers: HeaderMap,
+    Extension(ledger): Extension<Arc<QuotaLedger>>,
+    Path(key): Path<String>,
+) -> Response {
+    if let Err(e) = check_admin_token(&headers) {
+        return reject::<QuotaResponse>(StatusCode::UNAUTHORIZED, e);
+    }
+
+    let key = match QuotaKey::from_str(&key) {
+        Ok(key) => key,
+        Err(e) => {
+            return reject::<QuotaResponse>(StatusCode::BAD_REQUEST, FaucetError::internal(e))
+        }
+    };
+
+    let status = async {
+        let reset = ledger.reset(&key).await?;
+        info!(%key, reset, "Quota reset by admin");
+        ledger.status(&key).await
+    }
+    .await;
+
+    match status {
+        Ok(status) => (StatusCode::OK, Json(QuotaResponse::from(status))).into_response(),
+        Err(e) => reject::<QuotaResponse>(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// Checks that the request carries the admin token as a bearer token.
+fn check_admin_token(headers: &HeaderMap) -> Result<(), FaucetError> {
+    let Some(expected) = FAUCET_ADMIN_TOKEN.as_ref() else {
+        return Err(FaucetError::InvalidAdminToken);
+    };
+
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    if token != Some(expected.as_str()) {
+        return Err(FaucetError::InvalidAdminToken);
+    }
+
+    Ok(())
+}
+
+/// Respond with `error`, adding a `Retry-After` header if the request exhausted a quota.
+fn reject<R: From<FaucetError> + Serialize>(status: StatusCode, error: FaucetError) -> Response {
+    let retry_after = match &error {
+        FaucetError::QuotaExhausted {
+            retry_after: RetryAfter(secs),
+            ..
+        } => Some(*secs),
+        _ => None,
+    };
+
+    let mut response = (status, Json(R::from(error))).into_response();
+    if let Some(secs) = retry_after {
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(secs));
+    }
+
+    response
+}
+
 pub fn create_wallet_context(
     timeout_secs: u64,
     config_dir: PathBuf,
@@ -601,33 +749,44 @@ async fn handle_error(error: BoxError) -> impl IntoResponse {
     )
 }
 
-/// Format seconds to human readable format.
-fn secs_to_human_readable(seconds: u64) -> String {
-    let hours = seconds / 3600;
-    let minutes = (seconds % 3600) / 60;
-    let seconds = seconds % 60;
-
-    if hours > 0 {
-        format!("{}h {}m {}s", hours, minutes, seconds)
-    } else if minutes > 0 {
-        format!("{}m {}s", minutes, seconds)
-    } else {
-        format!("{}s", seconds)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
     use std::net::{IpAddr, Ipv4Addr};
     use std::time::Duration;
+    use sui_pg_db::temp::TempDb;
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     const MAX_REQUESTS_PER_IP: u64 = 3;
     const RESET_TIME_INTERVAL: Duration = Duration::from_secs(5);
 
+    fn requests_manager(uri: String) -> RequestsManager {
+        let ledger = QuotaLedger::in_memory(
+            RESET_TIME_INTERVAL,
+            MAX_REQUESTS_PER_IP,
+            MAX_REQUESTS_PER_IP,
+        );
+        RequestsManager::new(Arc::new(ledger), uri, "test_secret".to_string())
+    }
+
+    async fn pg_requests_manager(db: &TempDb, uri: String) -> RequestsManager {
+        let ledger = QuotaLedger::postgres(
+            db.database().url().clone(),
+            RESET_TIME_INTERVAL,
+            MAX_REQUESTS_PER_IP,
+            MAX_REQUESTS_PER_IP,
+        )
+        .await
+        .unwrap();
+        RequestsManager::new(Arc::new(ledger), uri, "test_secret".to_string())
+    }
+
+    async fn requests_used(manager: &RequestsManager, key: QuotaKey) -> u64 {
+        manager.ledger.status(&key).await.unwrap().requests_used
+    }
+
     async fn setup_mock_cloudflare() -> MockServer {
         let mock_server = MockServer::start().await;
 
@@ -646,61 +805,123 @@ mod tests {
     async fn test_token_validation_and_limits() {
         // Start mock server
         let mock_server = setup_mock_cloudflare().await;
-        let manager = RequestsManager::new(
-            MAX_REQUESTS_PER_IP,
-            RESET_TIME_INTERVAL,
-            mock_server.uri(),
-            "test_secret".to_string(),
-        );
+        let manager = requests_manager(mock_server.uri());
         let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
         let token = "test_token";
 
         // First request should succeed
-        let result = manager.validate_turnstile_token(ip, token).await;
+        let result = manager
+            .validate_turnstile_token(ip, token, SuiAddress::random_for_testing_only())
+            .await;
         assert!(result.is_ok());
 
         // Use up remaining requests
-        for _ in 1..manager.max_requests_per_ip {
-            let result = manager.validate_turnstile_token(ip, token).await;
+        for _ in 1..MAX_REQUESTS_PER_IP {
+            let result = manager
+                .validate_turnstile_token(ip, token, SuiAddress::random_for_testing_only())
+                .await;
             assert!(result.is_ok());
         }
 
         // Next request should fail due to limit
-        let result = manager.validate_turnstile_token(ip, token).await;
-        assert!(result.is_err());
+        let result = manager
+            .validate_turnstile_token(ip, token, SuiAddress::random_for_testing_only())
+            .await;
+        let (status, err) = result.unwrap_err();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(matches!(err, FaucetError::QuotaExhausted { .. }));
     }
 
     #[tokio::test]
-    async fn test_token_reset_after_interval() {
+    async fn test_recipient_limits_across_ips() {
         let mock_server = setup_mock_cloudflare().await;
-        let manager = RequestsManager::new(
-            MAX_REQUESTS_PER_IP,
-            RESET_TIME_INTERVAL,
-            mock_server.uri(),
-            "test_secret".to_string(),
+        let manager = requests_manager(mock_server.uri());
+        let recipient = SuiAddress::random_for_testing_only();
+        let token = "test_token";
+
+        // Each request comes from a different IP, but they are all for the same recipient.
+        for i in 0..MAX_REQUESTS_PER_IP {
+            let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, i as u8)), 8080);
+            let result = manager.validate_turnstile_token(ip, token, recipient).await;
+            assert!(result.is_ok());
+        }
+
+        let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 0)), 8080);
+        let result = manager.validate_turnstile_token(ip, token, recipient).await;
+        assert_eq!(result.unwrap_err().0, StatusCode::TOO_MANY_REQUESTS);
+
+        // The rejected request does not count against the IP's quota.
+        assert_eq!(requests_used(&manager, QuotaKey::Ip(ip.ip())).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_quota_shared_between_replicas() {
+        let mock_server = setup_mock_cloudflare().await;
+        let db = TempDb::new().unwrap();
+        let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+        let recipient = SuiAddress::random_for_testing_only();
+        let token = "test_token";
+
+        let replica = pg_requests_manager(&db, mock_server.uri()).await;
+        for _ in 0..MAX_REQUESTS_PER_IP {
+            let result = replica.validate_turnstile_token(ip, token, recipient).await;
+            assert!(result.is_ok());
+        }
+
+        // Another manager over the same database sees the requests served by the first.
+        let manager = pg_requests_manager(&db, mock_server.uri()).await;
+        let result = manager.validate_turnstile_token(ip, token, recipient).await;
+        assert_eq!(result.unwrap_err().0, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn test_quota_exhausted_has_retry_after() {
+        let response = reject::<BatchFaucetResponse>(
+            StatusCode::TOO_MANY_REQUESTS,
+            FaucetError::QuotaExhausted {
+                key: "127.0.0.1".to_string(),
+                retry_after: RetryAfter(42),
+            },
         );
 
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "42");
+
+        let response =
+            reject::<BatchFaucetResponse>(StatusCode::BAD_REQUEST, FaucetError::ChannelClosed);
+        assert!(response.headers().get(RETRY_AFTER).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_token_reset_after_interval() {
+        let mock_server = setup_mock_cloudflare().await;
+        let manager = requests_manager(mock_server.uri());
+
         let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+        let recipient = SuiAddress::random_for_testing_only();
         let token = "test_token";
 
         // Use up all requests
-        for _ in 0..manager.max_requests_per_ip {
-            let result = manager.validate_turnstile_token(ip, token).await;
+        for _ in 0..MAX_REQUESTS_PER_IP {
+            let result = manager.validate_turnstile_token(ip, token, recipient).await;
             assert!(result.is_ok());
         }
 
         // Try one more, it should fail
-        let result = manager.validate_turnstile_token(ip, token).await;
+        let result = manager.validate_turnstile_token(ip, token, recipient).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().0 == StatusCode::TOO_MANY_REQUESTS);
-        assert!(!manager.data.is_empty());
+        assert_eq!(
+            requests_used(&manager, QuotaKey::Ip(ip.ip())).await,
+            MAX_REQUESTS_PER_IP
+        );
 
         tokio::time::sleep(RESET_TIME_INTERVAL + Duration::from_secs(3)).await;
         // Trigger cleanup
-        manager.cleanup_expired_tokens();
+        manager.cleanup_expired_tokens().await;
 
         // Should be able to make new requests
-        let result = manager.validate_turnstile_token(ip, token).await;
+        let result = manager.validate_turnstile_token(ip, token, recipient).await;
         assert!(result.is_ok());
     }
 
@@ -718,28 +939,20 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let manager = RequestsManager::new(
-            MAX_REQUESTS_PER_IP,
-            RESET_TIME_INTERVAL,
-            mock_server.uri(),
-            "test_secret".to_string(),
-        );
+        let manager = requests_manager(mock_server.uri());
         let ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
         let token = "invalid_token";
 
-        let result = manager.validate_turnstile_token(ip, token).await;
+        let result = manager
+            .validate_turnstile_token(ip, token, SuiAddress::random_for_testing_only())
+            .await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_concurrent_ip_requests() {
         let mock_server = setup_mock_cloudflare().await;
-        let manager = Arc::new(RequestsManager::new(
-            MAX_REQUESTS_PER_IP,
-            RESET_TIME_INTERVAL,
-            mock_server.uri(),
-            "test_secret".to_string(),
-        ));
+        let manager = Arc::new(requests_manager(mock_server.uri()));
 
         // Create 10 different IP addresses
         let ips: Vec<SocketAddr> = (0..10)
@@ -760,7 +973,8 @@ mod tests {
                 let mut results = vec![];
                 // Each IP tries to make MAX_REQUESTS_PER_IP + 1 requests
                 for _ in 0..=MAX_REQUESTS_PER_IP {
-                    let result = manager.validate_turnstile_token(ip, token).await;
+                    let recipient = SuiAddress::random_for_testing_only();
+                    let result = manager.validate_turnstile_token(ip, token, recipient).await;
                     results.push(result);
                 }
                 (ip, results)
@@ -797,33 +1011,13 @@ mod tests {
             );
         }
 
-        // Verify the data in the DashMap
-        assert_eq!(manager.data.len(), 10, "Should have 10 IPs in the map");
-
-        for info in manager.data.iter() {
+        // Verify the data in the ledger
+        for ip in ips {
             assert_eq!(
-                info.requests_used, MAX_REQUESTS_PER_IP,
+                requests_used(&manager, QuotaKey::Ip(ip.ip())).await,
+                MAX_REQUESTS_PER_IP,
                 "Each IP should have used exactly MAX_REQUESTS_PER_IP requests"
             );
         }
     }
-
-    #[test]
-    fn test_secs_to_human_readable() {
-        // Test seconds only
-        assert_eq!(secs_to_human_readable(45), "45s");
-        assert_eq!(secs_to_human_readable(1), "1s");
-
-        // Test minutes and seconds
-        assert_eq!(secs_to_human_readable(65), "1m 5s");
-        assert_eq!(secs_to_human_readable(3599), "59m 59s");
-
-        // Test hours, minutes, and seconds
-        assert_eq!(secs_to_human_readable(3600), "1h 0m 0s");
-        assert_eq!(secs_to_human_readable(3661), "1h 1m 1s");
-        assert_eq!(secs_to_human_readable(7384), "2h 3m 4s");
-
-        // Test edge case
-        assert_eq!(secs_to_human_readable(0), "0s");
-    }
 }
@@ -126,6 +126,10 @@ pub struct Limits {
     pub max_multi_get_objects_keys: u32,
     /// Maximum number of candidates to scan when gathering a page of results.
     pub max_scan_limit: u32,
+    /// Maximum number of subscriptions that can be active at once across the service.
+    pub max_subscriptions: u32,
+    /// Maximum number of subscriptions that can be active at once on a single connection.
+    pub max_subscriptions_per_connection: u32,
 }
 
 #[DefaultConfig]
@@ -211,6 +215,8 @@ pub struct InternalFeatureConfig {
     pub(crate) feature_gate: bool,
     pub(crate) logger: bool,
     pub(crate) query_timeout: bool,
+    pub(crate) persisted_queries: bool,
+    pub(crate) response_cache: bool,
     pub(crate) metrics: bool,
     pub(crate) tracing: bool,
     pub(crate) apollo_tracing: bool,
@@ -354,6 +360,16 @@ impl ServiceConfig {
     async fn max_scan_limit(&self) -> u32 {
         self.limits.max_scan_limit
     }
+
+    /// Maximum number of subscriptions that can be active at once across the service.
+    async fn max_subscriptions(&self) -> u32 {
+        self.limits.max_subscriptions
+    }
+
+    /// Maximum number of subscriptions that can be active at once on a single connection.
+    async fn max_subscriptions_per_connection(&self) -> u32 {
+        self.limits.max_subscriptions_per_connection
+    }
 }
 
 impl TxExecFullNodeConfig {
@@ -519,6 +535,10 @@ impl Default for Limits {
             max_transaction_ids: 1000,
             max_multi_get_objects_keys: 500,
             max_scan_limit: 100_000_000,
+            // Each subscription polls the database as the watermark advances, so they are capped
+            // to bound the load they can generate.
+            max_subscriptions: 1_000,
+            max_subscriptions_per_connection: 10,
             // This value is set to be the size of the max transaction bytes allowed + base64
             // overhead (roughly 1/3 of the original string). This is rounded up.
             //
@@ -536,6 +556,8 @@ impl Default for InternalFeatureConfig {
             feature_gate: true,
             logger: true,
             query_timeout: true,
+            persisted_queries: true,
+            response_cache: false,
             metrics: true,
             tracing: false,
             apollo_tracing: false,
@@ -604,6 +626,8 @@ mod tests {
                 max-transaction-ids = 11
                 max-multi-get-objects-keys = 11
                 max-scan-limit = 50
+                max-subscriptions = 100
+                max-subscriptions-per-connection = 5
             "#,
         )
         .unwrap();
@@ -627,6 +651,8 @@ mod tests {
                 max_transaction_ids: 11,
                 max_multi_get_objects_keys: 11,
                 max_scan_limit: 50,
+                max_subscriptions: 100,
+                max_subscriptions_per_connection: 5,
             },
             ..Default::default()
         };
@@ -694,6 +720,8 @@ mod tests {
                 max-transaction-ids = 42
                 max-multi-get-objects-keys = 42
                 max-scan-limit = 420
+                max-subscriptions = 42
+                max-subscriptions-per-connection = 4
 
                 [experiments]
                 test-flag = true
@@ -720,6 +748,8 @@ mod tests {
                 max_transaction_ids: 42,
                 max_multi_get_objects_keys: 42,
                 max_scan_limit: 420,
+                max_subscriptions: 42,
+                max_subscriptions_per_connection: 4,
             },
             disabled_features: BTreeSet::from([FunctionalGroup::Analytics]),
             experiments: Experiments { test_flag: true },
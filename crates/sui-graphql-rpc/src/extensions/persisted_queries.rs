@@ -0,0 +1,401 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::{Arc, Mutex};
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest, NextValidation,
+};
+use async_graphql::{Request, ServerError, ServerResult, ValidationResult};
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::hash::{HashFunction, Sha256};
+use lru::LruCache;
+use serde::Deserialize;
+
+use crate::error::{code, graphql_error};
+use crate::extensions::query_limits_checker::PayloadSize;
+use crate::metrics::Metrics;
+
+/// The maximum total size of the queries remembered by the persisted query store, in bytes. Once
+/// full, the least recently used queries are evicted to make room.
+const MAX_PERSISTED_QUERY_BYTES: usize = 16 * 1024 * 1024;
+
+/// The only version of the persisted query protocol that is supported.
+const PERSISTED_QUERY_VERSION: u32 = 1;
+
+/// The key in the request's `extensions` that persisted query information is sent under.
+const PERSISTED_QUERY_EXTENSION: &str = "persistedQuery";
+
+/// Extension factory for supporting Automatic Persisted Queries (APQ).
+///
+/// Clients send a request with the SHA-256 hash of its query (in hex) in
+/// `extensions.persistedQuery.sha256Hash`, and no query text. If the query has been seen before,
+/// it is looked up from the store and executed, otherwise the request fails with a
+/// `PERSISTED_QUERY_NOT_FOUND` error, and the client is expected to retry with both the hash and
+/// the query text, which registers the query for subsequent requests. Queries are only registered
+/// once they have been parsed and validated (including passing the query limits), so the store
+/// never holds a query that could not have been run directly.
+///
+/// `<https://www.apollographql.com/docs/apollo-server/performance/apq/>`
+pub(crate) struct PersistedQueries {
+    queries: Arc<Mutex<Queries>>,
+}
+
+struct PersistedQueriesExt {
+    queries: Arc<Mutex<Queries>>,
+
+    /// Set when preparing a request that registers a query, which is added to the store if the
+    /// query passes validation.
+    pending: Mutex<Option<(String, Arc<str>)>>,
+}
+
+/// Persisted queries by the hex-encoded SHA-256 hash of their text, bounded by their total size.
+struct Queries {
+    entries: LruCache<String, Arc<str>>,
+
+    /// Sum of the sizes of all hashes and queries in the store, in bytes.
+    size: usize,
+
+    /// The maximum total size of all entries, in bytes.
+    max_size: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedQuery {
+    version: u32,
+    sha256_hash: String,
+}
+
+impl PersistedQueries {
+    pub(crate) fn new() -> Self {
+        Self::with_max_size(MAX_PERSISTED_QUERY_BYTES)
+    }
+
+    fn with_max_size(max_size: usize) -> Self {
+        Self {
+            queries: Arc::new(Mutex::new(Queries {
+                entries: LruCache::unbounded(),
+                size: 0,
+                max_size,
+            })),
+        }
+    }
+}
+
+impl Queries {
+    /// Add `query` to the store under `hash`, evicting the least recently used queries until the
+    /// store fits within its size limit again. Queries that are too large to store are ignored.
+    fn insert(&mut self, hash: String, query: Arc<str>) {
+        let size = hash.len() + query.len();
+        if size > self.max_size {
+            return;
+        }
+
+        let hash_len = hash.len();
+        self.size += size;
+        if let Some(replaced) = self.entries.put(hash, query) {
+            self.size -= hash_len + replaced.len();
+        }
+
+        while self.size > self.max_size {
+            let Some((hash, evicted)) = self.entries.pop_lru() else {
+                break;
+            };
+
+            self.size -= hash.len() + evicted.len();
+        }
+    }
+}
+
+impl ExtensionFactory for PersistedQueries {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(PersistedQueriesExt {
+            queries: self.queries.clone(),
+            pending: Mutex::new(None),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for PersistedQueriesExt {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let Some(extension) = request.extensions.remove(PERSISTED_QUERY_EXTENSION) else {
+            return next.run(ctx, request).await;
+        };
+
+        let PersistedQuery {
+            version,
+            sha256_hash,
+        } = extension
+            .into_json()
+            .ok()
+            .and_then(|json| serde_json::from_value(json).ok())
+            .ok_or_else(|| {
+                graphql_error(
+                    code::BAD_USER_INPUT,
+                    "Invalid persisted query: expected 'version' and 'sha256Hash'",
+                )
+            })?;
+
+        if version != PERSISTED_QUERY_VERSION {
+            return Err(graphql_error(
+                code::BAD_USER_INPUT,
+                format!("Unsupported persisted query version: {version}"),
+            ));
+        }
+
+        let sha256_hash = sha256_hash.to_ascii_lowercase();
+        let metrics = ctx.data_opt::<Metrics>();
+
+        if request.query.is_empty() {
+            let query = self
+                .queries
+                .lock()
+                .unwrap()
+                .entries
+                .get(&sha256_hash)
+                .cloned();
+            if let Some(metrics) = metrics {
+                metrics.persisted_query_lookup(query.is_some());
+            }
+
+            let Some(query) = query else {
+                return Err(graphql_error(
+                    code::PERSISTED_QUERY_NOT_FOUND,
+                    "PersistedQueryNotFound",
+                ));
+            };
+
+            // The request's payload size only covers the hash, so account for the query it refers
+            // to, as if it had been sent in full.
+            let variables = serde_json::to_vec(&request.variables).map_or(0, |v| v.len());
+            request
+                .data
+                .insert(PayloadSize((query.len() + variables) as u64));
+
+            request.query = query.to_string();
+        } else {
+            let digest = Hex::encode(Sha256::digest(request.query.as_bytes()).digest);
+            if digest != sha256_hash {
+                return Err(graphql_error(
+                    code::BAD_USER_INPUT,
+                    "Persisted query hash does not match the query",
+                ));
+            }
+
+            *self.pending.lock().unwrap() = Some((sha256_hash, request.query.as_str().into()));
+        }
+
+        next.run(ctx, request).await
+    }
+
+    async fn validation(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidation<'_>,
+    ) -> Result<ValidationResult, Vec<ServerError>> {
+        let result = next.run(ctx).await?;
+
+        if let Some((hash, query)) = self.pending.lock().unwrap().take() {
+            self.queries.lock().unwrap().insert(hash, query);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{EmptySubscription, Schema, Value};
+    use serde_json::json;
+
+    use crate::{config::ServiceConfig, mutation::Mutation, types::query::Query};
+
+    use super::*;
+
+    const QUERY: &str = "{ serviceConfig { maxPageSize } }";
+
+    fn schema(extension: PersistedQueries) -> Schema<Query, Mutation, EmptySubscription> {
+        Schema::build(Query, Mutation, EmptySubscription)
+            .data(ServiceConfig::default())
+            .extension(extension)
+            .finish()
+    }
+
+    fn request(query: &str, hash: &str) -> Request {
+        let mut request = Request::new(query);
+        request.extensions.insert(
+            PERSISTED_QUERY_EXTENSION.to_string(),
+            Value::from_json(json!({ "version": 1, "sha256Hash": hash })).unwrap(),
+        );
+        request
+    }
+
+    fn error_code(response: &async_graphql::Response) -> Option<String> {
+        let ext = response.errors.first()?.extensions.as_ref()?;
+        match ext.get("code")? {
+            Value::String(code) => Some(code.clone()),
+            _ => None,
+        }
+    }
+
+    fn hash(query: &str) -> String {
+        Hex::encode(Sha256::digest(query.as_bytes()).digest)
+    }
+
+    #[tokio::test]
+    async fn test_unknown_persisted_query() {
+        let schema = schema(PersistedQueries::new());
+        let response = schema.execute(request("", &hash(QUERY))).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "PersistedQueryNotFound");
+        assert_eq!(
+            error_code(&response).as_deref(),
+            Some(code::PERSISTED_QUERY_NOT_FOUND)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hash_mismatch() {
+        let schema = schema(PersistedQueries::new());
+        let response = schema
+            .execute(request(
+                QUERY,
+                &hash("{ serviceConfig { defaultPageSize } }"),
+            ))
+            .await;
+
+        assert_eq!(error_code(&response).as_deref(), Some(code::BAD_USER_INPUT));
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_version() {
+        let schema = schema(PersistedQueries::new());
+
+        let mut request = Request::new(QUERY);
+        request.extensions.insert(
+            PERSISTED_QUERY_EXTENSION.to_string(),
+            Value::from_json(json!({ "version": 2, "sha256Hash": hash(QUERY) })).unwrap(),
+        );
+
+        let response = schema.execute(request).await;
+        assert_eq!(error_code(&response).as_deref(), Some(code::BAD_USER_INPUT));
+    }
+
+    #[tokio::test]
+    async fn test_register_and_evict() {
+        // Only enough room for one of the two queries below.
+        let other = "{ serviceConfig { defaultPageSize } }";
+        let extension = PersistedQueries::with_max_size(hash(other).len() + other.len());
+        let queries = extension.queries.clone();
+        let schema = schema(extension);
+
+        // Registering a query stores it...
+        let response = schema.execute(request(QUERY, &hash(QUERY))).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+        assert_eq!(
+            queries
+                .lock()
+                .unwrap()
+                .entries
+                .peek(&hash(QUERY))
+                .map(|q| q.as_ref()),
+            Some(QUERY)
+        );
+
+        // ...so that it can be executed by its hash alone.
+        let registered = schema.execute(request("", &hash(QUERY))).await;
+        assert!(registered.is_ok(), "{:?}", registered.errors);
+        assert_eq!(registered.data, response.data);
+
+        // The store is bounded, so registering another query evicts the first.
+        let response = schema.execute(request(other, &hash(other))).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+        assert!(queries.lock().unwrap().entries.peek(&hash(QUERY)).is_none());
+        assert_eq!(
+            queries.lock().unwrap().size,
+            hash(other).len() + other.len()
+        );
+
+        let response = schema.execute(request("", &hash(QUERY))).await;
+        assert_eq!(
+            error_code(&response).as_deref(),
+            Some(code::PERSISTED_QUERY_NOT_FOUND)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_query_not_registered() {
+        let extension = PersistedQueries::new();
+        let queries = extension.queries.clone();
+        let schema = schema(extension);
+
+        // Queries that fail to parse or validate are not registered.
+        for invalid in [
+            "{ serviceConfig { maxPageSize }",
+            "{ serviceConfig { noSuchField } }",
+        ] {
+            let response = schema.execute(request(invalid, &hash(invalid))).await;
+            assert!(response.is_err());
+        }
+
+        assert!(queries.lock().unwrap().entries.is_empty());
+        assert_eq!(queries.lock().unwrap().size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_persisted_query_payload_size() {
+        /// Records the payload size that the query limits would be checked against.
+        struct RecordPayloadSizeExt(Arc<Mutex<Option<u64>>>);
+
+        #[async_trait::async_trait]
+        impl Extension for RecordPayloadSizeExt {
+            async fn parse_query(
+                &self,
+                ctx: &ExtensionContext<'_>,
+                query: &str,
+                variables: &async_graphql::Variables,
+                next: async_graphql::extensions::NextParseQuery<'_>,
+            ) -> ServerResult<async_graphql::parser::types::ExecutableDocument> {
+                *self.0.lock().unwrap() = ctx.data_opt::<PayloadSize>().map(|p| p.0);
+                next.run(ctx, query, variables).await
+            }
+        }
+
+        struct RecordPayloadSize(Arc<Mutex<Option<u64>>>);
+
+        impl ExtensionFactory for RecordPayloadSize {
+            fn create(&self) -> Arc<dyn Extension> {
+                Arc::new(RecordPayloadSizeExt(self.0.clone()))
+            }
+        }
+
+        let recorded = Arc::new(Mutex::new(None));
+        let schema = Schema::build(Query, Mutation, EmptySubscription)
+            .data(ServiceConfig::default())
+            .data(PayloadSize(10))
+            .extension(PersistedQueries::new())
+            .extension(RecordPayloadSize(recorded.clone()))
+            .finish();
+
+        // Registering a query is checked against the size of the request as sent.
+        let response = schema.execute(request(QUERY, &hash(QUERY))).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+        assert_eq!(*recorded.lock().unwrap(), Some(10));
+
+        // Looking it up by its hash is checked against the size of the query it refers to.
+        let response = schema.execute(request("", &hash(QUERY))).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+        assert_eq!(
+            *recorded.lock().unwrap(),
+            Some((QUERY.len() + "{}".len()) as u64)
+        );
+    }
+}
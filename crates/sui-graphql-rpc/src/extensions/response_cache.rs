@@ -0,0 +1,554 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::{Arc, Mutex};
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery,
+};
+use async_graphql::parser::types::{
+    ExecutableDocument, Field, OperationType, Selection, SelectionSet,
+};
+use async_graphql::{Response, ServerResult, Value, Variables};
+use async_graphql_value::Value as GqlValue;
+use lru::LruCache;
+
+use crate::metrics::Metrics;
+use crate::server::watermark_task::Watermark;
+
+/// The maximum total size of the responses kept in the cache, in bytes. Once full, the least
+/// recently used responses are evicted to make room.
+const MAX_CACHED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Responses larger than this (in bytes) are never cached, so that a single response cannot evict
+/// a large part of the cache.
+const MAX_CACHED_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// Fields of a `Checkpoint` whose values are fixed once the checkpoint has been created. Other
+/// fields (such as its epoch, or its transaction blocks) are viewed at the request's watermark, so
+/// they can change from one request to the next.
+const IMMUTABLE_CHECKPOINT_FIELDS: &[&str] = &[
+    "__typename",
+    "bcs",
+    "digest",
+    "networkTotalTransactions",
+    "previousCheckpointDigest",
+    "rollingGasSummary",
+    "sequenceNumber",
+    "timestamp",
+    "validatorSignatures",
+];
+
+/// Extension factory for caching responses to queries that are pinned to a checkpoint.
+///
+/// A query is pinned if it only reads fields of checkpoints that it identifies by sequence number,
+/// and that do not change once the checkpoint has been created. As long as those checkpoints are
+/// within the range available to the request, re-running the same query with the same variables
+/// produces the same response, regardless of the watermark, so it can be served from the cache
+/// instead. Mutations, responses with errors, and queries that are not pinned are never cached.
+pub(crate) struct ResponseCache {
+    responses: Arc<Mutex<Responses>>,
+}
+
+struct ResponseCacheExt {
+    responses: Arc<Mutex<Responses>>,
+
+    /// Set when parsing the query, if its response can be cached.
+    key: Mutex<Option<CacheKey>>,
+}
+
+/// Cached responses, bounded by their total size.
+struct Responses {
+    entries: LruCache<CacheKey, CachedResponse>,
+
+    /// Sum of the sizes of all entries, in bytes.
+    size: usize,
+
+    /// The maximum total size of all entries, in bytes.
+    max_size: usize,
+
+    /// The maximum size of a single entry, in bytes.
+    max_entry_size: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    query: String,
+    variables: String,
+    operation_name: Option<String>,
+}
+
+struct CachedResponse {
+    data: Value,
+    extensions: Vec<(String, Value)>,
+
+    /// Approximate size of the entry (including its key), in bytes.
+    size: usize,
+}
+
+impl ResponseCache {
+    pub(crate) fn new() -> Self {
+        Self::with_limits(MAX_CACHED_BYTES, MAX_CACHED_RESPONSE_BYTES)
+    }
+
+    fn with_limits(max_size: usize, max_entry_size: usize) -> Self {
+        Self {
+            responses: Arc::new(Mutex::new(Responses {
+                entries: LruCache::unbounded(),
+                size: 0,
+                max_size,
+                max_entry_size,
+            })),
+        }
+    }
+}
+
+impl Responses {
+    /// Rebuild the cached response for `key`, if there is one, marking it as recently used.
+    fn get(&mut self, key: &CacheKey) -> Option<Response> {
+        let cached = self.entries.get(key)?;
+        let response = Response::new(cached.data.clone());
+        Some(
+            cached
+                .extensions
+                .iter()
+                .fold(response, |response, (name, value)| {
+                    response.extension(name.clone(), value.clone())
+                }),
+        )
+    }
+
+    /// Cache the data and extensions of `response` under `key`, evicting the least recently used
+    /// entries until the cache fits within its size limit again. Responses that are too large to
+    /// cache are ignored.
+    fn insert(&mut self, key: CacheKey, response: &Response) {
+        let Ok(payload) = serde_json::to_vec(&(&response.data, &response.extensions)) else {
+            return;
+        };
+
+        let size = key.query.len()
+            + key.variables.len()
+            + key.operation_name.as_ref().map_or(0, String::len)
+            + payload.len();
+
+        if size > self.max_entry_size || size > self.max_size {
+            return;
+        }
+
+        let cached = CachedResponse {
+            data: response.data.clone(),
+            extensions: response
+                .extensions
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            size,
+        };
+
+        self.size += size;
+        if let Some(replaced) = self.entries.put(key, cached) {
+            self.size -= replaced.size;
+        }
+
+        while self.size > self.max_size {
+            let Some((_, evicted)) = self.entries.pop_lru() else {
+                break;
+            };
+
+            self.size -= evicted.size;
+        }
+    }
+}
+
+impl ExtensionFactory for ResponseCache {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ResponseCacheExt {
+            responses: self.responses.clone(),
+            key: Mutex::new(None),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for ResponseCacheExt {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+
+        // Without a watermark, there is no way to tell whether the checkpoints the query is pinned
+        // to are available yet.
+        let Some(watermark) = ctx.data_opt::<Watermark>() else {
+            return Ok(document);
+        };
+
+        let Some((lo, hi)) = pinned_checkpoints(&document, variables) else {
+            return Ok(document);
+        };
+
+        // Checkpoints outside the available range read as `null`, which will change once they
+        // become available.
+        if lo < watermark.lo_cp || hi > watermark.hi_cp {
+            return Ok(document);
+        }
+
+        let Ok(variables) = serde_json::to_string(variables) else {
+            return Ok(document);
+        };
+
+        *self.key.lock().unwrap() = Some(CacheKey {
+            query: query.to_string(),
+            variables,
+            operation_name: None,
+        });
+
+        Ok(document)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let Some(mut key) = self.key.lock().unwrap().take() else {
+            return next.run(ctx, operation_name).await;
+        };
+
+        key.operation_name = operation_name.map(str::to_string);
+
+        let cached = self.responses.lock().unwrap().get(&key);
+        if let Some(metrics) = ctx.data_opt::<Metrics>() {
+            metrics.response_cache_lookup(cached.is_some());
+        }
+
+        if let Some(response) = cached {
+            return response;
+        }
+
+        let response = next.run(ctx, operation_name).await;
+        if response.is_ok() {
+            self.responses.lock().unwrap().insert(key, &response);
+        }
+
+        response
+    }
+}
+
+/// The lowest and highest sequence numbers of the checkpoints that `document` is pinned to, if it
+/// is pinned. A document is pinned if all its operations are queries that only read immutable
+/// fields of checkpoints identified by their sequence numbers.
+fn pinned_checkpoints(document: &ExecutableDocument, variables: &Variables) -> Option<(u64, u64)> {
+    let mut range: Option<(u64, u64)> = None;
+    for (_, op) in document.operations.iter() {
+        if op.node.ty != OperationType::Query {
+            return None;
+        }
+
+        add_pinned_checkpoints(&op.node.selection_set.node, variables, &mut range)?;
+    }
+
+    range
+}
+
+/// Widen `range` to include the checkpoints that the top-level `selection_set` is pinned to.
+/// Returns `None` if any of its selections are not pinned.
+fn add_pinned_checkpoints(
+    selection_set: &SelectionSet,
+    variables: &Variables,
+    range: &mut Option<(u64, u64)>,
+) -> Option<()> {
+    for selection in &selection_set.items {
+        match &selection.node {
+            Selection::Field(field) if field.node.name.node.as_str() == "__typename" => {}
+
+            Selection::Field(field) if field.node.name.node.as_str() == "checkpoint" => {
+                let sequence_number = pinned_sequence_number(&field.node, variables)?;
+                if !reads_immutable_checkpoint_fields(&field.node.selection_set.node) {
+                    return None;
+                }
+
+                *range = Some(match *range {
+                    Some((lo, hi)) => (lo.min(sequence_number), hi.max(sequence_number)),
+                    None => (sequence_number, sequence_number),
+                });
+            }
+
+            Selection::InlineFragment(fragment) => {
+                add_pinned_checkpoints(&fragment.node.selection_set.node, variables, range)?;
+            }
+
+            Selection::Field(_) | Selection::FragmentSpread(_) => return None,
+        }
+    }
+
+    Some(())
+}
+
+/// The sequence number in the `id` argument of a `checkpoint` field, resolving variables if
+/// necessary.
+fn pinned_sequence_number(field: &Field, variables: &Variables) -> Option<u64> {
+    match &field.get_argument("id")?.node {
+        GqlValue::Object(id) => match id.get("sequenceNumber")? {
+            GqlValue::Number(num) => num.as_u64(),
+            GqlValue::Variable(var) => match variables.get(var)? {
+                Value::Number(num) => num.as_u64(),
+                _ => None,
+            },
+            _ => None,
+        },
+
+        GqlValue::Variable(var) => match variables.get(var)? {
+            Value::Object(id) => match id.get("sequenceNumber")? {
+                Value::Number(num) => num.as_u64(),
+                _ => None,
+            },
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
+
+fn reads_immutable_checkpoint_fields(selection_set: &SelectionSet) -> bool {
+    selection_set
+        .items
+        .iter()
+        .all(|selection| match &selection.node {
+            Selection::Field(field) => {
+                IMMUTABLE_CHECKPOINT_FIELDS.contains(&field.node.name.node.as_str())
+            }
+            Selection::InlineFragment(fragment) => {
+                reads_immutable_checkpoint_fields(&fragment.node.selection_set.node)
+            }
+            Selection::FragmentSpread(_) => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_graphql::{
+        value, EmptyMutation, EmptySubscription, Object, Request, Schema, SimpleObject,
+    };
+
+    use crate::types::checkpoint::CheckpointId;
+
+    use super::*;
+
+    struct TestQuery;
+
+    #[derive(SimpleObject)]
+    struct TestCheckpoint {
+        sequence_number: u64,
+    }
+
+    /// Counts the number of times the query was executed.
+    #[derive(Clone, Default)]
+    struct Executions(Arc<AtomicUsize>);
+
+    #[Object]
+    impl TestQuery {
+        async fn checkpoint(
+            &self,
+            ctx: &async_graphql::Context<'_>,
+            id: CheckpointId,
+        ) -> Option<TestCheckpoint> {
+            ctx.data_unchecked::<Executions>()
+                .0
+                .fetch_add(1, Ordering::SeqCst);
+
+            Some(TestCheckpoint {
+                sequence_number: id.sequence_number?.into(),
+            })
+        }
+    }
+
+    /// Stamps every executed response with an extension, to check that extensions survive a trip
+    /// through the cache.
+    struct Stamp;
+
+    struct StampExt;
+
+    impl ExtensionFactory for Stamp {
+        fn create(&self) -> Arc<dyn Extension> {
+            Arc::new(StampExt)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Extension for StampExt {
+        async fn execute(
+            &self,
+            ctx: &ExtensionContext<'_>,
+            operation_name: Option<&str>,
+            next: NextExecute<'_>,
+        ) -> Response {
+            next.run(ctx, operation_name)
+                .await
+                .extension("stamp", value!("fresh"))
+        }
+    }
+
+    fn watermark(lo_cp: u64, hi_cp: u64) -> Watermark {
+        Watermark {
+            hi_cp,
+            hi_cp_timestamp_ms: 0,
+            epoch: 0,
+            lo_cp,
+            lo_tx: 0,
+        }
+    }
+
+    fn key(query: &str) -> CacheKey {
+        CacheKey {
+            query: query.to_string(),
+            variables: "{}".to_string(),
+            operation_name: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_pinned_to_checkpoint() {
+        let extension = ResponseCache::new();
+        let responses = extension.responses.clone();
+        let executions = Executions::default();
+
+        // The stamp is registered after the cache, so it only runs when the cache misses.
+        let schema = Schema::build(TestQuery, EmptyMutation, EmptySubscription)
+            .data(executions.clone())
+            .extension(extension)
+            .extension(Stamp)
+            .finish();
+
+        let query = "{ checkpoint(id: { sequenceNumber: 5 }) { sequenceNumber } }";
+        let executed = || executions.0.load(Ordering::SeqCst);
+        let cached = || responses.lock().unwrap().entries.len();
+
+        // Requests without a watermark are not cached.
+        let response = schema.execute(Request::new(query)).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+        assert_eq!((executed(), cached()), (1, 0));
+
+        // Neither are requests for checkpoints outside the available range.
+        let _ = schema
+            .execute(Request::new(query).data(watermark(0, 4)))
+            .await;
+        let _ = schema
+            .execute(Request::new(query).data(watermark(6, 10)))
+            .await;
+        assert_eq!((executed(), cached()), (3, 0));
+
+        let response = schema
+            .execute(Request::new(query).data(watermark(0, 10)))
+            .await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+        assert_eq!((executed(), cached()), (4, 1));
+
+        // The cached response is reused as the watermark advances, along with its extensions.
+        let hit = schema
+            .execute(Request::new(query).data(watermark(1, 11)))
+            .await;
+        assert_eq!((executed(), cached()), (4, 1));
+        assert_eq!(hit.data, response.data);
+        assert_eq!(hit.extensions, response.extensions);
+        assert_eq!(hit.extensions.get("stamp"), Some(&value!("fresh")));
+
+        // Queries that are not pinned to a checkpoint are not cached.
+        let _ = schema
+            .execute(
+                Request::new("{ checkpoint(id: {}) { sequenceNumber } }").data(watermark(0, 10)),
+            )
+            .await;
+        assert_eq!((executed(), cached()), (5, 1));
+    }
+
+    #[test]
+    fn test_cache_bounded_by_size() {
+        let response = Response::new(value!({ "checkpoint": { "sequenceNumber": 5 } }));
+        let size = |query: &str| {
+            query.len()
+                + 2
+                + serde_json::to_vec(&(&response.data, &response.extensions))
+                    .unwrap()
+                    .len()
+        };
+
+        // Room for two entries.
+        let limit = size("a") * 2;
+        let cache = ResponseCache::with_limits(limit, limit);
+        let mut responses = cache.responses.lock().unwrap();
+
+        responses.insert(key("a"), &response);
+        responses.insert(key("b"), &response);
+        assert_eq!(responses.size, limit);
+        assert!(responses.get(&key("a")).is_some());
+
+        // Adding a third entry evicts the least recently used one.
+        responses.insert(key("c"), &response);
+        assert_eq!(responses.size, limit);
+        assert!(responses.get(&key("a")).is_some());
+        assert!(responses.get(&key("b")).is_none());
+        assert!(responses.get(&key("c")).is_some());
+
+        // Entries that are too large are not cached at all.
+        responses.insert(key("too long"), &response);
+        assert!(responses.get(&key("too long")).is_none());
+        assert_eq!(responses.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_pinned_documents() {
+        use async_graphql::parser::parse_query;
+
+        let variables = Variables::from_json(serde_json::json!({
+            "seq": 7,
+            "id": { "sequenceNumber": 8 },
+        }));
+
+        let pinned = |query: &str| pinned_checkpoints(&parse_query(query).unwrap(), &variables);
+
+        assert_eq!(
+            pinned("{ checkpoint(id: { sequenceNumber: 5 }) { digest } }"),
+            Some((5, 5))
+        );
+        assert_eq!(
+            pinned("query { a: checkpoint(id: { sequenceNumber: 5 }) { digest } b: checkpoint(id: { sequenceNumber: $seq }) { timestamp } }"),
+            Some((5, 7))
+        );
+        assert_eq!(
+            pinned("{ ... on Query { checkpoint(id: $id) { rollingGasSummary { computationCost } } } }"),
+            Some((8, 8))
+        );
+
+        assert_eq!(pinned("{ chainIdentifier }"), None);
+        assert_eq!(pinned("{ checkpoint { digest } }"), None);
+        assert_eq!(
+            pinned("{ checkpoint(id: { digest: \"\" }) { digest } }"),
+            None
+        );
+        assert_eq!(
+            pinned("{ checkpoint(id: { sequenceNumber: 5 }) { epoch { epochId } } }"),
+            None
+        );
+        assert_eq!(
+            pinned("{ checkpoint(id: { sequenceNumber: 5 }) { digest } chainIdentifier }"),
+            None
+        );
+        assert_eq!(
+            pinned("query { ...F } fragment F on Query { checkpoint(id: { sequenceNumber: 5 }) { digest } }"),
+            None
+        );
+        assert_eq!(
+            pinned(
+                "mutation { executeTransactionBlock(txBytes: \"\", signatures: []) { errors } }"
+            ),
+            None
+        );
+    }
+}
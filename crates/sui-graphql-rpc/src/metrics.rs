@@ -80,6 +80,10 @@ pub(crate) struct RequestMetrics {
     pub num_queries_top_level: IntCounterVec,
     /// Total inflight requests
     pub inflight_requests: Gauge,
+    /// Number of persisted query lookups by result (hit or miss)
+    pub persisted_query_lookups: IntCounterVec,
+    /// Number of response cache lookups by result (hit or miss)
+    pub response_cache_lookups: IntCounterVec,
 }
 
 #[derive(Clone)]
@@ -131,6 +135,24 @@ impl Metrics {
         self.request_metrics.num_queries.inc();
     }
 
+    /// Record whether a persisted query was found when looking it up by its hash
+    pub(crate) fn persisted_query_lookup(&self, hit: bool) {
+        let label = if hit { "hit" } else { "miss" };
+        self.request_metrics
+            .persisted_query_lookups
+            .with_label_values(&[label])
+            .inc();
+    }
+
+    /// Record whether a response was served from the response cache
+    pub(crate) fn response_cache_lookup(&self, hit: bool) {
+        let label = if hit { "hit" } else { "miss" };
+        self.request_metrics
+            .response_cache_lookups
+            .with_label_values(&[label])
+            .inc();
+    }
+
     /// Use this function to increment the number of errors per path and per error type.
     /// The error type is detected automatically from the passed errors.
     pub(crate) fn inc_errors(&self, errors: &[ServerError]) {
@@ -259,6 +281,20 @@ impl RequestMetrics {
                 registry
             )
             .unwrap(),
+            persisted_query_lookups: register_int_counter_vec_with_registry!(
+                "persisted_query_lookups",
+                "Number of persisted query lookups by result (hit or miss)",
+                &["type"],
+                registry,
+            )
+            .unwrap(),
+            response_cache_lookups: register_int_counter_vec_with_registry!(
+                "response_cache_lookups",
+                "Number of response cache lookups by result (hit or miss)",
+                &["type"],
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
@@ -25,20 +25,23 @@ use crate::{
     extensions::{
         feature_gate::FeatureGate,
         logger::Logger,
+        persisted_queries::PersistedQueries,
         query_limits_checker::{PayloadSize, QueryLimitsChecker, ShowUsage},
+        response_cache::ResponseCache,
         timeout::Timeout,
     },
     server::version::set_version_middleware,
     types::query::{Query, SuiGraphQLSchema},
+    types::subscription::{Subscription, SubscriptionSlots},
 };
 use async_graphql::extensions::ApolloTracing;
 use async_graphql::extensions::Tracing;
-use async_graphql::EmptySubscription;
-use async_graphql::{extensions::ExtensionFactory, Schema, SchemaBuilder};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql::http::ALL_WEBSOCKET_PROTOCOLS;
+use async_graphql::{extensions::ExtensionFactory, Executor, Schema, SchemaBuilder};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
 use axum::body::Body;
 use axum::extract::FromRef;
-use axum::extract::{ConnectInfo, Query as AxumQuery, State};
+use axum::extract::{ConnectInfo, Query as AxumQuery, State, WebSocketUpgrade};
 use axum::http::{HeaderMap, StatusCode};
 use axum::middleware::{self};
 use axum::response::IntoResponse;
@@ -48,6 +51,7 @@ use axum::Router;
 use axum_extra::headers::ContentLength;
 use axum_extra::TypedHeader;
 use chrono::Utc;
+use futures::stream::BoxStream;
 use http::{HeaderValue, Method, Request};
 use mysten_metrics::spawn_monitored_task;
 use mysten_network::callback::{CallbackLayer, MakeCallbackHandler, ResponseHandler};
@@ -145,7 +149,7 @@ impl Server {
 
 pub(crate) struct ServerBuilder {
     state: AppState,
-    schema: SchemaBuilder<Query, Mutation, EmptySubscription>,
+    schema: SchemaBuilder<Query, Mutation, Subscription>,
     router: Option<Router>,
     db_reader: Option<Db>,
     resolver: Option<PackageResolver>,
@@ -157,6 +161,7 @@ pub(crate) struct AppState {
     service: ServiceConfig,
     metrics: Metrics,
     cancellation_token: CancellationToken,
+    subscription_slots: SubscriptionSlots,
     pub version: Version,
 }
 
@@ -169,6 +174,7 @@ impl AppState {
         version: Version,
     ) -> Self {
         Self {
+            subscription_slots: SubscriptionSlots::new(&service.limits),
             connection,
             service,
             metrics,
@@ -184,6 +190,18 @@ impl FromRef<AppState> for ConnectionConfig {
     }
 }
 
+impl FromRef<AppState> for ServiceConfig {
+    fn from_ref(app_state: &AppState) -> ServiceConfig {
+        app_state.service.clone()
+    }
+}
+
+impl FromRef<AppState> for SubscriptionSlots {
+    fn from_ref(app_state: &AppState) -> SubscriptionSlots {
+        app_state.subscription_slots.clone()
+    }
+}
+
 impl FromRef<AppState> for Metrics {
     fn from_ref(app_state: &AppState) -> Metrics {
         app_state.metrics.clone()
@@ -219,7 +237,7 @@ impl ServerBuilder {
     }
 
     #[cfg(test)]
-    fn build_schema(self) -> Schema<Query, Mutation, EmptySubscription> {
+    fn build_schema(self) -> Schema<Query, Mutation, Subscription> {
         self.schema.finish()
     }
 
@@ -229,7 +247,7 @@ impl ServerBuilder {
         self,
     ) -> (
         String,
-        Schema<Query, Mutation, EmptySubscription>,
+        Schema<Query, Mutation, Subscription>,
         Db,
         PackageResolver,
         Router,
@@ -256,6 +274,8 @@ impl ServerBuilder {
             let router: Router = Router::new()
                 .route("/", post(graphql_handler))
                 .route("/graphql", post(graphql_handler))
+                .route("/subscriptions", get(subscription_handler))
+                .route("/graphql/subscriptions", get(subscription_handler))
                 .route("/health", get(health_check))
                 .route("/graphql/health", get(health_check))
                 .with_state(self.state.clone())
@@ -494,6 +514,14 @@ impl ServerBuilder {
             builder = builder.extension(Timeout);
         }
 
+        if config.internal_features.persisted_queries {
+            builder = builder.extension(PersistedQueries::new());
+        }
+
+        if config.internal_features.response_cache {
+            builder = builder.extension(ResponseCache::new());
+        }
+
         if config.internal_features.tracing {
             builder = builder.extension(Tracing);
         }
@@ -509,8 +537,8 @@ impl ServerBuilder {
     }
 }
 
-fn schema_builder() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
-    async_graphql::Schema::build(Query, Mutation, EmptySubscription)
+fn schema_builder() -> SchemaBuilder<Query, Mutation, Subscription> {
+    async_graphql::Schema::build(Query, Mutation, Subscription)
         .register_output_type::<IMoveObject>()
         .register_output_type::<IObject>()
         .register_output_type::<IOwner>()
@@ -558,6 +586,76 @@ async fn graphql_handler(
     (extensions, result.into())
 }
 
+/// Entry point for subscriptions, served over a websocket using either the `graphql-ws` or
+/// `graphql-transport-ws` protocol. Each connection is stamped with a unique ID, and the watermark
+/// at the time it was opened. Subscriptions follow the watermark as it advances, so they are also
+/// given access to the watermark lock. Each connection gets its own subscription slots, which
+/// also draw from the service-wide slots.
+async fn subscription_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(service): State<ServiceConfig>,
+    State(subscription_slots): State<SubscriptionSlots>,
+    schema: Extension<SuiGraphQLSchema>,
+    Extension(watermark_lock): Extension<WatermarkLock>,
+    Extension(chain_identifier_lock): Extension<ChainIdentifierLock>,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let mut data = async_graphql::Data::default();
+
+    data.insert(subscription_slots.for_connection(&service.limits));
+    data.insert(Uuid::new_v4());
+    data.insert(addr);
+    data.insert(Watermark::new(watermark_lock.clone()).await);
+    data.insert(watermark_lock);
+    data.insert(chain_identifier_lock.read().await);
+
+    let Extension(schema) = schema;
+    upgrade
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .max_message_size(service.limits.max_query_payload_size as usize)
+        .on_upgrade(move |stream| {
+            GraphQLWebSocket::new(stream, SubscriptionExecutor(schema), protocol)
+                .with_data(data)
+                .serve()
+        })
+}
+
+/// Executes the operations sent over a subscription websocket. Each operation is stamped with the
+/// size of its payload, which is checked against the limits in place of the `Content-Length` of
+/// an HTTP request.
+#[derive(Clone)]
+struct SubscriptionExecutor(SuiGraphQLSchema);
+
+impl SubscriptionExecutor {
+    fn with_payload_size(request: async_graphql::Request) -> async_graphql::Request {
+        // Measure the operation as it would be sent in the body of an HTTP request.
+        let payload = serde_json::json!({
+            "query": request.query,
+            "operationName": request.operation_name,
+            "variables": request.variables,
+            "extensions": request.extensions,
+        });
+
+        let payload_size = payload.to_string().len() as u64;
+        request.data(PayloadSize(payload_size))
+    }
+}
+
+impl Executor for SubscriptionExecutor {
+    async fn execute(&self, request: async_graphql::Request) -> async_graphql::Response {
+        self.0.execute(Self::with_payload_size(request)).await
+    }
+
+    fn execute_stream(
+        &self,
+        request: async_graphql::Request,
+        session_data: Option<Arc<async_graphql::Data>>,
+    ) -> BoxStream<'static, async_graphql::Response> {
+        Executor::execute_stream(&self.0, Self::with_payload_size(request), session_data)
+    }
+}
+
 #[derive(Clone)]
 struct MetricsMakeCallbackHandler {
     metrics: Metrics,
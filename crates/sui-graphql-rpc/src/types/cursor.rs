@@ -185,6 +185,16 @@ impl<C> Page<C> {
         }
     }
 
+    /// A page of up to `limit` entries, taken from the front of the range after `after`.
+    pub(crate) fn forward(after: Option<C>, limit: u64) -> Self {
+        Page {
+            after,
+            before: None,
+            limit,
+            end: End::Front,
+        }
+    }
+
     pub(crate) fn after(&self) -> Option<&C> {
         self.after.as_ref()
     }
@@ -30,6 +30,11 @@ pub(crate) struct EventKey {
     /// The checkpoint sequence number this was viewed at.
     #[serde(rename = "c")]
     pub checkpoint_viewed_at: u64,
+
+    /// Whether the cursor marks a position between events, rather than an event. Such cursors may
+    /// not have a corresponding node in the result set.
+    #[serde(rename = "i", default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_scan_limited: bool,
 }
 
 pub(crate) type Cursor = cursor::JsonCursor<EventKey>;
@@ -112,6 +117,7 @@ impl Target<Cursor> for StoredEvent {
             tx: self.tx_sequence_number as u64,
             e: self.event_sequence_number as u64,
             checkpoint_viewed_at,
+            is_scan_limited: false,
         })
     }
 }
@@ -122,7 +128,42 @@ impl Checkpointed for Cursor {
     }
 }
 
-impl ScanLimited for Cursor {}
+impl ScanLimited for Cursor {
+    fn is_scan_limited(&self) -> bool {
+        self.is_scan_limited
+    }
+
+    fn unlimited(&self) -> Self {
+        Cursor::new(EventKey {
+            is_scan_limited: false,
+            ..(**self).clone()
+        })
+    }
+}
+
+impl Cursor {
+    /// A cursor pointing after all the events emitted by transactions before `tx_hi`, and before
+    /// all the events emitted by transaction `tx_hi` and later, or `None` if there are no events
+    /// before `tx_hi`.
+    pub(crate) fn before_tx(tx_hi: u64, checkpoint_viewed_at: u64) -> Option<Self> {
+        Some(Cursor::new(EventKey {
+            tx: tx_hi.checked_sub(1)?,
+            // Event sequence numbers are stored as `i64`s, so this is past the last event of any
+            // transaction.
+            e: i64::MAX as u64,
+            checkpoint_viewed_at,
+            is_scan_limited: true,
+        }))
+    }
+
+    /// The same position, viewed at `checkpoint_viewed_at` instead.
+    pub(crate) fn viewed_at(&self, checkpoint_viewed_at: u64) -> Self {
+        Cursor::new(EventKey {
+            checkpoint_viewed_at,
+            ..(**self).clone()
+        })
+    }
+}
 
 impl Target<Cursor> for EvLookup {
     fn cursor(&self, checkpoint_viewed_at: u64) -> Cursor {
@@ -130,6 +171,7 @@ impl Target<Cursor> for EvLookup {
             tx: self.tx as u64,
             e: self.ev as u64,
             checkpoint_viewed_at,
+            is_scan_limited: false,
         })
     }
 }
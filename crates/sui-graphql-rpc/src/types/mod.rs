@@ -45,6 +45,7 @@ pub(crate) mod stake;
 pub(crate) mod stake_subsidy;
 pub(crate) mod storage_fund;
 pub(crate) mod string_input;
+pub(crate) mod subscription;
 pub(crate) mod sui_address;
 pub(crate) mod suins_registration;
 pub(crate) mod system_parameters;
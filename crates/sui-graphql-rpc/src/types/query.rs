@@ -18,6 +18,7 @@ use super::move_package::{
 use super::move_registry::named_move_package::NamedMovePackage;
 use super::move_registry::named_type::NamedType;
 use super::object::ObjectKey;
+use super::subscription::Subscription;
 use super::suins_registration::NameService;
 use super::uint53::UInt53;
 use super::{
@@ -51,7 +52,7 @@ use crate::types::zklogin_verify_signature::ZkLoginVerifyResult;
 use crate::{config::ServiceConfig, error::Error, mutation::Mutation};
 
 pub(crate) struct Query;
-pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, EmptySubscription>;
+pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, Subscription>;
 
 #[Object]
 impl Query {
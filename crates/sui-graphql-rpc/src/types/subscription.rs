@@ -0,0 +1,295 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_graphql::connection::CursorType;
+use async_graphql::*;
+use async_stream::try_stream;
+use futures::Stream;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::{
+    checkpoint::{self, Checkpoint, CheckpointId},
+    cursor::Page,
+    event::{self, Event, EventFilter},
+    transaction_block::{self, TransactionBlock, TransactionBlockFilter},
+};
+use crate::config::{Limits, ServiceConfig};
+use crate::error::Error;
+use crate::server::watermark_task::{Watermark, WatermarkLock};
+
+pub(crate) struct Subscription;
+
+/// Bounds the number of subscriptions that can be active at once, across the service and on each
+/// connection.
+#[derive(Clone)]
+pub(crate) struct SubscriptionSlots {
+    service: Arc<Semaphore>,
+    connection: Arc<Semaphore>,
+}
+
+/// Follows the watermark on behalf of a subscription, to find out when new checkpoints have been
+/// indexed.
+struct Follower {
+    watermark: WatermarkLock,
+    poll_interval: Duration,
+    page_size: u64,
+    scan_limit: u64,
+
+    /// Slots taken up by the subscription, released when its stream is dropped.
+    _slots: (OwnedSemaphorePermit, OwnedSemaphorePermit),
+}
+
+/// Subscriptions stream data from the network as it is indexed, starting from the latest
+/// checkpoint at the time of subscribing. Results are delivered in the order they occurred on
+/// chain, at most one page (`serviceConfig.maxPageSize`) at a time. The number of subscriptions
+/// that can be active at once is limited per connection
+/// (`serviceConfig.maxSubscriptionsPerConnection`) and across the service
+/// (`serviceConfig.maxSubscriptions`).
+#[Subscription]
+impl Subscription {
+    /// Checkpoints, as they are indexed.
+    async fn checkpoints(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = Result<Checkpoint>>> {
+        let follower = Follower::new(ctx)?;
+        let mut lo_cp = follower.hi_cp().await;
+
+        Ok(try_stream! {
+            loop {
+                let hi_cp = follower.wait_past(lo_cp).await;
+
+                let mut after = Some(checkpoint::Cursor::new(checkpoint::CheckpointCursor {
+                    checkpoint_viewed_at: hi_cp,
+                    sequence_number: lo_cp,
+                }));
+
+                loop {
+                    let page = Page::forward(after.take(), follower.page_size);
+                    let conn = Checkpoint::paginate(ctx.data_unchecked(), page, None, hi_cp)
+                        .await
+                        .extend()?;
+
+                    let has_next_page = conn.has_next_page;
+                    for edge in conn.edges {
+                        after = Some(decode_cursor(&edge.cursor)?);
+                        yield edge.node;
+                    }
+
+                    if !has_next_page {
+                        break;
+                    }
+                }
+
+                lo_cp = hi_cp;
+            }
+        })
+    }
+
+    /// Transaction blocks that match `filter`, as they are indexed.
+    ///
+    /// Filters that would require a `scanLimit` when querying `Query.transactionBlocks` are
+    /// scanned `serviceConfig.maxScanLimit` transactions at a time. Checkpoint bounds in `filter`
+    /// further restrict the transactions that are streamed.
+    async fn transaction_blocks(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<impl Stream<Item = Result<TransactionBlock>>> {
+        let follower = Follower::new(ctx)?;
+        let filter = filter.unwrap_or_default();
+        let mut lo_cp = follower.hi_cp().await;
+
+        Ok(try_stream! {
+            loop {
+                let hi_cp = follower.wait_past(lo_cp).await;
+
+                // Only consider transactions from checkpoints that have been indexed since the
+                // last time the watermark was checked.
+                let Some(window) = filter.clone().intersect(TransactionBlockFilter {
+                    after_checkpoint: Some(lo_cp.into()),
+                    ..Default::default()
+                }) else {
+                    break;
+                };
+
+                let scan_limit = window
+                    .requires_scan_limit()
+                    .then_some(follower.scan_limit);
+
+                let mut after: Option<transaction_block::Cursor> = None;
+                loop {
+                    let page = Page::forward(after.take(), follower.page_size);
+                    let conn =
+                        TransactionBlock::paginate(ctx, page, window.clone(), hi_cp, scan_limit)
+                            .await
+                            .extend()?;
+
+                    // With a scan limit, the end cursor can point past the last transaction in the
+                    // page, so it is used in preference to the cursor of the last edge.
+                    let end_cursor = conn
+                        .end_cursor
+                        .clone()
+                        .or_else(|| conn.edges.last().map(|edge| edge.cursor.clone()));
+
+                    let has_next_page = conn.has_next_page;
+                    for edge in conn.edges {
+                        yield edge.node;
+                    }
+
+                    match end_cursor {
+                        Some(cursor) if has_next_page => after = Some(decode_cursor(&cursor)?),
+                        _ => break,
+                    }
+                }
+
+                lo_cp = hi_cp;
+            }
+        })
+    }
+
+    /// Events that match `filter`, as they are indexed.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<EventFilter>,
+    ) -> Result<impl Stream<Item = Result<Event>>> {
+        let follower = Follower::new(ctx)?;
+        let filter = filter.unwrap_or_default();
+        let lo_cp = follower.hi_cp().await;
+
+        let Some(checkpoint) = Checkpoint::query(ctx, CheckpointId::by_seq_num(lo_cp), lo_cp)
+            .await
+            .extend()?
+        else {
+            return Err(Error::Internal(format!(
+                "Checkpoint {lo_cp} has not been indexed"
+            )))
+            .extend();
+        };
+
+        // Events are paginated by transaction, so start after the events of the last transaction
+        // in the latest checkpoint.
+        let mut after =
+            event::Cursor::before_tx(checkpoint.network_total_transactions_impl(), lo_cp);
+        let mut lo_cp = lo_cp;
+
+        Ok(try_stream! {
+            loop {
+                let hi_cp = follower.wait_past(lo_cp).await;
+
+                loop {
+                    let cursor = after.as_ref().map(|cursor| cursor.viewed_at(hi_cp));
+                    let page = Page::forward(cursor, follower.page_size);
+                    let conn = Event::paginate(ctx.data_unchecked(), page, filter.clone(), hi_cp)
+                        .await
+                        .extend()?;
+
+                    let has_next_page = conn.has_next_page;
+                    for edge in conn.edges {
+                        after = Some(decode_cursor(&edge.cursor)?);
+                        yield edge.node;
+                    }
+
+                    if !has_next_page {
+                        break;
+                    }
+                }
+
+                lo_cp = hi_cp;
+            }
+        })
+    }
+}
+
+impl SubscriptionSlots {
+    /// Slots for the whole service, which each connection draws from.
+    pub(crate) fn new(limits: &Limits) -> Self {
+        Self {
+            service: Arc::new(Semaphore::new(limits.max_subscriptions as usize)),
+            connection: Arc::new(Semaphore::new(
+                limits.max_subscriptions_per_connection as usize,
+            )),
+        }
+    }
+
+    /// Slots for a new connection, which also draw from the service's slots.
+    pub(crate) fn for_connection(&self, limits: &Limits) -> Self {
+        Self {
+            service: self.service.clone(),
+            connection: Arc::new(Semaphore::new(
+                limits.max_subscriptions_per_connection as usize,
+            )),
+        }
+    }
+
+    /// Take up a slot for a new subscription, if there is one free on both the connection and the
+    /// service.
+    fn acquire(&self) -> Result<(OwnedSemaphorePermit, OwnedSemaphorePermit)> {
+        let connection = self.connection.clone().try_acquire_owned().map_err(|_| {
+            Error::Client("Too many subscriptions on this connection".to_string()).extend()
+        })?;
+
+        let service = self.service.clone().try_acquire_owned().map_err(|_| {
+            Error::Client("Too many subscriptions on this service, try again later".to_string())
+                .extend()
+        })?;
+
+        Ok((connection, service))
+    }
+}
+
+impl Follower {
+    fn new(ctx: &Context<'_>) -> Result<Self> {
+        let slots: &SubscriptionSlots = ctx
+            .data()
+            .map_err(|_| Error::Internal("Unable to fetch subscription slots".to_string()))
+            .extend()?;
+
+        let watermark: &WatermarkLock = ctx
+            .data()
+            .map_err(|_| Error::Internal("Unable to fetch watermark".to_string()))
+            .extend()?;
+
+        let ServiceConfig {
+            limits,
+            background_tasks,
+            ..
+        } = ctx.data_unchecked();
+
+        Ok(Self {
+            watermark: watermark.clone(),
+            poll_interval: Duration::from_millis(background_tasks.watermark_update_ms),
+            page_size: limits.max_page_size as u64,
+            scan_limit: limits.max_scan_limit as u64,
+            _slots: slots.acquire()?,
+        })
+    }
+
+    /// The latest checkpoint that has been indexed.
+    async fn hi_cp(&self) -> u64 {
+        Watermark::new(self.watermark.clone()).await.hi_cp
+    }
+
+    /// Wait until a checkpoint after `cp` has been indexed, and return the latest checkpoint.
+    async fn wait_past(&self, cp: u64) -> u64 {
+        loop {
+            let hi_cp = self.hi_cp().await;
+            if hi_cp > cp {
+                return hi_cp;
+            }
+
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+/// Decode a cursor returned in a page of results, to continue paginating from.
+fn decode_cursor<C: CursorType>(cursor: &str) -> Result<C> {
+    C::decode_cursor(cursor)
+        .map_err(|_| Error::Internal(format!("Failed to decode cursor: {cursor}")))
+        .extend()
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use fastcrypto::encoding::{Base64, Encoding};
+use futures::{SinkExt, StreamExt};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use serde_json::json;
@@ -14,6 +15,9 @@ use sui_graphql_rpc::config::Limits;
 use sui_graphql_rpc::config::ServiceConfig;
 use sui_graphql_rpc::test_infra::cluster::prep_executor_cluster;
 use sui_graphql_rpc::test_infra::cluster::start_cluster;
+use sui_graphql_rpc::test_infra::cluster::Cluster;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::SuiAddress;
 use sui_types::digests::ChainIdentifier;
 use sui_types::gas_coin::GAS;
 use sui_types::transaction::CallArg;
@@ -24,6 +28,11 @@ use sui_types::SUI_FRAMEWORK_ADDRESS;
 use sui_types::SUI_FRAMEWORK_PACKAGE_ID;
 use tempfile::tempdir;
 use tokio::time::sleep;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+type WebSocket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
 
 #[tokio::test]
 async fn test_simple_client_validator_cluster() {
@@ -852,3 +861,152 @@ async fn test_payload_using_vars_mutation_passes() {
 
     assert!(res.errors().is_empty(), "{:#?}", res.errors());
 }
+
+#[tokio::test]
+async fn test_subscriptions() {
+    let cluster = start_cluster(ServiceConfig {
+        limits: Limits {
+            max_subscriptions_per_connection: 2,
+            ..Default::default()
+        },
+        ..ServiceConfig::test_defaults()
+    })
+    .await;
+
+    let mut ws = connect_subscriptions(&cluster).await;
+
+    // Checkpoints are streamed in order, as they are indexed.
+    subscribe(
+        &mut ws,
+        "1",
+        "subscription { checkpoints { sequenceNumber } }",
+    )
+    .await;
+    let mut checkpoints = vec![];
+    while checkpoints.len() < 3 {
+        let (id, data) = next_result(&mut ws).await;
+        assert_eq!(id, "1");
+        checkpoints.push(data["checkpoints"]["sequenceNumber"].as_u64().unwrap());
+    }
+    assert!(
+        checkpoints.windows(2).all(|w| w[1] == w[0] + 1),
+        "{checkpoints:?}"
+    );
+
+    // Events are streamed from transactions executed after subscribing.
+    subscribe(
+        &mut ws,
+        "2",
+        "subscription { events { sender { address } contents { type { repr } } } }",
+    )
+    .await;
+    sleep(Duration::from_secs(1)).await;
+    let sender = stake_with_validator(&cluster).await;
+    let event = loop {
+        let (id, data) = next_result(&mut ws).await;
+        let event = &data["events"];
+        if id == "2"
+            && event["contents"]["type"]["repr"]
+                .as_str()
+                .unwrap()
+                .ends_with("::validator::StakingRequestEvent")
+        {
+            break event.clone();
+        }
+    };
+    assert_eq!(event["sender"]["address"], json!(sender.to_string()));
+
+    // Both of this connection's subscriptions are in use.
+    subscribe(
+        &mut ws,
+        "3",
+        "subscription { checkpoints { sequenceNumber } }",
+    )
+    .await;
+    let rejection = loop {
+        let message = next_message(&mut ws).await;
+        if message["id"] == "3" {
+            break message;
+        }
+    };
+    assert!(
+        rejection
+            .to_string()
+            .contains("Too many subscriptions on this connection"),
+        "{rejection}"
+    );
+}
+
+/// Open a websocket to the service's subscription endpoint, and start a `graphql-transport-ws`
+/// session on it.
+async fn connect_subscriptions(cluster: &Cluster) -> WebSocket {
+    let config = &cluster.network.graphql_connection_config;
+    let mut request = format!("ws://{}:{}/subscriptions", config.host, config.port)
+        .into_client_request()
+        .unwrap();
+    request.headers_mut().insert(
+        "Sec-WebSocket-Protocol",
+        "graphql-transport-ws".parse().unwrap(),
+    );
+
+    let (mut ws, _) = connect_async(request).await.unwrap();
+    ws.send(Message::Text(
+        json!({ "type": "connection_init" }).to_string(),
+    ))
+    .await
+    .unwrap();
+    let ack = next_message(&mut ws).await;
+    assert_eq!(ack["type"], "connection_ack", "{ack}");
+    ws
+}
+
+async fn subscribe(ws: &mut WebSocket, id: &str, query: &str) {
+    let message = json!({ "id": id, "type": "subscribe", "payload": { "query": query } });
+    ws.send(Message::Text(message.to_string())).await.unwrap();
+}
+
+async fn next_message(ws: &mut WebSocket) -> serde_json::Value {
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(60), ws.next())
+            .await
+            .expect("Timed out waiting for a subscription message")
+            .expect("Subscription connection closed")
+            .unwrap();
+
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+/// The ID of the subscription that the next result belongs to, and its data.
+async fn next_result(ws: &mut WebSocket) -> (String, serde_json::Value) {
+    let message = next_message(ws).await;
+    assert_eq!(message["type"], "next", "{message}");
+    assert!(message["payload"]["errors"].is_null(), "{message}");
+    let id = message["id"].as_str().unwrap().to_string();
+    (id, message["payload"]["data"].clone())
+}
+
+/// Stake one of the cluster's gas coins with a validator, which emits a `StakingRequestEvent`.
+/// Returns the address that staked.
+async fn stake_with_validator(cluster: &Cluster) -> SuiAddress {
+    let network = &cluster.network.validator_fullnode_handle;
+    let gas_price = network.wallet.get_reference_gas_price().await.unwrap();
+    let accounts_and_objs = network
+        .wallet
+        .get_all_accounts_and_gas_objects()
+        .await
+        .unwrap();
+
+    let sender = accounts_and_objs[0].0;
+    let gas_object = accounts_and_objs[0].1[0];
+    let coin_to_stake = accounts_and_objs[0].1[1];
+    let validator_address = network.swarm.config().validator_configs()[0].sui_address();
+
+    let tx = TestTransactionBuilder::new(sender, gas_object, gas_price)
+        .call_staking(coin_to_stake, validator_address)
+        .build();
+    network.sign_and_execute_transaction(&tx).await;
+    sender
+}
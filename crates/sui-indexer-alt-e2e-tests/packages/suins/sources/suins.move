@@ -20,6 +20,15 @@ public struct NameRecord has copy, drop, store {
     data: VecMap<String, String>,
 }
 
+/// A mock of the NFT that controls a registration.
+public struct SuinsRegistration has key, store {
+    id: UID,
+}
+
+public fun mint_nft(owner: address, ctx: &mut TxContext) {
+    transfer::public_transfer(SuinsRegistration { id: object::new(ctx) }, owner)
+}
+
 public fun share_forward_registry(ctx: &mut TxContext) {
   let registry: Table<Domain, NameRecord> = table::new(ctx);
   transfer::public_share_object(registry)
@@ -9,7 +9,7 @@ use move_core_types::ident_str;
 use reqwest::Client;
 use serde_json::{json, Value};
 use simulacrum::Simulacrum;
-use sui_indexer_alt::config::IndexerConfig;
+use sui_indexer_alt::config::{IndexerConfig, Merge};
 use sui_indexer_alt_e2e_tests::{find_immutable, find_shared, FullCluster};
 use sui_indexer_alt_framework::IndexerArgs;
 use sui_indexer_alt_jsonrpc::{
@@ -20,6 +20,7 @@ use sui_move_build::BuildConfig;
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
     effects::TransactionEffectsAPI,
+    object::Owner,
     programmable_transaction_builder::ProgrammableTransactionBuilder,
     transaction::{ObjectArg, Transaction, TransactionData},
 };
@@ -269,6 +270,174 @@ async fn test_resolve_subdomain_no_parent() {
     c.cluster.stopped().await;
 }
 
+/// List all the names whose registration NFT is owned by an address, across multiple pages. Leaf
+/// names are listed along with their parent, and expired names are skipped.
+#[tokio::test]
+async fn test_names_by_address() {
+    let mut c = SuiNSCluster::new().await;
+
+    let owner = SuiAddress::random_for_testing_only();
+    let other = SuiAddress::random_for_testing_only();
+
+    let foo = c.mint_nft(owner).await.expect("Failed to mint NFT");
+    let bar = c.mint_nft(owner).await.expect("Failed to mint NFT");
+    let baz = c.mint_nft(other).await.expect("Failed to mint NFT");
+    let old = c.mint_nft(owner).await.expect("Failed to mint NFT");
+    let qux = c.mint_nft(owner).await.expect("Failed to mint NFT");
+
+    // Which address a name resolves to does not matter, only who owns its NFT.
+    for (nft, labels, target, expiry_ms) in [
+        (foo, &["sui", "foo"][..], Some(other), 4_000_000_000),
+        (foo, &["sui", "foo", "leaf"][..], None, 0),
+        (bar, &["sui", "bar"][..], None, 4_000_000_000),
+        (baz, &["sui", "baz"][..], Some(owner), 4_000_000_000),
+        (old, &["sui", "old"][..], Some(owner), 1000),
+        (qux, &["sui", "qux"][..], Some(owner), 4_000_000_000),
+    ] {
+        c.add_domain(nft, labels, target, expiry_ms)
+            .await
+            .expect("Failed to add domain");
+    }
+
+    c.cluster.advance_clock(Duration::from_millis(1000));
+    c.cluster.create_checkpoint().await;
+
+    let resp = c
+        .request("suix_getNameServiceNamesByAddress", json!([owner, null, 2]))
+        .await
+        .unwrap();
+    assert_eq!(names(&resp), vec!["bar.sui", "foo.sui"], "{resp:#?}");
+    assert!(resp["result"]["hasNextPage"].as_bool().unwrap());
+
+    // The expired name does not take up space on the page.
+    let cursor = resp["result"]["nextCursor"].clone();
+    let resp = c
+        .request(
+            "suix_getNameServiceNamesByAddress",
+            json!([owner, cursor, 2]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(names(&resp), vec!["leaf.foo.sui", "qux.sui"], "{resp:#?}");
+    assert_eq!(
+        resp["result"]["data"][0]["expirationTimestampMs"],
+        json!("4000000000"),
+    );
+    assert!(!resp["result"]["hasNextPage"].as_bool().unwrap());
+
+    c.cluster.stopped().await;
+}
+
+/// Sub-names are listed with their parent's expiry if they are leaves, and are not listed once
+/// they belong to a different registration than their parent.
+#[tokio::test]
+async fn test_subnames() {
+    let mut c = SuiNSCluster::new().await;
+
+    let nft = ObjectID::random();
+    let target = SuiAddress::random_for_testing_only();
+    let expiry_ms = 1_000_000_000;
+    c.add_domain(nft, &["sui", "foo"], Some(target), expiry_ms)
+        .await
+        .expect("Failed to add domain");
+    c.add_domain(nft, &["sui", "foo", "leaf"], Some(target), 0)
+        .await
+        .expect("Failed to add leaf");
+    c.add_domain(
+        ObjectID::random(),
+        &["sui", "foo", "node"],
+        None,
+        4_000_000_000,
+    )
+    .await
+    .expect("Failed to add node");
+    c.add_domain(ObjectID::random(), &["sui", "foo", "stale"], None, 0)
+        .await
+        .expect("Failed to add stale leaf");
+
+    c.cluster.create_checkpoint().await;
+
+    let resp = c
+        .request(
+            "suix_getNameServiceSubnames",
+            json!(["foo.sui", null, null]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        names(&resp),
+        vec!["leaf.foo.sui", "node.foo.sui"],
+        "{resp:#?}"
+    );
+    assert_eq!(
+        resp["result"]["data"][0]["expirationTimestampMs"],
+        json!(expiry_ms.to_string()),
+    );
+    assert_eq!(resp["result"]["data"][0]["isLeaf"], json!(true));
+
+    assert_invalid_params!(c
+        .request("suix_getNameServiceSubnames", json!(["foo", null, null]))
+        .await
+        .unwrap());
+
+    c.cluster.stopped().await;
+}
+
+/// Page through names in expiry order, excluding leaves and names expiring after the bound.
+#[tokio::test]
+async fn test_expiring_names() {
+    let mut c = SuiNSCluster::new().await;
+
+    let nft = ObjectID::random();
+    c.add_domain(nft, &["sui", "foo"], None, 2_000_000_000)
+        .await
+        .expect("Failed to add domain");
+    c.add_domain(nft, &["sui", "foo", "leaf"], None, 0)
+        .await
+        .expect("Failed to add leaf");
+    c.add_domain(ObjectID::random(), &["sui", "bar"], None, 1_000_000_000)
+        .await
+        .expect("Failed to add domain");
+    c.add_domain(ObjectID::random(), &["sui", "baz"], None, 3_000_000_000)
+        .await
+        .expect("Failed to add domain");
+
+    c.cluster.create_checkpoint().await;
+
+    let resp = c
+        .request(
+            "suix_getExpiringNameServiceNames",
+            json!(["3000000000", null, 1]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(names(&resp), vec!["bar.sui"], "{resp:#?}");
+    assert!(resp["result"]["hasNextPage"].as_bool().unwrap());
+
+    let cursor = resp["result"]["nextCursor"].clone();
+    let resp = c
+        .request(
+            "suix_getExpiringNameServiceNames",
+            json!(["3000000000", cursor, 1]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(names(&resp), vec!["foo.sui"], "{resp:#?}");
+    assert!(!resp["result"]["hasNextPage"].as_bool().unwrap());
+
+    c.cluster.stopped().await;
+}
+
+/// Extract the names from a page of names in a JSON-RPC response.
+fn names(resp: &Value) -> Vec<&str> {
+    resp["result"]["data"]
+        .as_array()
+        .expect("data should be an array")
+        .iter()
+        .map(|n| n["name"].as_str().expect("name should be a string"))
+        .collect()
+}
+
 struct SuiNSCluster {
     cluster: FullCluster,
     config: NameServiceConfig,
@@ -397,12 +566,18 @@ impl SuiNSCluster {
             ..Default::default()
         };
 
+        // The indexer needs to know about the registry as well, to index its name records.
+        let indexer_config = IndexerConfig::for_test().merge(IndexerConfig {
+            name_service: config.clone().into(),
+            ..Default::default()
+        });
+
         // (8) Spin up the rest of the cluster.
         let cluster = FullCluster::new_with_configs(
             sim,
             IndexerArgs::default(),
             SystemPackageTaskArgs::default(),
-            IndexerConfig::for_test(),
+            indexer_config,
             rpc_config,
             &prometheus::Registry::new(),
             CancellationToken::new(),
@@ -478,13 +653,58 @@ impl SuiNSCluster {
         Ok(())
     }
 
+    /// Create a registration NFT owned by `owner`, and return its ID.
+    async fn mint_nft(&mut self, owner: SuiAddress) -> anyhow::Result<ObjectID> {
+        let (sender, kp, gas) = self
+            .cluster
+            .funded_account(DEFAULT_GAS_BUDGET)
+            .expect("failed to get account");
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let owner_arg = builder.pure(owner)?;
+        builder.programmable_move_call(
+            self.config.package_address.into(),
+            ident_str!("suins").to_owned(),
+            ident_str!("mint_nft").to_owned(),
+            vec![],
+            vec![owner_arg],
+        );
+
+        let data = TransactionData::new_programmable(
+            sender,
+            vec![gas],
+            builder.finish(),
+            DEFAULT_GAS_BUDGET,
+            self.cluster.reference_gas_price(),
+        );
+
+        let (fx, _) = self
+            .cluster
+            .execute_transaction(Transaction::from_data_and_signer(data, vec![&kp]))
+            .expect("Failed to execute mint NFT transaction");
+
+        ensure!(fx.status().is_ok(), "mint NFT transaction failed");
+
+        fx.created()
+            .into_iter()
+            .find(|(_, o)| *o == Owner::AddressOwner(owner))
+            .map(|((id, _, _), _)| id)
+            .context("Couldn't find NFT")
+    }
+
     /// Send a JSON-RPC request to the cluster to resolve the given SuiNS name.
     async fn resolve_address(&self, name: &str) -> anyhow::Result<Value> {
+        self.request("suix_resolveNameServiceAddress", json!([name]))
+            .await
+    }
+
+    /// Send a JSON-RPC request to the cluster, calling `method` with `params`.
+    async fn request(&self, method: &str, params: Value) -> anyhow::Result<Value> {
         let query = json!({
             "jsonrpc": "2.0",
             "id": 1,
-            "method": "suix_resolveNameServiceAddress",
-            "params": [name],
+            "method": method,
+            "params": params,
         });
 
         let response = self
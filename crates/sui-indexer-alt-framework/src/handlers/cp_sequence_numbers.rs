@@ -8,7 +8,7 @@ use crate::pipeline::{concurrent::Handler, Processor};
 use crate::schema::cp_sequence_numbers;
 use anyhow::Result;
 use diesel_async::RunQueryDsl;
-use sui_pg_db::{self as db};
+use sui_pg_db::{self as db, Db};
 use sui_types::full_checkpoint_content::CheckpointData;
 
 pub struct CpSequenceNumbers;
@@ -34,6 +34,8 @@ impl Processor for CpSequenceNumbers {
 
 #[async_trait::async_trait]
 impl Handler for CpSequenceNumbers {
+    type Store = Db;
+
     async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>) -> Result<usize> {
         Ok(diesel::insert_into(cp_sequence_numbers::table)
             .values(values)
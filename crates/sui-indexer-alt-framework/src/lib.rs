@@ -11,13 +11,14 @@ use diesel::{
 use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 use ingestion::{client::IngestionClient, ClientArgs, IngestionConfig, IngestionService};
 use metrics::IndexerMetrics;
-use models::watermarks::{CommitterWatermark, PrunerWatermark};
+use models::watermarks::CommitterWatermark;
 use pipeline::{
     concurrent::{self, ConcurrentConfig},
     sequential::{self, SequentialConfig},
     Processor,
 };
 use prometheus::Registry;
+use store::{Connection, Store};
 use sui_indexer_alt_metrics::db::DbConnectionStatsCollector;
 use sui_pg_db::{temp::TempDb, Db, DbArgs};
 use task::graceful_shutdown;
@@ -32,6 +33,7 @@ pub(crate) mod metrics;
 pub mod models;
 pub mod pipeline;
 pub mod schema;
+pub mod store;
 pub mod task;
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
@@ -60,9 +62,9 @@ pub struct IndexerArgs {
     pub skip_watermark: bool,
 }
 
-pub struct Indexer {
-    /// Connection pool to the database.
-    db: Db,
+pub struct Indexer<S: Store = Db> {
+    /// Storage backend that pipelines write to, and that watermarks are kept in.
+    store: S,
 
     /// Prometheus Metrics.
     metrics: Arc<IndexerMetrics>,
@@ -100,7 +102,7 @@ pub struct Indexer {
     handles: Vec<JoinHandle<()>>,
 }
 
-impl Indexer {
+impl Indexer<Db> {
     /// Create a new instance of the indexer framework. `db_args`, `indexer_args,`, `client_args`,
     /// and `ingestion_config` contain configurations for the following, respectively:
     ///
@@ -125,13 +127,6 @@ impl Indexer {
         registry: &Registry,
         cancel: CancellationToken,
     ) -> Result<Self> {
-        let IndexerArgs {
-            first_checkpoint,
-            last_checkpoint,
-            pipeline,
-            skip_watermark,
-        } = indexer_args;
-
         let db = Db::for_write(db_args)
             .await
             .context("Failed to connect to database")?;
@@ -141,36 +136,19 @@ impl Indexer {
             .await
             .context("Failed to run pending migrations")?;
 
-        let metrics = IndexerMetrics::new(registry);
         registry.register(Box::new(DbConnectionStatsCollector::new(
             Some("indexer_db"),
             db.clone(),
         )))?;
 
-        let ingestion_service = IngestionService::new(
+        Self::new_with_store(
+            db,
+            indexer_args,
             client_args,
             ingestion_config,
-            metrics.clone(),
-            cancel.clone(),
-        )?;
-
-        Ok(Self {
-            db,
-            metrics,
-            ingestion_service,
-            first_checkpoint,
-            last_checkpoint,
-            skip_watermark,
-            enabled_pipelines: if pipeline.is_empty() {
-                None
-            } else {
-                Some(pipeline.into_iter().collect())
-            },
-            added_pipelines: BTreeSet::new(),
+            registry,
             cancel,
-            first_checkpoint_from_watermark: u64::MAX,
-            handles: vec![],
-        })
+        )
     }
 
     pub async fn new_for_testing(migrations: &'static EmbeddedMigrations) -> (Self, TempDb) {
@@ -195,7 +173,82 @@ impl Indexer {
 
     /// The database connection pool used by the indexer.
     pub fn db(&self) -> &Db {
-        &self.db
+        &self.store
+    }
+
+    /// Combine the provided `migrations` with the migrations necessary to set up the indexer
+    /// framework. The returned migration source can be passed to [Db::run_migrations] to ensure
+    /// the database's schema is up-to-date for both the indexer framework and the specific
+    /// indexer.
+    pub fn migrations(
+        migrations: Option<&'static EmbeddedMigrations>,
+    ) -> impl MigrationSource<Pg> + Send + Sync + 'static {
+        struct Migrations(Option<&'static EmbeddedMigrations>);
+        impl MigrationSource<Pg> for Migrations {
+            fn migrations(&self) -> migration::Result<Vec<Box<dyn Migration<Pg>>>> {
+                let mut migrations = MIGRATIONS.migrations()?;
+                if let Some(more_migrations) = self.0 {
+                    migrations.extend(more_migrations.migrations()?);
+                }
+                Ok(migrations)
+            }
+        }
+
+        Migrations(migrations)
+    }
+}
+
+impl<S: Store> Indexer<S> {
+    /// Create a new instance of the indexer framework, writing to an arbitrary `store`. The
+    /// store is expected to be ready to accept writes from pipelines and watermark updates (e.g.
+    /// any schema it needs has already been set up). The remaining arguments are interpreted as in
+    /// [Indexer::new].
+    pub fn new_with_store(
+        store: S,
+        indexer_args: IndexerArgs,
+        client_args: ClientArgs,
+        ingestion_config: IngestionConfig,
+        registry: &Registry,
+        cancel: CancellationToken,
+    ) -> Result<Self> {
+        let IndexerArgs {
+            first_checkpoint,
+            last_checkpoint,
+            pipeline,
+            skip_watermark,
+        } = indexer_args;
+
+        let metrics = IndexerMetrics::new(registry);
+
+        let ingestion_service = IngestionService::new(
+            client_args,
+            ingestion_config,
+            metrics.clone(),
+            cancel.clone(),
+        )?;
+
+        Ok(Self {
+            store,
+            metrics,
+            ingestion_service,
+            first_checkpoint,
+            last_checkpoint,
+            skip_watermark,
+            enabled_pipelines: if pipeline.is_empty() {
+                None
+            } else {
+                Some(pipeline.into_iter().collect())
+            },
+            added_pipelines: BTreeSet::new(),
+            cancel,
+            first_checkpoint_from_watermark: u64::MAX,
+            handles: vec![],
+        })
+    }
+
+    /// The storage backend used by the indexer.
+    pub fn store(&self) -> &S {
+        &self.store
     }
 
     /// The ingestion client used by the indexer to fetch checkpoints.
@@ -218,7 +271,7 @@ impl Indexer {
     /// Concurrent pipelines commit checkpoint data out-of-order to maximise throughput, and they
     /// keep the watermark table up-to-date with the highest point they can guarantee all data
     /// exists for, for their pipeline.
-    pub async fn concurrent_pipeline<H: concurrent::Handler + Send + Sync + 'static>(
+    pub async fn concurrent_pipeline<H: concurrent::Handler<Store = S> + Send + Sync + 'static>(
         &mut self,
         handler: H,
         config: ConcurrentConfig,
@@ -240,7 +293,7 @@ impl Indexer {
             watermark,
             config,
             self.skip_watermark,
-            self.db.clone(),
+            self.store.clone(),
             self.ingestion_service.subscribe().0,
             self.metrics.clone(),
             self.cancel.clone(),
@@ -259,7 +312,7 @@ impl Indexer {
     ///
     /// The pipeline can optionally be configured to lag behind the ingestion service by a fixed
     /// number of checkpoints (configured by `checkpoint_lag`).
-    pub async fn sequential_pipeline<H: sequential::Handler + Send + Sync + 'static>(
+    pub async fn sequential_pipeline<H: sequential::Handler<Store = S> + Send + Sync + 'static>(
         &mut self,
         handler: H,
         config: SequentialConfig,
@@ -285,7 +338,7 @@ impl Indexer {
             handler,
             watermark,
             config,
-            self.db.clone(),
+            self.store.clone(),
             checkpoint_rx,
             watermark_tx,
             self.metrics.clone(),
@@ -359,27 +412,6 @@ impl Indexer {
         }))
     }
 
-    /// Combine the provided `migrations` with the migrations necessary to set up the indexer
-    /// framework. The returned migration source can be passed to [Db::run_migrations] to ensure
-    /// the database's schema is up-to-date for both the indexer framework and the specific
-    /// indexer.
-    pub fn migrations(
-        migrations: Option<&'static EmbeddedMigrations>,
-    ) -> impl MigrationSource<Pg> + Send + Sync + 'static {
-        struct Migrations(Option<&'static EmbeddedMigrations>);
-        impl MigrationSource<Pg> for Migrations {
-            fn migrations(&self) -> migration::Result<Vec<Box<dyn Migration<Pg>>>> {
-                let mut migrations = MIGRATIONS.migrations()?;
-                if let Some(more_migrations) = self.0 {
-                    migrations.extend(more_migrations.migrations()?);
-                }
-                Ok(migrations)
-            }
-        }
-
-        Migrations(migrations)
-    }
-
     /// Update the indexer's first checkpoint based on the watermark for the pipeline by adding for
     /// handler `H` (as long as it's enabled). Returns `Ok(None)` if the pipeline is disabled,
     /// `Ok(Some(None))` if the pipeline is enabled but its watermark is not found, and
@@ -405,9 +437,14 @@ impl Indexer {
             }
         }
 
-        let mut conn = self.db.connect().await.context("Failed DB connection")?;
+        let mut conn = self
+            .store
+            .connect()
+            .await
+            .context("Failed to connect to store")?;
 
-        let watermark = CommitterWatermark::get(&mut conn, P::NAME)
+        let watermark = conn
+            .committer_watermark(P::NAME)
             .await
             .with_context(|| format!("Failed to get watermark for {}", P::NAME))?;
 
@@ -415,7 +452,7 @@ impl Indexer {
             // If the pruner of this pipeline requires processed values in order to prune,
             // we must start ingestion from just after the pruner watermark,
             // so that we can process all values needed by the pruner.
-            PrunerWatermark::get(&mut conn, P::NAME, Default::default())
+            conn.pruner_watermark(P::NAME, Default::default())
                 .await
                 .with_context(|| format!("Failed to get pruner watermark for {}", P::NAME))?
                 .map(|w| w.pruner_hi as u64)
@@ -441,6 +478,8 @@ mod tests {
     use sui_pg_db as db;
     use sui_types::full_checkpoint_content::CheckpointData;
 
+    use crate::models::watermarks::PrunerWatermark;
+
     use super::*;
 
     #[derive(FieldCount)]
@@ -467,6 +506,8 @@ mod tests {
 
             #[async_trait]
             impl concurrent::Handler for $name {
+                type Store = Db;
+
                 const PRUNING_REQUIRES_PROCESSED_VALUES: bool = $pruning_requires_processed_values;
                 async fn commit(
                     _values: &[Self::Value],
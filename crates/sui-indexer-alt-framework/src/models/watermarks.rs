@@ -28,7 +28,7 @@ pub(crate) struct StoredWatermark {
 /// Fields that the committer is responsible for setting.
 #[derive(AsChangeset, Selectable, Queryable, Debug, Clone, FieldCount)]
 #[diesel(table_name = watermarks)]
-pub(crate) struct CommitterWatermark<'p> {
+pub struct CommitterWatermark<'p> {
     pub pipeline: Cow<'p, str>,
     pub epoch_hi_inclusive: i64,
     pub checkpoint_hi_inclusive: i64,
@@ -38,14 +38,14 @@ pub(crate) struct CommitterWatermark<'p> {
 
 #[derive(AsChangeset, Selectable, Queryable, Debug, Clone, FieldCount)]
 #[diesel(table_name = watermarks)]
-pub(crate) struct ReaderWatermark<'p> {
+pub struct ReaderWatermark<'p> {
     pub pipeline: Cow<'p, str>,
     pub reader_lo: i64,
 }
 
 #[derive(Queryable, Debug, Clone, FieldCount, PartialEq, Eq)]
 #[diesel(table_name = watermarks)]
-pub(crate) struct PrunerWatermark<'p> {
+pub struct PrunerWatermark<'p> {
     /// The pipeline in question
     pub pipeline: Cow<'p, str>,
 
@@ -61,20 +61,6 @@ pub(crate) struct PrunerWatermark<'p> {
     pub pruner_hi: i64,
 }
 
-impl StoredWatermark {
-    pub(crate) async fn get(
-        conn: &mut Connection<'_>,
-        pipeline: &'static str,
-    ) -> QueryResult<Option<Self>> {
-        watermarks::table
-            .select(StoredWatermark::as_select())
-            .filter(watermarks::pipeline.eq(pipeline))
-            .first(conn)
-            .await
-            .optional()
-    }
-}
-
 impl CommitterWatermark<'static> {
     /// Get the current high watermark for the pipeline.
     pub(crate) async fn get(
@@ -136,6 +122,21 @@ impl<'p> CommitterWatermark<'p> {
     }
 }
 
+impl ReaderWatermark<'static> {
+    /// Get the current reader low watermark for the pipeline.
+    pub(crate) async fn get(
+        conn: &mut Connection<'_>,
+        pipeline: &'static str,
+    ) -> QueryResult<Option<Self>> {
+        watermarks::table
+            .select(ReaderWatermark::as_select())
+            .filter(watermarks::pipeline.eq(pipeline))
+            .first(conn)
+            .await
+            .optional()
+    }
+}
+
 impl<'p> ReaderWatermark<'p> {
     pub(crate) fn new(pipeline: impl Into<Cow<'p, str>>, reader_lo: u64) -> Self {
         ReaderWatermark {
@@ -196,7 +196,7 @@ pub(super) fn collector<H: Handler + 'static>(
 #[cfg(test)]
 mod tests {
     use sui_field_count::FieldCount;
-    use sui_pg_db as db;
+    use sui_pg_db::{self as db, Db};
     use sui_types::full_checkpoint_content::CheckpointData;
 
     use crate::{
@@ -230,6 +230,8 @@ mod tests {
 
     #[async_trait::async_trait]
     impl Handler for TestHandler {
+        type Store = Db;
+
         const MAX_PENDING_ROWS: usize = 10000;
         async fn commit(
             _values: &[Self::Value],
@@ -5,13 +5,12 @@ use std::{sync::Arc, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use sui_field_count::FieldCount;
-use sui_pg_db::{self as db, Db};
 use sui_types::full_checkpoint_content::CheckpointData;
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
-use crate::{metrics::IndexerMetrics, models::watermarks::CommitterWatermark};
+use crate::{metrics::IndexerMetrics, models::watermarks::CommitterWatermark, store::Store};
 
 use super::{processor::processor, CommitterConfig, Processor, WatermarkPart, PIPELINE_BUFFER};
 
@@ -50,8 +49,13 @@ const MAX_WATERMARK_UPDATES: usize = 10_000;
 /// Back-pressure is handled through the `MAX_PENDING_SIZE` constant -- if more than this many rows
 /// build up, the collector will stop accepting new checkpoints, which will eventually propagate
 /// back to the ingestion service.
+///
+/// Values are written to the handler's [Store], which also holds the pipeline's watermarks.
 #[async_trait::async_trait]
 pub trait Handler: Processor<Value: FieldCount> {
+    /// The storage backend that this handler writes to.
+    type Store: Store;
+
     /// If at least this many rows are pending, the committer will commit them eagerly.
     const MIN_EAGER_ROWS: usize = 50;
 
@@ -70,18 +74,20 @@ pub trait Handler: Processor<Value: FieldCount> {
     // 2. The name is a bit abstract.
     const PRUNING_REQUIRES_PROCESSED_VALUES: bool = false;
 
-    /// Take a chunk of values and commit them to the database, returning the number of rows
+    /// Take a chunk of values and commit them to the store, returning the number of rows
     /// affected.
-    async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>)
-        -> anyhow::Result<usize>;
+    async fn commit(
+        values: &[Self::Value],
+        conn: &mut <Self::Store as Store>::Connection<'_>,
+    ) -> anyhow::Result<usize>;
 
-    /// Clean up data between checkpoints `_from` and `_to_exclusive` (exclusive) in the database, returning
+    /// Clean up data between checkpoints `_from` and `_to_exclusive` (exclusive) in the store, returning
     /// the number of rows affected. This function is optional, and defaults to not pruning at all.
     async fn prune(
         &self,
         _from: u64,
         _to_exclusive: u64,
-        _conn: &mut db::Connection<'_>,
+        _conn: &mut <Self::Store as Store>::Connection<'_>,
     ) -> anyhow::Result<usize> {
         Ok(0)
     }
@@ -195,7 +201,7 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
     initial_commit_watermark: Option<CommitterWatermark<'static>>,
     config: ConcurrentConfig,
     skip_watermark: bool,
-    db: Db,
+    store: H::Store,
     checkpoint_rx: mpsc::Receiver<Arc<CheckpointData>>,
     metrics: Arc<IndexerMetrics>,
     cancel: CancellationToken,
@@ -243,7 +249,7 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
         skip_watermark,
         committer_rx,
         committer_tx,
-        db.clone(),
+        store.clone(),
         metrics.clone(),
         cancel.clone(),
     );
@@ -253,19 +259,25 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
         committer_config,
         skip_watermark,
         watermark_rx,
-        db.clone(),
+        store.clone(),
         metrics.clone(),
         cancel,
     );
 
     let reader_watermark = reader_watermark::<H>(
         pruner_config.clone(),
-        db.clone(),
+        store.clone(),
         metrics.clone(),
         pruner_cancel.clone(),
     );
 
-    let pruner = pruner(handler, pruner_config, db, metrics, pruner_cancel.clone());
+    let pruner = pruner(
+        handler,
+        pruner_config,
+        store,
+        metrics,
+        pruner_cancel.clone(),
+    );
 
     tokio::spawn(async move {
         let (_, _, _, _) = futures::join!(processor, collector, committer, commit_watermark);
@@ -3,14 +3,14 @@
 
 use std::sync::Arc;
 
-use sui_pg_db::Db;
 use tokio::{task::JoinHandle, time::interval};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 use crate::{
     metrics::IndexerMetrics,
-    models::watermarks::{ReaderWatermark, StoredWatermark},
+    models::watermarks::ReaderWatermark,
+    store::{Connection, Store},
 };
 
 use super::{Handler, PrunerConfig};
@@ -28,7 +28,7 @@ use super::{Handler, PrunerConfig};
 /// when the provided cancellation token is triggered.
 pub(super) fn reader_watermark<H: Handler + 'static>(
     config: Option<PrunerConfig>,
-    db: Db,
+    store: H::Store,
     metrics: Arc<IndexerMetrics>,
     cancel: CancellationToken,
 ) -> JoinHandle<()> {
@@ -48,12 +48,26 @@ pub(super) fn reader_watermark<H: Handler + 'static>(
                 }
 
                 _ = poll.tick() => {
-                    let Ok(mut conn) = db.connect().await else {
+                    let Ok(mut conn) = store.connect().await else {
                         warn!(pipeline = H::NAME, "Reader watermark task failed to get connection for DB");
                         continue;
                     };
 
-                    let current = match StoredWatermark::get(&mut conn, H::NAME).await {
+                    let committer = match conn.committer_watermark(H::NAME).await {
+                        Ok(Some(committer)) => committer,
+
+                        Ok(None) => {
+                            warn!(pipeline = H::NAME, "No watermark for pipeline, skipping");
+                            continue;
+                        }
+
+                        Err(e) => {
+                            warn!(pipeline = H::NAME, "Failed to get current watermark: {e}");
+                            continue;
+                        }
+                    };
+
+                    let current = match conn.reader_watermark(H::NAME).await {
                         Ok(Some(current)) => current,
 
                         Ok(None) => {
@@ -68,7 +82,7 @@ pub(super) fn reader_watermark<H: Handler + 'static>(
                     };
 
                     // Calculate the new reader watermark based on the current high watermark.
-                    let new_reader_lo = (current.checkpoint_hi_inclusive as u64 + 1)
+                    let new_reader_lo = (committer.checkpoint_hi_inclusive as u64 + 1)
                         .saturating_sub(config.retention);
 
                     if new_reader_lo <= current.reader_lo as u64 {
@@ -86,7 +100,8 @@ pub(super) fn reader_watermark<H: Handler + 'static>(
                         .with_label_values(&[H::NAME])
                         .set(new_reader_lo as i64);
 
-                    let Ok(updated) = ReaderWatermark::new(H::NAME, new_reader_lo).update(&mut conn).await else {
+                    let watermark = ReaderWatermark::new(H::NAME, new_reader_lo);
+                    let Ok(updated) = conn.set_reader_watermark(&watermark).await else {
                         warn!(pipeline = H::NAME, "Failed to update reader watermark");
                         continue;
                     };
@@ -4,14 +4,17 @@
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
-use sui_pg_db::{self as db, Db};
 use sui_types::full_checkpoint_content::CheckpointData;
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
 use super::{processor::processor, CommitterConfig, Processor, PIPELINE_BUFFER};
 
-use crate::{metrics::IndexerMetrics, models::watermarks::CommitterWatermark};
+use crate::{
+    metrics::IndexerMetrics,
+    models::watermarks::CommitterWatermark,
+    store::{Store, TransactionalStore},
+};
 
 use self::committer::committer;
 
@@ -34,8 +37,14 @@ mod committer;
 /// for, and in turn the ingestion service will only run ahead by its buffer size. This guarantees
 /// liveness and limits the amount of memory the pipeline can consume, by bounding the number of
 /// checkpoints that can be received before the next checkpoint.
+///
+/// Batches are written to the handler's [Store], which must support transactions, so that the
+/// batch and the pipeline's watermark can be written atomically.
 #[async_trait::async_trait]
 pub trait Handler: Processor {
+    /// The storage backend that this handler writes to.
+    type Store: TransactionalStore;
+
     /// If at least this many rows are pending, the committer will commit them eagerly.
     const MIN_EAGER_ROWS: usize = 50;
 
@@ -53,9 +62,12 @@ pub trait Handler: Processor {
     /// guaranteed to be presented to the batch in checkpoint order.
     fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>);
 
-    /// Take a batch of values and commit them to the database, returning the number of rows
+    /// Take a batch of values and commit them to the store, returning the number of rows
     /// affected.
-    async fn commit(batch: &Self::Batch, conn: &mut db::Connection<'_>) -> anyhow::Result<usize>;
+    async fn commit(
+        batch: &Self::Batch,
+        conn: &mut <Self::Store as Store>::Connection<'_>,
+    ) -> anyhow::Result<usize>;
 }
 
 /// Configuration for a sequential pipeline
@@ -97,7 +109,7 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
     handler: H,
     initial_watermark: Option<CommitterWatermark<'static>>,
     config: SequentialConfig,
-    db: Db,
+    store: H::Store,
     checkpoint_rx: mpsc::Receiver<Arc<CheckpointData>>,
     watermark_tx: mpsc::UnboundedSender<(&'static str, u64)>,
     metrics: Arc<IndexerMetrics>,
@@ -118,7 +130,7 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
         initial_watermark,
         committer_rx,
         watermark_tx,
-        db.clone(),
+        store,
         metrics.clone(),
         cancel.clone(),
     );
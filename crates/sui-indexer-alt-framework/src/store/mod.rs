@@ -0,0 +1,97 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use diesel_async::scoped_futures::ScopedBoxFuture;
+
+use crate::models::watermarks::{CommitterWatermark, PrunerWatermark, ReaderWatermark};
+
+mod pg;
+
+/// A connection to a [Store], used by pipelines to write the values they have processed, and to
+/// read and write the watermarks that track each pipeline's progress.
+///
+/// Handlers are given access to the store's own connection type (see [Store::Connection]), so they
+/// can use whatever API the store offers to write their data. The methods on this trait are the
+/// ones the framework itself needs to manage pipelines (watermarks and pruning bounds).
+#[async_trait]
+pub trait Connection: Send {
+    /// The committer's high watermark for `pipeline`, or `None` if the pipeline has not
+    /// committed anything yet.
+    async fn committer_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<CommitterWatermark<'static>>>;
+
+    /// The lowest checkpoint that readers can assume data is available for in `pipeline`, or
+    /// `None` if the pipeline does not have a watermark yet.
+    async fn reader_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<ReaderWatermark<'static>>>;
+
+    /// The bounds of the region the pruner still has to prune for `pipeline`, along with how long
+    /// the pruner must wait before acting on them, given that it must leave at least `delay`
+    /// between a change to the reader watermark and pruning data below it.
+    async fn pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        delay: Duration,
+    ) -> anyhow::Result<Option<PrunerWatermark<'static>>>;
+
+    /// Upsert the committer's high watermark, as long as it raises the watermark that is already
+    /// stored. Returns whether the watermark was actually updated.
+    async fn set_committer_watermark(
+        &mut self,
+        watermark: &CommitterWatermark<'_>,
+    ) -> anyhow::Result<bool>;
+
+    /// Raise the reader low watermark of an existing watermark, and record the time at which this
+    /// happened (according to the store's clock). Returns whether the watermark was actually
+    /// updated.
+    async fn set_reader_watermark(
+        &mut self,
+        watermark: &ReaderWatermark<'_>,
+    ) -> anyhow::Result<bool>;
+
+    /// Update the pruner high watermark of an existing watermark. Returns whether the watermark
+    /// was actually updated.
+    async fn set_pruner_watermark(
+        &mut self,
+        watermark: &PrunerWatermark<'_>,
+    ) -> anyhow::Result<bool>;
+}
+
+/// A storage backend that pipelines can write to. Instances of a store are expected to be cheap
+/// to clone and share access to the same underlying storage (e.g. a connection pool).
+///
+/// The framework ships with an implementation for Postgres ([sui_pg_db::Db]), and other backends
+/// can be supported by implementing this trait (and [TransactionalStore] to support sequential
+/// pipelines).
+#[async_trait]
+pub trait Store: Send + Sync + 'static + Clone {
+    type Connection<'c>: Connection
+    where
+        Self: 'c;
+
+    /// Get a connection to the store. Can fail if a connection could not be established (e.g. if
+    /// the store's connection pool is exhausted).
+    async fn connect<'c>(&'c self) -> anyhow::Result<Self::Connection<'c>>;
+}
+
+/// A [Store] that supports running a group of writes atomically. Sequential pipelines require
+/// this to write their batch and their watermark together.
+#[async_trait]
+pub trait TransactionalStore: Store {
+    /// Run `f` in a transaction: If it succeeds, all its writes are committed, otherwise none of
+    /// them are.
+    async fn transaction<'a, R, F>(&self, f: F) -> anyhow::Result<R>
+    where
+        R: Send + 'a,
+        F: Send + 'a,
+        F: for<'r> FnOnce(
+            &'r mut Self::Connection<'_>,
+        ) -> ScopedBoxFuture<'a, 'r, anyhow::Result<R>>;
+}
@@ -0,0 +1,82 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use diesel_async::{scoped_futures::ScopedBoxFuture, AsyncConnection};
+use sui_pg_db::{self as db, Db};
+
+use crate::models::watermarks::{CommitterWatermark, PrunerWatermark, ReaderWatermark};
+
+use super::{Connection, Store, TransactionalStore};
+
+#[async_trait]
+impl Connection for db::Connection<'_> {
+    async fn committer_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<CommitterWatermark<'static>>> {
+        Ok(CommitterWatermark::get(self, pipeline).await?)
+    }
+
+    async fn reader_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<ReaderWatermark<'static>>> {
+        Ok(ReaderWatermark::get(self, pipeline).await?)
+    }
+
+    async fn pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        delay: Duration,
+    ) -> anyhow::Result<Option<PrunerWatermark<'static>>> {
+        Ok(PrunerWatermark::get(self, pipeline, delay).await?)
+    }
+
+    async fn set_committer_watermark(
+        &mut self,
+        watermark: &CommitterWatermark<'_>,
+    ) -> anyhow::Result<bool> {
+        Ok(watermark.update(self).await?)
+    }
+
+    async fn set_reader_watermark(
+        &mut self,
+        watermark: &ReaderWatermark<'_>,
+    ) -> anyhow::Result<bool> {
+        Ok(watermark.update(self).await?)
+    }
+
+    async fn set_pruner_watermark(
+        &mut self,
+        watermark: &PrunerWatermark<'_>,
+    ) -> anyhow::Result<bool> {
+        Ok(watermark.update(self).await?)
+    }
+}
+
+#[async_trait]
+impl Store for Db {
+    type Connection<'c> = db::Connection<'c>;
+
+    async fn connect<'c>(&'c self) -> anyhow::Result<Self::Connection<'c>> {
+        Db::connect(self).await
+    }
+}
+
+#[async_trait]
+impl TransactionalStore for Db {
+    async fn transaction<'a, R, F>(&self, f: F) -> anyhow::Result<R>
+    where
+        R: Send + 'a,
+        F: Send + 'a,
+        F: for<'r> FnOnce(
+            &'r mut Self::Connection<'_>,
+        ) -> ScopedBoxFuture<'a, 'r, anyhow::Result<R>>,
+    {
+        let mut conn = Db::connect(self).await?;
+        AsyncConnection::transaction(&mut conn, |conn| f(conn)).await
+    }
+}
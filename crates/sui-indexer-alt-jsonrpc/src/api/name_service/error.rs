@@ -8,4 +8,7 @@ pub(super) enum Error {
 
     #[error(transparent)]
     NameService(sui_name_service::NameServiceError),
+
+    #[error("Pagination issue: {0}")]
+    Pagination(#[from] crate::paginate::Error),
 }
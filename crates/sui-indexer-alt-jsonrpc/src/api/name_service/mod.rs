@@ -2,16 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
+use sui_json_rpc_types::Page as PageResponse;
 use sui_name_service::NameServiceConfig;
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
-use sui_types::base_types::SuiAddress;
+use sui_types::{base_types::SuiAddress, sui_serde::BigInt};
 
-use crate::{context::Context, error::InternalContext as _};
+use crate::{context::Context, error::InternalContext as _, paginate::Page};
 
 use super::rpc_module::RpcModule;
 
-use self::error::Error;
+use self::{error::Error, response::NameServiceName};
 
 mod error;
 mod response;
@@ -26,18 +28,141 @@ trait NameServiceApi {
         /// The name to resolve
         name: String,
     ) -> RpcResult<Option<SuiAddress>>;
+
+    /// Return the SuiNS names whose registration NFT is owned by the given address, ordered by
+    /// name. Names that have expired are not included.
+    #[method(name = "getNameServiceNamesByAddress")]
+    async fn get_names_by_address(
+        &self,
+        /// The address that owns the names' registration NFTs
+        address: SuiAddress,
+        /// optional paging cursor
+        cursor: Option<String>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<NameServiceName, String>>;
+
+    /// Return the immediate sub-names of a SuiNS name, ordered by name. Names that have expired
+    /// are not included.
+    #[method(name = "getNameServiceSubnames")]
+    async fn get_subnames(
+        &self,
+        /// The name to list sub-names for
+        parent: String,
+        /// optional paging cursor
+        cursor: Option<String>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<NameServiceName, String>>;
+
+    /// Return the SuiNS names that have not expired yet, but will expire before the given
+    /// timestamp, ordered by expiry. Only names with their own registration are included (leaf
+    /// names expire with their parent).
+    #[method(name = "getExpiringNameServiceNames")]
+    async fn get_expiring_names(
+        &self,
+        /// Timestamp in milliseconds, exclusive upper bound on the expiry of names to return
+        expires_before_ms: BigInt<u64>,
+        /// optional paging cursor
+        cursor: Option<String>,
+        /// maximum number of items per page
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<NameServiceName, String>>;
 }
 
-pub(crate) struct NameService(pub Context, pub NameServiceConfig);
+pub(crate) struct NameService(pub Context, pub NameServiceConfig, pub NamesConfig);
+
+/// Limits for the methods that list names, configured in the `name-service` section alongside the
+/// registry addresses.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NamesConfig {
+    /// The default page size limit when listing names, if none is provided.
+    pub default_page_size: usize,
+
+    /// The largest acceptable page size when listing names. Requesting a page larger than this is
+    /// a user error.
+    pub max_page_size: usize,
+}
 
 #[async_trait::async_trait]
 impl NameServiceApiServer for NameService {
     async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
-        let Self(ctx, config) = self;
+        let Self(ctx, config, _) = self;
         Ok(response::resolved_address(ctx, config, &name)
             .await
             .with_internal_context(|| format!("Resolving SuiNS name {name:?}"))?)
     }
+
+    async fn get_names_by_address(
+        &self,
+        address: SuiAddress,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<NameServiceName, String>> {
+        let Self(ctx, _, config) = self;
+        let page: Page<response::NameCursor> = Page::from_params::<Error>(
+            config.default_page_size,
+            config.max_page_size,
+            cursor,
+            limit,
+            None,
+        )?;
+
+        Ok(response::names_by_owner(ctx, address, &page)
+            .await
+            .with_internal_context(|| format!("Listing SuiNS names for {address}"))?)
+    }
+
+    async fn get_subnames(
+        &self,
+        parent: String,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<NameServiceName, String>> {
+        let Self(ctx, _, config) = self;
+        let page: Page<response::NameCursor> = Page::from_params::<Error>(
+            config.default_page_size,
+            config.max_page_size,
+            cursor,
+            limit,
+            None,
+        )?;
+
+        Ok(response::subnames(ctx, &parent, &page)
+            .await
+            .with_internal_context(|| format!("Listing sub-names of SuiNS name {parent:?}"))?)
+    }
+
+    async fn get_expiring_names(
+        &self,
+        expires_before_ms: BigInt<u64>,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<PageResponse<NameServiceName, String>> {
+        let Self(ctx, _, config) = self;
+        let page: Page<response::ExpiryCursor> = Page::from_params::<Error>(
+            config.default_page_size,
+            config.max_page_size,
+            cursor,
+            limit,
+            None,
+        )?;
+
+        Ok(response::expiring_names(ctx, *expires_before_ms, &page)
+            .await
+            .with_internal_context(|| {
+                format!("Listing SuiNS names expiring before {expires_before_ms}")
+            })?)
+    }
+}
+
+impl Default for NamesConfig {
+    fn default() -> Self {
+        Self {
+            default_page_size: 50,
+            max_page_size: 100,
+        }
+    }
 }
 
 impl RpcModule for NameService {
@@ -2,21 +2,63 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Context as _;
-use diesel::{ExpressionMethods, QueryDsl};
+use diesel::{
+    sql_types::Bool, BoolExpressionMethods, ExpressionMethods, JoinOnDsl,
+    NullableExpressionMethods, QueryDsl, SelectableHelper,
+};
 use futures::future::OptionFuture;
-use sui_indexer_alt_schema::schema::watermarks;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sui_indexer_alt_schema::{
+    name_service::StoredNameRecord,
+    objects::StoredOwnerKind,
+    schema::{obj_info, sum_name_records, watermarks},
+};
+use sui_json_rpc_types::Page as PageResponse;
 use sui_name_service::{Domain, NameRecord, NameServiceConfig, NameServiceError};
-use sui_types::base_types::SuiAddress;
+use sui_sql_macro::sql;
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    sui_serde::BigInt,
+};
 use tokio::join;
 
 use crate::{
     data::objects::load_live,
     error::{invalid_params, RpcError},
+    paginate::{Cursor as _, JsonCursor, Page},
     Context,
 };
 
 use super::Error;
 
+/// Cursor for listings ordered by name.
+pub(super) type NameCursor = JsonCursor<String>;
+
+/// Cursor for listings ordered by expiry, and then name.
+pub(super) type ExpiryCursor = JsonCursor<(u64, String)>;
+
+/// A SuiNS name, as it appears in listings.
+#[serde_as]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NameServiceName {
+    /// The name, in dot format.
+    pub name: String,
+    /// The address that the name resolves to, if it has one.
+    pub target_address: Option<SuiAddress>,
+    /// ID of the registration NFT that controls this name.
+    pub nft_id: ObjectID,
+    /// Timestamp in milliseconds that the name expires at. Leaf names expire with their parent,
+    /// so this is their parent's expiry.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub expiration_timestamp_ms: u64,
+    /// Whether the name is a leaf name (a sub-name without a registration of its own).
+    pub is_leaf: bool,
+}
+
 /// Attempt to to translate the given SuiNS `name` to its address, as long as the mapping exists,
 /// and it hasn't expired.
 pub(super) async fn resolved_address(
@@ -42,8 +84,11 @@ pub(super) async fn resolved_address(
     // Fetch the current timestamp, the domain record. If the domain being resolved is a
     // sub-domain, then also fetch the parent record, because its expiry is controlled by its
     // parent's.
-    let (timestamp_ms, domain_object, parent_object) =
-        join!(latest_timestamp_ms(ctx), domain_object, parent_object);
+    let (timestamp_ms, domain_object, parent_object) = join!(
+        latest_timestamp_ms(ctx, "obj_info"),
+        domain_object,
+        parent_object
+    );
 
     let timestamp_ms = timestamp_ms.context("Failed to fetch latest timestamp")?;
 
@@ -91,10 +136,289 @@ pub(super) async fn resolved_address(
     }
 }
 
-/// Fetch the latest timestamp from the database, based on the watermark for the `obj_info`
-/// pipeline, because we know that the `obj_info` pipeline is being queried as part of address
-/// resolution.
-async fn latest_timestamp_ms(ctx: &Context) -> Result<u64, RpcError<Error>> {
+/// List the names whose registration NFT is owned by `owner`, ordered by name. Leaf names share
+/// their parent's NFT, so they are listed alongside it.
+pub(super) async fn names_by_owner(
+    ctx: &Context,
+    owner: SuiAddress,
+    page: &Page<NameCursor>,
+) -> Result<PageResponse<NameServiceName, String>, RpcError<Error>> {
+    use obj_info::dsl as o;
+    use sum_name_records::dsl as n;
+
+    let (candidates, newer) = diesel::alias!(obj_info as candidates, obj_info as newer);
+    let parents = diesel::alias!(sum_name_records as parents);
+
+    let timestamp_ms = latest_timestamp_ms(ctx, "sum_name_records").await? as i64;
+
+    // The NFT's latest `obj_info` record must be owned by `owner`. Names with their own
+    // registration must not have expired, and leaf names must belong to a parent that has not
+    // expired, or been re-registered since they were created.
+    let mut query = n::sum_name_records
+        .inner_join(candidates.on(candidates.field(o::object_id).eq(n::nft_id)))
+        .left_join(
+            newer.on(candidates
+                .field(o::object_id)
+                .eq(newer.field(o::object_id))
+                .and(
+                    candidates
+                        .field(o::cp_sequence_number)
+                        .lt(newer.field(o::cp_sequence_number)),
+                )),
+        )
+        .left_join(
+            parents.on(parents
+                .field(n::domain)
+                .eq(n::parent)
+                .and(parents.field(n::nft_id).eq(n::nft_id))
+                .and(parents.field(n::is_leaf).eq(false))),
+        )
+        .select((
+            StoredNameRecord::as_select(),
+            parents.field(n::expiration_timestamp_ms).nullable(),
+        ))
+        .filter(newer.field(o::object_id).is_null())
+        .filter(candidates.field(o::owner_kind).eq(StoredOwnerKind::Address))
+        .filter(candidates.field(o::owner_id).eq(owner.to_vec()))
+        .filter(
+            n::is_leaf
+                .eq(false)
+                .and(n::expiration_timestamp_ms.ge(timestamp_ms))
+                .or(n::is_leaf
+                    .eq(true)
+                    .and(parents.field(n::expiration_timestamp_ms).ge(timestamp_ms))),
+        )
+        .order_by(n::domain)
+        .limit(page.limit + 1)
+        .into_boxed();
+
+    if let Some(cursor) = &page.cursor {
+        query = query.filter(n::domain.gt(cursor.0.clone()));
+    }
+
+    let mut conn = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to database")?;
+
+    let records: Vec<(StoredNameRecord, Option<i64>)> = conn
+        .results(query)
+        .await
+        .context("Failed to fetch name records")?;
+
+    let (records, next_cursor, has_next_page) = paginate(records, page, |(r, _)| {
+        JsonCursor(r.domain.clone()).encode()
+    })?;
+
+    let data = records
+        .into_iter()
+        .map(|(r, parent_expiry)| {
+            let expiry = if r.is_leaf { parent_expiry } else { None };
+            name_service_name(r, expiry)
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(PageResponse {
+        data,
+        next_cursor,
+        has_next_page,
+    })
+}
+
+/// List the immediate sub-names of `parent` that have not expired, ordered by name.
+pub(super) async fn subnames(
+    ctx: &Context,
+    parent: &str,
+    page: &Page<NameCursor>,
+) -> Result<PageResponse<NameServiceName, String>, RpcError<Error>> {
+    use sum_name_records::dsl as n;
+
+    let parent: Domain = parent
+        .parse()
+        .map_err(|e| invalid_params(Error::NameService(e)))?;
+
+    let timestamp_ms = latest_timestamp_ms(ctx, "sum_name_records").await? as i64;
+
+    let mut conn = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to database")?;
+
+    // Leaf sub-names expire with their parent, so they are only live if the parent's registration
+    // is, and if they were created under that registration.
+    let query = n::sum_name_records
+        .select(StoredNameRecord::as_select())
+        .filter(n::domain.eq(parent.to_string()))
+        .filter(n::is_leaf.eq(false))
+        .filter(n::expiration_timestamp_ms.ge(timestamp_ms))
+        .limit(1);
+
+    let parent_record: Option<StoredNameRecord> = conn
+        .results(query)
+        .await
+        .context("Failed to fetch parent name record")?
+        .into_iter()
+        .next();
+
+    let live_node = n::is_leaf
+        .eq(false)
+        .and(n::expiration_timestamp_ms.ge(timestamp_ms));
+
+    let mut query = n::sum_name_records
+        .select(StoredNameRecord::as_select())
+        .filter(n::parent.eq(parent.to_string()))
+        .order_by(n::domain)
+        .limit(page.limit + 1)
+        .into_boxed();
+
+    query = if let Some(parent_record) = &parent_record {
+        query.filter(
+            live_node.or(n::is_leaf
+                .eq(true)
+                .and(n::nft_id.eq(parent_record.nft_id.clone()))),
+        )
+    } else {
+        query.filter(live_node)
+    };
+
+    if let Some(cursor) = &page.cursor {
+        query = query.filter(n::domain.gt(cursor.0.clone()));
+    }
+
+    let records: Vec<StoredNameRecord> = conn
+        .results(query)
+        .await
+        .context("Failed to fetch name records")?;
+
+    let (records, next_cursor, has_next_page) =
+        paginate(records, page, |r| JsonCursor(r.domain.clone()).encode())?;
+
+    let parent_expiry = parent_record.map(|p| p.expiration_timestamp_ms);
+    let data = records
+        .into_iter()
+        .map(|r| {
+            let expiry = if r.is_leaf { parent_expiry } else { None };
+            name_service_name(r, expiry)
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(PageResponse {
+        data,
+        next_cursor,
+        has_next_page,
+    })
+}
+
+/// List names that have their own registration, and that expire between the latest indexed
+/// timestamp (inclusive) and `expires_before_ms` (exclusive), ordered by expiry and then name.
+pub(super) async fn expiring_names(
+    ctx: &Context,
+    expires_before_ms: u64,
+    page: &Page<ExpiryCursor>,
+) -> Result<PageResponse<NameServiceName, String>, RpcError<Error>> {
+    use sum_name_records::dsl as n;
+
+    let timestamp_ms = latest_timestamp_ms(ctx, "sum_name_records").await?;
+
+    let mut query = n::sum_name_records
+        .select(StoredNameRecord::as_select())
+        .filter(n::is_leaf.eq(false))
+        .filter(n::expiration_timestamp_ms.ge(timestamp_ms as i64))
+        .filter(n::expiration_timestamp_ms.lt(expires_before_ms as i64))
+        .order_by(n::expiration_timestamp_ms)
+        .then_order_by(n::domain)
+        .limit(page.limit + 1)
+        .into_boxed();
+
+    if let Some(cursor) = &page.cursor {
+        let (expiration_timestamp_ms, domain) = &cursor.0;
+        query = query.filter(sql!(as Bool,
+            "(expiration_timestamp_ms, domain) > ({BigInt}, {Text})",
+            *expiration_timestamp_ms as i64,
+            domain.clone(),
+        ));
+    }
+
+    let mut conn = ctx
+        .pg_reader()
+        .connect()
+        .await
+        .context("Failed to connect to database")?;
+
+    let records: Vec<StoredNameRecord> = conn
+        .results(query)
+        .await
+        .context("Failed to fetch name records")?;
+
+    let (records, next_cursor, has_next_page) = paginate(records, page, |r| {
+        JsonCursor((r.expiration_timestamp_ms as u64, r.domain.clone())).encode()
+    })?;
+
+    let data = records
+        .into_iter()
+        .map(|r| name_service_name(r, None))
+        .collect::<Result<_, _>>()?;
+
+    Ok(PageResponse {
+        data,
+        next_cursor,
+        has_next_page,
+    })
+}
+
+/// Split `records`, fetched with a limit of one more than the page's limit, into the records
+/// for the page, a cursor pointing at the last of them, and whether there is a next page.
+fn paginate<C: crate::paginate::Cursor, R>(
+    mut records: Vec<R>,
+    page: &Page<C>,
+    cursor: impl Fn(&R) -> Result<String, crate::paginate::Error>,
+) -> Result<(Vec<R>, Option<String>, bool), RpcError<Error>> {
+    let has_next_page = records.len() > page.limit as usize;
+    if has_next_page {
+        records.truncate(page.limit as usize);
+    }
+
+    let next_cursor = records
+        .last()
+        .map(cursor)
+        .transpose()
+        .context("Failed to encode cursor")?;
+
+    Ok((records, next_cursor, has_next_page))
+}
+
+/// Convert a stored name record into its RPC representation, optionally overriding its expiry.
+fn name_service_name(
+    record: StoredNameRecord,
+    expiration_timestamp_ms: Option<i64>,
+) -> Result<NameServiceName, RpcError<Error>> {
+    let target_address = record
+        .target_address
+        .map(SuiAddress::from_bytes)
+        .transpose()
+        .context("Failed to deserialize target address")?;
+
+    let nft_id = ObjectID::from_bytes(&record.nft_id).context("Failed to deserialize NFT ID")?;
+
+    Ok(NameServiceName {
+        name: record.domain,
+        target_address,
+        nft_id,
+        expiration_timestamp_ms: expiration_timestamp_ms.unwrap_or(record.expiration_timestamp_ms)
+            as u64,
+        is_leaf: record.is_leaf,
+    })
+}
+
+/// Fetch the latest timestamp from the database, based on the watermark for `pipeline`. Callers
+/// pick the pipeline whose data they are querying, so that expiry is judged against the same point
+/// in time as the records themselves (e.g. `obj_info` for address resolution).
+async fn latest_timestamp_ms(
+    ctx: &Context,
+    pipeline: &'static str,
+) -> Result<u64, RpcError<Error>> {
     use watermarks::dsl as w;
 
     let mut conn = ctx
@@ -105,7 +429,7 @@ async fn latest_timestamp_ms(ctx: &Context) -> Result<u64, RpcError<Error>> {
 
     let query = w::watermarks
         .select(w::timestamp_ms_hi_inclusive)
-        .filter(w::pipeline.eq("obj_info"));
+        .filter(w::pipeline.eq(pipeline));
 
     let timestamp_ms: i64 = conn
         .first(query)
@@ -8,7 +8,10 @@ use sui_protocol_config::ProtocolConfig;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use tracing::warn;
 
-use crate::api::{coin::CoinsConfig, objects::ObjectsConfig, transactions::TransactionsConfig};
+use crate::api::{
+    coin::CoinsConfig, name_service::NamesConfig, objects::ObjectsConfig,
+    transactions::TransactionsConfig,
+};
 
 pub use sui_name_service::NameServiceConfig;
 
@@ -65,6 +68,8 @@ pub struct NameServiceLayer {
     pub package_address: Option<SuiAddress>,
     pub registry_id: Option<ObjectID>,
     pub reverse_registry_id: Option<ObjectID>,
+    pub default_page_size: Option<usize>,
+    pub max_page_size: Option<usize>,
 
     #[serde(flatten)]
     pub extra: toml::Table,
@@ -106,7 +111,11 @@ impl RpcConfig {
         Self {
             objects: ObjectsConfig::default().into(),
             transactions: TransactionsConfig::default().into(),
-            name_service: NameServiceConfig::default().into(),
+            name_service: NameServiceLayer {
+                default_page_size: Some(NamesConfig::default().default_page_size),
+                max_page_size: Some(NamesConfig::default().max_page_size),
+                ..NameServiceConfig::default().into()
+            },
             coins: CoinsConfig::default().into(),
             bigtable_config: None,
             package_resolver: PackageResolverLayer::default(),
@@ -144,13 +153,25 @@ impl TransactionsLayer {
 }
 
 impl NameServiceLayer {
-    pub fn finish(self, base: NameServiceConfig) -> NameServiceConfig {
+    pub fn finish(
+        self,
+        base: NameServiceConfig,
+        base_names: NamesConfig,
+    ) -> (NameServiceConfig, NamesConfig) {
         check_extra("name service", self.extra);
-        NameServiceConfig {
-            package_address: self.package_address.unwrap_or(base.package_address),
-            registry_id: self.registry_id.unwrap_or(base.registry_id),
-            reverse_registry_id: self.reverse_registry_id.unwrap_or(base.reverse_registry_id),
-        }
+        (
+            NameServiceConfig {
+                package_address: self.package_address.unwrap_or(base.package_address),
+                registry_id: self.registry_id.unwrap_or(base.registry_id),
+                reverse_registry_id: self.reverse_registry_id.unwrap_or(base.reverse_registry_id),
+            },
+            NamesConfig {
+                default_page_size: self
+                    .default_page_size
+                    .unwrap_or(base_names.default_page_size),
+                max_page_size: self.max_page_size.unwrap_or(base_names.max_page_size),
+            },
+        )
     }
 }
 
@@ -221,6 +242,8 @@ impl From<NameServiceConfig> for NameServiceLayer {
             package_address: Some(config.package_address),
             registry_id: Some(config.registry_id),
             reverse_registry_id: Some(config.reverse_registry_id),
+            default_page_size: None,
+            max_page_size: None,
             extra: Default::default(),
         }
     }
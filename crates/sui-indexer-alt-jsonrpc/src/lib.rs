@@ -9,7 +9,7 @@ use api::checkpoints::Checkpoints;
 use api::coin::{Coins, CoinsConfig};
 use api::dynamic_fields::DynamicFields;
 use api::move_utils::MoveUtils;
-use api::name_service::NameService;
+use api::name_service::{NameService, NamesConfig};
 use api::objects::{Objects, ObjectsConfig, QueryObjects};
 use api::rpc_module::RpcModule;
 use api::transactions::{QueryTransactions, Transactions, TransactionsConfig};
@@ -226,7 +226,8 @@ pub async fn start_rpc(
 
     let objects_config = objects.finish(ObjectsConfig::default());
     let transactions_config = transactions.finish(TransactionsConfig::default());
-    let name_service_config = name_service.finish(NameServiceConfig::default());
+    let (name_service_config, names_config) =
+        name_service.finish(NameServiceConfig::default(), NamesConfig::default());
     let coins_config = coins.finish(CoinsConfig::default());
     let package_resolver_limits = package_resolver.finish();
 
@@ -253,7 +254,11 @@ pub async fn start_rpc(
     rpc.add_module(DynamicFields(context.clone()))?;
     rpc.add_module(Governance(context.clone()))?;
     rpc.add_module(MoveUtils(context.clone()))?;
-    rpc.add_module(NameService(context.clone(), name_service_config))?;
+    rpc.add_module(NameService(
+        context.clone(),
+        name_service_config,
+        names_config,
+    ))?;
     rpc.add_module(Objects(context.clone(), objects_config.clone()))?;
     rpc.add_module(QueryObjects(context.clone(), objects_config))?;
     rpc.add_module(QueryTransactions(context.clone(), transactions_config))?;
@@ -0,0 +1,37 @@
+-- This table tracks the latest state of every SuiNS name record in the
+-- registry, keyed by the ID of the dynamic field that holds the record.
+CREATE TABLE IF NOT EXISTS sum_name_records
+(
+    -- Object ID of the registry's dynamic field for this name.
+    field_id                    BYTEA         PRIMARY KEY,
+    -- The name, in its canonical dot format (e.g. `leaf.node.sui`).
+    domain                      TEXT          NOT NULL,
+    -- The name's parent, in its canonical dot format (the TLD for
+    -- second-level names).
+    parent                      TEXT          NOT NULL,
+    -- The address the name resolves to, if it has one.
+    target_address              BYTEA,
+    -- ID of the registration NFT this record belongs to. Leaf records share
+    -- their parent's NFT ID.
+    nft_id                      BYTEA         NOT NULL,
+    -- Timestamp in milliseconds that the name expires at. This is zero for
+    -- leaf records, which expire along with their parent.
+    expiration_timestamp_ms     BIGINT        NOT NULL,
+    -- Whether this is a leaf record (a sub-name without its own NFT).
+    is_leaf                     BOOLEAN       NOT NULL,
+    -- The checkpoint in which this record was last updated.
+    cp_sequence_number          BIGINT        NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS sum_name_records_domain
+ON sum_name_records (domain);
+
+CREATE INDEX IF NOT EXISTS sum_name_records_nft
+ON sum_name_records (nft_id, domain);
+
+CREATE INDEX IF NOT EXISTS sum_name_records_parent
+ON sum_name_records (parent, domain);
+
+CREATE INDEX IF NOT EXISTS sum_name_records_expiry
+ON sum_name_records (expiration_timestamp_ms, domain)
+WHERE NOT is_leaf;
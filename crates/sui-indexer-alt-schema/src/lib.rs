@@ -7,6 +7,7 @@ pub mod checkpoints;
 pub mod displays;
 pub mod epochs;
 pub mod events;
+pub mod name_service;
 pub mod objects;
 pub mod packages;
 pub mod schema;
@@ -0,0 +1,20 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use sui_field_count::FieldCount;
+
+use crate::schema::sum_name_records;
+
+#[derive(Insertable, Queryable, Selectable, Debug, Clone, FieldCount)]
+#[diesel(table_name = sum_name_records, primary_key(field_id))]
+pub struct StoredNameRecord {
+    pub field_id: Vec<u8>,
+    pub domain: String,
+    pub parent: String,
+    pub target_address: Option<Vec<u8>>,
+    pub nft_id: Vec<u8>,
+    pub expiration_timestamp_ms: i64,
+    pub is_leaf: bool,
+    pub cp_sequence_number: i64,
+}
@@ -155,6 +155,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    sum_name_records (field_id) {
+        field_id -> Bytea,
+        domain -> Text,
+        parent -> Text,
+        target_address -> Nullable<Bytea>,
+        nft_id -> Bytea,
+        expiration_timestamp_ms -> Int8,
+        is_leaf -> Bool,
+        cp_sequence_number -> Int8,
+    }
+}
+
 diesel::table! {
     sum_packages (package_id) {
         package_id -> Bytea,
@@ -241,6 +254,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     obj_info,
     obj_versions,
     sum_displays,
+    sum_name_records,
     sum_packages,
     tx_affected_addresses,
     tx_affected_objects,
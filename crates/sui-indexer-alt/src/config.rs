@@ -12,8 +12,11 @@ use sui_indexer_alt_framework::{
         CommitterConfig,
     },
 };
+use sui_types::base_types::{ObjectID, SuiAddress};
 use tracing::warn;
 
+pub use sui_name_service::NameServiceConfig;
+
 /// Trait for merging configuration structs together.
 pub trait Merge {
     fn merge(self, other: Self) -> Self;
@@ -42,6 +45,9 @@ pub struct IndexerConfig {
     /// Per-pipeline configurations.
     pub pipeline: PipelineLayer,
 
+    /// Location of the SuiNS package and registries, for pipelines that index name records.
+    pub name_service: NameServiceLayer,
+
     #[serde(flatten)]
     pub extra: toml::Table,
 }
@@ -109,6 +115,17 @@ pub struct PrunerLayer {
     pub extra: toml::Table,
 }
 
+#[DefaultConfig]
+#[derive(Clone, Default, Debug)]
+pub struct NameServiceLayer {
+    pub package_address: Option<SuiAddress>,
+    pub registry_id: Option<ObjectID>,
+    pub reverse_registry_id: Option<ObjectID>,
+
+    #[serde(flatten)]
+    pub extra: toml::Table,
+}
+
 #[DefaultConfig]
 #[derive(Clone, Default, Debug)]
 #[serde(rename_all = "snake_case")]
@@ -119,6 +136,7 @@ pub struct PipelineLayer {
 
     // Sequential pipelines
     pub sum_displays: Option<SequentialLayer>,
+    pub sum_name_records: Option<SequentialLayer>,
     pub sum_packages: Option<SequentialLayer>,
 
     // All concurrent pipelines
@@ -159,6 +177,7 @@ impl IndexerConfig {
         example.committer = CommitterConfig::default().into();
         example.pruner = PrunerConfig::default().into();
         example.pipeline = PipelineLayer::example();
+        example.name_service = NameServiceConfig::default().into();
 
         example
     }
@@ -271,6 +290,17 @@ impl PrunerLayer {
     }
 }
 
+impl NameServiceLayer {
+    pub fn finish(self, base: NameServiceConfig) -> NameServiceConfig {
+        check_extra("name service", self.extra);
+        NameServiceConfig {
+            package_address: self.package_address.unwrap_or(base.package_address),
+            registry_id: self.registry_id.unwrap_or(base.registry_id),
+            reverse_registry_id: self.reverse_registry_id.unwrap_or(base.reverse_registry_id),
+        }
+    }
+}
+
 impl PipelineLayer {
     /// Generate an example configuration, suitable for demonstrating the fields available to
     /// configure.
@@ -279,6 +309,7 @@ impl PipelineLayer {
             coin_balance_buckets: Some(Default::default()),
             obj_info: Some(Default::default()),
             sum_displays: Some(Default::default()),
+            sum_name_records: Some(Default::default()),
             sum_packages: Some(Default::default()),
             cp_sequence_numbers: Some(Default::default()),
             ev_emit_mod: Some(Default::default()),
@@ -317,6 +348,7 @@ impl Merge for IndexerConfig {
             committer: self.committer.merge(other.committer),
             pruner: self.pruner.merge(other.pruner),
             pipeline: self.pipeline.merge(other.pipeline),
+            name_service: self.name_service.merge(other.name_service),
             extra: Default::default(),
         }
     }
@@ -393,6 +425,19 @@ impl Merge for PrunerLayer {
     }
 }
 
+impl Merge for NameServiceLayer {
+    fn merge(self, other: NameServiceLayer) -> NameServiceLayer {
+        check_extra("name service", self.extra);
+        check_extra("name service", other.extra);
+        NameServiceLayer {
+            package_address: other.package_address.or(self.package_address),
+            registry_id: other.registry_id.or(self.registry_id),
+            reverse_registry_id: other.reverse_registry_id.or(self.reverse_registry_id),
+            extra: Default::default(),
+        }
+    }
+}
+
 impl Merge for PipelineLayer {
     fn merge(self, other: PipelineLayer) -> PipelineLayer {
         check_extra("pipeline", self.extra);
@@ -401,6 +446,7 @@ impl Merge for PipelineLayer {
             coin_balance_buckets: self.coin_balance_buckets.merge(other.coin_balance_buckets),
             obj_info: self.obj_info.merge(other.obj_info),
             sum_displays: self.sum_displays.merge(other.sum_displays),
+            sum_name_records: self.sum_name_records.merge(other.sum_name_records),
             sum_packages: self.sum_packages.merge(other.sum_packages),
             cp_sequence_numbers: self.cp_sequence_numbers.merge(other.cp_sequence_numbers),
             ev_emit_mod: self.ev_emit_mod.merge(other.ev_emit_mod),
@@ -467,6 +513,17 @@ impl From<ConcurrentConfig> for ConcurrentLayer {
     }
 }
 
+impl From<NameServiceConfig> for NameServiceLayer {
+    fn from(config: NameServiceConfig) -> Self {
+        Self {
+            package_address: Some(config.package_address),
+            registry_id: Some(config.registry_id),
+            reverse_registry_id: Some(config.reverse_registry_id),
+            extra: Default::default(),
+        }
+    }
+}
+
 impl From<CommitterConfig> for CommitterLayer {
     fn from(config: CommitterConfig) -> Self {
         Self {
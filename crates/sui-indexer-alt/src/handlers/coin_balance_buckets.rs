@@ -12,7 +12,7 @@ use sui_indexer_alt_schema::{
     objects::{StoredCoinBalanceBucket, StoredCoinOwnerKind},
     schema::coin_balance_buckets,
 };
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
     full_checkpoint_content::CheckpointData,
@@ -157,6 +157,8 @@ impl Processor for CoinBalanceBuckets {
 
 #[async_trait::async_trait]
 impl Handler for CoinBalanceBuckets {
+    type Store = Db;
+
     const PRUNING_REQUIRES_PROCESSED_VALUES: bool = true;
 
     async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>) -> Result<usize> {
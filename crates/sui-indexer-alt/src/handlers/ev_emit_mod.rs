@@ -10,7 +10,7 @@ use diesel_async::RunQueryDsl;
 use sui_indexer_alt_framework::models::cp_sequence_numbers::tx_interval;
 use sui_indexer_alt_framework::pipeline::{concurrent::Handler, Processor};
 use sui_indexer_alt_schema::{events::StoredEvEmitMod, schema::ev_emit_mod};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::full_checkpoint_content::CheckpointData;
 
 pub(crate) struct EvEmitMod;
@@ -50,6 +50,8 @@ impl Processor for EvEmitMod {
 
 #[async_trait::async_trait]
 impl Handler for EvEmitMod {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 100;
     const MAX_PENDING_ROWS: usize = 10000;
 
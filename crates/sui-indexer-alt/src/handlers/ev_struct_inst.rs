@@ -11,7 +11,7 @@ use sui_indexer_alt_framework::{
     pipeline::{concurrent::Handler, Processor},
 };
 use sui_indexer_alt_schema::{events::StoredEvStructInst, schema::ev_struct_inst};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::full_checkpoint_content::CheckpointData;
 
 pub(crate) struct EvStructInst;
@@ -54,6 +54,8 @@ impl Processor for EvStructInst {
 
 #[async_trait::async_trait]
 impl Handler for EvStructInst {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 100;
     const MAX_PENDING_ROWS: usize = 10000;
 
@@ -12,7 +12,7 @@ use sui_indexer_alt_framework::{
     pipeline::{concurrent::Handler, Processor},
 };
 use sui_indexer_alt_schema::{epochs::StoredEpochEnd, schema::kv_epoch_ends};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::{
     event::SystemEpochInfoEvent,
     full_checkpoint_content::CheckpointData,
@@ -121,6 +121,8 @@ impl Processor for KvEpochEnds {
 
 #[async_trait::async_trait]
 impl Handler for KvEpochEnds {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 1;
 
     async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>) -> Result<usize> {
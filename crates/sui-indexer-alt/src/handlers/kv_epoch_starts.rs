@@ -12,7 +12,7 @@ use sui_indexer_alt_framework::{
     pipeline::{concurrent::Handler, Processor},
 };
 use sui_indexer_alt_schema::{epochs::StoredEpochStart, schema::kv_epoch_starts};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::{
     full_checkpoint_content::CheckpointData,
     sui_system_state::{get_sui_system_state, SuiSystemStateTrait},
@@ -68,6 +68,8 @@ impl Processor for KvEpochStarts {
 
 #[async_trait::async_trait]
 impl Handler for KvEpochStarts {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 1;
 
     async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>) -> Result<usize> {
@@ -9,7 +9,7 @@ use sui_indexer_alt_framework::pipeline::{concurrent::Handler, Processor};
 use sui_indexer_alt_schema::{
     checkpoints::StoredGenesis, epochs::StoredProtocolConfig, schema::kv_protocol_configs,
 };
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_protocol_config::ProtocolConfig;
 use sui_types::full_checkpoint_content::CheckpointData;
 
@@ -52,6 +52,8 @@ impl Processor for KvProtocolConfigs {
 
 #[async_trait::async_trait]
 impl Handler for KvProtocolConfigs {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 1;
     const MAX_PENDING_ROWS: usize = 10000;
 
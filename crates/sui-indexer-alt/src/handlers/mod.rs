@@ -14,6 +14,7 @@ pub(crate) mod kv_transactions;
 pub(crate) mod obj_info;
 pub(crate) mod obj_versions;
 pub(crate) mod sum_displays;
+pub(crate) mod sum_name_records;
 pub(crate) mod sum_packages;
 pub(crate) mod tx_affected_addresses;
 pub(crate) mod tx_affected_objects;
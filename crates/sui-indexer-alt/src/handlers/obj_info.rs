@@ -9,7 +9,7 @@ use diesel_async::RunQueryDsl;
 use sui_field_count::FieldCount;
 use sui_indexer_alt_framework::pipeline::{concurrent::Handler, Processor};
 use sui_indexer_alt_schema::{objects::StoredObjInfo, schema::obj_info};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::{base_types::ObjectID, full_checkpoint_content::CheckpointData, object::Object};
 
 use crate::consistent_pruning::{PruningInfo, PruningLookupTable};
@@ -91,6 +91,8 @@ impl Processor for ObjInfo {
 
 #[async_trait::async_trait]
 impl Handler for ObjInfo {
+    type Store = Db;
+
     const PRUNING_REQUIRES_PROCESSED_VALUES: bool = true;
 
     async fn commit(values: &[Self::Value], conn: &mut db::Connection<'_>) -> Result<usize> {
@@ -7,7 +7,7 @@ use anyhow::Result;
 use diesel_async::RunQueryDsl;
 use sui_indexer_alt_framework::pipeline::{concurrent::Handler, Processor};
 use sui_indexer_alt_schema::{objects::StoredObjVersion, schema::obj_versions};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::full_checkpoint_content::CheckpointData;
 
 pub(crate) struct ObjVersions;
@@ -44,6 +44,8 @@ impl Processor for ObjVersions {
 
 #[async_trait::async_trait]
 impl Handler for ObjVersions {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 100;
     const MAX_PENDING_ROWS: usize = 10000;
 
@@ -10,7 +10,7 @@ use futures::future::try_join_all;
 use sui_field_count::FieldCount;
 use sui_indexer_alt_framework::pipeline::{sequential::Handler, Processor};
 use sui_indexer_alt_schema::{displays::StoredDisplay, schema::sum_displays};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::{display::DisplayVersionUpdatedEvent, full_checkpoint_content::CheckpointData};
 
 const MAX_INSERT_CHUNK_ROWS: usize = i16::MAX as usize / StoredDisplay::FIELD_COUNT;
@@ -58,6 +58,8 @@ impl Processor for SumDisplays {
 
 #[async_trait::async_trait]
 impl Handler for SumDisplays {
+    type Store = Db;
+
     type Batch = BTreeMap<Vec<u8>, Self::Value>;
 
     fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
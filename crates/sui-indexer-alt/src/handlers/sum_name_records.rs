@@ -0,0 +1,168 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use diesel::{upsert::excluded, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use sui_field_count::FieldCount;
+use sui_indexer_alt_framework::pipeline::{sequential::Handler, Processor};
+use sui_indexer_alt_schema::{name_service::StoredNameRecord, schema::sum_name_records};
+use sui_name_service::{Domain, NameRecord, NameServiceConfig};
+use sui_pg_db::{self as db, Db};
+use sui_types::{
+    base_types::ObjectID,
+    dynamic_field::{DynamicFieldInfo, Field},
+    full_checkpoint_content::CheckpointData,
+    object::Object,
+    TypeTag,
+};
+
+const MAX_INSERT_CHUNK_ROWS: usize = i16::MAX as usize / StoredNameRecord::FIELD_COUNT;
+
+/// Maintains the latest state of all name records in the SuiNS registry, so that they can be
+/// listed by target address, by parent, and by expiry.
+pub(crate) struct SumNameRecords(pub NameServiceConfig);
+
+pub(crate) enum ProcessedNameRecord {
+    Upsert(StoredNameRecord),
+    Delete(ObjectID),
+}
+
+impl SumNameRecords {
+    /// Name records are dynamic fields of the registry, keyed by `Domain`.
+    fn is_name_record(&self, object: &Object) -> bool {
+        let Self(config) = self;
+
+        let is_registry_child = object
+            .get_single_owner()
+            .is_some_and(|owner| owner == config.registry_id.into());
+
+        let domain_type = TypeTag::Struct(Box::new(Domain::type_(config.package_address)));
+        let is_domain_field = object.struct_tag().is_some_and(|tag| {
+            DynamicFieldInfo::is_dynamic_field(&tag)
+                && tag.type_params.first() == Some(&domain_type)
+        });
+
+        is_registry_child && is_domain_field
+    }
+}
+
+impl ProcessedNameRecord {
+    fn field_id(&self) -> Vec<u8> {
+        match self {
+            ProcessedNameRecord::Upsert(stored) => stored.field_id.clone(),
+            ProcessedNameRecord::Delete(id) => id.to_vec(),
+        }
+    }
+}
+
+impl Processor for SumNameRecords {
+    const NAME: &'static str = "sum_name_records";
+
+    type Value = ProcessedNameRecord;
+
+    fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+        let cp_sequence_number = checkpoint.checkpoint_summary.sequence_number as i64;
+        let checkpoint_input_objects = checkpoint.checkpoint_input_objects();
+        let latest_live_output_objects = checkpoint
+            .latest_live_output_objects()
+            .into_iter()
+            .map(|o| (o.id(), o))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut values = vec![];
+
+        // Records that were an input to the checkpoint but are no longer live have been removed
+        // from the registry.
+        for (object_id, object) in &checkpoint_input_objects {
+            if self.is_name_record(object) && !latest_live_output_objects.contains_key(object_id) {
+                values.push(ProcessedNameRecord::Delete(*object_id));
+            }
+        }
+
+        for (object_id, object) in &latest_live_output_objects {
+            if !self.is_name_record(object) {
+                continue;
+            }
+
+            let Field {
+                name: domain,
+                value: record,
+                ..
+            }: Field<Domain, NameRecord> = object
+                .to_rust()
+                .ok_or_else(|| anyhow!("Failed to deserialize name record {object_id}"))?;
+
+            values.push(ProcessedNameRecord::Upsert(StoredNameRecord {
+                field_id: object_id.to_vec(),
+                domain: domain.to_string(),
+                parent: domain.parent().to_string(),
+                target_address: record.target_address.map(|a| a.to_vec()),
+                nft_id: record.nft_id.bytes.to_vec(),
+                expiration_timestamp_ms: record.expiration_timestamp_ms as i64,
+                is_leaf: record.is_leaf_record(),
+                cp_sequence_number,
+            }));
+        }
+
+        Ok(values)
+    }
+}
+
+#[async_trait::async_trait]
+impl Handler for SumNameRecords {
+    type Store = Db;
+
+    type Batch = BTreeMap<Vec<u8>, ProcessedNameRecord>;
+
+    fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
+        // `values` are presented in checkpoint order, so later updates to a record replace
+        // earlier ones.
+        for value in values {
+            batch.insert(value.field_id(), value);
+        }
+    }
+
+    async fn commit(batch: &Self::Batch, conn: &mut db::Connection<'_>) -> Result<usize> {
+        let mut upserts = vec![];
+        let mut deletes = vec![];
+        for value in batch.values() {
+            match value {
+                ProcessedNameRecord::Upsert(stored) => upserts.push(stored.clone()),
+                ProcessedNameRecord::Delete(id) => deletes.push(id.to_vec()),
+            }
+        }
+
+        let mut affected = 0;
+        for chunk in upserts.chunks(MAX_INSERT_CHUNK_ROWS) {
+            affected += diesel::insert_into(sum_name_records::table)
+                .values(chunk)
+                .on_conflict(sum_name_records::field_id)
+                .do_update()
+                .set((
+                    sum_name_records::domain.eq(excluded(sum_name_records::domain)),
+                    sum_name_records::parent.eq(excluded(sum_name_records::parent)),
+                    sum_name_records::target_address.eq(excluded(sum_name_records::target_address)),
+                    sum_name_records::nft_id.eq(excluded(sum_name_records::nft_id)),
+                    sum_name_records::expiration_timestamp_ms
+                        .eq(excluded(sum_name_records::expiration_timestamp_ms)),
+                    sum_name_records::is_leaf.eq(excluded(sum_name_records::is_leaf)),
+                    sum_name_records::cp_sequence_number
+                        .eq(excluded(sum_name_records::cp_sequence_number)),
+                ))
+                .execute(conn)
+                .await?;
+        }
+
+        if !deletes.is_empty() {
+            affected += diesel::delete(sum_name_records::table)
+                .filter(sum_name_records::field_id.eq_any(deletes))
+                .execute(conn)
+                .await?;
+        }
+
+        Ok(affected)
+    }
+}
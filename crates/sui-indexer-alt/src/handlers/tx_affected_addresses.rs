@@ -15,7 +15,7 @@ use sui_indexer_alt_framework::{
 use sui_indexer_alt_schema::{
     schema::tx_affected_addresses, transactions::StoredTxAffectedAddress,
 };
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::{full_checkpoint_content::CheckpointData, object::Owner};
 
 pub(crate) struct TxAffectedAddresses;
@@ -64,6 +64,8 @@ impl Processor for TxAffectedAddresses {
 
 #[async_trait::async_trait]
 impl Handler for TxAffectedAddresses {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 100;
     const MAX_PENDING_ROWS: usize = 10000;
 
@@ -12,7 +12,7 @@ use sui_indexer_alt_framework::{
     pipeline::{concurrent::Handler, Processor},
 };
 use sui_indexer_alt_schema::{schema::tx_affected_objects, transactions::StoredTxAffectedObject};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::{effects::TransactionEffectsAPI, full_checkpoint_content::CheckpointData};
 
 pub(crate) struct TxAffectedObjects;
@@ -54,6 +54,8 @@ impl Processor for TxAffectedObjects {
 
 #[async_trait::async_trait]
 impl Handler for TxAffectedObjects {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 100;
     const MAX_PENDING_ROWS: usize = 10000;
 
@@ -15,7 +15,7 @@ use sui_indexer_alt_schema::{
     schema::tx_balance_changes,
     transactions::{BalanceChange, StoredTxBalanceChange},
 };
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::{
     coin::Coin,
     effects::TransactionEffectsAPI,
@@ -60,6 +60,8 @@ impl Processor for TxBalanceChanges {
 
 #[async_trait::async_trait]
 impl Handler for TxBalanceChanges {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 100;
     const MAX_PENDING_ROWS: usize = 10000;
 
@@ -12,7 +12,7 @@ use sui_indexer_alt_framework::{
     pipeline::{concurrent::Handler, Processor},
 };
 use sui_indexer_alt_schema::{schema::tx_calls, transactions::StoredTxCalls};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::full_checkpoint_content::CheckpointData;
 use sui_types::transaction::TransactionDataAPI;
 
@@ -57,6 +57,8 @@ impl Processor for TxCalls {
 
 #[async_trait::async_trait]
 impl Handler for TxCalls {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 100;
     const MAX_PENDING_ROWS: usize = 10000;
 
@@ -12,7 +12,7 @@ use sui_indexer_alt_framework::{
     pipeline::{concurrent::Handler, Processor},
 };
 use sui_indexer_alt_schema::{schema::tx_digests, transactions::StoredTxDigest};
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::full_checkpoint_content::CheckpointData;
 
 pub(crate) struct TxDigests;
@@ -44,6 +44,8 @@ impl Processor for TxDigests {
 
 #[async_trait::async_trait]
 impl Handler for TxDigests {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 100;
     const MAX_PENDING_ROWS: usize = 10000;
 
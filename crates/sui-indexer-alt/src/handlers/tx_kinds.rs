@@ -15,7 +15,7 @@ use sui_indexer_alt_schema::{
     schema::tx_kinds,
     transactions::{StoredKind, StoredTxKind},
 };
-use sui_pg_db as db;
+use sui_pg_db::{self as db, Db};
 use sui_types::full_checkpoint_content::CheckpointData;
 
 pub(crate) struct TxKinds;
@@ -55,6 +55,8 @@ impl Processor for TxKinds {
 
 #[async_trait::async_trait]
 impl Handler for TxKinds {
+    type Store = Db;
+
     const MIN_EAGER_ROWS: usize = 100;
     const MAX_PENDING_ROWS: usize = 10000;
 
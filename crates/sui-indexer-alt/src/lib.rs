@@ -2,16 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use bootstrap::bootstrap;
-use config::{IndexerConfig, PipelineLayer};
+use config::{IndexerConfig, NameServiceConfig, PipelineLayer};
 use handlers::coin_balance_buckets::CoinBalanceBuckets;
 use handlers::{
     ev_emit_mod::EvEmitMod, ev_struct_inst::EvStructInst, kv_checkpoints::KvCheckpoints,
     kv_epoch_ends::KvEpochEnds, kv_epoch_starts::KvEpochStarts, kv_feature_flags::KvFeatureFlags,
     kv_objects::KvObjects, kv_protocol_configs::KvProtocolConfigs, kv_transactions::KvTransactions,
     obj_info::ObjInfo, obj_versions::ObjVersions, sum_displays::SumDisplays,
-    sum_packages::SumPackages, tx_affected_addresses::TxAffectedAddresses,
-    tx_affected_objects::TxAffectedObjects, tx_balance_changes::TxBalanceChanges,
-    tx_calls::TxCalls, tx_digests::TxDigests, tx_kinds::TxKinds,
+    sum_name_records::SumNameRecords, sum_packages::SumPackages,
+    tx_affected_addresses::TxAffectedAddresses, tx_affected_objects::TxAffectedObjects,
+    tx_balance_changes::TxBalanceChanges, tx_calls::TxCalls, tx_digests::TxDigests,
+    tx_kinds::TxKinds,
 };
 use prometheus::Registry;
 use sui_indexer_alt_framework::handlers::cp_sequence_numbers::CpSequenceNumbers;
@@ -53,11 +54,13 @@ pub async fn setup_indexer(
         committer,
         pruner,
         pipeline,
+        name_service,
         extra: _,
     } = indexer_config.finish();
 
     let PipelineLayer {
         sum_displays,
+        sum_name_records,
         sum_packages,
         coin_balance_buckets,
         cp_sequence_numbers,
@@ -85,6 +88,7 @@ pub async fn setup_indexer(
     let consistency = consistency.finish(PrunerConfig::default());
     let committer = committer.finish(CommitterConfig::default());
     let pruner = pruner.finish(PrunerConfig::default());
+    let name_service = name_service.finish(NameServiceConfig::default());
 
     let retry_interval = ingestion.retry_interval();
 
@@ -176,6 +180,7 @@ pub async fn setup_indexer(
 
     // Summary tables (without write-ahead log)
     add_sequential!(SumDisplays, sum_displays);
+    add_sequential!(SumNameRecords(name_service), sum_name_records);
     add_sequential!(SumPackages, sum_packages);
 
     // Unpruned concurrent pipelines
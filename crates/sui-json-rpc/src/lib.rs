@@ -137,13 +137,18 @@ impl JsonRpcServerBuilder {
 
         let metrics = Arc::new(Metrics::new(&self.registry, &methods_names));
         let traffic_controller_metrics = TrafficControllerMetrics::new(&self.registry);
-        let traffic_controller = self.policy_config.clone().map(|policy| {
-            Arc::new(TrafficController::init(
-                policy,
-                traffic_controller_metrics,
-                self.firewall_config.clone(),
-            ))
-        });
+        let traffic_controller = self
+            .policy_config
+            .clone()
+            .map(|policy| {
+                TrafficController::init(
+                    policy,
+                    traffic_controller_metrics,
+                    self.firewall_config.clone(),
+                )
+            })
+            .transpose()?
+            .map(Arc::new);
         let client_id_source = self
             .policy_config
             .clone()
@@ -46,11 +46,12 @@ where
         async move {
             if let Some(traffic_controller) = traffic_controller {
                 let client = req.extensions().get::<IpAddr>().cloned();
+                let method = req.method_name().to_string();
                 if let Err(response) = handle_traffic_req(&traffic_controller, &client).await {
                     response
                 } else {
                     let response = service.call(req).await;
-                    handle_traffic_resp(&traffic_controller, client, &response);
+                    handle_traffic_resp(&traffic_controller, client, method, &response);
                     response
                 }
             } else {
@@ -78,6 +79,7 @@ async fn handle_traffic_req(
 fn handle_traffic_resp(
     traffic_controller: &TrafficController,
     client: Option<IpAddr>,
+    method: String,
     response: &MethodResponse,
 ) {
     let error = response.as_error_code().map(ErrorCode::from);
@@ -96,7 +98,10 @@ fn handle_traffic_resp(
         // traffic and incentivize high volume clients to choose a
         // suitable rpc provider (or run their own). Later we may want
         // to provide a weight distribution based on the method being called.
+        // Policies that price methods differently (e.g. the token bucket
+        // policy) can do so using `method`.
         spam_weight: Weight::one(),
+        method: Some(method),
         timestamp: SystemTime::now(),
     });
 }
@@ -0,0 +1,588 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::keystore::{validate_alias, AccountKeystore, Alias, FileBasedKeystore};
+use crate::random_names::random_name;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, ensure, Context};
+use argon2::{Algorithm, Argon2, Params, Version};
+use fastcrypto::encoding::{Base64, Encoding, Hex};
+use mysocial_types::base_types::SuiAddress;
+use mysocial_types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Environment variable the `sui` CLI reads the passphrase of an encrypted keystore from, to unlock
+/// it before running a command. Loading a keystore never unlocks it on its own.
+pub const KEYSTORE_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE";
+
+const ENCRYPTED_KEYSTORE_VERSION: u8 = 1;
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER: &str = "aes-256-gcm";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// Plaintext encrypted under the derived key to check the passphrase on unlock, even if the
+/// keystore has no keys yet.
+const VERIFIER_PLAINTEXT: &[u8] = b"sui-encrypted-keystore";
+
+/// Parameters for deriving the keystore's encryption key from its passphrase with Argon2id. They
+/// are stored alongside the encrypted keys, so that a keystore can be unlocked on any platform.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory cost, in KiB.
+    pub memory_kib: u32,
+    /// Number of passes over memory.
+    pub iterations: u32,
+    /// Degree of parallelism.
+    pub parallelism: u32,
+}
+
+/// An encrypted value, with the nonce it was encrypted with. Both are Base64 encoded.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct EncryptedBlob {
+    nonce: String,
+    ciphertext: String,
+}
+
+/// A private key, encrypted with the public key as associated data, so that ciphertexts cannot be
+/// swapped between entries.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct EncryptedKey {
+    public_key_base64: String,
+    #[serde(flatten)]
+    encrypted: EncryptedBlob,
+}
+
+/// On-disk format of an [EncryptedFileKeystore].
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    version: u8,
+    kdf: String,
+    kdf_params: KdfParams,
+    salt: String,
+    cipher: String,
+    verifier: EncryptedBlob,
+    keys: Vec<EncryptedKey>,
+    aliases: Vec<Alias>,
+}
+
+/// Keys and the key they are encrypted under, only held in memory while the keystore is unlocked.
+struct Unlocked {
+    encryption_key: Zeroizing<[u8; 32]>,
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+}
+
+/// A keystore whose private keys are encrypted at rest with a key derived from a passphrase.
+///
+/// Public keys and aliases are stored in plaintext, so addresses can be listed while the keystore
+/// is locked, but signing and key management require it to be unlocked first.
+pub struct EncryptedFileKeystore {
+    path: PathBuf,
+    kdf_params: KdfParams,
+    salt: Vec<u8>,
+    verifier: EncryptedBlob,
+    encrypted_keys: BTreeMap<SuiAddress, EncryptedKey>,
+    aliases: BTreeMap<SuiAddress, Alias>,
+    unlocked: Option<Unlocked>,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+impl Serialize for EncryptedFileKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for EncryptedFileKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(Signature::new_hashed(msg, self.signing_key(address)?))
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(intent, msg),
+            self.signing_key(address)?,
+        ))
+    }
+
+    fn add_key(&mut self, alias: Option<String>, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        let address: SuiAddress = (&keypair.public()).into();
+        let alias = self.create_alias(alias)?;
+        let public_key_base64 = keypair.public().encode_base64();
+
+        let Some(unlocked) = &mut self.unlocked else {
+            bail!("Keystore is locked. Unlock it before adding keys.");
+        };
+
+        let private_key = Zeroizing::new(keypair.encode_base64());
+        let encrypted = encrypt(
+            &unlocked.encryption_key,
+            public_key_base64.as_bytes(),
+            private_key.as_bytes(),
+        )?;
+
+        self.encrypted_keys.insert(
+            address,
+            EncryptedKey {
+                public_key_base64: public_key_base64.clone(),
+                encrypted,
+            },
+        );
+        self.aliases.insert(
+            address,
+            Alias {
+                alias,
+                public_key_base64,
+            },
+        );
+        unlocked.keys.insert(address, keypair);
+        self.save()
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.encrypted_keys
+            .values()
+            .filter_map(|k| PublicKey::decode_base64(&k.public_key_base64).ok())
+            .collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        let Some(unlocked) = &self.unlocked else {
+            bail!("Keystore is locked. Unlock it to access keys.");
+        };
+
+        match unlocked.keys.get(address) {
+            Some(key) => Ok(key),
+            None => Err(anyhow!("Cannot find key for address: [{address}]")),
+        }
+    }
+
+    /// Return an array of `Alias`, consisting of every alias and its corresponding public key.
+    fn aliases(&self) -> Vec<&Alias> {
+        self.aliases.values().collect()
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        self.aliases.iter().collect::<Vec<_>>()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.aliases.values_mut().collect()
+    }
+
+    /// This function returns an error if the provided alias already exists. If the alias
+    /// has not already been used, then it returns the alias.
+    /// If no alias has been passed, it will generate a new alias.
+    fn create_alias(&self, alias: Option<String>) -> Result<String, anyhow::Error> {
+        match alias {
+            Some(a) if self.alias_exists(&a) => {
+                bail!("Alias {a} already exists. Please choose another alias.")
+            }
+            Some(a) => validate_alias(&a),
+            None => Ok(random_name(
+                &self
+                    .alias_names()
+                    .into_iter()
+                    .map(|x| x.to_string())
+                    .collect::<HashSet<_>>(),
+            )),
+        }
+    }
+
+    /// Get the address by its alias
+    fn get_address_by_alias(&self, alias: String) -> Result<&SuiAddress, anyhow::Error> {
+        self.addresses_with_alias()
+            .iter()
+            .find(|x| x.1.alias == alias)
+            .ok_or_else(|| anyhow!("Cannot resolve alias {alias} to an address"))
+            .map(|x| x.0)
+    }
+
+    /// Get the alias if it exists, or return an error if it does not exist.
+    fn get_alias_by_address(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        match self.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    /// Updates an old alias to the new alias and saves it to the keystore file.
+    /// If the new_alias is None, it will generate a new random alias.
+    fn update_alias(
+        &mut self,
+        old_alias: &str,
+        new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let new_alias_name = self.update_alias_value(old_alias, new_alias)?;
+        self.save()?;
+        Ok(new_alias_name)
+    }
+}
+
+impl EncryptedFileKeystore {
+    /// Load an encrypted keystore from `path`. The keystore starts out locked.
+    pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let reader = BufReader::new(File::open(path).with_context(|| {
+            format!(
+                "Cannot open the encrypted keystore file: {}",
+                path.display()
+            )
+        })?);
+
+        let file: EncryptedKeystoreFile = serde_json::from_reader(reader).with_context(|| {
+            format!(
+                "Cannot deserialize the encrypted keystore file: {}",
+                path.display()
+            )
+        })?;
+
+        ensure!(
+            file.version == ENCRYPTED_KEYSTORE_VERSION,
+            "Unsupported encrypted keystore version {} in {}",
+            file.version,
+            path.display(),
+        );
+        ensure!(
+            file.kdf == KDF_ALGORITHM && file.cipher == CIPHER,
+            "Unsupported encrypted keystore scheme {}/{} in {}",
+            file.kdf,
+            file.cipher,
+            path.display(),
+        );
+
+        let salt = Base64::decode(&file.salt)
+            .map_err(|e| anyhow!("Invalid salt in keystore: {}. {}", path.display(), e))?;
+
+        let encrypted_keys = file
+            .keys
+            .into_iter()
+            .map(|k| {
+                let key = PublicKey::decode_base64(&k.public_key_base64);
+                key.map(|pk| (SuiAddress::from(&pk), k))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid encrypted keystore file: {}. {}", path.display(), e))?;
+
+        let aliases = file
+            .aliases
+            .into_iter()
+            .map(|alias| {
+                let key = PublicKey::decode_base64(&alias.public_key_base64);
+                key.map(|k| (SuiAddress::from(&k), alias))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid aliases in keystore: {}. {}", path.display(), e))?;
+
+        Ok(Self {
+            path: path.clone(),
+            kdf_params: file.kdf_params,
+            salt,
+            verifier: file.verifier,
+            encrypted_keys,
+            aliases,
+            unlocked: None,
+        })
+    }
+
+    /// Create a new, empty encrypted keystore at `path`, protected by `passphrase`. The keystore
+    /// is returned unlocked. Fails if a file already exists at `path`.
+    pub fn create(
+        path: &Path,
+        passphrase: &str,
+        kdf_params: KdfParams,
+    ) -> Result<Self, anyhow::Error> {
+        ensure!(
+            !path.exists(),
+            "Cannot create encrypted keystore, file already exists: {}",
+            path.display()
+        );
+
+        let mut salt = vec![0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+
+        let encryption_key = derive_key(passphrase, &salt, &kdf_params)?;
+        let verifier = encrypt(&encryption_key, &[], VERIFIER_PLAINTEXT)?;
+
+        let keystore = Self {
+            path: path.to_path_buf(),
+            kdf_params,
+            salt,
+            verifier,
+            encrypted_keys: BTreeMap::new(),
+            aliases: BTreeMap::new(),
+            unlocked: Some(Unlocked {
+                encryption_key,
+                keys: BTreeMap::new(),
+            }),
+        };
+
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Encrypt the keys and aliases of the plaintext [FileBasedKeystore] at `from`, into a new
+    /// encrypted keystore at `to`. The plaintext keystore is left untouched, so that it can be
+    /// removed once the migration has been checked.
+    pub fn migrate_from_file(
+        from: &PathBuf,
+        to: &Path,
+        passphrase: &str,
+        kdf_params: KdfParams,
+    ) -> Result<Self, anyhow::Error> {
+        let file = FileBasedKeystore::new(from)?;
+        let mut keystore = Self::create(to, passphrase, kdf_params)?;
+
+        for keypair in file.key_pairs() {
+            let address: SuiAddress = (&keypair.public()).into();
+            let alias = file.get_alias_by_address(&address).ok();
+            keystore.add_key(alias, keypair.copy())?;
+        }
+
+        Ok(keystore)
+    }
+
+    /// Decrypt the keystore's keys with `passphrase`, so that they can be used to sign. Fails,
+    /// leaving the keystore locked, if the passphrase is wrong.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        let encryption_key = derive_key(passphrase, &self.salt, &self.kdf_params)?;
+
+        let verifier = decrypt(&encryption_key, &[], &self.verifier)
+            .map_err(|_| anyhow!("Incorrect passphrase for keystore {}", self.path.display()))?;
+        ensure!(
+            verifier.as_slice() == VERIFIER_PLAINTEXT,
+            "Incorrect passphrase for keystore {}",
+            self.path.display()
+        );
+
+        let keys = self
+            .encrypted_keys
+            .iter()
+            .map(|(address, k)| {
+                let private_key = decrypt(
+                    &encryption_key,
+                    k.public_key_base64.as_bytes(),
+                    &k.encrypted,
+                )?;
+                let private_key = std::str::from_utf8(&private_key)
+                    .map_err(|_| anyhow!("Invalid key for address {address}"))?;
+                let keypair = SuiKeyPair::decode_base64(private_key)
+                    .map_err(|e| anyhow!("Invalid key for address {address}. {e}"))?;
+                ensure!(
+                    SuiAddress::from(&keypair.public()) == *address,
+                    "Key does not match address {address}"
+                );
+                Ok((*address, keypair))
+            })
+            .collect::<Result<BTreeMap<_, _>, anyhow::Error>>()?;
+
+        self.unlocked = Some(Unlocked {
+            encryption_key,
+            keys,
+        });
+        Ok(())
+    }
+
+    /// Drop the decrypted keys from memory. The keystore needs to be unlocked again before it can
+    /// sign.
+    pub fn lock(&mut self) {
+        self.unlocked = None;
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.unlocked.is_none()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the encrypted keys, public keys and aliases to the keystore file. Private keys are
+    /// never written in plaintext, and on Unix the file is only readable by its owner.
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        let file = EncryptedKeystoreFile {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            kdf: KDF_ALGORITHM.to_string(),
+            kdf_params: self.kdf_params.clone(),
+            salt: Base64::encode(&self.salt),
+            cipher: CIPHER.to_string(),
+            verifier: self.verifier.clone(),
+            keys: self.encrypted_keys.values().cloned().collect(),
+            aliases: self.aliases.values().cloned().collect(),
+        };
+
+        let store = serde_json::to_string_pretty(&file).with_context(|| {
+            format!(
+                "Cannot serialize encrypted keystore to file: {}",
+                self.path.display()
+            )
+        })?;
+        write_owner_only(&self.path, store.as_bytes()).with_context(|| {
+            format!(
+                "Cannot write encrypted keystore to file: {}",
+                self.path.display()
+            )
+        })
+    }
+
+    fn signing_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, signature::Error> {
+        let Some(unlocked) = &self.unlocked else {
+            return Err(signature::Error::from_source(
+                "Keystore is locked. Unlock it before signing.",
+            ));
+        };
+
+        unlocked.keys.get(address).ok_or_else(|| {
+            signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+        })
+    }
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    params: &KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, anyhow::Error> {
+    let params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(32),
+    )
+    .map_err(|e| anyhow!("Invalid key derivation parameters: {e}"))?;
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key[..])
+        .map_err(|e| anyhow!("Cannot derive keystore encryption key: {e}"))?;
+    Ok(key)
+}
+
+fn encrypt(key: &[u8; 32], aad: &[u8], msg: &[u8]) -> Result<EncryptedBlob, anyhow::Error> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+
+    let ciphertext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg, aad })
+        .map_err(|_| anyhow!("Cannot encrypt keystore entry"))?;
+
+    Ok(EncryptedBlob {
+        nonce: Base64::encode(nonce),
+        ciphertext: Base64::encode(ciphertext),
+    })
+}
+
+fn decrypt(
+    key: &[u8; 32],
+    aad: &[u8],
+    blob: &EncryptedBlob,
+) -> Result<Zeroizing<Vec<u8>>, anyhow::Error> {
+    let nonce = Base64::decode(&blob.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+    let ciphertext =
+        Base64::decode(&blob.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+    ensure!(nonce.len() == NONCE_LENGTH, "Invalid nonce length");
+
+    let plaintext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| anyhow!("Cannot decrypt keystore entry"))?;
+
+    Ok(Zeroizing::new(plaintext))
+}
+
+/// Write `contents` to the file at `path`, replacing it atomically: the contents are written and
+/// synced to a temporary file in the same directory, which is then renamed over `path`, so a crash
+/// or a full disk never leaves the keystore truncated. On Unix, the file is made readable and
+/// writable by its owner only.
+fn write_owner_only(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Not a file path: {}", path.display()),
+        )
+    })?;
+
+    let mut suffix = [0u8; 8];
+    OsRng.fill_bytes(&mut suffix);
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(format!(".{}.tmp", Hex::encode(suffix)));
+    let tmp_path = dir.join(tmp_name);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let result = (|| {
+        let mut file = options.open(&tmp_path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+        return result;
+    }
+
+    // Persist the rename itself.
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    Ok(())
+}
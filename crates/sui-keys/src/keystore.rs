@@ -1,8 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::encrypted_keystore::EncryptedFileKeystore;
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 use crate::random_names::{random_name, random_names};
+use crate::remote_keystore::RemoteKeystore;
 use anyhow::{anyhow, bail, ensure, Context};
 use bip32::DerivationPath;
 use bip39::{Language, Mnemonic, Seed};
@@ -28,6 +30,8 @@ use mysocial_types::crypto::{
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Encrypted(EncryptedFileKeystore),
+    Remote(RemoteKeystore),
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -141,6 +145,55 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Encrypted(encrypted) => {
+                writeln!(writer, "Keystore Type : Encrypted File")?;
+                writeln!(writer, "Keystore Path : {:?}", encrypted.path())?;
+                write!(writer, "Locked : {}", encrypted.is_locked())?;
+                write!(f, "{}", writer)
+            }
+            Keystore::Remote(remote) => {
+                writeln!(writer, "Keystore Type : Remote")?;
+                write!(writer, "Signer Endpoint : {:?}", remote.endpoint())?;
+                write!(f, "{}", writer)
+            }
+        }
+    }
+}
+
+impl Keystore {
+    /// Whether the keystore needs to be unlocked before it can sign. Only encrypted keystores
+    /// can be locked.
+    pub fn is_locked(&self) -> bool {
+        match self {
+            Keystore::Encrypted(encrypted) => encrypted.is_locked(),
+            Keystore::File(_) | Keystore::InMem(_) | Keystore::Remote(_) => false,
+        }
+    }
+
+    /// Unlock an encrypted keystore with its passphrase.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        match self {
+            Keystore::Encrypted(encrypted) => encrypted.unlock(passphrase),
+            Keystore::File(_) | Keystore::InMem(_) | Keystore::Remote(_) => {
+                bail!("Only encrypted keystores can be unlocked")
+            }
+        }
+    }
+
+    /// Lock an encrypted keystore, dropping its decrypted keys from memory. Has no effect on other
+    /// keystores.
+    pub fn lock(&mut self) {
+        if let Keystore::Encrypted(encrypted) = self {
+            encrypted.lock();
+        }
+    }
+
+    /// Fetch the keys held by the signer of a remote keystore again, to pick up keys that were
+    /// added to or removed from it. Has no effect on other keystores.
+    pub fn refresh(&mut self) -> Result<(), anyhow::Error> {
+        match self {
+            Keystore::Remote(remote) => remote.refresh(),
+            Keystore::File(_) | Keystore::InMem(_) | Keystore::Encrypted(_) => Ok(()),
         }
     }
 }
@@ -394,6 +447,10 @@ impl FileBasedKeystore {
         self.path = Some(path.to_path_buf());
     }
 
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     pub fn save_aliases(&self) -> Result<(), anyhow::Error> {
         if let Some(path) = &self.path {
             let aliases_store =
@@ -593,7 +650,7 @@ impl InMemKeystore {
     }
 }
 
-fn validate_alias(alias: &str) -> Result<String, anyhow::Error> {
+pub(crate) fn validate_alias(alias: &str) -> Result<String, anyhow::Error> {
     let re = Regex::new(r"^[A-Za-z][A-Za-z0-9-_\.]*$")
         .map_err(|_| anyhow!("Cannot build the regex needed to validate the alias naming"))?;
     let alias = alias.trim();
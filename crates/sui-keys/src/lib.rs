@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod encrypted_keystore;
 pub mod key_derive;
 pub mod keypair_file;
 pub mod keystore;
 pub mod random_names;
+pub mod remote_keystore;
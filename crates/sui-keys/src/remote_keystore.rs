@@ -0,0 +1,465 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A keystore whose keys live in an out-of-process signer.
+//!
+//! The keystore talks to the signer over a Unix socket (on Unix platforms only) or HTTP(S), using
+//! JSON messages of at most [MAX_SIGNER_MESSAGE_SIZE] bytes:
+//!
+//! - Over a Unix socket, the keystore opens a new connection per request, writes the request as a
+//!   single line of JSON, and reads a single line of JSON back.
+//! - Over HTTP(S), the keystore `POST`s the request as the JSON body to the configured URL, and
+//!   reads the JSON response from the body of the response.
+//!
+//! Requests are tagged by `method`:
+//!
+//! ```json
+//! {"method": "keys"}
+//! {"method": "sign", "address": "0x...", "message": "<Base64>", "intent_message": "<Base64>"}
+//! ```
+//!
+//! `keys` lists the public keys (Base64 encoded `flag || pk`) the signer holds, with an optional
+//! alias for each:
+//!
+//! ```json
+//! {"keys": [{"public_key": "<Base64>", "alias": "my-key"}]}
+//! ```
+//!
+//! `sign` asks the signer to sign the bytes in `message` with the key for `address`, and returns
+//! the Base64 encoded `flag || signature || pk`:
+//!
+//! ```json
+//! {"signature": "<Base64>"}
+//! ```
+//!
+//! When signing an intent message (see [AccountKeystore::sign_secure]), `message` is the digest of
+//! the BCS-encoded intent message, which is also included in `intent_message`, so that the signer
+//! can inspect what it is signing, and check that `message` is its digest, before signing.
+//!
+//! Any request can fail with:
+//!
+//! ```json
+//! {"error": "<description>"}
+//! ```
+//!
+//! The keystore only contacts the signer once it is first used, so that a configuration containing
+//! a remote keystore can still be loaded while the signer is unavailable. The keys it lists are
+//! cached from then on, until [RemoteKeystore::refresh] is called. Signing always goes to the
+//! signer, so keys it has gained since can be used before the cache is refreshed.
+
+use crate::keystore::{validate_alias, AccountKeystore, Alias};
+use crate::random_names::random_name;
+use anyhow::{anyhow, bail, Context};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::HashFunction;
+use fastcrypto::traits::{ToFromBytes, VerifyingKey};
+use mysocial_types::base_types::SuiAddress;
+use mysocial_types::crypto::{
+    DefaultHash, EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair, SuiSignature,
+    SuiSignatureInner,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// How long to wait for the signer to accept, read or answer a request.
+const SIGNER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The largest request or response, in bytes, that is exchanged with a signer.
+pub const MAX_SIGNER_MESSAGE_SIZE: usize = 1 << 20;
+
+/// Where to reach a remote signer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerEndpoint {
+    /// Path to a Unix socket the signer is listening on.
+    Unix(PathBuf),
+    /// `http://` or `https://` URL the signer accepts requests on.
+    Http(String),
+}
+
+/// A request to a remote signer.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SignerRequest {
+    /// List the public keys the signer holds.
+    Keys,
+    /// Sign `message` with the key for `address`.
+    Sign {
+        address: SuiAddress,
+        /// Base64 encoded bytes to sign.
+        message: String,
+        /// Base64 encoded BCS bytes of the intent message that `message` is the digest of, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        intent_message: Option<String>,
+    },
+}
+
+/// A response from a remote signer.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    Keys(Vec<SignerKey>),
+    /// Base64 encoded `flag || signature || pk`.
+    Signature(String),
+    Error(String),
+}
+
+/// A key held by a remote signer.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SignerKey {
+    /// Base64 encoded `flag || pk`.
+    pub public_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+/// A keystore that forwards signing requests to a remote signer, so that private keys never need
+/// to be present on the host using the keystore. Keys cannot be added to or read out of a remote
+/// keystore: they are managed by the signer.
+///
+/// The keys the signer holds are fetched the first time they are needed, and cached until the
+/// keystore is refreshed. Until the signer can be reached, methods that can fail return an error,
+/// and the others behave as if it held no keys.
+pub struct RemoteKeystore {
+    endpoint: SignerEndpoint,
+    signer_keys: OnceLock<SignerKeys>,
+}
+
+/// The keys held by a remote signer, and their aliases.
+#[derive(Default)]
+struct SignerKeys {
+    keys: BTreeMap<SuiAddress, PublicKey>,
+    aliases: BTreeMap<SuiAddress, Alias>,
+}
+
+impl Serialize for RemoteKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.endpoint.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RemoteKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(RemoteKeystore::new(SignerEndpoint::deserialize(
+            deserializer,
+        )?))
+    }
+}
+
+impl AccountKeystore for RemoteKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        let signature = self.sign(address, msg, None)?;
+
+        // Make sure the signer used the key we asked for, to sign the message we asked for.
+        let public_key =
+            PublicKey::try_from_bytes(signature.scheme(), signature.public_key_bytes())
+                .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        if SuiAddress::from(&public_key) != *address {
+            return Err(signature::Error::from_source(format!(
+                "Remote signer signed with the wrong key for address: [{address}]"
+            )));
+        }
+
+        verify_hashed(&signature, msg).map_err(|e| {
+            signature::Error::from_source(format!("Invalid signature from remote signer: {e}"))
+        })?;
+
+        Ok(signature)
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let intent_msg = IntentMessage::new(intent, msg);
+        let bytes = bcs::to_bytes(&intent_msg).map_err(signature::Error::from_source)?;
+
+        let mut hasher = DefaultHash::default();
+        hasher.update(&bytes);
+        let digest = hasher.finalize().digest;
+
+        let signature = self.sign(address, &digest, Some(&bytes))?;
+        signature
+            .verify_secure(&intent_msg, *address, signature.scheme())
+            .map_err(signature::Error::from_source)?;
+
+        Ok(signature)
+    }
+
+    fn add_key(
+        &mut self,
+        _alias: Option<String>,
+        _keypair: SuiKeyPair,
+    ) -> Result<(), anyhow::Error> {
+        bail!("Keys cannot be added to a remote keystore, add them to the signer instead")
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.signer_keys()
+            .map(|signer_keys| signer_keys.keys.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn get_key(&self, _address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        bail!("Private keys are not accessible through a remote keystore")
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        self.signer_keys()
+            .map(|signer_keys| signer_keys.aliases.values().collect())
+            .unwrap_or_default()
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        self.signer_keys()
+            .map(|signer_keys| signer_keys.aliases.iter().collect())
+            .unwrap_or_default()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.signer_keys_mut()
+            .map(|signer_keys| signer_keys.aliases.values_mut().collect())
+            .unwrap_or_default()
+    }
+
+    /// This function returns an error if the provided alias already exists. If the alias
+    /// has not already been used, then it returns the alias.
+    /// If no alias has been passed, it will generate a new alias.
+    fn create_alias(&self, alias: Option<String>) -> Result<String, anyhow::Error> {
+        self.signer_keys()?.create_alias(alias)
+    }
+
+    /// Get the address by its alias
+    fn get_address_by_alias(&self, alias: String) -> Result<&SuiAddress, anyhow::Error> {
+        self.signer_keys()?
+            .aliases
+            .iter()
+            .find(|x| x.1.alias == alias)
+            .ok_or_else(|| anyhow!("Cannot resolve alias {alias} to an address"))
+            .map(|x| x.0)
+    }
+
+    /// Get the alias if it exists, or return an error if it does not exist.
+    fn get_alias_by_address(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        match self.signer_keys()?.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    /// Updates an old alias to the new alias. Aliases of a remote keystore are not persisted: they
+    /// are reset to the ones the signer reports whenever the keystore is loaded.
+    fn update_alias(
+        &mut self,
+        old_alias: &str,
+        new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        self.update_alias_value(old_alias, new_alias)
+    }
+}
+
+impl RemoteKeystore {
+    /// A keystore for the signer at `endpoint`. The signer is not contacted until the keystore is
+    /// first used.
+    pub fn new(endpoint: SignerEndpoint) -> Self {
+        Self {
+            endpoint,
+            signer_keys: OnceLock::new(),
+        }
+    }
+
+    pub fn endpoint(&self) -> &SignerEndpoint {
+        &self.endpoint
+    }
+
+    /// Fetch the keys the signer holds again, replacing the cached keys and their aliases. Aliases
+    /// that were updated locally are reset to the ones the signer reports.
+    pub fn refresh(&mut self) -> Result<(), anyhow::Error> {
+        let signer_keys = SignerKeys::fetch(&self.endpoint)?;
+        self.signer_keys = OnceLock::from(signer_keys);
+        Ok(())
+    }
+
+    /// The keys held by the signer, fetching them if they haven't been fetched yet.
+    fn signer_keys(&self) -> Result<&SignerKeys, anyhow::Error> {
+        if let Some(signer_keys) = self.signer_keys.get() {
+            return Ok(signer_keys);
+        }
+
+        let signer_keys = SignerKeys::fetch(&self.endpoint)?;
+        Ok(self.signer_keys.get_or_init(|| signer_keys))
+    }
+
+    fn signer_keys_mut(&mut self) -> Result<&mut SignerKeys, anyhow::Error> {
+        self.signer_keys()?;
+        Ok(self
+            .signer_keys
+            .get_mut()
+            .expect("Signer keys were fetched above"))
+    }
+
+    fn sign(
+        &self,
+        address: &SuiAddress,
+        message: &[u8],
+        intent_message: Option<&[u8]>,
+    ) -> Result<Signature, signature::Error> {
+        let req = SignerRequest::Sign {
+            address: *address,
+            message: Base64::encode(message),
+            intent_message: intent_message.map(Base64::encode),
+        };
+
+        match request(&self.endpoint, &req) {
+            Ok(SignerResponse::Signature(signature)) => {
+                let bytes = Base64::decode(&signature).map_err(signature::Error::from_source)?;
+                Signature::from_bytes(&bytes).map_err(signature::Error::from_source)
+            }
+            Ok(_) => Err(signature::Error::from_source(
+                "Unexpected response from remote signer when signing",
+            )),
+            Err(e) => Err(signature::Error::from_source(e.to_string())),
+        }
+    }
+}
+
+/// Check that `signature` is a valid signature over `msg`, by the public key it carries.
+fn verify_hashed(signature: &Signature, msg: &[u8]) -> Result<(), anyhow::Error> {
+    fn verify<S: SuiSignatureInner>(signature: &S, msg: &[u8]) -> Result<(), anyhow::Error> {
+        let (signature, public_key) = signature.get_verification_inputs()?;
+        public_key.verify(msg, &signature)?;
+        Ok(())
+    }
+
+    match signature {
+        Signature::Ed25519SuiSignature(signature) => verify(signature, msg),
+        Signature::Secp256k1SuiSignature(signature) => verify(signature, msg),
+        Signature::Secp256r1SuiSignature(signature) => verify(signature, msg),
+    }
+}
+
+impl SignerKeys {
+    /// List the keys held by the signer at `endpoint`.
+    fn fetch(endpoint: &SignerEndpoint) -> Result<Self, anyhow::Error> {
+        let SignerResponse::Keys(keys) = request(endpoint, &SignerRequest::Keys)? else {
+            bail!("Unexpected response from remote signer when listing keys");
+        };
+
+        let mut signer_keys = Self::default();
+        for SignerKey { public_key, alias } in keys {
+            let public_key = PublicKey::decode_base64(&public_key)
+                .map_err(|e| anyhow!("Invalid public key from remote signer: {e}"))?;
+            let address = SuiAddress::from(&public_key);
+            let alias = signer_keys.create_alias(alias)?;
+            signer_keys.aliases.insert(
+                address,
+                Alias {
+                    alias,
+                    public_key_base64: public_key.encode_base64(),
+                },
+            );
+            signer_keys.keys.insert(address, public_key);
+        }
+
+        Ok(signer_keys)
+    }
+
+    fn create_alias(&self, alias: Option<String>) -> Result<String, anyhow::Error> {
+        match alias {
+            Some(a) if self.aliases.values().any(|x| x.alias == a) => {
+                bail!("Alias {a} already exists. Please choose another alias.")
+            }
+            Some(a) => validate_alias(&a),
+            None => Ok(random_name(
+                &self
+                    .aliases
+                    .values()
+                    .map(|x| x.alias.clone())
+                    .collect::<HashSet<_>>(),
+            )),
+        }
+    }
+}
+
+/// Send `req` to the signer at `endpoint`, and wait for its response. Errors reported by the signer
+/// are returned as errors.
+pub fn request(
+    endpoint: &SignerEndpoint,
+    req: &SignerRequest,
+) -> Result<SignerResponse, anyhow::Error> {
+    let body = serde_json::to_vec(req)?;
+    let response = match endpoint {
+        SignerEndpoint::Unix(path) => request_unix(path, &body)
+            .with_context(|| format!("Cannot reach remote signer at {}", path.display()))?,
+        SignerEndpoint::Http(url) => request_http(url, &body)
+            .with_context(|| format!("Cannot reach remote signer at {url}"))?,
+    };
+
+    match serde_json::from_slice(&response)
+        .map_err(|e| anyhow!("Invalid response from remote signer: {e}"))?
+    {
+        SignerResponse::Error(e) => bail!("Remote signer error: {e}"),
+        response => Ok(response),
+    }
+}
+
+#[cfg(unix)]
+fn request_unix(path: &Path, body: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+    let mut stream = UnixStream::connect(path)?;
+    stream.set_read_timeout(Some(SIGNER_TIMEOUT))?;
+    stream.set_write_timeout(Some(SIGNER_TIMEOUT))?;
+
+    stream.write_all(body)?;
+    stream.write_all(b"\n")?;
+    stream.flush()?;
+
+    let mut response = Vec::new();
+    BufReader::new(stream.take(MAX_SIGNER_MESSAGE_SIZE as u64)).read_until(b'\n', &mut response)?;
+    Ok(response)
+}
+
+#[cfg(not(unix))]
+fn request_unix(_path: &Path, _body: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+    bail!("Unix sockets are not supported on this platform")
+}
+
+fn request_http(url: &str, body: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+    let agent = ureq::AgentBuilder::new().timeout(SIGNER_TIMEOUT).build();
+    let response = match agent
+        .post(url)
+        .set("Content-Type", "application/json")
+        .send_bytes(body)
+    {
+        Ok(response) => response,
+        // Signers report errors in the body, so read it from error responses as well.
+        Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_SIGNER_MESSAGE_SIZE as u64)
+        .read_to_end(&mut body)?;
+    Ok(body)
+}
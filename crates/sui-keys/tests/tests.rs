@@ -4,9 +4,15 @@
 use std::fs;
 use std::str::FromStr;
 
+use fastcrypto::encoding::{Base64, Encoding};
 use fastcrypto::hash::HashFunction;
 use fastcrypto::traits::EncodeDecodeBase64;
+use shared_crypto::intent::Intent;
+use sui_keys::encrypted_keystore::{EncryptedFileKeystore, KdfParams};
 use sui_keys::key_derive::generate_new_key;
+use sui_keys::remote_keystore::{
+    RemoteKeystore, SignerEndpoint, SignerKey, SignerRequest, SignerResponse,
+};
 use tempfile::TempDir;
 
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
@@ -279,3 +285,266 @@ fn get_alias_by_address_test() {
     let address = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
     assert!(keystore.get_alias_by_address(&address.0).is_err())
 }
+
+/// Cheap key derivation parameters, so that tests do not spend their time in Argon2.
+fn test_kdf_params() -> KdfParams {
+    KdfParams {
+        memory_kib: 8,
+        iterations: 1,
+        parallelism: 1,
+    }
+}
+
+#[test]
+fn encrypted_keystore_lock_unlock_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore.enc");
+    let mut keystore = Keystore::from(
+        EncryptedFileKeystore::create(&keystore_path, "passphrase", test_kdf_params()).unwrap(),
+    );
+
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(
+            SignatureScheme::ED25519,
+            Some("my_alias_test".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    let private_key = keystore.get_key(&address).unwrap().encode_base64();
+    assert!(!keystore.is_locked());
+    assert!(keystore
+        .sign_secure(&address, &"message", Intent::sui_transaction())
+        .is_ok());
+
+    // The private key is never written in plaintext
+    let contents = fs::read_to_string(&keystore_path).unwrap();
+    assert!(!contents.contains(&private_key));
+
+    // Only the owner can read or write the keystore file
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&keystore_path).unwrap().permissions().mode();
+        assert_eq!(0o600, mode & 0o777);
+    }
+
+    // Saving replaces the file through a temporary file, which is not left behind
+    let files: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+    assert_eq!(1, files.len());
+
+    // Locking the keystore prevents signing and adding keys, but not listing addresses
+    keystore.lock();
+    assert!(keystore.is_locked());
+    assert!(keystore
+        .sign_secure(&address, &"message", Intent::sui_transaction())
+        .is_err());
+    assert!(keystore.get_key(&address).is_err());
+    assert!(keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .is_err());
+    assert_eq!(vec![address], keystore.addresses());
+
+    // A keystore read back from disk starts locked, and only the right passphrase unlocks it
+    let mut keystore = Keystore::from(EncryptedFileKeystore::new(&keystore_path).unwrap());
+    assert!(keystore.is_locked());
+    assert_eq!(vec!["my_alias_test"], keystore.alias_names());
+    assert!(keystore.unlock("wrong passphrase").is_err());
+    assert!(keystore.is_locked());
+    keystore.unlock("passphrase").unwrap();
+    assert_eq!(
+        private_key,
+        keystore.get_key(&address).unwrap().encode_base64()
+    );
+}
+
+#[test]
+fn encrypted_keystore_wrong_passphrase_empty_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore.enc");
+    EncryptedFileKeystore::create(&keystore_path, "passphrase", test_kdf_params()).unwrap();
+
+    // Even without any keys, the passphrase is checked
+    let mut keystore = EncryptedFileKeystore::new(&keystore_path).unwrap();
+    assert!(keystore.unlock("wrong passphrase").is_err());
+    assert!(keystore.unlock("passphrase").is_ok());
+
+    // An existing keystore cannot be overwritten
+    assert!(
+        EncryptedFileKeystore::create(&keystore_path, "passphrase", test_kdf_params()).is_err()
+    );
+
+    // Non-encrypted keystores cannot be unlocked
+    let mut keystore = Keystore::InMem(InMemKeystore::new_insecure_for_tests(1));
+    assert!(!keystore.is_locked());
+    assert!(keystore.unlock("passphrase").is_err());
+}
+
+#[test]
+fn encrypted_keystore_migrate_from_file_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let encrypted_path = temp_dir.path().join("sui.keystore.enc");
+
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path).unwrap());
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(
+            SignatureScheme::ED25519,
+            Some("my_alias_test".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    keystore
+        .generate_and_add_new_key(SignatureScheme::Secp256k1, None, None, None)
+        .unwrap();
+
+    let mut encrypted = Keystore::from(
+        EncryptedFileKeystore::migrate_from_file(
+            &keystore_path,
+            &encrypted_path,
+            "passphrase",
+            test_kdf_params(),
+        )
+        .unwrap(),
+    );
+
+    encrypted.lock();
+    encrypted.unlock("passphrase").unwrap();
+    assert_eq!(keystore.addresses(), encrypted.addresses());
+    assert_eq!(
+        keystore.get_alias_by_address(&address).unwrap(),
+        encrypted.get_alias_by_address(&address).unwrap()
+    );
+    for address in keystore.addresses() {
+        assert_eq!(
+            keystore.get_key(&address).unwrap().encode_base64(),
+            encrypted.get_key(&address).unwrap().encode_base64(),
+        );
+    }
+}
+
+#[test]
+fn remote_keystore_unreachable_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("signer.sock");
+
+    // The keystore can be loaded while the signer is unreachable
+    let keystore = Keystore::from(RemoteKeystore::new(SignerEndpoint::Unix(socket_path)));
+    let keystore: Keystore =
+        serde_json::from_str(&serde_json::to_string(&keystore).unwrap()).unwrap();
+
+    // But it holds no keys, and cannot sign
+    assert!(keystore.addresses().is_empty());
+    let (address, _, _, _) = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
+    assert!(keystore
+        .sign_secure(&address, &"message", Intent::sui_transaction())
+        .is_err());
+    assert!(keystore.get_alias_by_address(&address).is_err());
+}
+
+/// Serve remote signer requests on a Unix socket, answering each with `handle`.
+#[cfg(unix)]
+fn spawn_fake_signer(
+    listener: std::os::unix::net::UnixListener,
+    handle: impl Fn(SignerRequest) -> SignerResponse + Send + 'static,
+) {
+    use std::io::{BufRead, BufReader, Write};
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut line = String::new();
+            BufReader::new(&stream).read_line(&mut line).unwrap();
+
+            let response = handle(serde_json::from_str(&line).unwrap());
+            let mut response = serde_json::to_vec(&response).unwrap();
+            response.push(b'\n');
+            stream.write_all(&response).unwrap();
+        }
+    });
+}
+
+#[cfg(unix)]
+fn signer_keys(keystore: &Keystore, count: usize) -> Vec<SignerKey> {
+    keystore
+        .addresses_with_alias()
+        .into_iter()
+        .take(count)
+        .map(|(_, alias)| SignerKey {
+            public_key: alias.public_key_base64.clone(),
+            alias: Some(alias.alias.clone()),
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+#[test]
+fn remote_keystore_refresh_test() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("signer.sock");
+    let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+
+    // The signer starts out listing only one of its keys.
+    let signer_keystore = Keystore::InMem(InMemKeystore::new_insecure_for_tests(2));
+    let addresses = signer_keystore.addresses();
+    let listed = Arc::new(AtomicUsize::new(1));
+    spawn_fake_signer(listener, {
+        let listed = listed.clone();
+        move |request| match request {
+            SignerRequest::Keys => {
+                SignerResponse::Keys(signer_keys(&signer_keystore, listed.load(Ordering::SeqCst)))
+            }
+            SignerRequest::Sign {
+                address, message, ..
+            } => {
+                let message = Base64::decode(&message).unwrap();
+                let signature = signer_keystore.sign_hashed(&address, &message).unwrap();
+                SignerResponse::Signature(Base64::encode(signature.as_ref()))
+            }
+        }
+    });
+
+    let mut keystore = Keystore::from(RemoteKeystore::new(SignerEndpoint::Unix(socket_path)));
+    assert_eq!(keystore.addresses(), addresses[..1]);
+
+    // Keys the signer gains are not listed until the keystore is refreshed...
+    listed.store(2, Ordering::SeqCst);
+    assert_eq!(keystore.addresses(), addresses[..1]);
+
+    // ...but they can already be used to sign.
+    assert!(keystore
+        .sign_secure(&addresses[1], &"message", Intent::sui_transaction())
+        .is_ok());
+
+    keystore.refresh().unwrap();
+    assert_eq!(keystore.addresses(), addresses);
+}
+
+#[cfg(unix)]
+#[test]
+fn remote_keystore_wrong_message_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("signer.sock");
+    let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+
+    // A signer that signs a different message from the one it was asked to, with the right key.
+    let signer_keystore = Keystore::InMem(InMemKeystore::new_insecure_for_tests(1));
+    let address = signer_keystore.addresses()[0];
+    spawn_fake_signer(listener, move |request| match request {
+        SignerRequest::Keys => SignerResponse::Keys(signer_keys(&signer_keystore, 1)),
+        SignerRequest::Sign { address, .. } => {
+            let signature = signer_keystore.sign_hashed(&address, b"other").unwrap();
+            SignerResponse::Signature(Base64::encode(signature.as_ref()))
+        }
+    });
+
+    let keystore = Keystore::from(RemoteKeystore::new(SignerEndpoint::Unix(socket_path)));
+    assert!(keystore.sign_hashed(&address, b"message").is_err());
+    assert!(keystore
+        .sign_secure(&address, &"message", Intent::sui_transaction())
+        .is_err());
+}
@@ -1,11 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::proof::{Proof, ProofTarget, TransactionProof};
+use crate::proof::{InclusionProof, Proof, ProofTarget, TransactionProof};
+
+use std::{collections::HashMap, ops::Range};
 
 use anyhow::anyhow;
 use sui_rpc_api::{CheckpointData, CheckpointTransaction};
-use sui_types::effects::TransactionEffectsAPI;
+use sui_types::{digests::TransactionDigest, effects::TransactionEffectsAPI};
 
 /// Construct a proof from the given checkpoint data and proof targets.
 ///
@@ -89,3 +91,66 @@ pub fn construct_proof(targets: ProofTarget, data: &CheckpointData) -> anyhow::R
 
     Ok(this_proof)
 }
+
+/// Construct a proof that the transactions with the given digests are included in the given
+/// checkpoint. A single proof can cover any number of transactions from the same checkpoint.
+///
+/// Returns `Err` if any of the transactions is not in the checkpoint.
+pub fn construct_inclusion_proof(
+    digests: &[TransactionDigest],
+    data: &CheckpointData,
+) -> anyhow::Result<InclusionProof> {
+    let summary = &data.checkpoint_summary;
+    let contents = &data.checkpoint_contents;
+
+    let included: HashMap<_, _> = contents
+        .enumerate_transactions(summary)
+        .map(|(seq, d)| (d.transaction, (seq, *d)))
+        .collect();
+
+    let transactions = digests
+        .iter()
+        .map(|digest| {
+            included
+                .get(digest)
+                .copied()
+                .ok_or(anyhow!("Transaction {digest} not found in checkpoint data"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(InclusionProof {
+        checkpoint_summary: summary.clone(),
+        checkpoint_contents: contents.clone(),
+        transactions,
+    })
+}
+
+/// Construct a proof that a range of transactions, identified by their sequence numbers
+/// across all transactions in the network, is included in the given checkpoint.
+///
+/// Returns `Err` if the range is empty or is not entirely contained in the checkpoint.
+pub fn construct_range_inclusion_proof(
+    range: Range<u64>,
+    data: &CheckpointData,
+) -> anyhow::Result<InclusionProof> {
+    let summary = &data.checkpoint_summary;
+    let contents = &data.checkpoint_contents;
+
+    let transactions: Vec<_> = contents
+        .enumerate_transactions(summary)
+        .filter(|(seq, _)| range.contains(seq))
+        .map(|(seq, d)| (seq, *d))
+        .collect();
+
+    if range.is_empty() || transactions.len() as u64 != range.end - range.start {
+        return Err(anyhow!(
+            "Transaction range {range:?} not contained in checkpoint data"
+        ));
+    }
+
+    Ok(InclusionProof {
+        checkpoint_summary: summary.clone(),
+        checkpoint_contents: contents.clone(),
+        transactions,
+    })
+}
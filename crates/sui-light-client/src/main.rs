@@ -20,21 +20,27 @@ use sui_types::{
 
 use sui_config::genesis::Genesis;
 
+use sui_package_resolver::error::Error as ResolverError;
 use sui_package_resolver::Result as ResolverResult;
 use sui_package_resolver::{Package, PackageStore, Resolver};
 use sui_sdk::SuiClientBuilder;
 
 use clap::{Parser, Subcommand};
-use std::{collections::HashMap, fs, io::Write, path::PathBuf, str::FromStr, sync::Mutex};
+use std::{
+    collections::HashMap, fs, io::Write, net::SocketAddr, path::PathBuf, str::FromStr,
+    time::Duration,
+};
 use std::{io::Read, sync::Arc};
 
 use log::info;
 use object_store::parse_url;
-use object_store::path::Path;
+use parking_lot::Mutex;
 use serde_json::json;
 use serde_json::Value;
 use url::Url;
 
+mod serve;
+
 /// A light client for the Sui blockchain
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -67,18 +73,23 @@ impl PackageStore for RemotePackageStore {
     /// some way.
     async fn fetch(&self, id: AccountAddress) -> ResolverResult<Arc<Package>> {
         // Check if we have it in the cache
-        if let Some(package) = self.cache.lock().unwrap().get(&id) {
+        if let Some(package) = self.cache.lock().get(&id) {
             info!("Fetch Package: {} cache hit", id);
             return Ok(package.clone());
         }
 
         info!("Fetch Package: {}", id);
 
-        let object = get_verified_object(&self.config, id.into()).await.unwrap();
-        let package = Arc::new(Package::read_from_object(&object).unwrap());
+        let object = get_verified_object(&self.config, id.into())
+            .await
+            .map_err(|e| ResolverError::Store {
+                store: "RemotePackageStore",
+                error: e.to_string(),
+            })?;
+        let package = Arc::new(Package::read_from_object(&object)?);
 
         // Add to the cache
-        self.cache.lock().unwrap().insert(id, package.clone());
+        self.cache.lock().insert(id, package.clone());
 
         Ok(package)
     }
@@ -102,6 +113,18 @@ enum SCommands {
         #[arg(short, long, value_name = "OID")]
         oid: String,
     },
+
+    /// Run the light client as a long-running service that follows epochs and serves verified
+    /// reads over a local HTTP API
+    Serve {
+        /// Address to serve the HTTP API on
+        #[arg(short, long, value_name = "ADDR", default_value = "127.0.0.1:9300")]
+        listen_address: SocketAddr,
+
+        /// How often to sync new end-of-epoch checkpoints, in seconds
+        #[arg(long, value_name = "SECS", default_value_t = 300)]
+        sync_interval_secs: u64,
+    },
 }
 
 // The config file for the light client including the root of trust genesis digest
@@ -238,8 +261,8 @@ async fn download_checkpoint_summary(
     // Download the checkpoint from the server
 
     let url = Url::parse(&config.object_store_url)?;
-    let (dyn_store, _store_path) = parse_url(&url).unwrap();
-    let path = Path::from(format!("{}.chk", checkpoint_number));
+    let (dyn_store, store_path) = parse_url(&url).unwrap();
+    let path = store_path.child(format!("{}.chk", checkpoint_number));
     let response = dyn_store.get(&path).await?;
     let bytes = response.bytes().await?;
     let (_, blob) = bcs::from_bytes::<(u8, CheckpointData)>(&bytes)?;
@@ -298,7 +321,9 @@ async fn sync_checkpoint_list_to_latest(config: &Config) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn check_and_sync_checkpoints(config: &Config) -> anyhow::Result<()> {
+/// Sync and verify all end-of-epoch checkpoints, returning the chain of verified committees, from
+/// the genesis committee to the committee of the latest epoch.
+async fn check_and_sync_checkpoints(config: &Config) -> anyhow::Result<Vec<Committee>> {
     sync_checkpoint_list_to_latest(config)
         .await
         .map_err(|e| anyhow!(format!("Cannot refresh list: {e}")))?;
@@ -317,6 +342,7 @@ async fn check_and_sync_checkpoints(config: &Config) -> anyhow::Result<()> {
     // Check the signatures of all checkpoints
     // And download any missing ones
 
+    let mut committees = vec![genesis_committee.clone()];
     let mut prev_committee = genesis_committee;
     for ckp_id in &checkpoints_list.checkpoints {
         // check if there is a file with this name ckp_id.yaml in the checkpoint_summary_dir
@@ -354,6 +380,7 @@ async fn check_and_sync_checkpoints(config: &Config) -> anyhow::Result<()> {
             let next_committee = next_epoch_committee.iter().cloned().collect();
             prev_committee =
                 Committee::new(summary.epoch().checked_add(1).unwrap(), next_committee);
+            committees.push(prev_committee.clone());
         } else {
             return Err(anyhow!(
                 "Expected all checkpoints to be end-of-epoch checkpoints"
@@ -361,7 +388,7 @@ async fn check_and_sync_checkpoints(config: &Config) -> anyhow::Result<()> {
         }
     }
 
-    Ok(())
+    Ok(committees)
 }
 
 async fn get_full_checkpoint(
@@ -370,8 +397,8 @@ async fn get_full_checkpoint(
 ) -> anyhow::Result<CheckpointData> {
     let url = Url::parse(&config.object_store_url)
         .map_err(|_| anyhow!("Cannot parse object store URL"))?;
-    let (dyn_store, _store_path) = parse_url(&url).unwrap();
-    let path = Path::from(format!("{}.chk", checkpoint_number));
+    let (dyn_store, store_path) = parse_url(&url).unwrap();
+    let path = store_path.child(format!("{}.chk", checkpoint_number));
     info!("Request full checkpoint: {}", path);
     let response = dyn_store
         .get(&path)
@@ -423,7 +450,7 @@ async fn get_verified_effects_and_events(
     let sui_mainnet: sui_sdk::SuiClient = SuiClientBuilder::default()
         .build(config.full_node_url.as_str())
         .await
-        .unwrap();
+        .map_err(|e| anyhow!(format!("Cannot connect to full node: {e}")))?;
     let read_api = sui_mainnet.read_api();
 
     info!("Getting effects and events for TID: {}", tid);
@@ -494,7 +521,7 @@ async fn get_verified_object(config: &Config, id: ObjectID) -> anyhow::Result<Ob
         SuiClientBuilder::default()
             .build(config.full_node_url.as_str())
             .await
-            .unwrap(),
+            .map_err(|e| anyhow!(format!("Cannot connect to full node: {e}")))?,
     );
 
     info!("Getting object: {}", id);
@@ -503,16 +530,18 @@ async fn get_verified_object(config: &Config, id: ObjectID) -> anyhow::Result<Ob
     let object_json = read_api
         .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
         .await
-        .expect("Cannot get object");
+        .map_err(|e| anyhow!(format!("Cannot get object: {e}")))?;
     let object = object_json
         .into_object()
-        .expect("Cannot make into object data");
-    let object: Object = object.try_into().expect("Cannot reconstruct object");
+        .map_err(|e| anyhow!(format!("Cannot make into object data: {e}")))?;
+    let object: Object = object
+        .try_into()
+        .map_err(|e| anyhow!(format!("Cannot reconstruct object: {e}")))?;
 
     // Need to authenticate this object
     let (effects, _) = get_verified_effects_and_events(config, object.previous_transaction)
         .await
-        .expect("Cannot get effects and events");
+        .map_err(|e| anyhow!(format!("Cannot get effects and events: {e}")))?;
 
     // check that this object ID, version and hash is in the effects
     let target_object_ref = object.compute_object_reference();
@@ -520,8 +549,7 @@ async fn get_verified_object(config: &Config, id: ObjectID) -> anyhow::Result<Ob
         .all_changed_objects()
         .iter()
         .find(|object_ref| object_ref.0 == target_object_ref)
-        .ok_or(anyhow!("Object not found"))
-        .expect("Object not found");
+        .ok_or(anyhow!("Object not found"))?;
 
     Ok(object)
 }
@@ -621,6 +649,19 @@ pub async fn main() {
                 .await
                 .expect("Failed to sync checkpoints");
         }
+
+        Some(SCommands::Serve {
+            listen_address,
+            sync_interval_secs,
+        }) => {
+            serve::serve(
+                config,
+                listen_address,
+                Duration::from_secs(sync_interval_secs),
+            )
+            .await
+            .expect("Light client service failed");
+        }
         _ => {}
     }
 }
@@ -769,4 +810,20 @@ mod tests {
         )
         .is_err());
     }
+
+    #[tokio::test]
+    async fn test_fetch_package_unreachable_full_node() {
+        let config = Config {
+            // Nothing listens on the discard port, so the connection is refused.
+            full_node_url: "http://127.0.0.1:9".to_string(),
+            checkpoint_summary_dir: PathBuf::new(),
+            genesis_filename: PathBuf::new(),
+            object_store_url: "http://127.0.0.1:9".to_string(),
+            graphql_url: "http://127.0.0.1:9".to_string(),
+        };
+
+        let store = RemotePackageStore::new(config);
+        let err = store.fetch(AccountAddress::ONE).await.unwrap_err();
+        assert!(matches!(err, ResolverError::Store { .. }), "{err}");
+    }
 }
@@ -5,7 +5,7 @@ use anyhow::anyhow;
 
 use serde::{Deserialize, Serialize};
 use sui_types::{
-    base_types::ObjectRef,
+    base_types::{ExecutionDigests, ObjectRef},
     committee::Committee,
     effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
     event::{Event, EventID},
@@ -88,6 +88,43 @@ pub struct Proof {
     pub contents_proof: Option<TransactionProof>,
 }
 
+/// A proof that one or more transactions were included in a certified checkpoint.
+///
+/// Unlike a [Proof] with a [TransactionProof], it does not carry the transactions, effects or
+/// events themselves, only the transaction and effects digest pairs being proven. It does carry the
+/// full checkpoint contents: the checkpoint summary only commits to the digest of the contents as a
+/// whole, so every execution digest and user signature in the checkpoint is needed to check any one
+/// transaction against it.
+///
+/// The size of the proof therefore grows with the size of the checkpoint, not with the number of
+/// transactions being proven. Proving a batch of transactions from one checkpoint costs little more
+/// than proving one, but even a single transaction from a large checkpoint needs a large proof.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// A summary of the checkpoint the transactions are included in.
+    pub checkpoint_summary: CertifiedCheckpointSummary,
+
+    /// Contents of the checkpoint, used to check the transactions against the summary.
+    pub checkpoint_contents: CheckpointContents,
+
+    /// The transactions being certified, by their sequence number across all transactions in the
+    /// network, and their execution digests.
+    pub transactions: Vec<(u64, ExecutionDigests)>,
+}
+
+impl InclusionProof {
+    /// Encode the proof as BCS bytes.
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bcs::to_bytes(self).map_err(|e| anyhow!("Unable to serialize inclusion proof: {e}"))
+    }
+
+    /// Decode a proof from BCS bytes. The decoded proof still needs to be verified before it can
+    /// be trusted.
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bcs::from_bytes(bytes).map_err(|e| anyhow!("Unable to parse inclusion proof: {e}"))
+    }
+}
+
 /// Verify a proof against a committee. A proof is valid if it certifies the checkpoint summary
 /// and optionally includes transaction evidence to certify objects and events.
 ///
@@ -231,3 +268,44 @@ pub fn verify_proof(committee: &Committee, proof: &Proof) -> anyhow::Result<()>
 
     Ok(())
 }
+
+/// Verify an inclusion proof against a committee. The proof is valid if it certifies the checkpoint
+/// summary and contents, and each of its transactions appears in the contents with the given
+/// transaction and effects digests, at the given sequence number.
+///
+/// If the result is `Ok(())` then the transactions in the proof can be trusted to have been
+/// executed in the checkpoint, with the given effects.
+pub fn verify_inclusion_proof(committee: &Committee, proof: &InclusionProof) -> anyhow::Result<()> {
+    let summary = &proof.checkpoint_summary;
+    let contents = &proof.checkpoint_contents;
+
+    // Verify the checkpoint summary and contents using the committee
+    summary.verify_with_contents(committee, Some(contents))?;
+
+    if proof.transactions.is_empty() {
+        return Err(anyhow!("Inclusion proof has no transactions"));
+    }
+
+    // Sequence number of the first transaction in the checkpoint
+    let start = summary
+        .network_total_transactions
+        .checked_sub(contents.size() as u64)
+        .ok_or(anyhow!("Checkpoint contents larger than the network total"))?;
+
+    let included = contents.iter().as_slice();
+    for (seq, digests) in &proof.transactions {
+        let position = seq
+            .checked_sub(start)
+            .and_then(|p| usize::try_from(p).ok())
+            .filter(|p| *p < included.len())
+            .ok_or(anyhow!("Transaction sequence number not in the checkpoint"))?;
+
+        if &included[position] != digests {
+            return Err(anyhow!(
+                "Transaction digests not found in the checkpoint contents"
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,547 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Long-running light client mode. The light client keeps the chain of verified committees up to
+//! date as epochs change, and serves object and event reads over a local HTTP/JSON API. Every
+//! response is verified against the committee of the epoch it was produced in, so callers do not
+//! need to trust the full node the data was fetched from.
+
+use std::{
+    collections::BTreeMap, fmt, fs, net::SocketAddr, str::FromStr, sync::Arc, time::Duration,
+};
+
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use fastcrypto::encoding::{Base64, Encoding};
+use log::{info, warn};
+use move_core_types::language_storage::TypeTag;
+use parking_lot::RwLock;
+use serde_json::{json, Value};
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockResponseOptions};
+use sui_light_client::{construct_proof, verify_proof, Proof, ProofTarget};
+use sui_package_resolver::Resolver;
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use sui_types::{
+    base_types::ObjectID,
+    committee::{Committee, EpochId},
+    digests::TransactionDigest,
+    effects::TransactionEffectsAPI,
+    messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber},
+    object::{bounded_visitor::BoundedVisitor, Data, Object},
+};
+
+use crate::{
+    check_and_sync_checkpoints, extract_verified_effects_and_events, get_full_checkpoint,
+    read_checkpoint_general, write_checkpoint_general, Config, RemotePackageStore,
+};
+
+/// Sub-directory of the checkpoint summary directory that checkpoint summaries verified while
+/// serving reads are persisted to.
+const TRUSTED_SUMMARIES_DIR: &str = "trusted";
+
+/// Maximum number of trusted checkpoint summaries kept in memory. Older summaries remain
+/// available on disk.
+const MAX_CACHED_SUMMARIES: usize = 1024;
+
+struct LightClientState {
+    config: Config,
+    client: SuiClient,
+    resolver: Resolver<RemotePackageStore>,
+
+    /// Verified committees, keyed by the epoch they are authoritative for.
+    committees: RwLock<BTreeMap<EpochId, Committee>>,
+
+    summaries: TrustedSummaries,
+}
+
+/// Checkpoint summaries that have been verified against a trusted committee, keyed by sequence
+/// number. The latest summaries are kept in memory, and all of them are persisted to the
+/// [TRUSTED_SUMMARIES_DIR], from which they are reloaded when the light client restarts.
+struct TrustedSummaries {
+    config: Config,
+    summaries: RwLock<BTreeMap<CheckpointSequenceNumber, CertifiedCheckpointSummary>>,
+}
+
+/// An error returned by the HTTP API, rendered as a JSON body with an `error` message.
+struct ApiError(StatusCode, anyhow::Error);
+
+/// A read refers to an epoch that the light client has not synced the committee for yet. This is
+/// served as [StatusCode::SERVICE_UNAVAILABLE], as the read can succeed after the next sync.
+#[derive(Debug)]
+struct EpochNotSynced(EpochId);
+
+impl LightClientState {
+    async fn new(config: Config) -> anyhow::Result<Self> {
+        let client = SuiClientBuilder::default()
+            .build(config.full_node_url.as_str())
+            .await
+            .map_err(|e| anyhow!(format!("Cannot connect to full node: {e}")))?;
+
+        Ok(Self {
+            resolver: Resolver::new(RemotePackageStore::new(config.clone())),
+            summaries: TrustedSummaries::load(config.clone())?,
+            config,
+            client,
+            committees: RwLock::new(BTreeMap::new()),
+        })
+    }
+
+    /// Sync end-of-epoch checkpoints up to the latest epoch, and replace the verified committee
+    /// chain with the result.
+    async fn sync(&self) -> anyhow::Result<()> {
+        let committees = check_and_sync_checkpoints(&self.config).await?;
+        let committees = committees.into_iter().map(|c| (c.epoch, c)).collect();
+        *self.committees.write() = committees;
+        Ok(())
+    }
+
+    /// The verified committee for `epoch`, if the light client has synced that far.
+    fn committee(&self, epoch: EpochId) -> anyhow::Result<Committee> {
+        self.committees
+            .read()
+            .get(&epoch)
+            .cloned()
+            .ok_or_else(|| EpochNotSynced(epoch).into())
+    }
+
+    /// The sequence number of the checkpoint that `digest` was included in, according to the
+    /// full node. This is not trusted: it only tells us which checkpoint to verify.
+    async fn checkpoint_of(&self, digest: TransactionDigest) -> anyhow::Result<u64> {
+        self.client
+            .read_api()
+            .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+            .await
+            .map_err(|e| anyhow!(format!("Cannot get transaction: {e}")))?
+            .checkpoint
+            .ok_or(anyhow!("Transaction not found in a checkpoint"))
+    }
+
+    /// Fetch the version of object `id` that the full node reports, along with a proof that it was
+    /// written by a transaction in a certified checkpoint. The proof is verified before it is
+    /// returned. It does not show that the object has not been modified since that checkpoint.
+    async fn verified_object(&self, id: ObjectID) -> anyhow::Result<(Object, Proof)> {
+        let object: Object = self
+            .client
+            .read_api()
+            .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
+            .await
+            .map_err(|e| anyhow!(format!("Cannot get object: {e}")))?
+            .into_object()
+            .map_err(|e| anyhow!(format!("Cannot get object: {e}")))?
+            .try_into()?;
+
+        let seq = self.checkpoint_of(object.previous_transaction).await?;
+        let checkpoint = get_full_checkpoint(&self.config, seq).await?;
+        let committee = self.committee(checkpoint.checkpoint_summary.epoch())?;
+
+        let targets =
+            ProofTarget::new().add_object(object.compute_object_reference(), object.clone());
+        let proof = construct_proof(targets, &checkpoint)?;
+        verify_proof(&committee, &proof)?;
+
+        self.summaries.trust(&proof.checkpoint_summary)?;
+        Ok((object, proof))
+    }
+
+    /// Decode the contents of a Move value of type `type_` into JSON, using packages that are
+    /// themselves verified by the light client.
+    async fn decode(&self, type_: TypeTag, contents: &[u8]) -> anyhow::Result<Value> {
+        let layout = self.resolver.type_layout(type_).await?;
+        let value = BoundedVisitor::deserialize_value(contents, &layout)?;
+        Ok(serde_json::to_value(&value)?)
+    }
+}
+
+impl TrustedSummaries {
+    /// Load the latest summaries persisted by a previous run of the light client.
+    fn load(config: Config) -> anyhow::Result<Self> {
+        let mut trusted_path = config.checkpoint_summary_dir.clone();
+        trusted_path.push(TRUSTED_SUMMARIES_DIR);
+        fs::create_dir_all(&trusted_path)?;
+
+        let mut persisted = vec![];
+        for entry in fs::read_dir(&trusted_path)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "yaml") {
+                if let Some(seq) = path.file_stem().and_then(|s| s.to_str()?.parse().ok()) {
+                    persisted.push(seq);
+                }
+            }
+        }
+
+        persisted.sort_unstable();
+        let skip = persisted.len().saturating_sub(MAX_CACHED_SUMMARIES);
+
+        let mut summaries = BTreeMap::new();
+        for seq in persisted.into_iter().skip(skip) {
+            match read_checkpoint_general(&config, seq, Some(TRUSTED_SUMMARIES_DIR)) {
+                Ok(summary) => {
+                    summaries.insert(seq, summary);
+                }
+                Err(e) => warn!("Cannot reload trusted checkpoint {seq}: {e}"),
+            }
+        }
+
+        info!("Reloaded {} trusted checkpoint summaries", summaries.len());
+        Ok(Self {
+            config,
+            summaries: RwLock::new(summaries),
+        })
+    }
+
+    /// Record a checkpoint summary that has been verified, both in memory and on disk.
+    fn trust(&self, summary: &CertifiedCheckpointSummary) -> anyhow::Result<()> {
+        if self.summaries.read().contains_key(&summary.sequence_number) {
+            return Ok(());
+        }
+
+        write_checkpoint_general(&self.config, summary, Some(TRUSTED_SUMMARIES_DIR))?;
+
+        let mut summaries = self.summaries.write();
+        summaries.insert(summary.sequence_number, summary.clone());
+        while summaries.len() > MAX_CACHED_SUMMARIES {
+            summaries.pop_first();
+        }
+
+        Ok(())
+    }
+
+    /// The sequence number of the latest verified checkpoint.
+    fn latest(&self) -> Option<CheckpointSequenceNumber> {
+        self.summaries.read().keys().last().copied()
+    }
+}
+
+impl ApiError {
+    fn bad_request(e: impl Into<anyhow::Error>) -> Self {
+        Self(StatusCode::BAD_REQUEST, e.into())
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        if e.is::<EpochNotSynced>() {
+            Self(StatusCode::SERVICE_UNAVAILABLE, e)
+        } else {
+            Self(StatusCode::INTERNAL_SERVER_ERROR, e)
+        }
+    }
+}
+
+impl fmt::Display for EpochNotSynced {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "No verified committee for epoch {}. Need to Sync.",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for EpochNotSynced {}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let Self(status, e) = self;
+        (status, Json(json!({ "error": e.to_string() }))).into_response()
+    }
+}
+
+/// Start the light client service: sync the committee chain, keep it up to date every
+/// `sync_interval`, and serve the HTTP API on `listen_address` until the process is stopped.
+pub(crate) async fn serve(
+    config: Config,
+    listen_address: SocketAddr,
+    sync_interval: Duration,
+) -> anyhow::Result<()> {
+    let state = Arc::new(LightClientState::new(config).await?);
+    state
+        .sync()
+        .await
+        .map_err(|e| anyhow!(format!("Initial sync failed: {e}")))?;
+
+    tokio::spawn(follow_epochs(state.clone(), sync_interval));
+
+    let app = router(state);
+    let listener = tokio::net::TcpListener::bind(listen_address).await?;
+    info!("Light client serving on {}", listen_address);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+fn router(state: Arc<LightClientState>) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/objects/:object_id", get(object))
+        .route("/transactions/:digest/events", get(events))
+        .with_state(state)
+}
+
+/// Periodically sync new end-of-epoch checkpoints, so that reads from new epochs can be verified.
+async fn follow_epochs(state: Arc<LightClientState>, sync_interval: Duration) {
+    loop {
+        tokio::time::sleep(sync_interval).await;
+        match state.sync().await {
+            Ok(()) => info!("Synced committees"),
+            Err(e) => warn!("Failed to sync committees: {e}"),
+        }
+    }
+}
+
+/// `GET /status`: The latest epoch the light client has a verified committee for, and the latest
+/// checkpoint it has verified while serving reads.
+async fn status(State(state): State<Arc<LightClientState>>) -> Json<Value> {
+    let epoch = state.committees.read().keys().last().copied();
+    let checkpoint = state.summaries.latest();
+    Json(json!({
+        "epoch": epoch,
+        "latest_verified_checkpoint": checkpoint,
+    }))
+}
+
+/// `GET /objects/:object_id`: An object as of the checkpoint it was verified against, decoded as
+/// JSON, along with the BCS-encoded, Base64 proof of that checkpoint.
+async fn object(
+    State(state): State<Arc<LightClientState>>,
+    Path(object_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let id = ObjectID::from_str(&object_id).map_err(ApiError::bad_request)?;
+    let (object, proof) = state.verified_object(id).await?;
+
+    let (type_, fields) = if let Data::Move(move_object) = &object.data {
+        let type_ = move_object.type_().clone();
+        let fields = state
+            .decode(type_.clone().into(), move_object.contents())
+            .await?;
+        (Some(type_.to_string()), fields)
+    } else {
+        (None, Value::Null)
+    };
+
+    let (oid, version, digest) = object.compute_object_reference();
+    Ok(Json(json!({
+        "object_id": oid.to_string(),
+        "version": version.value(),
+        "digest": digest.to_string(),
+        "owner": object.owner.to_string(),
+        "type": type_,
+        "fields": fields,
+        "epoch": proof.checkpoint_summary.epoch(),
+        "checkpoint": proof.checkpoint_summary.sequence_number,
+        "proof": Base64::encode(bcs::to_bytes(&proof).map_err(anyhow::Error::from)?),
+    })))
+}
+
+/// `GET /transactions/:digest/events`: The events emitted by a transaction, decoded as JSON, once
+/// the transaction's inclusion in a certified checkpoint and its events have been verified.
+async fn events(
+    State(state): State<Arc<LightClientState>>,
+    Path(digest): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let digest = TransactionDigest::from_str(&digest).map_err(ApiError::bad_request)?;
+
+    let seq = state.checkpoint_of(digest).await?;
+    let checkpoint = get_full_checkpoint(&state.config, seq).await?;
+    let committee = state.committee(checkpoint.checkpoint_summary.epoch())?;
+    let (effects, events) = extract_verified_effects_and_events(&checkpoint, &committee, digest)?;
+    state.summaries.trust(&checkpoint.checkpoint_summary)?;
+
+    let mut decoded = vec![];
+    for event in events.map(|e| e.data).unwrap_or_default() {
+        let contents = state
+            .decode(event.type_.clone().into(), &event.contents)
+            .await?;
+        decoded.push(json!({
+            "package_id": event.package_id.to_string(),
+            "module": event.transaction_module.to_string(),
+            "sender": event.sender.to_string(),
+            "type": event.type_.to_string(),
+            "contents": contents,
+        }));
+    }
+
+    let digests = effects.execution_digests();
+    Ok(Json(json!({
+        "transaction_digest": digests.transaction.to_string(),
+        "effects_digest": digests.effects.to_string(),
+        "epoch": checkpoint.checkpoint_summary.epoch(),
+        "checkpoint": seq,
+        "events": decoded,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use axum::routing::post;
+
+    use super::*;
+
+    fn config(checkpoint_summary_dir: PathBuf) -> Config {
+        Config {
+            full_node_url: "http://127.0.0.1:9000".to_string(),
+            checkpoint_summary_dir,
+            genesis_filename: "genesis.blob".into(),
+            object_store_url: "http://127.0.0.1:9001".to_string(),
+            graphql_url: "http://127.0.0.1:9002".to_string(),
+        }
+    }
+
+    fn example_summary() -> CertifiedCheckpointSummary {
+        let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        d.push("example_config/20873329.yaml");
+        bcs::from_bytes(&fs::read(d).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_trusted_summaries_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let summary = example_summary();
+
+        let trusted = TrustedSummaries::load(config(dir.path().to_owned())).unwrap();
+        assert_eq!(trusted.latest(), None);
+        trusted.trust(&summary).unwrap();
+        assert_eq!(trusted.latest(), Some(summary.sequence_number));
+
+        // A restarted light client remembers the summaries it verified before.
+        let trusted = TrustedSummaries::load(config(dir.path().to_owned())).unwrap();
+        assert_eq!(trusted.latest(), Some(summary.sequence_number));
+        assert_eq!(
+            trusted.summaries.read()[&summary.sequence_number].digest(),
+            summary.digest(),
+        );
+    }
+
+    #[test]
+    fn test_unreadable_trusted_summaries_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let trusted_dir = dir.path().join(TRUSTED_SUMMARIES_DIR);
+        fs::create_dir_all(&trusted_dir).unwrap();
+        fs::write(trusted_dir.join("42.yaml"), b"not a summary").unwrap();
+        fs::write(trusted_dir.join("notes.txt"), b"not a summary either").unwrap();
+
+        let trusted = TrustedSummaries::load(config(dir.path().to_owned())).unwrap();
+        assert_eq!(trusted.latest(), None);
+    }
+
+    /// Serve a minimal full node JSON-RPC API, which reports every transaction as included in
+    /// `checkpoint`, and return its URL.
+    async fn mock_full_node(checkpoint: CheckpointSequenceNumber) -> String {
+        let app = Router::new().route(
+            "/",
+            post(move |Json(request): Json<Value>| async move {
+                let id = request["id"].clone();
+                let result = match request["method"].as_str() {
+                    Some("rpc.discover") => {
+                        json!({ "info": { "version": "1.0.0" }, "methods": [] })
+                    }
+                    Some("sui_getTransactionBlock") => json!({
+                        "digest": request["params"][0],
+                        "checkpoint": checkpoint.to_string(),
+                    }),
+                    _ => {
+                        return Json(json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": { "code": -32601, "message": "Method not found" },
+                        }))
+                    }
+                };
+                Json(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    /// Serve the light client's HTTP API for `state`, and return its URL.
+    async fn serve_api(state: Arc<LightClientState>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+        url
+    }
+
+    /// A light client that reads from a mock full node, and the example checkpoints.
+    async fn light_client_state(
+        checkpoint_summary_dir: PathBuf,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Arc<LightClientState> {
+        let example_config = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("example_config");
+        let config = Config {
+            full_node_url: mock_full_node(checkpoint).await,
+            object_store_url: format!("file://{}", example_config.display()),
+            ..config(checkpoint_summary_dir)
+        };
+        Arc::new(LightClientState::new(config).await.unwrap())
+    }
+
+    async fn get_json(url: String) -> (StatusCode, Value) {
+        let response = reqwest::get(url).await.unwrap();
+        let status = response.status();
+        (status, response.json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_status_route() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = light_client_state(dir.path().to_owned(), 20958462).await;
+        let url = serve_api(state.clone()).await;
+
+        let (status, body) = get_json(format!("{url}/status")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            json!({ "epoch": null, "latest_verified_checkpoint": null })
+        );
+
+        let summary = example_summary();
+        state.summaries.trust(&summary).unwrap();
+        let (_, body) = get_json(format!("{url}/status")).await;
+        assert_eq!(
+            body["latest_verified_checkpoint"],
+            json!(summary.sequence_number)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_malformed_ids_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = light_client_state(dir.path().to_owned(), 20958462).await;
+        let url = serve_api(state).await;
+
+        for path in ["objects/not-an-object", "transactions/not-a-digest/events"] {
+            let (status, body) = get_json(format!("{url}/{path}")).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{path}: {body}");
+            assert!(body["error"].is_string(), "{path}: {body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unsynced_epoch_unavailable() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = light_client_state(dir.path().to_owned(), 20958462).await;
+        let url = serve_api(state.clone()).await;
+
+        // No committees have been synced, so the checkpoint the transaction is in can't be
+        // verified until the next sync.
+        let digest = TransactionDigest::random();
+        let (status, body) = get_json(format!("{url}/transactions/{digest}/events")).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "{body}");
+        assert!(
+            body["error"].as_str().unwrap().contains("Need to Sync"),
+            "{body}"
+        );
+        assert_eq!(state.summaries.latest(), None);
+    }
+}
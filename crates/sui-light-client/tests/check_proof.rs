@@ -3,12 +3,19 @@
 
 use anyhow::anyhow;
 
-use sui_light_client::construct::construct_proof;
-use sui_light_client::proof::{verify_proof, Proof, ProofTarget};
+use sui_light_client::construct::{
+    construct_inclusion_proof, construct_proof, construct_range_inclusion_proof,
+};
+use sui_light_client::proof::{
+    verify_inclusion_proof, verify_proof, InclusionProof, Proof, ProofTarget,
+};
 
 use sui_types::event::{Event, EventID};
 
-use sui_types::{committee::Committee, effects::TransactionEffectsAPI, object::Object};
+use sui_types::{
+    committee::Committee, effects::TransactionEffectsAPI, messages_checkpoint::CheckpointContents,
+    object::Object,
+};
 
 use sui_rpc_api::CheckpointData;
 
@@ -271,3 +278,206 @@ async fn test_event_target_fail_bad_event() {
 
     assert!(verify_proof(&committee, &event_proof).is_err());
 }
+
+#[tokio::test]
+async fn test_inclusion_proof_success() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let digest = *full_checkpoint.transactions[1].effects.transaction_digest();
+    let inclusion_proof = construct_inclusion_proof(&[digest], &full_checkpoint).unwrap();
+    assert!(verify_inclusion_proof(&committee, &inclusion_proof).is_ok());
+
+    // Survives a round trip through its encoding
+    let bytes = inclusion_proof.to_bytes().unwrap();
+    let decoded = InclusionProof::from_bytes(&bytes).unwrap();
+    assert!(verify_inclusion_proof(&committee, &decoded).is_ok());
+
+    // And is smaller than a proof carrying the full transaction
+    let sample_event: Event = full_checkpoint.transactions[1]
+        .events
+        .as_ref()
+        .unwrap()
+        .data[0]
+        .clone();
+    let target = ProofTarget::new().add_event(EventID::from((digest, 0)), sample_event);
+    let event_proof = construct_proof(target, &full_checkpoint).unwrap();
+    assert!(bytes.len() < bcs::to_bytes(&event_proof).unwrap().len());
+}
+
+#[tokio::test]
+async fn test_inclusion_proof_batch_success() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let digests: Vec<_> = full_checkpoint
+        .transactions
+        .iter()
+        .map(|tx| *tx.effects.transaction_digest())
+        .collect();
+    let inclusion_proof = construct_inclusion_proof(&digests, &full_checkpoint).unwrap();
+    assert_eq!(inclusion_proof.transactions.len(), digests.len());
+    assert!(verify_inclusion_proof(&committee, &inclusion_proof).is_ok());
+}
+
+#[tokio::test]
+async fn test_inclusion_proof_size() {
+    let (_, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let digests: Vec<_> = full_checkpoint
+        .transactions
+        .iter()
+        .map(|tx| *tx.effects.transaction_digest())
+        .collect();
+    assert!(digests.len() > 1);
+
+    let single = construct_inclusion_proof(&digests[..1], &full_checkpoint).unwrap();
+    let batch = construct_inclusion_proof(&digests, &full_checkpoint).unwrap();
+
+    // The proof is made up of the summary, the full contents, and the proven transactions, so
+    // its size is bounded below by the size of the checkpoint contents.
+    let size = |proof: &InclusionProof| {
+        let bytes = proof.to_bytes().unwrap();
+        assert_eq!(
+            bytes.len(),
+            bcs::serialized_size(&proof.checkpoint_summary).unwrap()
+                + bcs::serialized_size(&proof.checkpoint_contents).unwrap()
+                + bcs::serialized_size(&proof.transactions).unwrap()
+        );
+        bytes.len()
+    };
+
+    // Proving more transactions from the same checkpoint only adds their digests.
+    assert_eq!(
+        size(&batch) - size(&single),
+        bcs::serialized_size(&batch.transactions).unwrap()
+            - bcs::serialized_size(&single.transactions).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_inclusion_proof_range_success() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let end = full_checkpoint
+        .checkpoint_summary
+        .network_total_transactions;
+    let start = end - full_checkpoint.checkpoint_contents.size() as u64;
+    let inclusion_proof = construct_range_inclusion_proof(start..end, &full_checkpoint).unwrap();
+    assert!(verify_inclusion_proof(&committee, &inclusion_proof).is_ok());
+
+    // Ranges that are empty or extend past the checkpoint cannot be proven
+    assert!(construct_range_inclusion_proof(start..start, &full_checkpoint).is_err());
+    assert!(construct_range_inclusion_proof(start..end + 1, &full_checkpoint).is_err());
+}
+
+#[tokio::test]
+async fn test_inclusion_proof_fail_wrong_digests() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let digests: Vec<_> = full_checkpoint
+        .transactions
+        .iter()
+        .map(|tx| *tx.effects.transaction_digest())
+        .collect();
+
+    // Claim the effects of one transaction belong to another
+    let mut bad_proof = construct_inclusion_proof(&digests[..2], &full_checkpoint).unwrap();
+    bad_proof.transactions[0].1.effects = bad_proof.transactions[1].1.effects; // WRONG
+    assert!(verify_inclusion_proof(&committee, &bad_proof).is_err());
+
+    // Claim a transaction is at a different position
+    let mut bad_proof = construct_inclusion_proof(&digests[..1], &full_checkpoint).unwrap();
+    bad_proof.transactions[0].0 += 1; // WRONG
+    assert!(verify_inclusion_proof(&committee, &bad_proof).is_err());
+
+    // Claim a transaction is outside the checkpoint
+    let mut bad_proof = construct_inclusion_proof(&digests[..1], &full_checkpoint).unwrap();
+    bad_proof.transactions[0].0 = full_checkpoint
+        .checkpoint_summary
+        .network_total_transactions; // WRONG
+    assert!(verify_inclusion_proof(&committee, &bad_proof).is_err());
+
+    // Prove nothing
+    let mut bad_proof = construct_inclusion_proof(&digests[..1], &full_checkpoint).unwrap();
+    bad_proof.transactions.clear(); // WRONG
+    assert!(verify_inclusion_proof(&committee, &bad_proof).is_err());
+}
+
+#[tokio::test]
+async fn test_inclusion_proof_fail_tampered_contents() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let digest = *full_checkpoint.transactions[0].effects.transaction_digest();
+    let proof = construct_inclusion_proof(&[digest], &full_checkpoint).unwrap();
+
+    // Drop the user signatures from the contents, keeping only the digests
+    let mut bad_proof = proof.clone();
+    bad_proof.checkpoint_contents = CheckpointContents::new_with_digests_only_for_tests(
+        proof.checkpoint_contents.iter().cloned(),
+    ); // WRONG
+    assert!(verify_inclusion_proof(&committee, &bad_proof).is_err());
+
+    // Keep only the transaction being proven in the contents
+    let mut bad_proof = proof.clone();
+    bad_proof.checkpoint_contents = CheckpointContents::new_with_digests_and_signatures(
+        [proof.transactions[0].1],
+        vec![vec![]],
+    ); // WRONG
+    assert!(verify_inclusion_proof(&committee, &bad_proof).is_err());
+
+    // Swap the proven transaction for another one in the contents
+    let mut bad_proof = proof.clone();
+    let (other, _) = proof
+        .checkpoint_contents
+        .clone()
+        .into_iter_with_signatures()
+        .find(|(d, _)| d.transaction != digest)
+        .unwrap();
+    let contents = proof
+        .checkpoint_contents
+        .clone()
+        .into_iter_with_signatures()
+        .map(|(d, sigs)| (if d.transaction == digest { other } else { d }, sigs));
+    let (transactions, signatures): (Vec<_>, Vec<_>) = contents.unzip();
+    bad_proof.checkpoint_contents =
+        CheckpointContents::new_with_digests_and_signatures(transactions, signatures); // WRONG
+    bad_proof.transactions[0].1 = other;
+    assert!(verify_inclusion_proof(&committee, &bad_proof).is_err());
+}
+
+#[tokio::test]
+async fn test_inclusion_proof_fail_tampered_bytes() {
+    let (committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let digest = *full_checkpoint.transactions[0].effects.transaction_digest();
+    let proof = construct_inclusion_proof(&[digest], &full_checkpoint).unwrap();
+    let bytes = proof.to_bytes().unwrap();
+
+    // Flipping any byte of the encoded contents or transactions either makes the proof fail to
+    // decode, or fail to verify. The summary is covered by its signature.
+    let start = bcs::serialized_size(&proof.checkpoint_summary).unwrap();
+    let stride = ((bytes.len() - start) / 256).max(1);
+    for i in (start..bytes.len())
+        .step_by(stride)
+        .chain([bytes.len() - 1])
+    {
+        let mut tampered = bytes.clone();
+        tampered[i] ^= 0x01; // WRONG
+        if let Ok(bad_proof) = InclusionProof::from_bytes(&tampered) {
+            assert!(
+                verify_inclusion_proof(&committee, &bad_proof).is_err(),
+                "Tampered byte {i} was not detected"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_inclusion_proof_fail_incorrect_cert() {
+    let (mut committee, full_checkpoint) = read_data(15918264, 16005062).await;
+
+    let digest = *full_checkpoint.transactions[0].effects.transaction_digest();
+    let inclusion_proof = construct_inclusion_proof(&[digest], &full_checkpoint).unwrap();
+
+    committee.epoch += 1; // WRONG
+    assert!(verify_inclusion_proof(&committee, &inclusion_proof).is_err());
+}
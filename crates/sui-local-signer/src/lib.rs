@@ -0,0 +1,150 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A reference implementation of the remote signer protocol (see [sui_keys::remote_keystore]),
+//! signing with keys from a local keystore. It is meant for tests and local development: a
+//! production signer would typically keep its keys in an HSM or KMS, and apply its own policy to
+//! what it is willing to sign.
+
+use anyhow::{anyhow, bail};
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::HashFunction;
+use mysocial_types::base_types::SuiAddress;
+use mysocial_types::crypto::DefaultHash;
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Read, Write};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::Arc;
+#[cfg(unix)]
+use std::thread;
+use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_keys::remote_keystore::{
+    SignerKey, SignerRequest, SignerResponse, MAX_SIGNER_MESSAGE_SIZE,
+};
+use tokio::net::TcpListener;
+
+pub struct LocalSigner {
+    keystore: Keystore,
+}
+
+impl LocalSigner {
+    pub fn new(keystore: Keystore) -> Self {
+        Self { keystore }
+    }
+
+    /// Handle a single request from a remote keystore.
+    pub fn handle(&self, request: SignerRequest) -> SignerResponse {
+        match request {
+            SignerRequest::Keys => SignerResponse::Keys(
+                self.keystore
+                    .addresses_with_alias()
+                    .into_iter()
+                    .map(|(_, alias)| SignerKey {
+                        public_key: alias.public_key_base64.clone(),
+                        alias: Some(alias.alias.clone()),
+                    })
+                    .collect(),
+            ),
+
+            SignerRequest::Sign {
+                address,
+                message,
+                intent_message,
+            } => match self.sign(&address, &message, intent_message.as_deref()) {
+                Ok(signature) => SignerResponse::Signature(signature),
+                Err(e) => SignerResponse::Error(e.to_string()),
+            },
+        }
+    }
+
+    /// Serve requests on a Unix socket, one JSON request and response per line, until the listener
+    /// fails.
+    #[cfg(unix)]
+    pub fn serve_unix(self, listener: UnixListener) -> Result<(), anyhow::Error> {
+        let signer = Arc::new(self);
+        for stream in listener.incoming() {
+            let signer = signer.clone();
+            let stream = stream?;
+            thread::spawn(move || {
+                let _ = signer.handle_unix(stream);
+            });
+        }
+        Ok(())
+    }
+
+    /// Serve requests over HTTP, one JSON request and response per `POST /`, until the server
+    /// fails.
+    pub async fn serve_http(self, listener: TcpListener) -> Result<(), anyhow::Error> {
+        let app = Router::new()
+            .route("/", post(handle_http))
+            .layer(DefaultBodyLimit::max(MAX_SIGNER_MESSAGE_SIZE))
+            .with_state(Arc::new(self));
+        axum::serve(listener, app).await?;
+        Ok(())
+    }
+
+    fn sign(
+        &self,
+        address: &SuiAddress,
+        message: &str,
+        intent_message: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let message = Base64::decode(message).map_err(|e| anyhow!("Invalid message: {e}"))?;
+
+        // If the intent message is provided, only sign if the message is its digest.
+        if let Some(intent_message) = intent_message {
+            let intent_message = Base64::decode(intent_message)
+                .map_err(|e| anyhow!("Invalid intent message: {e}"))?;
+            let mut hasher = DefaultHash::default();
+            hasher.update(&intent_message);
+            if hasher.finalize().digest.as_slice() != message.as_slice() {
+                bail!("Message is not the digest of the intent message");
+            }
+        }
+
+        let signature = self
+            .keystore
+            .sign_hashed(address, &message)
+            .map_err(|e| anyhow!("{e}"))?;
+        Ok(Base64::encode(signature.as_ref()))
+    }
+
+    #[cfg(unix)]
+    fn handle_unix(&self, stream: UnixStream) -> Result<(), anyhow::Error> {
+        let mut reader = BufReader::new(stream.try_clone()?.take(MAX_SIGNER_MESSAGE_SIZE as u64));
+        let mut line = Vec::new();
+        reader.read_until(b'\n', &mut line)?;
+
+        let mut response = serde_json::to_vec(&self.handle_bytes(&line))?;
+        response.push(b'\n');
+
+        let mut stream = stream;
+        stream.write_all(&response)?;
+        Ok(())
+    }
+
+    fn handle_bytes(&self, request: &[u8]) -> SignerResponse {
+        match serde_json::from_slice(request) {
+            Ok(request) => self.handle(request),
+            Err(e) => SignerResponse::Error(format!("Invalid request: {e}")),
+        }
+    }
+}
+
+async fn handle_http(
+    State(signer): State<Arc<LocalSigner>>,
+    body: Bytes,
+) -> (StatusCode, Json<SignerResponse>) {
+    let response = signer.handle_bytes(&body);
+    let status = match response {
+        SignerResponse::Error(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::OK,
+    };
+    (status, Json(response))
+}
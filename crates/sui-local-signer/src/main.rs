@@ -0,0 +1,61 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use sui_keys::keystore::{FileBasedKeystore, Keystore};
+use sui_local_signer::LocalSigner;
+use tokio::net::TcpListener;
+
+/// A reference remote signer, serving signing requests from a remote keystore with keys from a
+/// local keystore file.
+#[derive(Parser)]
+#[clap(name = "sui-local-signer", rename_all = "kebab-case")]
+struct Args {
+    /// Path to the keystore file holding the signer's keys.
+    #[clap(long)]
+    keystore_path: PathBuf,
+
+    /// Serve requests on a Unix socket at this path. Only supported on Unix platforms.
+    #[clap(
+        long,
+        conflicts_with = "http_address",
+        required_unless_present = "http_address"
+    )]
+    unix_socket: Option<PathBuf>,
+
+    /// Serve requests over HTTP on this address.
+    #[clap(long)]
+    http_address: Option<SocketAddr>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let args = Args::parse();
+    let keystore = Keystore::from(FileBasedKeystore::new(&args.keystore_path)?);
+    let signer = LocalSigner::new(keystore);
+
+    if let Some(path) = args.unix_socket {
+        serve_unix(signer, path).await
+    } else if let Some(address) = args.http_address {
+        println!("Serving signing requests on http://{address}");
+        signer.serve_http(TcpListener::bind(address).await?).await
+    } else {
+        unreachable!("clap requires either a Unix socket or an HTTP address")
+    }
+}
+
+#[cfg(unix)]
+async fn serve_unix(signer: LocalSigner, path: PathBuf) -> Result<(), anyhow::Error> {
+    println!("Serving signing requests on {}", path.display());
+    let listener = UnixListener::bind(path)?;
+    tokio::task::spawn_blocking(move || signer.serve_unix(listener)).await?
+}
+
+#[cfg(not(unix))]
+async fn serve_unix(_signer: LocalSigner, _path: PathBuf) -> Result<(), anyhow::Error> {
+    anyhow::bail!("Unix sockets are not supported on this platform")
+}
@@ -0,0 +1,119 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use shared_crypto::intent::Intent;
+use sui_keys::key_derive::generate_new_key;
+use sui_keys::keystore::{AccountKeystore, InMemKeystore, Keystore};
+use sui_keys::remote_keystore::{
+    request, RemoteKeystore, SignerEndpoint, SignerRequest, MAX_SIGNER_MESSAGE_SIZE,
+};
+use sui_local_signer::LocalSigner;
+use sui_types::crypto::SignatureScheme;
+#[cfg(unix)]
+use tempfile::TempDir;
+
+fn check_remote_keystore(endpoint: SignerEndpoint, expected: &Keystore, aliases: Vec<String>) {
+    let mut keystore = Keystore::from(RemoteKeystore::new(endpoint));
+    assert_eq!(expected.addresses(), keystore.addresses());
+    assert_eq!(aliases, keystore.alias_names());
+
+    // Signatures from the remote signer match the ones produced locally
+    for address in expected.addresses() {
+        let remote = keystore
+            .sign_secure(&address, &"message", Intent::sui_transaction())
+            .unwrap();
+        let local = expected
+            .sign_secure(&address, &"message", Intent::sui_transaction())
+            .unwrap();
+        assert_eq!(local.as_ref(), remote.as_ref());
+    }
+
+    // Private keys never leave the signer
+    let address = expected.addresses()[0];
+    assert!(keystore.get_key(&address).is_err());
+    assert!(keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .is_err());
+
+    // Addresses the signer does not hold cannot be signed for
+    let (unknown, _, _, _) = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
+    assert!(keystore
+        .sign_secure(&unknown, &"message", Intent::sui_transaction())
+        .is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn remote_keystore_unix_socket_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let socket_path = temp_dir.path().join("signer.sock");
+    let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+
+    let keystore = Keystore::InMem(InMemKeystore::new_insecure_for_tests(2));
+    let signer_keystore = Keystore::InMem(InMemKeystore::new_insecure_for_tests(2));
+    let aliases = signer_keystore
+        .alias_names()
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let signer = LocalSigner::new(signer_keystore);
+    std::thread::spawn(move || signer.serve_unix(listener));
+
+    check_remote_keystore(SignerEndpoint::Unix(socket_path), &keystore, aliases);
+}
+
+#[test]
+fn remote_keystore_http_test() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let keystore = Keystore::InMem(InMemKeystore::new_insecure_for_tests(2));
+    let signer_keystore = Keystore::InMem(InMemKeystore::new_insecure_for_tests(2));
+    let aliases = signer_keystore
+        .alias_names()
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let signer = LocalSigner::new(signer_keystore);
+    spawn_http_signer(signer, listener);
+
+    check_remote_keystore(
+        SignerEndpoint::Http(format!("http://{address}/")),
+        &keystore,
+        aliases,
+    );
+}
+
+fn spawn_http_signer(signer: LocalSigner, listener: std::net::TcpListener) {
+    listener.set_nonblocking(true).unwrap();
+    std::thread::spawn(move || {
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(async move {
+                let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+                signer.serve_http(listener).await
+            })
+    });
+}
+
+#[test]
+fn remote_keystore_http_body_limit_test() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = SignerEndpoint::Http(format!("http://{}/", listener.local_addr().unwrap()));
+
+    let signer_keystore = Keystore::InMem(InMemKeystore::new_insecure_for_tests(1));
+    let address = signer_keystore.addresses()[0];
+    spawn_http_signer(LocalSigner::new(signer_keystore), listener);
+
+    // Requests over the size limit are rejected without being handled
+    let oversized = SignerRequest::Sign {
+        address,
+        message: "A".repeat(MAX_SIGNER_MESSAGE_SIZE),
+        intent_message: None,
+    };
+    assert!(request(&endpoint, &oversized).is_err());
+
+    // The signer keeps serving requests within the limit
+    let keystore = RemoteKeystore::new(endpoint);
+    assert_eq!(keystore.addresses(), vec![address]);
+}
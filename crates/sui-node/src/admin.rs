@@ -9,13 +9,16 @@ use axum::{
     Router,
 };
 use base64::Engine;
-use humantime::parse_duration;
+use humantime::{format_duration, format_rfc3339_seconds, parse_duration};
+use mysocial_core::transaction_deny_config_reloader::ADMIN_UPDATE_SOURCE;
 use serde::Deserialize;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
 };
+use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_types::{
     base_types::AuthorityName,
     crypto::{RandomnessPartialSignature, RandomnessRound, RandomnessSignature},
@@ -68,6 +71,24 @@ use tracing::info;
 // Inject a full signature from another node, bypassing validity checks.
 //
 //  $ curl 'http://127.0.0.1:1337/randomness-inject-full-sig?round=123&sigs=base64encodedsig'
+//
+// View the clients currently blocked by the validator's traffic controller.
+//
+//  $ curl 'http://127.0.0.1:1337/traffic-control-blocklist'
+//
+// Unblock a client, or clear the blocklists entirely if no ip is given.
+//
+//  $ curl -X POST 'http://127.0.0.1:1337/traffic-control-clear-blocklist?ip=1.2.3.4'
+//
+// View the transaction deny config currently in effect.
+//
+//  $ curl 'http://127.0.0.1:1337/transaction-deny-config'
+//
+// Replace the transaction deny config with one in YAML or JSON. The new config only takes effect
+// if it is valid. If the node loads its deny config from a file, the next change to that file
+// will override it.
+//
+//  $ curl -X POST 'http://127.0.0.1:1337/transaction-deny-config' --data-binary @deny-config.yaml
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -80,6 +101,9 @@ const NODE_CONFIG: &str = "/node-config";
 const RANDOMNESS_PARTIAL_SIGS_ROUTE: &str = "/randomness-partial-sigs";
 const RANDOMNESS_INJECT_PARTIAL_SIGS_ROUTE: &str = "/randomness-inject-partial-sigs";
 const RANDOMNESS_INJECT_FULL_SIG_ROUTE: &str = "/randomness-inject-full-sig";
+const TRAFFIC_CONTROL_BLOCKLIST_ROUTE: &str = "/traffic-control-blocklist";
+const TRAFFIC_CONTROL_CLEAR_BLOCKLIST_ROUTE: &str = "/traffic-control-clear-blocklist";
+const TRANSACTION_DENY_CONFIG_ROUTE: &str = "/transaction-deny-config";
 
 struct AppState {
     node: Arc<SuiNode>,
@@ -119,6 +143,19 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
             RANDOMNESS_INJECT_FULL_SIG_ROUTE,
             post(randomness_inject_full_sig),
         )
+        .route(
+            TRAFFIC_CONTROL_BLOCKLIST_ROUTE,
+            get(traffic_control_blocklist),
+        )
+        .route(
+            TRAFFIC_CONTROL_CLEAR_BLOCKLIST_ROUTE,
+            post(traffic_control_clear_blocklist),
+        )
+        .route(TRANSACTION_DENY_CONFIG_ROUTE, get(transaction_deny_config))
+        .route(
+            TRANSACTION_DENY_CONFIG_ROUTE,
+            post(update_transaction_deny_config),
+        )
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -443,3 +480,84 @@ async fn randomness_inject_full_sig(
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
     }
 }
+
+async fn traffic_control_blocklist(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let Some(traffic_controller) = state.node.traffic_controller().await else {
+        return (
+            StatusCode::NOT_FOUND,
+            "traffic control is not enabled on this node\n".to_string(),
+        );
+    };
+
+    let now = SystemTime::now();
+    let mut output = String::new();
+    for entry in traffic_controller.blocklist() {
+        let remaining = entry.expiration.duration_since(now).unwrap_or_default();
+        output.push_str(&format!(
+            "{} {} until {} ({} left)\n",
+            entry.client,
+            if entry.proxied { "proxied" } else { "direct" },
+            format_rfc3339_seconds(entry.expiration),
+            format_duration(Duration::from_secs(remaining.as_secs())),
+        ));
+    }
+
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+struct ClearBlocklist {
+    ip: Option<IpAddr>,
+}
+
+async fn traffic_control_clear_blocklist(
+    State(state): State<Arc<AppState>>,
+    args: Query<ClearBlocklist>,
+) -> (StatusCode, String) {
+    let Query(ClearBlocklist { ip }) = args;
+
+    let Some(traffic_controller) = state.node.traffic_controller().await else {
+        return (
+            StatusCode::NOT_FOUND,
+            "traffic control is not enabled on this node\n".to_string(),
+        );
+    };
+
+    let removed = traffic_controller.clear_blocklist(ip);
+    (
+        StatusCode::OK,
+        format!("removed {removed} blocklist entries\n"),
+    )
+}
+
+async fn transaction_deny_config(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let config = state.node.state().transaction_deny_config().load();
+    match serde_yaml::to_string(&**config) {
+        Ok(config) => (StatusCode::OK, config),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn update_transaction_deny_config(
+    State(state): State<Arc<AppState>>,
+    new_config: String,
+) -> (StatusCode, String) {
+    let new_config: TransactionDenyConfig = match serde_yaml::from_str(&new_config) {
+        Ok(config) => config,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()),
+    };
+
+    match state
+        .node
+        .state()
+        .transaction_deny_config()
+        .update(new_config, ADMIN_UPDATE_SOURCE)
+    {
+        Ok(changes) if changes.is_empty() => (
+            StatusCode::OK,
+            "transaction deny config unchanged\n".to_string(),
+        ),
+        Ok(changes) => (StatusCode::OK, format!("{}\n", changes.join("\n"))),
+        Err(err) => (StatusCode::BAD_REQUEST, format!("{err:#}\n")),
+    }
+}
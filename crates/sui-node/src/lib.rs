@@ -40,6 +40,7 @@ use mysocial_core::execution_cache::build_execution_cache;
 use mysocial_core::state_accumulator::StateAccumulatorMetrics;
 use mysocial_core::storage::RestReadStore;
 use mysocial_core::traffic_controller::metrics::TrafficControllerMetrics;
+use mysocial_core::traffic_controller::TrafficController;
 use sui_json_rpc::bridge_api::BridgeReadApi;
 use sui_json_rpc_api::JsonRpcMetrics;
 use sui_network::randomness;
@@ -155,6 +156,8 @@ pub mod metrics;
 
 pub struct ValidatorComponents {
     validator_server_handle: SpawnOnce,
+    // Kept so that the admin server can inspect and clear the validator's blocklists.
+    traffic_controller: Option<Arc<TrafficController>>,
     validator_overload_monitor_handle: Option<JoinHandle<()>>,
     consensus_manager: ConsensusManager,
     consensus_store_pruner: ConsensusStorePruner,
@@ -441,7 +444,8 @@ impl SuiNode {
         custom_rpc_runtime: Option<Handle>,
         software_version: &'static str,
     ) -> Result<Arc<SuiNode>> {
-        NodeConfigMetrics::new(&registry_service.default_registry()).record_metrics(&config);
+        let node_config_metrics = NodeConfigMetrics::new(&registry_service.default_registry());
+        node_config_metrics.record_metrics(&config);
         let mut config = config.clone();
         if config.supported_protocol_versions.is_none() {
             info!(
@@ -764,6 +768,21 @@ impl SuiNode {
         // Start the loop that receives new randomness and generates transactions for it.
         RandomnessRoundReceiver::spawn(state.clone(), randomness_rx);
 
+        // The transaction deny config can be updated through the admin server, or by editing the
+        // file it is loaded from, so keep its metrics up to date.
+        let mut deny_config_updates = state.transaction_deny_config().subscribe();
+        spawn_monitored_task!(async move {
+            while deny_config_updates.changed().await.is_ok() {
+                node_config_metrics
+                    .record_transaction_deny_config(&deny_config_updates.borrow_and_update());
+            }
+        });
+
+        if let Some(path) = config.transaction_deny_config_path.clone() {
+            info!(path = %path.display(), "watching transaction deny config file");
+            state.transaction_deny_config().watch_file(path)?;
+        }
+
         if config
             .expensive_safety_check_config
             .enable_secondary_index_checks()
@@ -930,6 +949,16 @@ impl SuiNode {
         self.config.db_checkpoint_path()
     }
 
+    /// The traffic controller guarding the validator's gRPC service, if this node is a validator
+    /// with traffic control configured.
+    pub async fn traffic_controller(&self) -> Option<Arc<TrafficController>> {
+        self.validator_components
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|components| components.traffic_controller.clone())
+    }
+
     // Init reconfig process by starting to reject user certs
     pub async fn close_epoch(&self, epoch_store: &Arc<AuthorityPerEpochStore>) -> SuiResult {
         info!("close_epoch (current epoch = {})", epoch_store.epoch());
@@ -1002,6 +1031,7 @@ impl SuiNode {
                 &config.db_checkpoint_path(),
                 &config.snapshot_path(),
                 remote_store_config.clone(),
+                config.state_snapshot_write_config.full_snapshot_interval_epochs,
                 60,
                 prometheus_registry,
                 checkpoint_store,
@@ -1270,7 +1300,7 @@ impl SuiNode {
         let sui_tx_validator_metrics =
             SuiTxValidatorMetrics::new(&registry_service.default_registry());
 
-        let validator_server_handle = Self::start_grpc_validator_service(
+        let (validator_server_handle, traffic_controller) = Self::start_grpc_validator_service(
             &config,
             state.clone(),
             consensus_adapter.clone(),
@@ -1309,6 +1339,7 @@ impl SuiNode {
             accumulator,
             backpressure_manager,
             validator_server_handle,
+            traffic_controller,
             validator_overload_monitor_handle,
             checkpoint_metrics,
             sui_node_metrics,
@@ -1330,6 +1361,7 @@ impl SuiNode {
         accumulator: Weak<StateAccumulator>,
         backpressure_manager: Arc<BackpressureManager>,
         validator_server_handle: SpawnOnce,
+        traffic_controller: Option<Arc<TrafficController>>,
         validator_overload_monitor_handle: Option<JoinHandle<()>>,
         checkpoint_metrics: Arc<CheckpointMetrics>,
         sui_node_metrics: Arc<SuiNodeMetrics>,
@@ -1440,6 +1472,7 @@ impl SuiNode {
 
         Ok(ValidatorComponents {
             validator_server_handle,
+            traffic_controller,
             validator_overload_monitor_handle,
             consensus_manager,
             consensus_store_pruner,
@@ -1530,7 +1563,7 @@ impl SuiNode {
         state: Arc<AuthorityState>,
         consensus_adapter: Arc<ConsensusAdapter>,
         prometheus_registry: &Registry,
-    ) -> Result<SpawnOnce> {
+    ) -> Result<(SpawnOnce, Option<Arc<TrafficController>>)> {
         let validator_service = ValidatorService::new(
             state.clone(),
             consensus_adapter,
@@ -1538,7 +1571,8 @@ impl SuiNode {
             TrafficControllerMetrics::new(prometheus_registry),
             config.policy_config.clone(),
             config.firewall_config.clone(),
-        );
+        )?;
+        let traffic_controller = validator_service.traffic_controller();
 
         let mut server_conf = mysten_network::config::Config::new();
         server_conf.global_concurrency_limit = config.grpc_concurrency_limit;
@@ -1559,7 +1593,10 @@ impl SuiNode {
         let local_addr = server.local_addr();
         info!("Listening to traffic on {local_addr}");
 
-        Ok(SpawnOnce::new(server.serve().map_err(Into::into)))
+        Ok((
+            SpawnOnce::new(server.serve().map_err(Into::into)),
+            traffic_controller,
+        ))
     }
 
     async fn reexecute_pending_consensus_certs(
@@ -1832,6 +1869,7 @@ impl SuiNode {
 
             let new_validator_components = if let Some(ValidatorComponents {
                 validator_server_handle,
+                traffic_controller,
                 validator_overload_monitor_handle,
                 consensus_manager,
                 consensus_store_pruner,
@@ -1891,6 +1929,7 @@ impl SuiNode {
                             weak_accumulator,
                             self.backpressure_manager.clone(),
                             validator_server_handle,
+                            traffic_controller,
                             validator_overload_monitor_handle,
                             checkpoint_metrics,
                             self.metrics.clone(),
@@ -10,12 +10,14 @@ use tonic::metadata::MetadataMap;
 
 use crate::proto::node::v2::node_service_client::NodeServiceClient;
 use crate::proto::node::v2::{
-    ExecuteTransactionResponse, GetCheckpointResponse, GetFullCheckpointResponse, GetObjectResponse,
+    ExecuteTransactionResponse, GetCheckpointResponse, GetFullCheckpointResponse,
+    GetObjectResponse, GetTransactionResponse,
 };
 use crate::proto::types::Bcs;
 use crate::proto::TryFromProtoError;
 use crate::types::ExecuteTransactionOptions;
 use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::TransactionDigest;
 use sui_types::effects::{TransactionEffects, TransactionEvents};
 use sui_types::full_checkpoint_content::CheckpointData;
 use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
@@ -214,6 +216,46 @@ impl Client {
         object_try_from_proto(object_bcs).map_err(|e| status_from_error_with_metadata(e, metadata))
     }
 
+    /// The effects of transaction `digest`, and the sequence number of the checkpoint it was
+    /// included in, if it has been included in one.
+    pub async fn get_transaction_effects(
+        &self,
+        digest: TransactionDigest,
+    ) -> Result<(TransactionEffects, Option<CheckpointSequenceNumber>)> {
+        let request = crate::proto::node::v2::GetTransactionRequest {
+            digest: Some(sui_sdk_types::TransactionDigest::from(digest).into()),
+            options: Some(crate::proto::node::v2::GetTransactionOptions {
+                effects: Some(false),
+                effects_bcs: Some(true),
+                ..Default::default()
+            }),
+            read_mask: Some(FieldMask {
+                paths: ["effects_bcs", "checkpoint"]
+                    .into_iter()
+                    .map(ToOwned::to_owned)
+                    .collect(),
+            }),
+        };
+
+        let (
+            metadata,
+            GetTransactionResponse {
+                effects_bcs,
+                checkpoint,
+                ..
+            },
+            _extentions,
+        ) = self
+            .raw_client()
+            .get_transaction(request)
+            .await?
+            .into_parts();
+
+        let effects = transaction_effects_try_from_proto(effects_bcs)
+            .map_err(|e| status_from_error_with_metadata(e, metadata))?;
+        Ok((effects, checkpoint))
+    }
+
     pub async fn execute_transaction(
         &self,
         parameters: &ExecuteTransactionOptions,
@@ -383,6 +425,17 @@ fn object_try_from_proto(object_bcs: Option<Bcs>) -> Result<Object, TryFromProto
         .map_err(TryFromProtoError::from_error)
 }
 
+/// Attempts to parse `TransactionEffects` from the bcs fields in `GetTransactionResponse`
+fn transaction_effects_try_from_proto(
+    effects_bcs: Option<Bcs>,
+) -> Result<TransactionEffects, TryFromProtoError> {
+    effects_bcs
+        .as_ref()
+        .ok_or_else(|| TryFromProtoError::missing("effects_bcs"))?
+        .deserialize()
+        .map_err(TryFromProtoError::from_error)
+}
+
 /// Attempts to parse `TransactionExecutionResponse` from the fields in `TransactionExecutionResponse`
 fn execute_transaction_response_try_from_proto(
     ExecuteTransactionResponse {
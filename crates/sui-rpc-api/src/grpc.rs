@@ -11,7 +11,7 @@ use tonic::{
 };
 use tower::{Service, ServiceExt};
 
-use crate::subscription::SubscriptionServiceHandle;
+use crate::subscription::TransactionFilter;
 
 pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
@@ -310,7 +310,7 @@ use crate::proto::node::v2alpha::SubscribeCheckpointsResponse;
 
 #[tonic::async_trait]
 impl crate::proto::node::v2alpha::subscription_service_server::SubscriptionService
-    for SubscriptionServiceHandle
+    for crate::RpcService
 {
     /// Server streaming response type for the SubscribeCheckpoints method.
     type SubscribeCheckpointsStream = Pin<
@@ -324,25 +324,49 @@ impl crate::proto::node::v2alpha::subscription_service_server::SubscriptionServi
         &self,
         request: tonic::Request<crate::proto::node::v2alpha::SubscribeCheckpointsRequest>,
     ) -> Result<tonic::Response<Self::SubscribeCheckpointsStream>, tonic::Status> {
-        let read_mask = request.into_inner().read_mask.unwrap_or_default();
+        let request = request.into_inner();
+        let read_mask = request.read_mask.unwrap_or_default();
+        let filter = request
+            .filter
+            .as_ref()
+            .map(TransactionFilter::try_from)
+            .transpose()?
+            .unwrap_or_default();
 
-        let Some(mut receiver) = self.register_subscription().await else {
+        let Some(subscription_service_handle) = &self.subscription_service_handle else {
             return Err(tonic::Status::unavailable(
-                "too many existing subscriptions",
+                "subscriptions are not supported by this node",
             ));
         };
 
+        let checkpoints = subscription_service_handle
+            .subscribe(self.reader.clone(), request.start_cursor)
+            .await?;
+
         let response = Box::pin(async_stream::stream! {
-            while let Some(checkpoint) = receiver.recv().await {
-                let Some(cursor) = checkpoint.sequence_number else {
+            for await checkpoint in checkpoints {
+                let checkpoint = match checkpoint {
+                    Ok(checkpoint) => checkpoint,
+                    Err(e) => {
+                        yield Err(e.into());
+                        break;
+                    }
+                };
+
+                let Some(cursor) = checkpoint.response.sequence_number else {
                     yield Err(tonic::Status::internal("unable to determine cursor"));
                     break;
                 };
 
-                let checkpoint = apply_checkpoint_read_mask(&read_mask, &checkpoint);
+                let mut response = apply_checkpoint_read_mask(&read_mask, &checkpoint.response);
+                if let Err(e) = filter.apply(&checkpoint.data, &mut response) {
+                    yield Err(e.into());
+                    break;
+                }
+
                 let response = SubscribeCheckpointsResponse {
                     cursor: Some(cursor),
-                    checkpoint: Some(checkpoint),
+                    checkpoint: Some(response),
                 };
 
                 yield Ok(response);
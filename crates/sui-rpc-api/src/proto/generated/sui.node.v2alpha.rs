@@ -1007,6 +1007,21 @@ pub struct SubscribeCheckpointsRequest {
     /// SubscribeCheckpointsResponse should be returned.
     #[prost(message, optional, tag = "3")]
     pub read_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// Optional. The sequence number of the first checkpoint to return.
+    ///
+    /// Checkpoints from `start_cursor` up to the latest executed checkpoint are
+    /// replayed from the server's store before the subscription switches over to
+    /// live checkpoints. Fails with `NOT_FOUND` if `start_cursor` has been
+    /// pruned. If `start_cursor` has not been executed yet, the stream begins
+    /// once it has been.
+    #[prost(uint64, optional, tag = "4")]
+    pub start_cursor: ::core::option::Option<u64>,
+    /// Optional. Only return transactions that match this filter.
+    ///
+    /// A response is still returned for every checkpoint, even if none of its
+    /// transactions match, so that the cursor always advances.
+    #[prost(message, optional, tag = "5")]
+    pub filter: ::core::option::Option<CheckpointTransactionFilter>,
 }
 /// Response message for SubscriptionService.SubscribeCheckpoints
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1019,6 +1034,28 @@ pub struct SubscribeCheckpointsResponse {
     #[prost(message, optional, tag = "2")]
     pub checkpoint: ::core::option::Option<super::v2::GetFullCheckpointResponse>,
 }
+/// Filter for the transactions returned by SubscriptionService.SubscribeCheckpoints.
+///
+/// A transaction matches the filter if it matches all of the criteria that are
+/// set, and it matches a criterion if it matches any of its values. Move
+/// filters are formatted as `<package>`, `<package>::<module>` or
+/// `<package>::<module>::<name>`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckpointTransactionFilter {
+    /// Transactions sent by one of these addresses.
+    #[prost(message, repeated, tag = "1")]
+    pub senders: ::prost::alloc::vec::Vec<super::super::types::Address>,
+    /// Transactions sent by one of these addresses, or that read or wrote an
+    /// object owned by one of these addresses.
+    #[prost(message, repeated, tag = "2")]
+    pub affected_addresses: ::prost::alloc::vec::Vec<super::super::types::Address>,
+    /// Transactions that call a Move function matching one of these filters.
+    #[prost(string, repeated, tag = "3")]
+    pub move_calls: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Transactions that emit an event whose type matches one of these filters.
+    #[prost(string, repeated, tag = "4")]
+    pub event_types: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
 /// Generated client implementations.
 pub mod subscription_service_client {
     #![allow(
@@ -1115,14 +1152,14 @@ pub mod subscription_service_client {
         ///
         /// This API provides a subscription to the checkpoint stream for the Sui
         /// blockchain. When a subscription is initialized the stream will begin with
-        /// the latest executed checkpoint as seen by the server. Responses are
-        /// gaurenteed to return checkpoints in-order and without gaps. This enables
-        /// clients to know exactly the last checkpoint they have processed and in the
-        /// event the subscription terminates (either by the client/server or by the
-        /// connection breaking), clients will be able to reinitailize a subscription
-        /// and then leverage other APIs (e.g.
-        /// sui.node.v2.NodeService.GetFullCheckpoint) in order to request data for
-        /// the checkpoints they missed.
+        /// the latest executed checkpoint as seen by the server, or with
+        /// `start_cursor` if one is provided. Responses are gaurenteed to return
+        /// checkpoints in-order and without gaps. This enables clients to know
+        /// exactly the last checkpoint they have processed and in the event the
+        /// subscription terminates (either by the client/server or by the connection
+        /// breaking), clients will be able to reinitailize a subscription starting
+        /// from the checkpoint after the last one they processed, as long as it has
+        /// not been pruned.
         pub async fn subscribe_checkpoints(
             &mut self,
             request: impl tonic::IntoRequest<super::SubscribeCheckpointsRequest>,
@@ -1182,14 +1219,14 @@ pub mod subscription_service_server {
         ///
         /// This API provides a subscription to the checkpoint stream for the Sui
         /// blockchain. When a subscription is initialized the stream will begin with
-        /// the latest executed checkpoint as seen by the server. Responses are
-        /// gaurenteed to return checkpoints in-order and without gaps. This enables
-        /// clients to know exactly the last checkpoint they have processed and in the
-        /// event the subscription terminates (either by the client/server or by the
-        /// connection breaking), clients will be able to reinitailize a subscription
-        /// and then leverage other APIs (e.g.
-        /// sui.node.v2.NodeService.GetFullCheckpoint) in order to request data for
-        /// the checkpoints they missed.
+        /// the latest executed checkpoint as seen by the server, or with
+        /// `start_cursor` if one is provided. Responses are gaurenteed to return
+        /// checkpoints in-order and without gaps. This enables clients to know
+        /// exactly the last checkpoint they have processed and in the event the
+        /// subscription terminates (either by the client/server or by the connection
+        /// breaking), clients will be able to reinitailize a subscription starting
+        /// from the checkpoint after the last one they processed, as long as it has
+        /// not been pruned.
         async fn subscribe_checkpoints(
             &self,
             request: tonic::Request<super::SubscribeCheckpointsRequest>,
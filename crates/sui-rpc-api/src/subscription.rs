@@ -4,11 +4,20 @@
 use crate::metrics::SubscriptionMetrics;
 use crate::proto::node::v2::GetFullCheckpointOptions;
 use crate::proto::node::v2::GetFullCheckpointResponse;
+use crate::proto::node::v2alpha::CheckpointTransactionFilter;
+use crate::reader::StateReader;
+use crate::RpcError;
+use std::collections::BTreeSet;
+use std::str::FromStr;
 use std::sync::Arc;
-use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::transaction::TransactionDataAPI;
 use tap::Pipe;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
+use tokio::sync::Semaphore;
 use tracing::error;
 use tracing::info;
 use tracing::trace;
@@ -17,26 +26,399 @@ const CHECKPOINT_MAILBOX_SIZE: usize = 1024;
 const MAILBOX_SIZE: usize = 128;
 const SUBSCRIPTION_CHANNEL_SIZE: usize = 256;
 const MAX_SUBSCRIBERS: usize = 1024;
+/// How many subscriptions can replay checkpoints from the store at the same time.
+const MAX_REPLAYING_SUBSCRIBERS: usize = 32;
 
 struct SubscriptionRequest {
-    sender: oneshot::Sender<mpsc::Receiver<Arc<GetFullCheckpointResponse>>>,
+    sender: oneshot::Sender<mpsc::Receiver<Arc<SubscriptionCheckpoint>>>,
+}
+
+/// A checkpoint as it is delivered to subscribers: its full response, alongside the data it was
+/// built from, which is used to filter its transactions.
+pub struct SubscriptionCheckpoint {
+    pub data: CheckpointData,
+    pub response: GetFullCheckpointResponse,
 }
 
 #[derive(Clone)]
 pub struct SubscriptionServiceHandle {
     sender: mpsc::Sender<SubscriptionRequest>,
+    /// Bounds the number of subscriptions replaying from the store, as they do so before taking
+    /// up a subscriber slot.
+    replay_permits: Arc<Semaphore>,
 }
 
 impl SubscriptionServiceHandle {
     pub async fn register_subscription(
         &self,
-    ) -> Option<mpsc::Receiver<Arc<GetFullCheckpointResponse>>> {
+    ) -> Option<mpsc::Receiver<Arc<SubscriptionCheckpoint>>> {
         let (sender, reciever) = oneshot::channel();
         let request = SubscriptionRequest { sender };
         self.sender.send(request).await.ok()?;
 
         reciever.await.ok()
     }
+
+    /// Subscribe to the checkpoint stream, starting from `start_cursor` if one is provided, or
+    /// from the next checkpoint to be executed otherwise.
+    ///
+    /// Retained checkpoints from `start_cursor` up to the latest executed checkpoint are replayed
+    /// from the store before the subscription is registered. Any checkpoints executed during the
+    /// replay are read from the store as well, so that the stream has no gaps. Only a limited
+    /// number of subscriptions can replay at the same time, and others are turned away.
+    pub(crate) async fn subscribe(
+        &self,
+        reader: StateReader,
+        start_cursor: Option<CheckpointSequenceNumber>,
+    ) -> crate::Result<
+        impl tokio_stream::Stream<Item = crate::Result<Arc<SubscriptionCheckpoint>>> + Send,
+    > {
+        // Without a cursor to replay from, the subscription can be registered straight away.
+        let receiver = if start_cursor.is_none() {
+            let receiver = self
+                .register_subscription()
+                .await
+                .ok_or_else(too_many_subscriptions)?;
+            Some(receiver)
+        } else {
+            None
+        };
+
+        // Replaying holds a permit until the subscription is registered.
+        let replay_permit = if start_cursor.is_some() {
+            let permit = Arc::clone(&self.replay_permits)
+                .try_acquire_owned()
+                .map_err(|_| too_many_replaying_subscriptions())?;
+            Some(permit)
+        } else {
+            None
+        };
+
+        if let Some(start_cursor) = start_cursor {
+            let lowest_available = reader.inner().get_lowest_available_checkpoint_objects()?;
+            if start_cursor < lowest_available {
+                return Err(RpcError::new(
+                    tonic::Code::NotFound,
+                    format!(
+                        "checkpoint {start_cursor} has been pruned, \
+                         the lowest available checkpoint is {lowest_available}"
+                    ),
+                ));
+            }
+        }
+
+        let handle = self.clone();
+        Ok(async_stream::stream! {
+            // The sequence number of the next checkpoint to return, once it is known.
+            let mut next = start_cursor;
+
+            if let Some(start_cursor) = start_cursor {
+                let latest = match reader.inner().get_latest_checkpoint() {
+                    Ok(latest) => *latest.sequence_number(),
+                    Err(e) => {
+                        yield Err(e.into());
+                        return;
+                    }
+                };
+
+                for sequence_number in start_cursor..=latest {
+                    yield load_checkpoint(&reader, sequence_number);
+                    next = Some(sequence_number + 1);
+                }
+            }
+
+            let mut receiver = match receiver {
+                Some(receiver) => receiver,
+                None => match handle.register_subscription().await {
+                    Some(receiver) => receiver,
+                    None => {
+                        yield Err(too_many_subscriptions());
+                        return;
+                    }
+                },
+            };
+            drop(replay_permit);
+
+            while let Some(checkpoint) = receiver.recv().await {
+                let sequence_number = *checkpoint.data.checkpoint_summary.sequence_number();
+
+                if let Some(mut expected) = next {
+                    if sequence_number < expected {
+                        continue;
+                    }
+
+                    // Fill in any checkpoints that were executed after the replay finished, but
+                    // before the subscription was registered.
+                    while expected < sequence_number {
+                        yield load_checkpoint(&reader, expected);
+                        expected += 1;
+                    }
+                }
+
+                yield Ok(checkpoint);
+                next = Some(sequence_number + 1);
+            }
+        })
+    }
+}
+
+impl SubscriptionCheckpoint {
+    fn new(data: CheckpointData) -> crate::Result<Self> {
+        let response = crate::service::checkpoints::checkpoint_data_to_full_checkpoint_response(
+            data.clone(),
+            &GetFullCheckpointOptions::all().into(),
+        )?
+        .into();
+
+        Ok(Self { data, response })
+    }
+}
+
+/// Read a checkpoint that has already been executed from the store.
+fn load_checkpoint(
+    reader: &StateReader,
+    sequence_number: CheckpointSequenceNumber,
+) -> crate::Result<Arc<SubscriptionCheckpoint>> {
+    let not_found = || {
+        RpcError::new(
+            tonic::Code::NotFound,
+            format!("checkpoint {sequence_number} not found"),
+        )
+    };
+
+    let summary = reader
+        .inner()
+        .get_checkpoint_by_sequence_number(sequence_number)
+        .ok_or_else(not_found)?;
+
+    let contents = reader
+        .inner()
+        .get_checkpoint_contents_by_digest(&summary.content_digest)
+        .ok_or_else(not_found)?;
+
+    let data = reader.inner().get_checkpoint_data(summary, contents)?;
+    SubscriptionCheckpoint::new(data).map(Arc::new)
+}
+
+fn too_many_subscriptions() -> RpcError {
+    RpcError::new(tonic::Code::Unavailable, "too many existing subscriptions")
+}
+
+fn too_many_replaying_subscriptions() -> RpcError {
+    RpcError::new(
+        tonic::Code::Unavailable,
+        "too many subscriptions replaying checkpoints",
+    )
+}
+
+/// Filter over the transactions in a checkpoint, parsed from a `CheckpointTransactionFilter`.
+///
+/// A transaction matches if it matches every criterion that is set, and it matches a criterion if
+/// it matches any of its values.
+#[derive(Default, Debug)]
+pub(crate) struct TransactionFilter {
+    senders: BTreeSet<SuiAddress>,
+    affected_addresses: BTreeSet<SuiAddress>,
+    move_calls: Vec<MoveFilter>,
+    event_types: Vec<MoveFilter>,
+}
+
+/// A filter on Move functions or types, formatted as `<package>`, `<package>::<module>` or
+/// `<package>::<module>::<name>`.
+#[derive(Debug, PartialEq, Eq)]
+struct MoveFilter {
+    package: ObjectID,
+    module: Option<String>,
+    name: Option<String>,
+}
+
+impl TransactionFilter {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.senders.is_empty()
+            && self.affected_addresses.is_empty()
+            && self.move_calls.is_empty()
+            && self.event_types.is_empty()
+    }
+
+    pub(crate) fn matches(&self, transaction: &CheckpointTransaction) -> bool {
+        let data = transaction.transaction.transaction_data();
+        let sender = data.sender();
+
+        if !self.senders.is_empty() && !self.senders.contains(&sender) {
+            return false;
+        }
+
+        if !self.affected_addresses.is_empty()
+            && !std::iter::once(sender)
+                .chain(
+                    transaction
+                        .input_objects
+                        .iter()
+                        .chain(&transaction.output_objects)
+                        .filter_map(|object| object.owner.get_address_owner_address().ok()),
+                )
+                .any(|address| self.affected_addresses.contains(&address))
+        {
+            return false;
+        }
+
+        if !self.move_calls.is_empty()
+            && !data
+                .move_calls()
+                .into_iter()
+                .any(|(package, module, function)| {
+                    self.move_calls
+                        .iter()
+                        .any(|filter| filter.matches(package, module, function))
+                })
+        {
+            return false;
+        }
+
+        if !self.event_types.is_empty()
+            && !transaction
+                .events
+                .iter()
+                .flat_map(|e| &e.data)
+                .any(|event| {
+                    let package = ObjectID::from(event.type_.address);
+                    self.event_types.iter().any(|filter| {
+                        filter.matches(
+                            &package,
+                            event.type_.module.as_str(),
+                            event.type_.name.as_str(),
+                        )
+                    })
+                })
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Remove the transactions from `response` that don't match this filter. `response` must have
+    /// been built from `data`, with its transactions in the same order.
+    pub(crate) fn apply(
+        &self,
+        data: &CheckpointData,
+        response: &mut GetFullCheckpointResponse,
+    ) -> crate::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        if response.transactions.len() != data.transactions.len() {
+            return Err(RpcError::new(
+                tonic::Code::Internal,
+                format!(
+                    "unable to filter checkpoint with {} transactions, its response has {}",
+                    data.transactions.len(),
+                    response.transactions.len(),
+                ),
+            ));
+        }
+
+        let mut transactions = data.transactions.iter();
+        response
+            .transactions
+            .retain(|_| transactions.next().is_some_and(|tx| self.matches(tx)));
+        Ok(())
+    }
+}
+
+impl TryFrom<&CheckpointTransactionFilter> for TransactionFilter {
+    type Error = RpcError;
+
+    fn try_from(
+        CheckpointTransactionFilter {
+            senders,
+            affected_addresses,
+            move_calls,
+            event_types,
+        }: &CheckpointTransactionFilter,
+    ) -> Result<Self, Self::Error> {
+        let addresses = |addresses: &[crate::proto::types::Address], field: &str| {
+            addresses
+                .iter()
+                .map(|address| {
+                    sui_sdk_types::Address::try_from(address)
+                        .map(SuiAddress::from)
+                        .map_err(|e| {
+                            RpcError::new(
+                                tonic::Code::InvalidArgument,
+                                format!("invalid {field}: {e}"),
+                            )
+                        })
+                })
+                .collect::<Result<BTreeSet<_>, _>>()
+        };
+
+        let move_filters = |filters: &[String], field: &str| {
+            filters
+                .iter()
+                .map(|filter| {
+                    filter.parse::<MoveFilter>().map_err(|e| {
+                        RpcError::new(
+                            tonic::Code::InvalidArgument,
+                            format!("invalid {field} '{filter}': {e}"),
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            senders: addresses(senders, "sender")?,
+            affected_addresses: addresses(affected_addresses, "affected address")?,
+            move_calls: move_filters(move_calls, "move call")?,
+            event_types: move_filters(event_types, "event type")?,
+        })
+    }
+}
+
+impl MoveFilter {
+    fn matches(&self, package: &ObjectID, module: &str, name: &str) -> bool {
+        self.package == *package
+            && self.module.as_ref().map_or(true, |m| m == module)
+            && self.name.as_ref().map_or(true, |n| n == name)
+    }
+}
+
+impl FromStr for MoveFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split("::");
+
+        let package = parts.next().unwrap_or_default();
+        let package = ObjectID::from_str(package)
+            .map_err(|e| anyhow::anyhow!("invalid package '{package}': {e}"))?;
+
+        let mut identifier = || {
+            parts
+                .next()
+                .map(|part| {
+                    move_core_types::identifier::Identifier::new(part)
+                        .map(|ident| ident.into_string())
+                        .map_err(|_| anyhow::anyhow!("invalid identifier '{part}'"))
+                })
+                .transpose()
+        };
+
+        let module = identifier()?;
+        let name = identifier()?;
+
+        if parts.next().is_some() {
+            anyhow::bail!("expected at most three components separated by '::'");
+        }
+
+        Ok(Self {
+            package,
+            module,
+            name,
+        })
+    }
 }
 
 pub struct SubscriptionService {
@@ -45,7 +427,7 @@ pub struct SubscriptionService {
     // Expectation is that checkpoints are recieved in-order
     checkpoint_mailbox: mpsc::Receiver<CheckpointData>,
     mailbox: mpsc::Receiver<SubscriptionRequest>,
-    subscribers: Vec<mpsc::Sender<Arc<GetFullCheckpointResponse>>>,
+    subscribers: Vec<mpsc::Sender<Arc<SubscriptionCheckpoint>>>,
 
     metrics: SubscriptionMetrics,
 }
@@ -72,6 +454,7 @@ impl SubscriptionService {
             checkpoint_sender,
             SubscriptionServiceHandle {
                 sender: subscription_request_sender,
+                replay_permits: Arc::new(Semaphore::new(MAX_REPLAYING_SUBSCRIBERS)),
             },
         )
     }
@@ -122,17 +505,13 @@ impl SubscriptionService {
             self.metrics.last_recieved_checkpoint.set(sequence_number);
         }
 
-        let checkpoint =
-            match crate::service::checkpoints::checkpoint_data_to_full_checkpoint_response(
-                checkpoint,
-                &GetFullCheckpointOptions::all().into(),
-            ) {
-                Ok(checkpoint) => GetFullCheckpointResponse::from(checkpoint).pipe(Arc::new),
-                Err(e) => {
-                    error!("unable to convert checkpoint to proto: {e:?}");
-                    return;
-                }
-            };
+        let checkpoint = match SubscriptionCheckpoint::new(checkpoint) {
+            Ok(checkpoint) => Arc::new(checkpoint),
+            Err(e) => {
+                error!("unable to convert checkpoint to proto: {e:?}");
+                return;
+            }
+        };
 
         // Try to send the latest checkpoint to all subscribers. If a subscriber's channel is full
         // then they are likely too slow so we drop them.
@@ -175,3 +554,94 @@ impl SubscriptionService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+
+    #[test]
+    fn parse_move_filter() {
+        let filter: MoveFilter = "0x2".parse().unwrap();
+        assert_eq!(
+            filter,
+            MoveFilter {
+                package: ObjectID::from_single_byte(2),
+                module: None,
+                name: None,
+            }
+        );
+
+        let filter: MoveFilter = "0x2::coin::Coin".parse().unwrap();
+        assert_eq!(filter.module.as_deref(), Some("coin"));
+        assert_eq!(filter.name.as_deref(), Some("Coin"));
+
+        assert!(filter.matches(&ObjectID::from_single_byte(2), "coin", "Coin"));
+        assert!(!filter.matches(&ObjectID::from_single_byte(2), "coin", "TreasuryCap"));
+        assert!(!filter.matches(&ObjectID::from_single_byte(3), "coin", "Coin"));
+
+        let filter: MoveFilter = "0x2::coin".parse().unwrap();
+        assert!(filter.matches(&ObjectID::from_single_byte(2), "coin", "TreasuryCap"));
+        assert!(!filter.matches(&ObjectID::from_single_byte(2), "balance", "Balance"));
+    }
+
+    #[test]
+    fn parse_invalid_move_filter() {
+        assert!("".parse::<MoveFilter>().is_err());
+        assert!("coin".parse::<MoveFilter>().is_err());
+        assert!("0x2::".parse::<MoveFilter>().is_err());
+        assert!("0x2::coin::Coin::value".parse::<MoveFilter>().is_err());
+        assert!("0x2::1coin".parse::<MoveFilter>().is_err());
+    }
+
+    #[test]
+    fn parse_transaction_filter() {
+        let filter = TransactionFilter::try_from(&CheckpointTransactionFilter::default()).unwrap();
+        assert!(filter.is_empty());
+
+        let filter = TransactionFilter::try_from(&CheckpointTransactionFilter {
+            senders: vec![sui_sdk_types::Address::new([0; 32]).into()],
+            event_types: vec!["0x2::coin".to_owned()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(!filter.is_empty());
+        assert!(filter.senders.contains(&SuiAddress::ZERO));
+
+        let err = TransactionFilter::try_from(&CheckpointTransactionFilter {
+            move_calls: vec!["not a filter".to_owned()],
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(
+            tonic::Status::from(err).code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn apply_transaction_filter() {
+        let data = TestCheckpointDataBuilder::new(1)
+            .start_transaction(0)
+            .finish_transaction()
+            .start_transaction(1)
+            .finish_transaction()
+            .build_checkpoint();
+        let sender = data.transactions[0].transaction.transaction_data().sender();
+        let checkpoint = SubscriptionCheckpoint::new(data).unwrap();
+
+        let filter = TransactionFilter {
+            senders: BTreeSet::from([sender]),
+            ..Default::default()
+        };
+        let mut response = checkpoint.response.clone();
+        filter.apply(&checkpoint.data, &mut response).unwrap();
+        assert_eq!(response.transactions.len(), 1);
+
+        // A response that doesn't line up with the checkpoint's transactions can't be filtered
+        let mut response = checkpoint.response.clone();
+        response.transactions.pop();
+        let err = filter.apply(&checkpoint.data, &mut response).unwrap_err();
+        assert_eq!(tonic::Status::from(err).code(), tonic::Code::Internal);
+    }
+}
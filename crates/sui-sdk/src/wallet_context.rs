@@ -54,6 +54,21 @@ impl WalletContext {
         self.config.keystore.addresses()
     }
 
+    /// Whether the wallet's keystore is encrypted and needs to be unlocked before it can sign.
+    pub fn is_locked(&self) -> bool {
+        self.config.keystore.is_locked()
+    }
+
+    /// Unlock the wallet's encrypted keystore with its passphrase.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        self.config.keystore.unlock(passphrase)
+    }
+
+    /// Lock the wallet's encrypted keystore, dropping its decrypted keys from memory.
+    pub fn lock(&mut self) {
+        self.config.keystore.lock()
+    }
+
     pub async fn get_client(&self) -> Result<SuiClient, anyhow::Error> {
         let read = self.client.read().await;
 
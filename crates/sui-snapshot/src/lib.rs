@@ -7,7 +7,7 @@ mod tests;
 
 pub mod reader;
 pub mod uploader;
-mod writer;
+pub mod writer;
 
 use anyhow::Result;
 use fastcrypto::hash::MultisetHash;
@@ -73,6 +73,13 @@ use tokio::time::Instant;
 ///       - 1_1.obj
 ///       - ...
 ///
+/// A delta snapshot has the same layout, but its *.obj and *.ref files hold only the objects that
+/// were created or mutated since a base snapshot, and its *.rm files hold the references of the
+/// objects in the base snapshot that are no longer live, i.e. were deleted or mutated since. The
+/// base snapshot may itself be a delta, so restoring a delta means restoring the full snapshot at
+/// the start of its chain, and applying each delta in the chain in order. *.rm files use the
+/// REFERENCE file format.
+///
 /// Object File Disk Format
 ///┌──────────────────────────────┐
 ///│  magic(0x00B7EC75) <4 byte>  │
@@ -143,6 +150,7 @@ const FILE_METADATA_BYTES: usize =
 pub enum FileType {
     Object = 0,
     Reference,
+    Removed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -163,6 +171,9 @@ impl FileMetadata {
             FileType::Reference => {
                 dir_path.child(&*format!("{}_{}.ref", self.bucket_num, self.part_num))
             }
+            FileType::Removed => {
+                dir_path.child(&*format!("{}_{}.rm", self.bucket_num, self.part_num))
+            }
         }
     }
     pub fn local_file_path(&self, root_path: &std::path::Path, dir_path: &Path) -> Result<PathBuf> {
@@ -178,30 +189,62 @@ pub struct ManifestV1 {
     pub epoch: u64,
 }
 
+/// Manifest of a snapshot holding only the changes to the live object set since the snapshot at
+/// the end of `base_epoch`.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DeltaManifestV1 {
+    pub snapshot_version: u8,
+    pub address_length: u64,
+    pub file_metadata: Vec<FileMetadata>,
+    pub epoch: u64,
+    pub base_epoch: u64,
+    /// Root state hash of the live object set at the end of `epoch`, checked after applying the
+    /// delta so that a bad link in a chain of deltas is caught where it happens.
+    pub root_state_hash: ECMHLiveObjectSetDigest,
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Manifest {
     V1(ManifestV1),
+    DeltaV1(DeltaManifestV1),
 }
 
 impl Manifest {
     pub fn snapshot_version(&self) -> u8 {
         match self {
             Self::V1(manifest) => manifest.snapshot_version,
+            Self::DeltaV1(manifest) => manifest.snapshot_version,
         }
     }
     pub fn address_length(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.address_length,
+            Self::DeltaV1(manifest) => manifest.address_length,
         }
     }
     pub fn file_metadata(&self) -> &Vec<FileMetadata> {
         match self {
             Self::V1(manifest) => &manifest.file_metadata,
+            Self::DeltaV1(manifest) => &manifest.file_metadata,
         }
     }
     pub fn epoch(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.epoch,
+            Self::DeltaV1(manifest) => manifest.epoch,
+        }
+    }
+    /// Epoch of the snapshot this one applies on top of, if it is a delta.
+    pub fn base_epoch(&self) -> Option<u64> {
+        match self {
+            Self::V1(_) => None,
+            Self::DeltaV1(manifest) => Some(manifest.base_epoch),
+        }
+    }
+    pub fn root_state_hash(&self) -> Option<&ECMHLiveObjectSetDigest> {
+        match self {
+            Self::V1(_) => None,
+            Self::DeltaV1(manifest) => Some(&manifest.root_state_hash),
         }
     }
 }
@@ -2,23 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    FileMetadata, FileType, Manifest, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
-    OBJECT_ID_BYTES, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, SHA3_BYTES,
+    compute_sha3_checksum, FileMetadata, FileType, Manifest, MAGIC_BYTES, MANIFEST_FILE_MAGIC,
+    OBJECT_FILE_MAGIC, OBJECT_ID_BYTES, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
+    SHA3_BYTES,
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
 use byteorder::{BigEndian, ReadBytesExt};
 use bytes::{Buf, Bytes};
 use fastcrypto::hash::MultisetHash;
 use fastcrypto::hash::{HashFunction, Sha3_256};
-use futures::future::{AbortRegistration, Abortable};
+use futures::future::{AbortHandle, AbortRegistration, Abortable};
 use futures::{StreamExt, TryStreamExt};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use integer_encoding::VarIntReader;
 use object_store::path::Path;
-use std::collections::BTreeMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt;
 use std::fs;
 use std::fs::File;
 use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::iter::Peekable;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
@@ -33,7 +38,8 @@ use sui_storage::object_store::util::{copy_file, copy_files, path_to_filesystem}
 use sui_storage::object_store::{ObjectStoreGetExt, ObjectStoreListExt, ObjectStorePutExt};
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber};
-use tokio::sync::Mutex;
+use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
+use tokio::sync::{mpsc, Mutex};
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
 use tokio::time::Instant;
@@ -42,14 +48,30 @@ use tracing::{error, info};
 pub type SnapshotChecksums = (DigestByBucketAndPartition, Accumulator);
 pub type DigestByBucketAndPartition = BTreeMap<u32, BTreeMap<u32, [u8; 32]>>;
 pub type Sha3DigestType = Arc<Mutex<BTreeMap<u32, BTreeMap<u32, [u8; 32]>>>>;
+
+/// Where snapshots are read from.
+#[derive(Clone, Debug)]
+pub enum SnapshotSource {
+    /// Download snapshot files from a remote object store, staging them in a local one.
+    ObjectStore {
+        remote_store_config: ObjectStoreConfig,
+        local_store_config: ObjectStoreConfig,
+    },
+    /// Read snapshot files in place from a local directory laid out like a snapshot store.
+    LocalDir(PathBuf),
+}
+
 #[derive(Clone)]
 pub struct StateSnapshotReaderV1 {
     epoch: u64,
     local_staging_dir_root: PathBuf,
     remote_object_store: Arc<dyn ObjectStoreGetExt>,
-    local_object_store: Arc<dyn ObjectStorePutExt>,
     ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
     object_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    /// References of objects removed since the base snapshot, if this is a delta snapshot.
+    removed_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    base_epoch: Option<u64>,
+    root_state_hash: Option<ECMHLiveObjectSetDigest>,
     m: MultiProgress,
     concurrency: usize,
 }
@@ -98,40 +120,19 @@ impl StateSnapshotReaderV1 {
             local_staging_dir_root.clone(),
             &manifest_file_path,
         )?)?;
-        let snapshot_version = manifest.snapshot_version();
-        if snapshot_version != 1u8 {
-            return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
-        }
-        if manifest.address_length() as usize > ObjectID::LENGTH {
-            return Err(anyhow!(
-                "Max possible address length is: {}",
-                ObjectID::LENGTH
-            ));
-        }
-        if manifest.epoch() != epoch {
-            return Err(anyhow!("Download manifest is not for epoch: {}", epoch,));
-        }
-        let mut object_files = BTreeMap::new();
-        let mut ref_files = BTreeMap::new();
-        for file_metadata in manifest.file_metadata() {
-            match file_metadata.file_type {
-                FileType::Object => {
-                    let entry = object_files
-                        .entry(file_metadata.bucket_num)
-                        .or_insert_with(BTreeMap::new);
-                    entry.insert(file_metadata.part_num, file_metadata.clone());
-                }
-                FileType::Reference => {
-                    let entry = ref_files
-                        .entry(file_metadata.bucket_num)
-                        .or_insert_with(BTreeMap::new);
-                    entry.insert(file_metadata.part_num, file_metadata.clone());
-                }
-            }
-        }
+        let reader = Self::from_manifest(
+            epoch,
+            &manifest,
+            local_staging_dir_root,
+            remote_object_store.clone(),
+            m.clone(),
+            download_concurrency,
+        )?;
         let epoch_dir_path = Path::from(epoch_dir);
-        let files: Vec<Path> = ref_files
+        let files: Vec<Path> = reader
+            .ref_files
             .values()
+            .chain(reader.removed_files.values())
             .flat_map(|entry| {
                 let files: Vec<_> = entry
                     .values()
@@ -177,24 +178,171 @@ impl StateSnapshotReaderV1 {
         )
         .await?;
         progress_bar.finish_with_message("Missing ref files download complete");
+        Ok(reader)
+    }
+
+    /// Reads the snapshot for `epoch` in place from `snapshot_dir`, a local directory laid out
+    /// like a snapshot store, without any object store. Every file listed in the manifest is
+    /// checked against its SHA3 digest first.
+    pub async fn new_from_dir(
+        epoch: u64,
+        snapshot_dir: &std::path::Path,
+        concurrency: NonZeroUsize,
+        m: MultiProgress,
+    ) -> Result<Self> {
+        let epoch_dir = Path::from(format!("epoch_{}", epoch));
+        let manifest = Self::read_manifest(path_to_filesystem(
+            snapshot_dir.to_path_buf(),
+            &epoch_dir.child("MANIFEST"),
+        )?)?;
+        let reader = Self::from_manifest(
+            epoch,
+            &manifest,
+            snapshot_dir.to_path_buf(),
+            Arc::new(LocalSnapshotDir(snapshot_dir.to_path_buf())),
+            m.clone(),
+            concurrency,
+        )?;
+
+        let files = manifest.file_metadata();
+        let progress_bar = m.add(
+            ProgressBar::new(files.len() as u64).with_style(
+                ProgressStyle::with_template(
+                    "[{elapsed_precise}] {wide_bar} {pos} out of {len} files checksummed ({msg})",
+                )
+                .unwrap(),
+            ),
+        );
+        futures::stream::iter(files.clone())
+            .map(|file_metadata| {
+                let file_path = file_metadata.local_file_path(snapshot_dir, &epoch_dir);
+                tokio::task::spawn_blocking(move || {
+                    let file_path = file_path?;
+                    let sha3_digest = compute_sha3_checksum(&file_path)?;
+                    if sha3_digest != file_metadata.sha3_digest {
+                        bail!(
+                            "Checksum of {} does not match the one in its manifest",
+                            file_path.display()
+                        );
+                    }
+                    Ok(())
+                })
+            })
+            .buffer_unordered(concurrency.get())
+            .map(|result| -> Result<()> { result? })
+            .try_for_each(|()| {
+                progress_bar.inc(1);
+                futures::future::ready(Ok(()))
+            })
+            .await?;
+        progress_bar.finish_with_message("Checksumming complete");
+        Ok(reader)
+    }
+
+    /// Opens the snapshot for `epoch` from `source`.
+    pub async fn open(
+        epoch: u64,
+        source: &SnapshotSource,
+        concurrency: NonZeroUsize,
+        m: MultiProgress,
+    ) -> Result<Self> {
+        match source {
+            SnapshotSource::ObjectStore {
+                remote_store_config,
+                local_store_config,
+            } => {
+                Self::new(
+                    epoch,
+                    remote_store_config,
+                    local_store_config,
+                    concurrency,
+                    m,
+                    false, // skip_reset_local_store
+                )
+                .await
+            }
+            SnapshotSource::LocalDir(snapshot_dir) => {
+                Self::new_from_dir(epoch, snapshot_dir, concurrency, m).await
+            }
+        }
+    }
+
+    fn from_manifest(
+        epoch: u64,
+        manifest: &Manifest,
+        local_staging_dir_root: PathBuf,
+        remote_object_store: Arc<dyn ObjectStoreGetExt>,
+        m: MultiProgress,
+        concurrency: NonZeroUsize,
+    ) -> Result<Self> {
+        let snapshot_version = manifest.snapshot_version();
+        if snapshot_version != 1u8 {
+            return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
+        }
+        if manifest.address_length() as usize > ObjectID::LENGTH {
+            return Err(anyhow!(
+                "Max possible address length is: {}",
+                ObjectID::LENGTH
+            ));
+        }
+        if manifest.epoch() != epoch {
+            return Err(anyhow!("Download manifest is not for epoch: {}", epoch,));
+        }
+        let mut object_files = BTreeMap::new();
+        let mut ref_files = BTreeMap::new();
+        let mut removed_files = BTreeMap::new();
+        for file_metadata in manifest.file_metadata() {
+            let files = match file_metadata.file_type {
+                FileType::Object => &mut object_files,
+                FileType::Reference => &mut ref_files,
+                FileType::Removed => &mut removed_files,
+            };
+            files
+                .entry(file_metadata.bucket_num)
+                .or_insert_with(BTreeMap::new)
+                .insert(file_metadata.part_num, file_metadata.clone());
+        }
         Ok(StateSnapshotReaderV1 {
             epoch,
             local_staging_dir_root,
             remote_object_store,
-            local_object_store,
             ref_files,
             object_files,
+            removed_files,
+            base_epoch: manifest.base_epoch(),
+            root_state_hash: manifest.root_state_hash().cloned(),
             m,
-            concurrency: download_concurrency.get(),
+            concurrency: concurrency.get(),
         })
     }
 
+    /// Epoch of the snapshot this one applies on top of, if it is a delta snapshot.
+    pub fn base_epoch(&self) -> Option<u64> {
+        self.base_epoch
+    }
+
+    /// Root state hash recorded in the manifest of a delta snapshot.
+    pub fn root_state_hash(&self) -> Option<&ECMHLiveObjectSetDigest> {
+        self.root_state_hash.as_ref()
+    }
+
+    /// Reads the snapshot into `perpetual_db`, sending accumulators of the objects it adds on
+    /// `sender`. A delta snapshot must be read into a store holding its base snapshot, and it also
+    /// removes the objects in `removed_object_refs` from it, whose digests have to be removed from
+    /// the accumulated state.
     pub async fn read(
         &mut self,
         perpetual_db: &AuthorityPerpetualTables,
         abort_registration: AbortRegistration,
         sender: Option<tokio::sync::mpsc::Sender<(Accumulator, u64)>>,
     ) -> Result<()> {
+        if !self.removed_files.is_empty() {
+            AuthorityStore::bulk_remove_live_objects(
+                perpetual_db,
+                self.removed_object_refs()?.into_iter(),
+            )?;
+        }
+
         // This computes and stores the sha3 digest of object references in REFERENCE file for each
         // bucket partition. When downloading objects, we will match sha3 digest of object references
         // per *.obj file against this. We do this so during restore we can pre fetch object
@@ -509,6 +657,46 @@ impl StateSnapshotReaderV1 {
         )
     }
 
+    /// The bucket and part numbers of the partitions of the snapshot.
+    pub fn partitions(&self) -> Vec<(u32, u32)> {
+        self.ref_files
+            .iter()
+            .flat_map(|(bucket_num, parts)| parts.keys().map(|part_num| (*bucket_num, *part_num)))
+            .collect()
+    }
+
+    /// An iterator over all objects in a partition of the snapshot. Object files are only
+    /// available locally if the snapshot was read from a local directory, with
+    /// [`StateSnapshotReaderV1::new_from_dir`].
+    pub fn object_iter(&self, bucket_num: u32, part_num: u32) -> Result<LiveObjectIter> {
+        let file_metadata = self
+            .object_files
+            .get(&bucket_num)
+            .context(format!("No object files found for bucket: {bucket_num}"))?
+            .get(&part_num)
+            .context(format!(
+                "No object files found for bucket: {bucket_num}, part: {part_num}"
+            ))?;
+        let file_path =
+            file_metadata.local_file_path(&self.local_staging_dir_root, &self.epoch_dir())?;
+        let bytes = fs::read(&file_path)
+            .with_context(|| format!("Failed to read {}", file_path.display()))?;
+        LiveObjectIter::new(file_metadata, Bytes::from(bytes))
+    }
+
+    /// References of the objects in the base snapshot that are no longer live in this one.
+    pub fn removed_object_refs(&self) -> Result<Vec<ObjectRef>> {
+        let mut object_refs = vec![];
+        for file_metadata in self.removed_files.values().flat_map(|parts| parts.values()) {
+            object_refs.extend(ObjectRefIter::new(
+                file_metadata,
+                self.local_staging_dir_root.clone(),
+                self.epoch_dir(),
+            )?);
+        }
+        Ok(object_refs)
+    }
+
     fn buckets(&self) -> Result<Vec<u32>> {
         Ok(self.ref_files.keys().copied().collect())
     }
@@ -517,6 +705,20 @@ impl StateSnapshotReaderV1 {
         Path::from(format!("epoch_{}", self.epoch))
     }
 
+    fn local_ref_files<'a>(
+        &'a self,
+        files: &'a BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    ) -> impl Iterator<Item = LocalRefFile> + 'a {
+        files
+            .values()
+            .flat_map(|parts| parts.values())
+            .map(|file_metadata| LocalRefFile {
+                file_metadata: file_metadata.clone(),
+                root_path: self.local_staging_dir_root.clone(),
+                dir_path: self.epoch_dir(),
+            })
+    }
+
     fn read_manifest(path: PathBuf) -> anyhow::Result<Manifest> {
         let manifest_file = File::open(path)?;
         let manifest_file_size = manifest_file.metadata()?.len() as usize;
@@ -553,6 +755,208 @@ impl StateSnapshotReaderV1 {
     }
 }
 
+/// Opens readers for the chain of snapshots ending at `epoch`: the full snapshot it starts from,
+/// followed by each delta snapshot on top of it, in order.
+async fn open_snapshot_chain(
+    epoch: u64,
+    source: &SnapshotSource,
+    concurrency: NonZeroUsize,
+    m: MultiProgress,
+) -> Result<Vec<StateSnapshotReaderV1>> {
+    let mut chain = vec![];
+    let mut next_epoch = Some(epoch);
+    while let Some(epoch) = next_epoch {
+        let reader = StateSnapshotReaderV1::open(epoch, source, concurrency, m.clone()).await?;
+        next_epoch = reader.base_epoch();
+        if next_epoch.is_some_and(|base_epoch| base_epoch >= epoch) {
+            bail!("Delta snapshot for epoch {epoch} has a base epoch that is not before it");
+        }
+        chain.push(reader);
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Restores the live object set at the end of `epoch` into `perpetual_db`, from the full snapshot
+/// and chain of delta snapshots ending there. The root state hash recorded by each delta is
+/// checked as it is applied. Returns the accumulator of the restored live object set, and the
+/// number of objects in it.
+pub async fn restore_snapshot_chain(
+    epoch: u64,
+    source: &SnapshotSource,
+    concurrency: NonZeroUsize,
+    perpetual_db: &AuthorityPerpetualTables,
+    m: MultiProgress,
+) -> Result<(Accumulator, u64)> {
+    let chain = open_snapshot_chain(epoch, source, concurrency, m).await?;
+
+    let mut acc = Accumulator::default();
+    let mut num_live_objects = 0u64;
+    for mut reader in chain {
+        let removed_object_refs = reader.removed_object_refs()?;
+        num_live_objects = num_live_objects
+            .checked_sub(removed_object_refs.len() as u64)
+            .context("Delta snapshot removes more objects than are live")?;
+        acc.remove_all(removed_object_refs.iter().map(|object_ref| object_ref.2));
+
+        let (sender, mut receiver) = mpsc::channel(concurrency.get());
+        let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+        let read = reader.read(perpetual_db, abort_registration, Some(sender));
+        let accumulate = async {
+            while let Some((partial_acc, num_objects)) = receiver.recv().await {
+                acc.union(&partial_acc);
+                num_live_objects += num_objects;
+            }
+        };
+        let (result, ()) = tokio::join!(read, accumulate);
+        result?;
+
+        if let Some(root_state_hash) = reader.root_state_hash() {
+            let local_digest = ECMHLiveObjectSetDigest::from(acc.digest());
+            if *root_state_hash != local_digest {
+                bail!(
+                    "Root state hash {} of delta snapshot for epoch {} does not match local root \
+                    state hash {} after applying it",
+                    root_state_hash.digest,
+                    reader.epoch,
+                    local_digest.digest,
+                );
+            }
+        }
+    }
+    Ok((acc, num_live_objects))
+}
+
+/// Downloads the references of the live object set at the end of `epoch`, from the full snapshot
+/// and chain of delta snapshots ending there, without downloading any objects. This is the base a
+/// new delta snapshot is written against.
+pub async fn read_object_refs(
+    epoch: u64,
+    source: &SnapshotSource,
+    concurrency: NonZeroUsize,
+    m: MultiProgress,
+) -> Result<DeltaBase> {
+    let chain = open_snapshot_chain(epoch, source, concurrency, m).await?;
+
+    let mut ref_files = vec![];
+    let mut removed_files = vec![];
+    for reader in &chain {
+        ref_files.extend(reader.local_ref_files(&reader.ref_files));
+        removed_files.extend(reader.local_ref_files(&reader.removed_files));
+    }
+    Ok(DeltaBase {
+        epoch,
+        ref_files,
+        removed_files,
+    })
+}
+
+/// The live object set of the snapshot a delta snapshot is written against, as the reference
+/// files of the chain of snapshots ending at `epoch`, on local disk.
+pub struct DeltaBase {
+    pub epoch: u64,
+    ref_files: Vec<LocalRefFile>,
+    removed_files: Vec<LocalRefFile>,
+}
+
+impl DeltaBase {
+    /// Streams the references of the live object set, in object ID order.
+    pub fn object_refs(&self) -> Result<LiveObjectRefIter> {
+        let merge = |files: &[LocalRefFile]| -> Result<_> {
+            let iters = files
+                .iter()
+                .map(LocalRefFile::iter)
+                .collect::<Result<_>>()?;
+            Ok(MergedObjectRefIter::new(iters).peekable())
+        };
+        Ok(LiveObjectRefIter {
+            epoch: self.epoch,
+            object_refs: merge(&self.ref_files)?,
+            removed_object_refs: merge(&self.removed_files)?,
+        })
+    }
+}
+
+/// A *.ref or *.rm file of a snapshot, on local disk.
+struct LocalRefFile {
+    file_metadata: FileMetadata,
+    root_path: PathBuf,
+    dir_path: Path,
+}
+
+impl LocalRefFile {
+    fn iter(&self) -> Result<ObjectRefIter> {
+        ObjectRefIter::new(
+            &self.file_metadata,
+            self.root_path.clone(),
+            self.dir_path.clone(),
+        )
+    }
+}
+
+/// Merges iterators over object refs that are each in order, into one iterator over all of them
+/// in order. Every file of a snapshot lists its object refs in object ID order, because they are
+/// written while iterating over the live object set.
+struct MergedObjectRefIter {
+    iters: Vec<ObjectRefIter>,
+    heads: BinaryHeap<Reverse<(ObjectRef, usize)>>,
+}
+
+impl MergedObjectRefIter {
+    fn new(mut iters: Vec<ObjectRefIter>) -> Self {
+        let heads = iters
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, iter)| Some(Reverse((iter.next()?, i))))
+            .collect();
+        Self { iters, heads }
+    }
+}
+
+impl Iterator for MergedObjectRefIter {
+    type Item = ObjectRef;
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((object_ref, i)) = self.heads.pop()?;
+        if let Some(next) = self.iters[i].next() {
+            self.heads.push(Reverse((next, i)));
+        }
+        Some(object_ref)
+    }
+}
+
+/// An iterator over the live object set of a chain of snapshots, in object ID order: the object
+/// refs in any of its snapshots that a later delta snapshot does not remove.
+pub struct LiveObjectRefIter {
+    epoch: u64,
+    object_refs: Peekable<MergedObjectRefIter>,
+    removed_object_refs: Peekable<MergedObjectRefIter>,
+}
+
+impl Iterator for LiveObjectRefIter {
+    type Item = Result<ObjectRef>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let object_ref = self.object_refs.next();
+            let Some(removed) = self.removed_object_refs.peek().copied() else {
+                return object_ref.map(Ok);
+            };
+            if object_ref == Some(removed) {
+                self.removed_object_refs.next();
+            } else if object_ref.map_or(true, |object_ref| removed < object_ref) {
+                // Both are in order, so this was never live in the chain.
+                self.removed_object_refs.next();
+                return Some(Err(anyhow!(
+                    "Snapshot chain ending at epoch {} removes {:?}, which is not live in its base",
+                    self.epoch,
+                    removed,
+                )));
+            } else {
+                return object_ref.map(Ok);
+            }
+        }
+    }
+}
+
 pub async fn download_bytes(
     remote_object_store: Arc<dyn ObjectStoreGetExt>,
     file_metadata: &FileMetadata,
@@ -602,6 +1006,26 @@ pub async fn download_bytes(
     (bytes, sha3_digest)
 }
 
+/// Serves snapshot files in place from a local directory.
+struct LocalSnapshotDir(PathBuf);
+
+impl fmt::Display for LocalSnapshotDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+#[async_trait]
+impl ObjectStoreGetExt for LocalSnapshotDir {
+    async fn get_bytes(&self, src: &Path) -> Result<Bytes> {
+        let file_path = path_to_filesystem(self.0.clone(), src)?;
+        let bytes = tokio::fs::read(&file_path)
+            .await
+            .with_context(|| format!("Failed to read {}", file_path.display()))?;
+        Ok(Bytes::from(bytes))
+    }
+}
+
 /// An iterator over all object refs in a .ref file.
 pub struct ObjectRefIter {
     reader: Box<dyn Read>,
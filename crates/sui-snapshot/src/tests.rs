@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::reader::StateSnapshotReaderV1;
+use crate::reader::{
+    read_object_refs, restore_snapshot_chain, SnapshotSource, StateSnapshotReaderV1,
+};
 use crate::writer::StateSnapshotWriterV1;
 use crate::FileCompression;
 use fastcrypto::hash::MultisetHash;
@@ -12,12 +14,13 @@ use std::num::NonZeroUsize;
 use std::sync::Arc;
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::authority::AuthorityStore;
 use sui_core::state_accumulator::StateAccumulator;
 use sui_protocol_config::ProtocolConfig;
 use sui_types::accumulator::Accumulator;
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{ObjectID, SequenceNumber};
 use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
-use sui_types::object::Object;
+use sui_types::object::{Object, Owner};
 use tempfile::tempdir;
 
 fn temp_dir() -> std::path::PathBuf {
@@ -181,3 +184,198 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     )?;
     Ok(())
 }
+
+fn file_store_config(directory: std::path::PathBuf) -> ObjectStoreConfig {
+    ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(directory),
+        ..Default::default()
+    }
+}
+
+/// Deletes the first `num_deleted` objects in `ids`, and bumps the version of the next
+/// `num_mutated`.
+fn delete_and_mutate(
+    db: &AuthorityPerpetualTables,
+    ids: &[ObjectID],
+    num_deleted: usize,
+    num_mutated: usize,
+    version: u64,
+) -> Result<(), anyhow::Error> {
+    let live_refs: Vec<_> = db
+        .iter_live_object_set(true)
+        .map(|live_object| live_object.object_reference())
+        .filter(|object_ref| ids[..num_deleted].contains(&object_ref.0))
+        .collect();
+    AuthorityStore::bulk_remove_live_objects(db, live_refs.into_iter())?;
+    for id in &ids[num_deleted..num_deleted + num_mutated] {
+        db.insert_object_test_only(Object::with_id_owner_version_for_testing(
+            *id,
+            SequenceNumber::from_u64(version),
+            Owner::Immutable,
+        ))?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delta_snapshot_chain() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let restored_db_path = temp_dir();
+    let local_store_config = file_store_config(temp_dir().join("local_dir"));
+    let remote_store_config = file_store_config(temp_dir().join("remote_dir"));
+    let base_source = SnapshotSource::ObjectStore {
+        remote_store_config: remote_store_config.clone(),
+        local_store_config: file_store_config(temp_dir().join("local_dir_base")),
+    };
+    let restore_source = SnapshotSource::ObjectStore {
+        remote_store_config: remote_store_config.clone(),
+        local_store_config: file_store_config(temp_dir().join("local_dir_restore")),
+    };
+    let writer = || {
+        StateSnapshotWriterV1::new(
+            &local_store_config,
+            &remote_store_config,
+            FileCompression::Zstd,
+            NonZeroUsize::new(1).unwrap(),
+        )
+    };
+
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_keys(&perpetual_db, 100)?;
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    writer()
+        .await?
+        .write_internal(0, true, perpetual_db.clone(), root_accumulator)
+        .await?;
+
+    // Epoch 1 deletes 10 objects, mutates 10 and creates 10.
+    let ids = ObjectID::in_range(ObjectID::ZERO, 110)?;
+    delete_and_mutate(&perpetual_db, &ids, 10, 10, 2)?;
+    for id in &ids[100..] {
+        perpetual_db.insert_object_test_only(Object::immutable_with_id_for_testing(*id))?;
+    }
+    let base = read_object_refs(
+        0,
+        &base_source,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await?;
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    writer()
+        .await?
+        .write_delta_internal(1, base, true, perpetual_db.clone(), root_accumulator)
+        .await?;
+
+    // The live object set of a chain of snapshots is streamed in object ID order.
+    let base = read_object_refs(
+        1,
+        &base_source,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await?;
+    let base_object_refs = base.object_refs()?.collect::<Result<Vec<_>, _>>()?;
+    let live_object_refs: Vec<_> = perpetual_db
+        .iter_live_object_set(true)
+        .map(|object| object.object_reference())
+        .collect();
+    assert_eq!(base_object_refs.len(), 100);
+    assert_eq!(base_object_refs, live_object_refs);
+
+    // Epoch 2 deletes one of the objects created in epoch 1, and mutates some more.
+    delete_and_mutate(&perpetual_db, &ids[100..], 1, 5, 3)?;
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    writer()
+        .await?
+        .write_delta_internal(2, base, true, perpetual_db.clone(), root_accumulator)
+        .await?;
+
+    // The delta for epoch 1 only holds the objects that changed.
+    let delta_reader = StateSnapshotReaderV1::open(
+        1,
+        &base_source,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await?;
+    assert_eq!(delta_reader.base_epoch(), Some(0));
+    assert_eq!(delta_reader.removed_object_refs()?.len(), 20);
+    assert_eq!(delta_reader.ref_iter(1, 1)?.count(), 20);
+
+    let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
+    let (restored_accumulator, num_live_objects) = restore_snapshot_chain(
+        2,
+        &restore_source,
+        NonZeroUsize::new(1).unwrap(),
+        &restored_perpetual_db,
+        MultiProgress::new(),
+    )
+    .await?;
+    compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
+    assert_eq!(num_live_objects, 99);
+    assert_eq!(
+        ECMHLiveObjectSetDigest::from(restored_accumulator.digest()),
+        root_accumulator,
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_restore_from_dir() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let restored_db_path = temp_dir();
+    let remote = temp_dir().join("remote_dir");
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &file_store_config(temp_dir().join("local_dir")),
+        &file_store_config(remote.clone()),
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_keys(&perpetual_db, 1000)?;
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    snapshot_writer
+        .write_internal(0, true, perpetual_db.clone(), root_accumulator.clone())
+        .await?;
+
+    let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
+    let (restored_accumulator, num_live_objects) = restore_snapshot_chain(
+        0,
+        &SnapshotSource::LocalDir(remote.clone()),
+        NonZeroUsize::new(1).unwrap(),
+        &restored_perpetual_db,
+        MultiProgress::new(),
+    )
+    .await?;
+    compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
+    assert_eq!(num_live_objects, 1000);
+    assert_eq!(
+        ECMHLiveObjectSetDigest::from(restored_accumulator.digest()),
+        root_accumulator,
+    );
+
+    // A file that does not match the digest in its manifest is rejected before anything is read.
+    let object_file = remote.join("epoch_0").join("1_1.obj");
+    let mut bytes = std::fs::read(&object_file)?;
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    std::fs::write(&object_file, bytes)?;
+    let err = StateSnapshotReaderV1::new_from_dir(
+        0,
+        &remote,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+    )
+    .await
+    .err()
+    .expect("Corrupted snapshot should fail verification");
+    assert!(err.to_string().contains("does not match"), "{err}");
+    Ok(())
+}
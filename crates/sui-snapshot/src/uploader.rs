@@ -1,15 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::reader::{read_object_refs, SnapshotSource};
 use crate::writer::StateSnapshotWriterV1;
 use anyhow::Result;
 use bytes::Bytes;
+use indicatif::{MultiProgress, ProgressDrawTarget};
+use object_store::path::Path;
 use object_store::DynObjectStore;
 use prometheus::{
     register_int_counter_with_registry, register_int_gauge_with_registry, IntCounter, IntGauge,
     Registry,
 };
-use std::num::NonZeroUsize;
+use std::fs;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -18,7 +22,7 @@ use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::checkpoints::CheckpointStore;
 use sui_core::db_checkpoint_handler::{STATE_SNAPSHOT_COMPLETED_MARKER, SUCCESS_MARKER};
 use sui_storage::object_store::util::{
-    find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, path_to_filesystem, put,
+    exists, find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, path_to_filesystem, put,
     run_manifest_update_loop,
 };
 use sui_storage::FileCompression;
@@ -64,6 +68,11 @@ pub struct StateSnapshotUploader {
     staging_store: Arc<DynObjectStore>,
     /// Remote store i.e. S3, GCS, etc where state snapshots are uploaded to
     snapshot_store: Arc<DynObjectStore>,
+    /// Config of the remote store, to read back the snapshot a delta snapshot is based on
+    snapshot_store_config: ObjectStoreConfig,
+    /// Number of epochs between full state snapshots, with delta snapshots against the previous
+    /// epoch in between. Every snapshot is a full one if unset
+    full_snapshot_interval_epochs: Option<NonZeroU64>,
     /// Time interval to check for presence of new db checkpoint
     interval: Duration,
     metrics: Arc<StateSnapshotUploaderMetrics>,
@@ -77,6 +86,7 @@ impl StateSnapshotUploader {
         db_checkpoint_path: &std::path::Path,
         staging_path: &std::path::Path,
         snapshot_store_config: ObjectStoreConfig,
+        full_snapshot_interval_epochs: Option<NonZeroU64>,
         interval_s: u64,
         registry: &Registry,
         checkpoint_store: Arc<CheckpointStore>,
@@ -99,6 +109,8 @@ impl StateSnapshotUploader {
             staging_path: staging_path.to_path_buf(),
             staging_store: staging_store_config.make()?,
             snapshot_store: snapshot_store_config.make()?,
+            snapshot_store_config,
+            full_snapshot_interval_epochs,
             interval: Duration::from_secs(interval_s),
             metrics: StateSnapshotUploaderMetrics::new(registry),
             chain_identifier,
@@ -145,9 +157,32 @@ impl StateSnapshotUploader {
                     .last()
                     .expect("Expected at least one commitment")
                     .clone();
-                state_snapshot_writer
-                    .write(*epoch, db, state_hash_commitment, self.chain_identifier)
-                    .await?;
+                match self.delta_base_epoch(*epoch).await {
+                    Some(base_epoch) => {
+                        let base = read_object_refs(
+                            base_epoch,
+                            &self.delta_base_source(),
+                            NonZeroUsize::new(20).unwrap(),
+                            MultiProgress::with_draw_target(ProgressDrawTarget::hidden()),
+                        )
+                        .await?;
+                        state_snapshot_writer
+                            .write_delta(
+                                *epoch,
+                                base,
+                                db,
+                                state_hash_commitment,
+                                self.chain_identifier,
+                            )
+                            .await?;
+                        fs::remove_dir_all(self.delta_base_path())?;
+                    }
+                    None => {
+                        state_snapshot_writer
+                            .write(*epoch, db, state_hash_commitment, self.chain_identifier)
+                            .await?;
+                    }
+                }
                 info!("State snapshot creation successful for epoch: {}", *epoch);
                 // Drop marker in the output directory that upload completed successfully
                 let bytes = Bytes::from_static(b"success");
@@ -211,6 +246,36 @@ impl StateSnapshotUploader {
         Ok(())
     }
 
+    /// The epoch the snapshot for `epoch` is written as a delta against, if any. That is the
+    /// previous epoch, unless a full snapshot is due or there is no snapshot for it to build on.
+    async fn delta_base_epoch(&self, epoch: u64) -> Option<u64> {
+        let interval = self.full_snapshot_interval_epochs?;
+        if epoch == 0 || epoch % interval.get() == 0 {
+            return None;
+        }
+        let base_epoch = epoch - 1;
+        let success_marker = Path::from(format!("epoch_{base_epoch}")).child(SUCCESS_MARKER);
+        exists(&self.snapshot_store, &success_marker)
+            .await
+            .then_some(base_epoch)
+    }
+
+    /// Staging directory for the reference files of the snapshot a delta is written against.
+    fn delta_base_path(&self) -> PathBuf {
+        self.staging_path.join("delta_base")
+    }
+
+    fn delta_base_source(&self) -> SnapshotSource {
+        SnapshotSource::ObjectStore {
+            remote_store_config: self.snapshot_store_config.clone(),
+            local_store_config: ObjectStoreConfig {
+                object_store: Some(ObjectStoreType::File),
+                directory: Some(self.delta_base_path()),
+                ..Default::default()
+            },
+        }
+    }
+
     async fn get_missing_epochs(&self) -> Result<Vec<u64>> {
         let missing_epochs = find_missing_epochs_dirs(&self.snapshot_store, SUCCESS_MARKER).await?;
         Ok(missing_epochs.to_vec())
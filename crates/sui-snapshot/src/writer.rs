@@ -1,13 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-#![allow(dead_code)]
 
+use crate::reader::DeltaBase;
 use crate::{
-    compute_sha3_checksum, create_file_metadata, FileCompression, FileMetadata, FileType, Manifest,
-    ManifestV1, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
-    OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
+    compute_sha3_checksum, create_file_metadata, DeltaManifestV1, FileCompression, FileMetadata,
+    FileType, Manifest, ManifestV1, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC,
+    OBJECT_FILE_MAGIC, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
 };
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use byteorder::{BigEndian, ByteOrder};
 use fastcrypto::hash::MultisetHash;
 use futures::StreamExt;
@@ -193,19 +193,124 @@ impl LiveObjectSetWriterV1 {
         Ok(())
     }
     fn write_object_ref(&mut self, object_ref: &ObjectRef) -> Result<()> {
-        let mut buf = [0u8; OBJECT_REF_BYTES];
-        buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
-        BigEndian::write_u64(
-            &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
-            object_ref.1.value(),
-        );
-        buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
-            .copy_from_slice(object_ref.2.as_ref());
-        self.ref_wbuf.write_all(&buf)?;
+        self.ref_wbuf.write_all(&encode_object_ref(object_ref))?;
         Ok(())
     }
 }
 
+/// RemovedObjectRefWriterV1 writes the references of objects removed from the live object set
+/// since the base of a delta snapshot to *.rm files. No file is created until the first reference
+/// is written.
+struct RemovedObjectRefWriterV1 {
+    dir_path: PathBuf,
+    bucket_num: u32,
+    current_part_num: u32,
+    wbuf: Option<BufWriter<File>>,
+    n: usize,
+    files: Vec<FileMetadata>,
+    sender: Option<Sender<FileMetadata>>,
+    file_compression: FileCompression,
+}
+
+impl RemovedObjectRefWriterV1 {
+    fn new(
+        dir_path: PathBuf,
+        bucket_num: u32,
+        file_compression: FileCompression,
+        sender: Sender<FileMetadata>,
+    ) -> Self {
+        RemovedObjectRefWriterV1 {
+            dir_path,
+            bucket_num,
+            current_part_num: 0,
+            wbuf: None,
+            n: 0,
+            files: vec![],
+            sender: Some(sender),
+            file_compression,
+        }
+    }
+    pub fn write(&mut self, object_ref: &ObjectRef) -> Result<()> {
+        if self.wbuf.is_none() || self.n + OBJECT_REF_BYTES > FILE_MAX_BYTES {
+            self.cut()?;
+        }
+        let wbuf = self
+            .wbuf
+            .as_mut()
+            .context("Unexpected missing removed file writer")?;
+        wbuf.write_all(&encode_object_ref(object_ref))?;
+        self.n += OBJECT_REF_BYTES;
+        Ok(())
+    }
+    pub fn done(mut self) -> Result<Vec<FileMetadata>> {
+        self.finalize()?;
+        self.sender = None;
+        Ok(self.files.clone())
+    }
+    fn removed_file(dir_path: PathBuf, bucket_num: u32, part_num: u32) -> Result<(usize, File)> {
+        let removed_path = dir_path.join(format!("{bucket_num}_{part_num}.rm"));
+        let removed_tmp_path = dir_path.join(format!("{bucket_num}_{part_num}.rm.tmp"));
+        let mut f = File::create(removed_tmp_path.clone())?;
+        f.rewind()?;
+        let mut metab = [0u8; MAGIC_BYTES];
+        BigEndian::write_u32(&mut metab, REFERENCE_FILE_MAGIC);
+        let n = f.write(&metab)?;
+        drop(f);
+        fs::rename(removed_tmp_path, removed_path.clone())?;
+        let mut f = OpenOptions::new().append(true).open(removed_path)?;
+        f.seek(SeekFrom::Start(n as u64))?;
+        Ok((n, f))
+    }
+    fn cut(&mut self) -> Result<()> {
+        self.finalize()?;
+        self.current_part_num += 1;
+        let (n, f) = Self::removed_file(
+            self.dir_path.clone(),
+            self.bucket_num,
+            self.current_part_num,
+        )?;
+        self.n = n;
+        self.wbuf = Some(BufWriter::new(f));
+        Ok(())
+    }
+    fn finalize(&mut self) -> Result<()> {
+        let Some(wbuf) = &mut self.wbuf else {
+            return Ok(());
+        };
+        wbuf.flush()?;
+        wbuf.get_ref().sync_data()?;
+        let off = wbuf.get_ref().stream_position()?;
+        wbuf.get_ref().set_len(off)?;
+        let file_path = self
+            .dir_path
+            .join(format!("{}_{}.rm", self.bucket_num, self.current_part_num));
+        let file_metadata = create_file_metadata(
+            &file_path,
+            self.file_compression,
+            FileType::Removed,
+            self.bucket_num,
+            self.current_part_num,
+        )?;
+        self.files.push(file_metadata.clone());
+        if let Some(sender) = &self.sender {
+            sender.blocking_send(file_metadata)?;
+        }
+        Ok(())
+    }
+}
+
+fn encode_object_ref(object_ref: &ObjectRef) -> [u8; OBJECT_REF_BYTES] {
+    let mut buf = [0u8; OBJECT_REF_BYTES];
+    buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
+    BigEndian::write_u64(
+        &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
+        object_ref.1.value(),
+    );
+    buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
+        .copy_from_slice(object_ref.2.as_ref());
+    buf
+}
+
 /// StateSnapshotWriterV1 writes snapshot files to a local staging dir and simultaneously uploads them
 /// to a remote object store
 pub struct StateSnapshotWriterV1 {
@@ -279,9 +384,80 @@ impl StateSnapshotWriterV1 {
         .await
     }
 
+    /// Writes a delta snapshot for `epoch`, holding only the changes to the live object set since
+    /// `base`, which is read with [crate::reader::read_object_refs].
+    pub async fn write_delta(
+        self,
+        epoch: u64,
+        base: DeltaBase,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        root_state_hash: ECMHLiveObjectSetDigest,
+        chain_identifier: ChainIdentifier,
+    ) -> Result<()> {
+        let system_state_object = get_sui_system_state(&perpetual_db)?;
+
+        let protocol_version = system_state_object.protocol_version();
+        let protocol_config = ProtocolConfig::get_for_version(
+            ProtocolVersion::new(protocol_version),
+            chain_identifier.chain(),
+        );
+        let include_wrapped_tombstone = !protocol_config.simplified_unwrap_then_delete();
+        self.write_delta_internal(
+            epoch,
+            base,
+            include_wrapped_tombstone,
+            perpetual_db,
+            root_state_hash,
+        )
+        .await
+    }
+
     pub(crate) async fn write_internal(
+        self,
+        epoch: u64,
+        include_wrapped_tombstone: bool,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        root_state_hash: ECMHLiveObjectSetDigest,
+    ) -> Result<()> {
+        self.write_snapshot(
+            epoch,
+            None,
+            include_wrapped_tombstone,
+            perpetual_db,
+            root_state_hash,
+        )
+        .await
+    }
+
+    pub(crate) async fn write_delta_internal(
+        self,
+        epoch: u64,
+        base: DeltaBase,
+        include_wrapped_tombstone: bool,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        root_state_hash: ECMHLiveObjectSetDigest,
+    ) -> Result<()> {
+        if base.epoch >= epoch {
+            return Err(anyhow!(
+                "Base epoch {} of delta snapshot must be before epoch {}",
+                base.epoch,
+                epoch
+            ));
+        }
+        self.write_snapshot(
+            epoch,
+            Some(base),
+            include_wrapped_tombstone,
+            perpetual_db,
+            root_state_hash,
+        )
+        .await
+    }
+
+    async fn write_snapshot(
         mut self,
         epoch: u64,
+        base: Option<DeltaBase>,
         include_wrapped_tombstone: bool,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         root_state_hash: ECMHLiveObjectSetDigest,
@@ -298,6 +474,7 @@ impl StateSnapshotWriterV1 {
         let write_handler = tokio::task::spawn_blocking(move || {
             self.write_live_object_set(
                 epoch,
+                base,
                 perpetual_db,
                 sender,
                 Self::bucket_func,
@@ -367,6 +544,7 @@ impl StateSnapshotWriterV1 {
     fn write_live_object_set<F>(
         &mut self,
         epoch: u64,
+        base: Option<DeltaBase>,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         sender: Sender<FileMetadata>,
         bucket_func: F,
@@ -380,8 +558,41 @@ impl StateSnapshotWriterV1 {
         let local_staging_dir_path =
             path_to_filesystem(self.local_staging_dir.clone(), &self.epoch_dir(epoch))?;
         let mut acc = Accumulator::default();
+        let mut base_object_refs = match &base {
+            Some(base) => Some(base.object_refs()?.peekable()),
+            None => None,
+        };
+        let mut removed_writer = RemovedObjectRefWriterV1::new(
+            local_staging_dir_path.clone(),
+            1,
+            self.file_compression,
+            sender.clone(),
+        );
         for object in perpetual_db.iter_live_object_set(include_wrapped_tombstone) {
             StateAccumulator::accumulate_live_object(&mut acc, &object);
+            if let Some(base_object_refs) = &mut base_object_refs {
+                // The base and the live object set are both in object ID order, so base objects
+                // ordered before this one have been deleted since. Objects that are unchanged
+                // since the base are left out of the delta, and the base version of mutated ones
+                // is removed before the new one is added.
+                let object_ref = object.object_reference();
+                let mut unchanged = false;
+                while let Some(base_ref) = base_object_refs.next_if(|base_ref| {
+                    base_ref
+                        .as_ref()
+                        .map_or(true, |base_ref| base_ref.0 <= object_ref.0)
+                }) {
+                    let base_ref = base_ref?;
+                    if base_ref == object_ref {
+                        unchanged = true;
+                    } else {
+                        removed_writer.write(&base_ref)?;
+                    }
+                }
+                if unchanged {
+                    continue;
+                }
+            }
             let bucket_num = bucket_func(&object);
             if let Vacant(entry) = object_writers.entry(bucket_num) {
                 entry.insert(LiveObjectSetWriterV1::new(
@@ -405,19 +616,36 @@ impl StateSnapshotWriterV1 {
         for (_, writer) in object_writers.into_iter() {
             files.extend(writer.done()?);
         }
-        self.write_manifest(epoch, files)?;
+        let manifest = match base {
+            None => Manifest::V1(ManifestV1 {
+                snapshot_version: 1,
+                address_length: ObjectID::LENGTH as u64,
+                file_metadata: files,
+                epoch,
+            }),
+            Some(base) => {
+                // Whatever is left of the base live object set has been deleted since.
+                for base_ref in base_object_refs.into_iter().flatten() {
+                    removed_writer.write(&base_ref?)?;
+                }
+                files.extend(removed_writer.done()?);
+                Manifest::DeltaV1(DeltaManifestV1 {
+                    snapshot_version: 1,
+                    address_length: ObjectID::LENGTH as u64,
+                    file_metadata: files,
+                    epoch,
+                    base_epoch: base.epoch,
+                    root_state_hash,
+                })
+            }
+        };
+        self.write_manifest(epoch, manifest)?;
         Ok(())
     }
 
-    fn write_manifest(&mut self, epoch: u64, file_metadata: Vec<FileMetadata>) -> Result<()> {
+    fn write_manifest(&mut self, epoch: u64, manifest: Manifest) -> Result<()> {
         let (f, manifest_file_path) = self.manifest_file(epoch)?;
         let mut wbuf = BufWriter::new(f);
-        let manifest: Manifest = Manifest::V1(ManifestV1 {
-            snapshot_version: 1,
-            address_length: ObjectID::LENGTH as u64,
-            file_metadata,
-            epoch,
-        });
         let serialized_manifest = bcs::to_bytes(&manifest)?;
         wbuf.write_all(&serialized_manifest)?;
         wbuf.flush()?;
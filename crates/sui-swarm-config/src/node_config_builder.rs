@@ -209,6 +209,7 @@ impl ValidatorConfigBuilder {
             name_service_registry_id: None,
             name_service_reverse_registry_id: None,
             transaction_deny_config: Default::default(),
+            transaction_deny_config_path: None,
             certificate_deny_config: Default::default(),
             state_debug_dump_config: Default::default(),
             state_archive_write_config: StateArchiveConfig::default(),
@@ -510,6 +511,7 @@ impl FullnodeConfigBuilder {
             name_service_registry_id: None,
             name_service_reverse_registry_id: None,
             transaction_deny_config: Default::default(),
+            transaction_deny_config_path: None,
             certificate_deny_config: Default::default(),
             state_debug_dump_config: Default::default(),
             state_archive_write_config: StateArchiveConfig::default(),
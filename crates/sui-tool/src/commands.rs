@@ -6,8 +6,9 @@ use crate::{
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
     download_db_snapshot, download_formal_snapshot, dump_checkpoints_from_archive,
     get_latest_available_epoch, get_object, get_transaction_block, make_clients,
-    restore_from_db_checkpoint, verify_archive, verify_archive_by_checksum, ConciseObjectOutput,
-    GroupedObjectOutput, SnapshotVerifyMode, VerboseObjectOutput,
+    restore_formal_snapshot, restore_from_db_checkpoint, verify_archive,
+    verify_archive_by_checksum, ConciseObjectOutput, GroupedObjectOutput, SnapshotVerifyMode,
+    VerboseObjectOutput,
 };
 use anyhow::Result;
 use futures::{future::join_all, StreamExt};
@@ -26,10 +27,12 @@ use mysocial_types::{
 
 use clap::*;
 use fastcrypto::encoding::Encoding;
-use sui_archival::{read_manifest_as_json, write_manifest_from_json};
+use sui_archival::{read_manifest_as_json, rechunk_archive, write_manifest_from_json};
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
 use sui_config::Config;
 use sui_core::authority_aggregator::AuthorityAggregatorBuilder;
+use sui_snapshot::reader::SnapshotSource;
+use sui_storage::FileCompression;
 use sui_types::messages_checkpoint::{
     CheckpointRequest, CheckpointResponse, CheckpointSequenceNumber,
 };
@@ -164,6 +167,28 @@ pub enum ToolCommand {
         download_concurrency: usize,
     },
 
+    /// Tool to rewrite an existing archive into a new one with a different file size and
+    /// compression, without re-syncing checkpoints from the network
+    #[command(name = "rechunk-archive")]
+    RechunkArchive {
+        #[command(flatten)]
+        object_store_config: ObjectStoreConfig,
+        /// Local directory to write the rewritten archive to. Must be empty.
+        #[arg(long = "dest-dir")]
+        dest_dir: PathBuf,
+        /// Local directory to stage files in before they are moved to `--dest-dir`.
+        #[arg(long = "staging-dir")]
+        staging_dir: PathBuf,
+        /// Size in bytes after which a new checkpoint file is started.
+        #[arg(long = "commit-file-size", default_value_t = 256 * 1024 * 1024)]
+        commit_file_size: usize,
+        /// Write files without zstd compression.
+        #[arg(long = "no-compression")]
+        no_compression: bool,
+        #[arg(default_value_t = 5)]
+        download_concurrency: usize,
+    },
+
     /// Tool to print archive contents in checkpoint range
     #[command(name = "dump-archive")]
     DumpArchiveByChecksum {
@@ -378,6 +403,58 @@ pub enum ToolCommand {
         all_checkpoints: bool,
     },
 
+    /// Restore from a formal snapshot on local disk, e.g. one copied to an air-gapped host. Snapshot
+    /// files are read in place and checked against the digests in their manifest, and checkpoint
+    /// summaries are read from a local archive, so no network access is needed.
+    #[clap(
+        name = "restore-formal-snapshot",
+        about = "Restores database from a formal snapshot in a local directory, outputs to local disk"
+    )]
+    RestoreFormalSnapshot {
+        #[clap(long = "epoch", conflicts_with = "latest")]
+        epoch: Option<u64>,
+        #[clap(long = "genesis")]
+        genesis: PathBuf,
+        #[clap(long = "path")]
+        path: PathBuf,
+        /// Directory holding the snapshot, laid out like a snapshot bucket: an `epoch_<N>`
+        /// directory per epoch, and a top level MANIFEST if `--latest` is used.
+        #[clap(long = "from-dir")]
+        from_dir: PathBuf,
+        /// Directory holding a checkpoint archive to read checkpoint summaries from.
+        #[clap(long = "archive-dir")]
+        archive_dir: PathBuf,
+        /// Number of files to read in parallel. Defaults to a reasonable
+        /// value based on number of available logical cores.
+        #[clap(long = "num-parallel-reads")]
+        num_parallel_reads: Option<usize>,
+        /// Verification mode to employ.
+        #[clap(long = "verify", default_value = "normal")]
+        verify: Option<SnapshotVerifyMode>,
+        /// Network the snapshot is for. Defaults to "mainnet".
+        #[clap(long = "network", default_value = "mainnet")]
+        network: Chain,
+        /// Restore the snapshot of the latest epoch in `--from-dir`.
+        /// If `--epoch` is specified, then this flag gets ignored.
+        #[clap(
+            long = "latest",
+            conflicts_with = "epoch",
+            help = "defaults to latest available snapshot in --from-dir"
+        )]
+        latest: bool,
+        /// If false (default), log level will be overridden to "off",
+        /// and output will be reduced to necessary status information.
+        #[clap(long = "verbose")]
+        verbose: bool,
+
+        /// If provided, all checkpoint summaries from genesis to the end of the target epoch
+        /// will be read and (if --verify is provided) full checkpoint chain verification
+        /// will be performed. If omitted, only end of epoch checkpoint summaries will be
+        /// read, and (if --verify is provided) will be verified via committee signature.
+        #[clap(long = "all-checkpoints")]
+        all_checkpoints: bool,
+    },
+
     #[clap(name = "replay")]
     Replay {
         #[arg(long = "rpc")]
@@ -881,6 +958,72 @@ impl ToolCommand {
                 )
                 .await?;
             }
+            ToolCommand::RestoreFormalSnapshot {
+                epoch,
+                genesis,
+                path,
+                from_dir,
+                archive_dir,
+                num_parallel_reads,
+                verify,
+                network,
+                latest,
+                verbose,
+                all_checkpoints,
+            } => {
+                if !verbose {
+                    tracing_handle
+                        .update_log("off")
+                        .expect("Failed to update log level");
+                }
+                let num_parallel_reads = num_parallel_reads.unwrap_or_else(|| {
+                    num_cpus::get()
+                        .checked_sub(1)
+                        .expect("Failed to get number of CPUs")
+                });
+                let latest_available_epoch = if latest {
+                    let snapshot_store_config = ObjectStoreConfig {
+                        object_store: Some(ObjectStoreType::File),
+                        directory: Some(from_dir.clone()),
+                        ..Default::default()
+                    };
+                    Some(get_latest_available_epoch(&snapshot_store_config).await?)
+                } else {
+                    None
+                };
+                let epoch_to_restore = epoch.or(latest_available_epoch).expect(
+                    "Either pass epoch with --epoch <epoch_num> or use latest with --latest",
+                );
+
+                let success_marker = from_dir
+                    .join(format!("epoch_{}", epoch_to_restore))
+                    .join("_SUCCESS");
+                if !success_marker.exists() {
+                    panic!(
+                        "Aborting snapshot restore: missing success marker at {}, snapshot may be incomplete",
+                        success_marker.display()
+                    );
+                }
+
+                let archive_store_config = ObjectStoreConfig {
+                    object_store: Some(ObjectStoreType::File),
+                    directory: Some(archive_dir),
+                    ..Default::default()
+                };
+                let verify = verify.unwrap_or_default();
+                restore_formal_snapshot(
+                    &path,
+                    epoch_to_restore,
+                    &genesis,
+                    SnapshotSource::LocalDir(from_dir),
+                    archive_store_config,
+                    num_parallel_reads,
+                    network,
+                    verify,
+                    all_checkpoints,
+                )
+                .await?;
+            }
             ToolCommand::DownloadDBSnapshot {
                 epoch,
                 path,
@@ -1067,6 +1210,39 @@ impl ToolCommand {
             } => {
                 verify_archive_by_checksum(object_store_config, download_concurrency).await?;
             }
+            ToolCommand::RechunkArchive {
+                object_store_config,
+                dest_dir,
+                staging_dir,
+                commit_file_size,
+                no_compression,
+                download_concurrency,
+            } => {
+                let local_store_config = ObjectStoreConfig {
+                    object_store: Some(ObjectStoreType::File),
+                    directory: Some(staging_dir),
+                    ..Default::default()
+                };
+                let dest_store_config = ObjectStoreConfig {
+                    object_store: Some(ObjectStoreType::File),
+                    directory: Some(dest_dir),
+                    ..Default::default()
+                };
+                let file_compression = if no_compression {
+                    FileCompression::None
+                } else {
+                    FileCompression::Zstd
+                };
+                rechunk_archive(
+                    object_store_config,
+                    local_store_config,
+                    dest_store_config,
+                    file_compression,
+                    commit_file_size,
+                    download_concurrency,
+                )
+                .await?;
+            }
             ToolCommand::DumpArchiveByChecksum {
                 object_store_config,
                 start,
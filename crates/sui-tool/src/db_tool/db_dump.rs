@@ -18,7 +18,7 @@ use mysocial_core::authority::authority_store_pruner::{
 };
 use mysocial_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use mysocial_core::authority::authority_store_types::{StoreData, StoreObject};
-use mysocial_core::checkpoints::CheckpointStore;
+use mysocial_core::checkpoints::{CheckpointStore, CheckpointStoreTables};
 use mysocial_core::epoch::committee_store::CommitteeStoreTables;
 use mysocial_core::jsonrpc_index::IndexStoreTables;
 use mysocial_core::rpc_index::RpcIndexStore;
@@ -33,6 +33,7 @@ pub enum StoreName {
     Validator,
     Index,
     Epoch,
+    Checkpoint,
     // TODO: Add the new checkpoint v2 tables.
 }
 impl std::fmt::Display for StoreName {
@@ -85,6 +86,10 @@ pub fn table_summary(
             CommitteeStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
                 .table_summary(table_name)
         }
+        StoreName::Checkpoint => {
+            CheckpointStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
+                .table_summary(table_name)
+        }
     }
     .map_err(|err| anyhow!(err.to_string()))
 }
@@ -119,6 +124,11 @@ pub fn print_table_metadata(
                 .committee_map
                 .rocksdb
         }
+        StoreName::Checkpoint => {
+            CheckpointStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
+                .checkpoint_content
+                .rocksdb
+        }
     };
 
     let mut table = Table::new();
@@ -290,6 +300,10 @@ pub fn dump_table(
             CommitteeStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
                 .dump(table_name, page_size, page_number)
         }
+        StoreName::Checkpoint => {
+            CheckpointStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
+                .dump(table_name, page_size, page_number)
+        }
     }
     .map_err(|err| anyhow!(err.to_string()))
 }
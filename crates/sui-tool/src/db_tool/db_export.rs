@@ -0,0 +1,247 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::db_tool::db_dump::StoreName;
+use anyhow::anyhow;
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::annotated_value::MoveStructLayout;
+use move_core_types::language_storage::{ModuleId, StructTag};
+use mysocial_core::authority::authority_per_epoch_store::AuthorityEpochTables;
+use mysocial_core::authority::authority_store_tables::{
+    AuthorityPerpetualTables, AuthorityPerpetualTablesReadOnly,
+};
+use mysocial_core::checkpoints::CheckpointStoreTables;
+use mysocial_core::epoch::committee_store::CommitteeStoreTables;
+use mysocial_core::jsonrpc_index::IndexStoreTables;
+use mysocial_types::base_types::{EpochId, ObjectID};
+use mysocial_types::error::{SuiError, SuiResult};
+use mysocial_types::object::{Data, Object};
+use mysocial_types::storage::{
+    get_module_by_id, load_package_object_from_object_store, BackingPackageStore, ObjectKey,
+    PackageObject,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use typed_store::rocks::MetricConf;
+use typed_store::traits::Map;
+
+/// Export the entries of a table as JSON lines of the form `{"key": ..., "value": ...}`, to
+/// `output` or stdout. Only keys in `[lower_bound, upper_bound)` are exported, where bounds are
+/// keys encoded as JSON. Objects in the perpetual `objects` table are rendered with their Move
+/// layouts. Returns the number of entries exported.
+pub fn export_table(
+    store_name: StoreName,
+    epoch: Option<EpochId>,
+    db_path: PathBuf,
+    table_name: &str,
+    lower_bound: Option<&str>,
+    upper_bound: Option<&str>,
+    output: Option<PathBuf>,
+) -> anyhow::Result<usize> {
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    };
+    let mut write_entry = |key: Value, value: Value| -> eyre::Result<()> {
+        serde_json::to_writer(&mut writer, &json!({ "key": key, "value": value }))?;
+        writeln!(writer)?;
+        Ok(())
+    };
+
+    let count = match store_name {
+        StoreName::Validator => {
+            let epoch_tables = AuthorityEpochTables::describe_tables();
+            if epoch_tables.contains_key(table_name) {
+                let epoch = epoch.ok_or_else(|| anyhow!("--epoch is required"))?;
+                AuthorityEpochTables::open_readonly(epoch, &db_path).export_json(
+                    table_name,
+                    lower_bound,
+                    upper_bound,
+                    &mut write_entry,
+                )
+            } else if table_name == "objects" {
+                let perpetual_tables = AuthorityPerpetualTables::open_readonly(&db_path);
+                export_objects(
+                    &perpetual_tables,
+                    lower_bound,
+                    upper_bound,
+                    &mut write_entry,
+                )
+            } else {
+                AuthorityPerpetualTables::open_readonly(&db_path).export_json(
+                    table_name,
+                    lower_bound,
+                    upper_bound,
+                    &mut write_entry,
+                )
+            }
+        }
+        StoreName::Index => {
+            IndexStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
+                .export_json(table_name, lower_bound, upper_bound, &mut write_entry)
+        }
+        StoreName::Epoch => {
+            CommitteeStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
+                .export_json(table_name, lower_bound, upper_bound, &mut write_entry)
+        }
+        StoreName::Checkpoint => {
+            CheckpointStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
+                .export_json(table_name, lower_bound, upper_bound, &mut write_entry)
+        }
+    }
+    .map_err(|err| anyhow!(err.to_string()))?;
+
+    writer.flush()?;
+    Ok(count)
+}
+
+/// Export the perpetual `objects` table, rendering the contents of Move objects with their
+/// layouts. Deleted and wrapped objects are exported as they are stored.
+fn export_objects(
+    perpetual_tables: &AuthorityPerpetualTablesReadOnly,
+    lower_bound: Option<&str>,
+    upper_bound: Option<&str>,
+    f: &mut dyn FnMut(Value, Value) -> eyre::Result<()>,
+) -> eyre::Result<usize> {
+    let lower_bound = lower_bound
+        .map(serde_json::from_str::<ObjectKey>)
+        .transpose()?;
+    let upper_bound = upper_bound
+        .map(serde_json::from_str::<ObjectKey>)
+        .transpose()?;
+    let modules = PerpetualModules(perpetual_tables);
+    let mut layouts = HashMap::new();
+    let mut count = 0;
+    for item in perpetual_tables
+        .objects
+        .safe_iter_with_bounds(lower_bound, upper_bound)
+    {
+        let (key, store_object) = item?;
+        let value = match perpetual_tables.object(&key, store_object.clone())? {
+            Some(object) => object_to_json(&object, &modules, &mut layouts)?,
+            None => serde_json::to_value(&store_object)?,
+        };
+        f(serde_json::to_value(key)?, value)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn object_to_json(
+    object: &Object,
+    modules: &PerpetualModules,
+    layouts: &mut HashMap<StructTag, MoveStructLayout>,
+) -> eyre::Result<Value> {
+    let (type_, contents) = match &object.data {
+        Data::Move(move_object) => {
+            let struct_tag: StructTag = move_object.type_().clone().into();
+            if !layouts.contains_key(&struct_tag) {
+                layouts.insert(struct_tag.clone(), move_object.get_layout(modules)?);
+            }
+            let contents =
+                serde_json::to_value(move_object.to_move_struct(&layouts[&struct_tag])?)?;
+            (struct_tag.to_canonical_string(true), contents)
+        }
+        Data::Package(package) => (
+            "package".to_string(),
+            json!({ "modules": package.serialized_module_map().keys().collect::<Vec<_>>() }),
+        ),
+    };
+
+    Ok(json!({
+        "id": object.id(),
+        "version": object.version(),
+        "digest": object.digest(),
+        "type": type_,
+        "owner": object.owner,
+        "previous_transaction": object.previous_transaction,
+        "storage_rebate": object.storage_rebate,
+        "contents": contents,
+    }))
+}
+
+/// Resolves Move modules from the packages in a perpetual store opened in read only mode.
+struct PerpetualModules<'a>(&'a AuthorityPerpetualTablesReadOnly);
+
+impl BackingPackageStore for PerpetualModules<'_> {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<PackageObject>> {
+        load_package_object_from_object_store(self.0, package_id)
+    }
+}
+
+impl GetModule for PerpetualModules<'_> {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> anyhow::Result<Option<Self::Item>, Self::Error> {
+        get_module_by_id(self, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mysocial_types::base_types::SuiAddress;
+    use sui_framework::BuiltInFramework;
+
+    use super::*;
+
+    #[test]
+    fn export_objects_with_layouts() {
+        let db_path = tempfile::tempdir().unwrap();
+        let coin = Object::with_id_owner_gas_for_testing(ObjectID::random(), SuiAddress::ZERO, 42);
+        let mut objects: Vec<_> = BuiltInFramework::genesis_objects().collect();
+        objects.push(coin.clone());
+        {
+            let perpetual_tables = AuthorityPerpetualTables::open(db_path.path(), None);
+            for object in &objects {
+                perpetual_tables
+                    .insert_object_test_only(object.clone())
+                    .unwrap();
+            }
+        }
+
+        let output = db_path.path().join("objects.jsonl");
+        let count = export_table(
+            StoreName::Validator,
+            None,
+            db_path.path().to_owned(),
+            "objects",
+            None,
+            None,
+            Some(output.clone()),
+        )
+        .unwrap();
+        assert_eq!(count, objects.len());
+
+        let entries: HashMap<ObjectID, Value> = std::fs::read_to_string(&output)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let entry: Value = serde_json::from_str(line).unwrap();
+                let id = serde_json::from_value(entry["value"]["id"].clone()).unwrap();
+                (id, entry["value"].clone())
+            })
+            .collect();
+
+        // The coin's contents are rendered with the layout resolved from the framework packages
+        // in the same store.
+        let exported = &entries[&coin.id()];
+        assert_eq!(
+            exported["type"],
+            json!(coin.struct_tag().unwrap().to_canonical_string(true))
+        );
+        assert_eq!(exported["version"], json!(coin.version()));
+        assert_eq!(
+            exported["contents"]["fields"]["balance"]["fields"]["value"],
+            json!(42)
+        );
+
+        for package in BuiltInFramework::genesis_objects() {
+            assert_eq!(entries[&package.id()]["type"], json!("package"));
+        }
+    }
+}
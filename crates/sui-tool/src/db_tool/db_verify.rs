@@ -0,0 +1,706 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::bail;
+use fastcrypto::hash::MultisetHash;
+use mysocial_core::authority::authority_per_epoch_store::AuthorityEpochTables;
+use mysocial_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use mysocial_core::authority::epoch_start_configuration::EpochStartConfigTrait;
+use mysocial_core::checkpoints::CheckpointStore;
+use mysocial_core::jsonrpc_index::{IndexStoreTables, IndexStoreTablesReadOnly};
+use mysocial_core::rpc_index::RpcIndexStore;
+use mysocial_core::state_accumulator::StateAccumulator;
+use mysocial_protocol_config::ProtocolConfig;
+use mysocial_types::accumulator::Accumulator;
+use mysocial_types::base_types::{EpochId, ObjectID, SequenceNumber, SuiAddress};
+use mysocial_types::digests::{
+    ChainIdentifier, CheckpointContentsDigest, ObjectDigest, TransactionDigest,
+    TransactionEffectsDigest,
+};
+use mysocial_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use mysocial_types::message_envelope::Message;
+use mysocial_types::messages_checkpoint::{
+    CheckpointCommitment, CheckpointSequenceNumber, ECMHLiveObjectSetDigest,
+};
+use mysocial_types::mysocial_system_state::epoch_start_mysocial_system_state::EpochStartSystemStateTrait;
+use mysocial_types::object::Owner;
+use serde::Serialize;
+use std::path::Path;
+use tracing::info;
+use typed_store::rocks::MetricConf;
+use typed_store::traits::Map;
+
+/// The secondary indexes that are checked against the perpetual tables.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexName {
+    JsonrpcIndex,
+    RpcIndex,
+}
+
+/// An inconsistency found between the tables of a node's database.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Discrepancy {
+    /// An executed checkpoint's summary is missing.
+    MissingCheckpoint {
+        checkpoint: CheckpointSequenceNumber,
+    },
+    /// An executed checkpoint's contents are missing.
+    MissingCheckpointContents {
+        checkpoint: CheckpointSequenceNumber,
+        digest: CheckpointContentsDigest,
+    },
+    /// A transaction in an executed checkpoint is missing.
+    MissingTransaction {
+        checkpoint: CheckpointSequenceNumber,
+        transaction: TransactionDigest,
+    },
+    /// A transaction in an executed checkpoint has no executed effects.
+    MissingEffects {
+        checkpoint: CheckpointSequenceNumber,
+        transaction: TransactionDigest,
+    },
+    /// The executed effects of a transaction differ from the effects certified in its checkpoint.
+    EffectsDigestMismatch {
+        checkpoint: CheckpointSequenceNumber,
+        transaction: TransactionDigest,
+        expected: TransactionEffectsDigest,
+        actual: TransactionEffectsDigest,
+    },
+    /// An object version a transaction modified is missing, but has not been pruned.
+    MissingInputObject {
+        checkpoint: CheckpointSequenceNumber,
+        transaction: TransactionDigest,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    },
+    /// An object version a transaction wrote is missing, but has not been pruned.
+    MissingOutputObject {
+        checkpoint: CheckpointSequenceNumber,
+        transaction: TransactionDigest,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    },
+    /// An object version a transaction wrote is stored with a different digest.
+    ObjectDigestMismatch {
+        checkpoint: CheckpointSequenceNumber,
+        transaction: TransactionDigest,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        expected: ObjectDigest,
+        actual: ObjectDigest,
+    },
+    /// An executed transaction is missing from an index.
+    MissingIndexedTransaction {
+        index: IndexName,
+        checkpoint: CheckpointSequenceNumber,
+        transaction: TransactionDigest,
+    },
+    /// An index records a transaction as executed in a different checkpoint.
+    IndexedTransactionCheckpointMismatch {
+        index: IndexName,
+        transaction: TransactionDigest,
+        expected: CheckpointSequenceNumber,
+        actual: CheckpointSequenceNumber,
+    },
+    /// A live address-owned object is missing from an owner index.
+    MissingOwnerIndexEntry {
+        index: IndexName,
+        owner: SuiAddress,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    },
+    /// An owner index entry does not match the live version of the object, or its owner.
+    StaleOwnerIndexEntry {
+        index: IndexName,
+        owner: SuiAddress,
+        object_id: ObjectID,
+        indexed_version: SequenceNumber,
+        live_version: Option<SequenceNumber>,
+    },
+    /// An epoch that has ended has no root state hash.
+    MissingRootStateHash { epoch: EpochId },
+    /// An epoch's root state hash was computed at a checkpoint other than its last checkpoint.
+    RootStateHashCheckpointMismatch {
+        epoch: EpochId,
+        expected: CheckpointSequenceNumber,
+        actual: CheckpointSequenceNumber,
+    },
+    /// An epoch's root state hash differs from the commitment in its last checkpoint.
+    RootStateHashMismatch {
+        epoch: EpochId,
+        expected: ECMHLiveObjectSetDigest,
+        actual: ECMHLiveObjectSetDigest,
+    },
+    /// The state accumulator has no running root for the highest executed checkpoint.
+    MissingRunningRoot {
+        checkpoint: CheckpointSequenceNumber,
+    },
+    /// The live object set does not hash to the state accumulator's running root.
+    LiveObjectSetMismatch {
+        checkpoint: CheckpointSequenceNumber,
+        expected: ECMHLiveObjectSetDigest,
+        actual: ECMHLiveObjectSetDigest,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyReport {
+    pub start_checkpoint: CheckpointSequenceNumber,
+    pub end_checkpoint: CheckpointSequenceNumber,
+    pub checked_transactions: u64,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// Cross-check the tables of the database at `db_path` for the executed checkpoints in
+/// `[start_checkpoint, end_checkpoint]`, which default to the lowest unpruned checkpoint and the
+/// highest executed checkpoint.
+///
+/// For every transaction in range, its effects must have been executed and match its checkpoint,
+/// the object versions it read and wrote must exist unless the objects pruner has already passed
+/// its checkpoint, and the JSON-RPC and RPC indexes (if present) must know about it. The owner
+/// indexes are checked against the live versions of the objects, and every epoch's root state
+/// hash against the commitment in its last checkpoint. If `check_live_object_set` is set, the
+/// whole live object set is also hashed and compared with the state accumulator's running root for
+/// the highest executed checkpoint.
+///
+/// The database is opened as a secondary instance, so it can be verified while the node is
+/// running, and is never written to.
+pub fn verify_db(
+    db_path: &Path,
+    start_checkpoint: Option<CheckpointSequenceNumber>,
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+    check_live_object_set: bool,
+) -> anyhow::Result<VerifyReport> {
+    let perpetual_db = AuthorityPerpetualTables::open_secondary(&db_path.join("store"));
+    let checkpoint_store = CheckpointStore::open_secondary(&db_path.join("checkpoints"));
+    let jsonrpc_index = db_path.join("indexes").exists().then(|| {
+        IndexStoreTables::get_read_only_handle(
+            db_path.join("indexes"),
+            None,
+            None,
+            MetricConf::default(),
+        )
+    });
+    let rpc_index = db_path
+        .join("rpc-index")
+        .exists()
+        .then(|| RpcIndexStore::open_secondary(db_path));
+
+    let Some(highest_executed) = checkpoint_store.get_highest_executed_checkpoint()? else {
+        bail!("No checkpoints have been executed");
+    };
+    let highest_executed = *highest_executed.sequence_number();
+    let highest_pruned = checkpoint_store.get_highest_pruned_checkpoint_seq_number()?;
+    let start_checkpoint = start_checkpoint.unwrap_or(if highest_pruned == 0 {
+        0
+    } else {
+        highest_pruned + 1
+    });
+    let end_checkpoint = end_checkpoint.unwrap_or(highest_executed);
+    if end_checkpoint > highest_executed {
+        bail!(
+            "Cannot verify past the highest executed checkpoint ({} > {})",
+            end_checkpoint,
+            highest_executed
+        );
+    }
+    if check_live_object_set && end_checkpoint != highest_executed {
+        bail!("The live object set can only be checked up to the highest executed checkpoint");
+    }
+
+    let mut verifier = Verifier {
+        perpetual_db: &perpetual_db,
+        jsonrpc_index: jsonrpc_index.as_ref(),
+        rpc_index: rpc_index.as_ref(),
+        objects_pruned_checkpoint: perpetual_db.get_highest_pruned_checkpoint()?,
+        checked_transactions: 0,
+        discrepancies: vec![],
+    };
+
+    info!(
+        "Verifying checkpoints {} to {}",
+        start_checkpoint, end_checkpoint
+    );
+    let mut end_epoch = 0;
+    for checkpoint in start_checkpoint..=end_checkpoint {
+        if checkpoint % 10_000 == 0 {
+            info!("Verifying checkpoint {}", checkpoint);
+        }
+
+        let Some(summary) = checkpoint_store.get_checkpoint_by_sequence_number(checkpoint)? else {
+            verifier.report(Discrepancy::MissingCheckpoint { checkpoint });
+            continue;
+        };
+        end_epoch = summary.epoch();
+
+        let Some(contents) = checkpoint_store.get_checkpoint_contents(&summary.content_digest)?
+        else {
+            verifier.report(Discrepancy::MissingCheckpointContents {
+                checkpoint,
+                digest: summary.content_digest,
+            });
+            continue;
+        };
+
+        for digests in contents.iter() {
+            verifier.verify_transaction(checkpoint, &digests.transaction, &digests.effects)?;
+        }
+    }
+
+    info!("Verifying owner indexes");
+    verifier.verify_jsonrpc_owner_index()?;
+
+    info!("Verifying root state hashes");
+    for epoch in 0..=end_epoch {
+        verifier.verify_root_state_hash(&checkpoint_store, epoch)?;
+    }
+
+    if check_live_object_set {
+        info!("Verifying live object set");
+        verifier.verify_live_object_set(db_path, &checkpoint_store, end_epoch, end_checkpoint)?;
+    }
+
+    Ok(VerifyReport {
+        start_checkpoint,
+        end_checkpoint,
+        checked_transactions: verifier.checked_transactions,
+        discrepancies: verifier.discrepancies,
+    })
+}
+
+struct Verifier<'a> {
+    perpetual_db: &'a AuthorityPerpetualTables,
+    jsonrpc_index: Option<&'a IndexStoreTablesReadOnly>,
+    rpc_index: Option<&'a RpcIndexStore>,
+    /// Old object versions modified in checkpoints up to and including this one may have been
+    /// removed by the objects pruner.
+    objects_pruned_checkpoint: CheckpointSequenceNumber,
+    checked_transactions: u64,
+    discrepancies: Vec<Discrepancy>,
+}
+
+impl Verifier<'_> {
+    fn report(&mut self, discrepancy: Discrepancy) {
+        self.discrepancies.push(discrepancy);
+    }
+
+    fn verify_transaction(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        transaction: &TransactionDigest,
+        effects_digest: &TransactionEffectsDigest,
+    ) -> anyhow::Result<()> {
+        self.checked_transactions += 1;
+
+        if self.perpetual_db.get_transaction(transaction)?.is_none() {
+            self.report(Discrepancy::MissingTransaction {
+                checkpoint,
+                transaction: *transaction,
+            });
+        }
+
+        match self.perpetual_db.get_effects(transaction)? {
+            None => self.report(Discrepancy::MissingEffects {
+                checkpoint,
+                transaction: *transaction,
+            }),
+            Some(effects) if effects.digest() != *effects_digest => {
+                self.report(Discrepancy::EffectsDigestMismatch {
+                    checkpoint,
+                    transaction: *transaction,
+                    expected: *effects_digest,
+                    actual: effects.digest(),
+                })
+            }
+            Some(effects) => self.verify_objects(checkpoint, &effects)?,
+        }
+
+        self.verify_indexed_transaction(checkpoint, transaction)
+    }
+
+    fn verify_objects(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        effects: &TransactionEffects,
+    ) -> anyhow::Result<()> {
+        let transaction = *effects.transaction_digest();
+        let pruned = checkpoint <= self.objects_pruned_checkpoint;
+
+        for (object_id, version) in effects.modified_at_versions() {
+            if !pruned
+                && self
+                    .perpetual_db
+                    .get_object_by_key_fallible(&object_id, version)?
+                    .is_none()
+            {
+                self.report(Discrepancy::MissingInputObject {
+                    checkpoint,
+                    transaction,
+                    object_id,
+                    version,
+                });
+            }
+        }
+
+        for ((object_id, version, digest), owner, _) in effects.all_changed_objects() {
+            match self
+                .perpetual_db
+                .get_object_by_key_fallible(&object_id, version)?
+            {
+                Some(object) if object.digest() != digest => {
+                    self.report(Discrepancy::ObjectDigestMismatch {
+                        checkpoint,
+                        transaction,
+                        object_id,
+                        version,
+                        expected: digest,
+                        actual: object.digest(),
+                    })
+                }
+                Some(_) => {
+                    if let Owner::AddressOwner(owner) = owner {
+                        self.verify_owner_indexed(owner, object_id, version)?;
+                    }
+                }
+                None => {
+                    // The version can only have been pruned once a later transaction in a pruned
+                    // checkpoint modified the object.
+                    let superseded = !self
+                        .perpetual_db
+                        .get_newer_object_keys(&(object_id, version))?
+                        .is_empty();
+                    if !(pruned && superseded) {
+                        self.report(Discrepancy::MissingOutputObject {
+                            checkpoint,
+                            transaction,
+                            object_id,
+                            version,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn verify_indexed_transaction(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+        transaction: &TransactionDigest,
+    ) -> anyhow::Result<()> {
+        if let Some(jsonrpc_index) = self.jsonrpc_index {
+            if jsonrpc_index.transactions_seq.get(transaction)?.is_none() {
+                self.report(Discrepancy::MissingIndexedTransaction {
+                    index: IndexName::JsonrpcIndex,
+                    checkpoint,
+                    transaction: *transaction,
+                });
+            }
+        }
+
+        if let Some(rpc_index) = self.rpc_index {
+            match rpc_index.get_transaction_info(transaction)? {
+                None => self.report(Discrepancy::MissingIndexedTransaction {
+                    index: IndexName::RpcIndex,
+                    checkpoint,
+                    transaction: *transaction,
+                }),
+                Some(info) if info.checkpoint != checkpoint => {
+                    self.report(Discrepancy::IndexedTransactionCheckpointMismatch {
+                        index: IndexName::RpcIndex,
+                        transaction: *transaction,
+                        expected: checkpoint,
+                        actual: info.checkpoint,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that the owner indexes know about `object_id` at `version`, if that is still its live
+    /// version.
+    fn verify_owner_indexed(
+        &mut self,
+        owner: SuiAddress,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> anyhow::Result<()> {
+        let is_live = self
+            .perpetual_db
+            .get_latest_object_ref_or_tombstone(object_id)?
+            .is_some_and(|(_, live_version, _)| live_version == version);
+        if !is_live {
+            return Ok(());
+        }
+
+        // Entries that exist but disagree are reported by the scan of the owner index.
+        if let Some(jsonrpc_index) = self.jsonrpc_index {
+            if jsonrpc_index
+                .owner_index
+                .get(&(owner, object_id))?
+                .is_none()
+            {
+                self.report(Discrepancy::MissingOwnerIndexEntry {
+                    index: IndexName::JsonrpcIndex,
+                    owner,
+                    object_id,
+                    version,
+                });
+            }
+        }
+
+        if let Some(rpc_index) = self.rpc_index {
+            let entry = rpc_index
+                .owner_iter(owner, Some(object_id))?
+                .next()
+                .filter(|(key, _)| key.object_id == object_id);
+            match entry {
+                None => self.report(Discrepancy::MissingOwnerIndexEntry {
+                    index: IndexName::RpcIndex,
+                    owner,
+                    object_id,
+                    version,
+                }),
+                Some((_, info)) if info.version != version => {
+                    self.report(Discrepancy::StaleOwnerIndexEntry {
+                        index: IndexName::RpcIndex,
+                        owner,
+                        object_id,
+                        indexed_version: info.version,
+                        live_version: Some(version),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that every entry in the JSON-RPC owner index refers to the live version of an object
+    /// owned by that address.
+    fn verify_jsonrpc_owner_index(&mut self) -> anyhow::Result<()> {
+        let Some(jsonrpc_index) = self.jsonrpc_index else {
+            return Ok(());
+        };
+
+        for item in jsonrpc_index.owner_index.safe_iter() {
+            let ((owner, object_id), info) = item?;
+            let live_object = self.perpetual_db.get_object_fallible(&object_id)?;
+            let is_consistent = live_object.as_ref().is_some_and(|object| {
+                object.version() == info.version && object.owner == Owner::AddressOwner(owner)
+            });
+            if !is_consistent {
+                self.report(Discrepancy::StaleOwnerIndexEntry {
+                    index: IndexName::JsonrpcIndex,
+                    owner,
+                    object_id,
+                    indexed_version: info.version,
+                    live_version: live_object.map(|object| object.version()),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that an epoch that has ended has a root state hash, computed at its last checkpoint,
+    /// matching the commitment in that checkpoint.
+    fn verify_root_state_hash(
+        &mut self,
+        checkpoint_store: &CheckpointStore,
+        epoch: EpochId,
+    ) -> anyhow::Result<()> {
+        let Some(last_checkpoint) = checkpoint_store.get_epoch_last_checkpoint(epoch)? else {
+            return Ok(());
+        };
+        let last_checkpoint = *last_checkpoint.sequence_number();
+
+        let Some((checkpoint, root_state_hash)) = self.perpetual_db.get_root_state_hash(epoch)?
+        else {
+            self.report(Discrepancy::MissingRootStateHash { epoch });
+            return Ok(());
+        };
+
+        if checkpoint != last_checkpoint {
+            self.report(Discrepancy::RootStateHashCheckpointMismatch {
+                epoch,
+                expected: last_checkpoint,
+                actual: checkpoint,
+            });
+        }
+
+        let actual: ECMHLiveObjectSetDigest = root_state_hash.digest().into();
+        for commitment in checkpoint_store
+            .get_epoch_state_commitments(epoch)?
+            .unwrap_or_default()
+        {
+            let CheckpointCommitment::ECMHLiveObjectSetDigest(expected) = commitment;
+            if expected != actual {
+                self.report(Discrepancy::RootStateHashMismatch {
+                    epoch,
+                    expected,
+                    actual: actual.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hash the live object set and compare it with the running root at `checkpoint`, which must
+    /// be the highest executed checkpoint. Wrapped object tombstones are part of the live object
+    /// set unless the protocol version of `epoch` has simplified unwrap then delete.
+    fn verify_live_object_set(
+        &mut self,
+        db_path: &Path,
+        checkpoint_store: &CheckpointStore,
+        epoch: EpochId,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> anyhow::Result<()> {
+        let epoch_tables = AuthorityEpochTables::open_readonly(epoch, &db_path.join("store"));
+        let running_root = match epoch_tables.running_root_accumulators.get(&checkpoint)? {
+            Some(running_root) => Some(running_root),
+            // The running roots of an epoch that has ended are only kept as its root state hash.
+            None => self
+                .perpetual_db
+                .get_root_state_hash(epoch)?
+                .filter(|(last_checkpoint, _)| *last_checkpoint == checkpoint)
+                .map(|(_, root_state_hash)| root_state_hash),
+        };
+
+        let Some(running_root) = running_root else {
+            self.report(Discrepancy::MissingRunningRoot { checkpoint });
+            return Ok(());
+        };
+
+        let protocol_config = self.protocol_config(checkpoint_store, epoch)?;
+        let include_wrapped_tombstone = !protocol_config.simplified_unwrap_then_delete();
+
+        let mut live_object_set = Accumulator::default();
+        for live_object in self
+            .perpetual_db
+            .iter_live_object_set(include_wrapped_tombstone)
+        {
+            StateAccumulator::accumulate_live_object(&mut live_object_set, &live_object);
+        }
+
+        let expected: ECMHLiveObjectSetDigest = running_root.digest().into();
+        let actual: ECMHLiveObjectSetDigest = live_object_set.digest().into();
+        if expected != actual {
+            self.report(Discrepancy::LiveObjectSetMismatch {
+                checkpoint,
+                expected,
+                actual,
+            });
+        }
+
+        Ok(())
+    }
+    /// The protocol config in effect during `epoch`, which is either the epoch the node is in, or
+    /// an epoch whose successor's protocol version is recorded in its last checkpoint.
+    fn protocol_config(
+        &self,
+        checkpoint_store: &CheckpointStore,
+        epoch: EpochId,
+    ) -> anyhow::Result<ProtocolConfig> {
+        let Some(genesis) = checkpoint_store.get_checkpoint_by_sequence_number(0)? else {
+            bail!("The genesis checkpoint is missing, so the chain cannot be identified");
+        };
+        let chain = ChainIdentifier::from(*genesis.digest()).chain();
+
+        let epoch_start_configuration = self.perpetual_db.get_epoch_start_configuration()?;
+        let current_protocol_version = epoch_start_configuration
+            .as_ref()
+            .map(|config| config.epoch_start_state())
+            .filter(|state| state.epoch() == epoch)
+            .map(|state| state.protocol_version());
+
+        let protocol_version = if let Some(protocol_version) = current_protocol_version {
+            protocol_version
+        } else {
+            let previous_last_checkpoint = match epoch.checked_sub(1) {
+                Some(previous) => checkpoint_store.get_epoch_last_checkpoint(previous)?,
+                None => None,
+            };
+
+            let Some(end_of_epoch_data) = previous_last_checkpoint
+                .and_then(|checkpoint| checkpoint.end_of_epoch_data.clone())
+            else {
+                bail!("Cannot find the protocol version of epoch {epoch}");
+            };
+
+            end_of_epoch_data.next_epoch_protocol_version
+        };
+
+        Ok(ProtocolConfig::get_for_version(protocol_version, chain))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mysocial_types::messages_checkpoint::VerifiedCheckpoint;
+    use mysocial_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+
+    use super::*;
+
+    #[test]
+    fn reports_missing_transaction_data() {
+        let db_path = tempfile::tempdir().unwrap();
+        let mut builder = TestCheckpointDataBuilder::new(0)
+            .start_transaction(0)
+            .create_owned_object(0)
+            .finish_transaction();
+        let data = builder.build_checkpoint();
+        let transaction = *data.transactions[0].transaction.digest();
+        let checkpoint = VerifiedCheckpoint::new_unchecked(data.checkpoint_summary);
+
+        // The checkpoint was executed and ended its epoch, but the store has lost its transaction,
+        // the transaction's effects, and the epoch's root state hash.
+        {
+            let checkpoint_store = CheckpointStore::new(&db_path.path().join("checkpoints"));
+            checkpoint_store
+                .insert_verified_checkpoint(&checkpoint)
+                .unwrap();
+            checkpoint_store
+                .insert_checkpoint_contents(data.checkpoint_contents)
+                .unwrap();
+            checkpoint_store
+                .update_highest_executed_checkpoint(&checkpoint)
+                .unwrap();
+            checkpoint_store
+                .insert_epoch_last_checkpoint(0, &checkpoint)
+                .unwrap();
+            AuthorityPerpetualTables::open(&db_path.path().join("store"), None);
+        }
+
+        let report = verify_db(db_path.path(), None, None, false).unwrap();
+        assert_eq!(report.start_checkpoint, 0);
+        assert_eq!(report.end_checkpoint, 0);
+        assert_eq!(report.checked_transactions, 1);
+        assert!(
+            matches!(
+                report.discrepancies.as_slice(),
+                [
+                    Discrepancy::MissingTransaction {
+                        checkpoint: 0,
+                        transaction: missing_transaction,
+                    },
+                    Discrepancy::MissingEffects {
+                        checkpoint: 0,
+                        transaction: missing_effects,
+                    },
+                    Discrepancy::MissingRootStateHash { epoch: 0 },
+                ] if *missing_transaction == transaction && *missing_effects == transaction
+            ),
+            "Unexpected discrepancies: {:?}",
+            report.discrepancies
+        );
+    }
+}
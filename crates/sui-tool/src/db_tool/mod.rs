@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use self::db_dump::{dump_table, duplicate_objects_summary, list_tables, table_summary, StoreName};
+use self::db_export::export_table;
+use self::db_verify::verify_db;
 use self::index_search::{search_index, SearchRange};
 use crate::db_tool::db_dump::{compact, print_table_metadata, prune_checkpoints, prune_objects};
 use anyhow::{anyhow, bail};
@@ -16,6 +18,8 @@ use sui_types::effects::TransactionEffectsAPI;
 use sui_types::messages_checkpoint::{CheckpointDigest, CheckpointSequenceNumber};
 use typed_store::rocks::MetricConf;
 pub mod db_dump;
+mod db_export;
+mod db_verify;
 mod index_search;
 
 #[derive(Parser)]
@@ -23,6 +27,7 @@ mod index_search;
 pub enum DbToolCommand {
     ListTables,
     Dump(Options),
+    Export(ExportOptions),
     IndexSearchKeyRange(IndexSearchKeyRangeOptions),
     IndexSearchCount(IndexSearchCountOptions),
     TableSummary(Options),
@@ -40,6 +45,7 @@ pub enum DbToolCommand {
     PruneObjects,
     PruneCheckpoints,
     SetCheckpointWatermark(SetCheckpointWatermarkOptions),
+    Verify(VerifyOptions),
 }
 
 #[derive(Parser)]
@@ -88,6 +94,29 @@ pub struct Options {
     epoch: Option<EpochId>,
 }
 
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct ExportOptions {
+    /// The type of store to export from
+    #[arg(long = "store", short = 's', value_enum)]
+    store_name: StoreName,
+    /// The name of the table to export
+    #[arg(long = "table-name", short = 't')]
+    table_name: String,
+    /// The epoch to use when loading AuthorityEpochTables.
+    #[arg(long = "epoch", short = 'e')]
+    epoch: Option<EpochId>,
+    /// Only export keys greater than or equal to this key, encoded as JSON
+    #[arg(long = "lower-bound")]
+    lower_bound: Option<String>,
+    /// Only export keys less than this key, encoded as JSON
+    #[arg(long = "upper-bound")]
+    upper_bound: Option<String>,
+    /// The file to write JSON lines to. Defaults to stdout
+    #[arg(long = "output", short = 'o')]
+    output: Option<PathBuf>,
+}
+
 #[derive(Parser)]
 #[command(rename_all = "kebab-case")]
 pub struct PrintConsensusCommitOptions {
@@ -176,6 +205,22 @@ pub struct SetCheckpointWatermarkOptions {
     highest_synced: Option<CheckpointSequenceNumber>,
 }
 
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct VerifyOptions {
+    /// The first checkpoint to verify. Defaults to the lowest checkpoint that has not been pruned
+    #[arg(long)]
+    start_checkpoint: Option<CheckpointSequenceNumber>,
+
+    /// The last checkpoint to verify. Defaults to the highest executed checkpoint
+    #[arg(long)]
+    end_checkpoint: Option<CheckpointSequenceNumber>,
+
+    /// Also hash the entire live object set and compare it with the state accumulator
+    #[arg(long)]
+    check_live_object_set: bool,
+}
+
 pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> anyhow::Result<()> {
     match cmd {
         DbToolCommand::ListTables => print_db_all_tables(db_path),
@@ -187,6 +232,19 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
             d.page_size,
             d.page_number,
         ),
+        DbToolCommand::Export(d) => {
+            let count = export_table(
+                d.store_name,
+                d.epoch,
+                db_path,
+                &d.table_name,
+                d.lower_bound.as_deref(),
+                d.upper_bound.as_deref(),
+                d.output,
+            )?;
+            eprintln!("Exported {} entries from {}", count, d.table_name);
+            Ok(())
+        }
         DbToolCommand::TableSummary(d) => {
             print_db_table_summary(d.store_name, d.epoch, db_path, &d.table_name)
         }
@@ -232,6 +290,7 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
             Ok(())
         }
         DbToolCommand::SetCheckpointWatermark(d) => set_checkpoint_watermark(&db_path, d),
+        DbToolCommand::Verify(d) => verify(&db_path, d),
     }
 }
 
@@ -499,3 +558,21 @@ pub fn set_checkpoint_watermark(
     }
     Ok(())
 }
+
+/// Checks that the tables of a node's database are consistent with each other, e.g. after a crash,
+/// printing the discrepancies found as JSON.
+/// Run with (for example):
+/// cargo run --package sui-tool -- db-tool --db-path /opt/sui/db/authorities_db/live verify --check-live-object-set
+pub fn verify(path: &Path, options: VerifyOptions) -> anyhow::Result<()> {
+    let report = verify_db(
+        path,
+        options.start_checkpoint,
+        options.end_checkpoint,
+        options.check_live_object_set,
+    )?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if !report.discrepancies.is_empty() {
+        bail!("Found {} discrepancies", report.discrepancies.len());
+    }
+    Ok(())
+}
@@ -5,7 +5,6 @@
 use anyhow::Result;
 use fastcrypto::traits::ToFromBytes;
 use futures::future::join_all;
-use futures::future::AbortHandle;
 use itertools::Itertools;
 use std::collections::BTreeMap;
 use std::fmt::Write;
@@ -27,13 +26,11 @@ use sui_storage::object_store::http::HttpDownloaderBuilder;
 use sui_storage::object_store::util::Manifest;
 use sui_storage::object_store::util::PerEpochManifest;
 use sui_storage::object_store::util::MANIFEST_FILENAME;
-use sui_types::accumulator::Accumulator;
 use sui_types::committee::QUORUM_THRESHOLD;
 use sui_types::crypto::AuthorityPublicKeyBytes;
 use sui_types::messages_grpc::LayoutGenerationOption;
 use sui_types::multiaddr::Multiaddr;
 use sui_types::{base_types::*, object::Owner};
-use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tokio::time::Instant;
 
@@ -54,7 +51,7 @@ use sui_core::authority::AuthorityStore;
 use sui_core::checkpoints::CheckpointStore;
 use sui_core::epoch::committee_store::CommitteeStore;
 use sui_core::storage::RocksDbStore;
-use sui_snapshot::reader::StateSnapshotReaderV1;
+use sui_snapshot::reader::{restore_snapshot_chain, SnapshotSource};
 use sui_snapshot::setup_db_state;
 use sui_storage::object_store::util::{copy_file, exists, get_path};
 use sui_storage::object_store::ObjectStoreGetExt;
@@ -825,6 +822,48 @@ pub async fn download_formal_snapshot(
     network: Chain,
     verify: SnapshotVerifyMode,
     all_checkpoints: bool,
+) -> Result<(), anyhow::Error> {
+    let snapshot_dir = path.join("snapshot");
+    if snapshot_dir.exists() {
+        fs::remove_dir_all(snapshot_dir.clone())?;
+    }
+    let snapshot_source = SnapshotSource::ObjectStore {
+        remote_store_config: snapshot_store_config,
+        local_store_config: ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(snapshot_dir.clone()),
+            ..Default::default()
+        },
+    };
+    restore_formal_snapshot(
+        path,
+        epoch,
+        genesis,
+        snapshot_source,
+        archive_store_config,
+        num_parallel_downloads,
+        network,
+        verify,
+        all_checkpoints,
+    )
+    .await?;
+    fs::remove_dir_all(snapshot_dir)?;
+    Ok(())
+}
+
+/// Restores a node database at `path` from the formal snapshot for `epoch` in `snapshot_source`,
+/// with checkpoint summaries up to the end of that epoch from the archive in
+/// `archive_store_config`.
+pub async fn restore_formal_snapshot(
+    path: &Path,
+    epoch: EpochId,
+    genesis: &Path,
+    snapshot_source: SnapshotSource,
+    archive_store_config: ObjectStoreConfig,
+    num_parallel_downloads: usize,
+    network: Chain,
+    verify: SnapshotVerifyMode,
+    all_checkpoints: bool,
 ) -> Result<(), anyhow::Error> {
     let m = MultiProgress::new();
     m.println(format!(
@@ -857,52 +896,30 @@ pub async fn download_formal_snapshot(
         verify != SnapshotVerifyMode::None,
         all_checkpoints,
     );
-    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
     let perpetual_db_clone = perpetual_db.clone();
-    let snapshot_dir = path.parent().unwrap().join("snapshot");
-    if snapshot_dir.exists() {
-        fs::remove_dir_all(snapshot_dir.clone())?;
-    }
-    let snapshot_dir_clone = snapshot_dir.clone();
-
-    // TODO if verify is false, we should skip generating these and
-    // not pass in a channel to the reader
-    let (sender, mut receiver) = mpsc::channel(num_parallel_downloads);
     let m_clone = m.clone();
 
+    // If the snapshot for `epoch` is a delta, this restores the full snapshot it is based on, and
+    // applies each delta in its chain on top.
     let snapshot_handle = tokio::spawn(async move {
-        let local_store_config = ObjectStoreConfig {
-            object_store: Some(ObjectStoreType::File),
-            directory: Some(snapshot_dir_clone.to_path_buf()),
-            ..Default::default()
-        };
-        let mut reader = StateSnapshotReaderV1::new(
+        restore_snapshot_chain(
             epoch,
-            &snapshot_store_config,
-            &local_store_config,
+            &snapshot_source,
             NonZeroUsize::new(num_parallel_downloads).unwrap(),
+            &perpetual_db_clone,
             m_clone,
-            false, // skip_reset_local_store
         )
         .await
-        .unwrap_or_else(|err| panic!("Failed to create reader: {}", err));
-        reader
-            .read(&perpetual_db_clone, abort_registration, Some(sender))
-            .await
-            .unwrap_or_else(|err| panic!("Failed during read: {}", err));
-        Ok::<(), anyhow::Error>(())
+        .unwrap_or_else(|err| panic!("Failed during read: {}", err))
     });
-    let mut root_accumulator = Accumulator::default();
-    let mut num_live_objects = 0;
-    while let Some((partial_acc, num_objects)) = receiver.recv().await {
-        num_live_objects += num_objects;
-        root_accumulator.union(&partial_acc);
-    }
     summaries_handle
         .await
         .expect("Task join failed")
         .expect("Summaries task failed");
 
+    let (root_accumulator, num_live_objects) =
+        snapshot_handle.await.expect("Snapshot restore task failed");
+
     let last_checkpoint = checkpoint_store
         .get_highest_verified_checkpoint()?
         .expect("Expected nonempty checkpoint store");
@@ -957,11 +974,6 @@ pub async fn download_formal_snapshot(
         )?;
     }
 
-    snapshot_handle
-        .await
-        .expect("Task join failed")
-        .expect("Snapshot restore task failed");
-
     // TODO we should ensure this map is being updated for all end of epoch
     // checkpoints during summary sync. This happens in `insert_{verified|certified}_checkpoint`
     // in checkpoint store, but not in the corresponding functions in ObjectStore trait
@@ -985,7 +997,6 @@ pub async fn download_formal_snapshot(
         fs::remove_dir_all(new_path.clone())?;
     }
     fs::rename(&path, &new_path)?;
-    fs::remove_dir_all(snapshot_dir.clone())?;
     println!(
         "Successfully restored state from snapshot at end of epoch {}",
         epoch
@@ -11,17 +11,88 @@ use mysocial_types::{
     transaction::{Command, InputObjectKind, TransactionData, TransactionDataAPI},
 };
 macro_rules! deny_if_true {
-    ($cond:expr, $msg:expr) => {
+    ($rule:expr, $cond:expr, $msg:expr) => {
         if ($cond) {
-            return Err(SuiError::UserInputError {
-                error: UserInputError::TransactionDenied {
-                    error: $msg.to_string(),
+            return Err(DenyCheckError::Denied(
+                $rule,
+                SuiError::UserInputError {
+                    error: UserInputError::TransactionDenied {
+                        error: $msg.to_string(),
+                    },
                 },
-            });
+            ));
         }
     };
 }
 
+/// The rules of the deny config that a transaction can be rejected by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DenyRule {
+    UserTransactionDisabled,
+    ZkLoginSigDisabled,
+    ZkLoginProviderDisabled,
+    PackagePublishDisabled,
+    PackageUpgradeDisabled,
+    AddressDenied,
+    ObjectDenied,
+    SharedObjectDisabled,
+    PackageDenied,
+    ReceivingObjectsDisabled,
+}
+
+impl DenyRule {
+    /// Name of the rule, matching the corresponding field of the deny config.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DenyRule::UserTransactionDisabled => "user-transaction-disabled",
+            DenyRule::ZkLoginSigDisabled => "zklogin-sig-disabled",
+            DenyRule::ZkLoginProviderDisabled => "zklogin-disabled-providers",
+            DenyRule::PackagePublishDisabled => "package-publish-disabled",
+            DenyRule::PackageUpgradeDisabled => "package-upgrade-disabled",
+            DenyRule::AddressDenied => "address-deny-list",
+            DenyRule::ObjectDenied => "object-deny-list",
+            DenyRule::SharedObjectDisabled => "shared-object-disabled",
+            DenyRule::PackageDenied => "package-deny-list",
+            DenyRule::ReceivingObjectsDisabled => "receiving-objects-disabled",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DenyCheckError {
+    /// The transaction was rejected by a rule of the deny config.
+    Denied(DenyRule, SuiError),
+    /// The transaction could not be checked against the deny config, e.g. because a package it
+    /// calls into does not exist.
+    Error(SuiError),
+}
+
+impl DenyCheckError {
+    /// The rule that rejected the transaction, if it was rejected by the deny config.
+    pub fn rule(&self) -> Option<DenyRule> {
+        match self {
+            DenyCheckError::Denied(rule, _) => Some(*rule),
+            DenyCheckError::Error(_) => None,
+        }
+    }
+}
+
+impl From<SuiError> for DenyCheckError {
+    fn from(error: SuiError) -> Self {
+        DenyCheckError::Error(error)
+    }
+}
+
+impl From<DenyCheckError> for SuiError {
+    fn from(error: DenyCheckError) -> Self {
+        match error {
+            DenyCheckError::Denied(_, error) | DenyCheckError::Error(error) => error,
+        }
+    }
+}
+
+type DenyCheckResult = Result<(), DenyCheckError>;
+
 /// Check that the provided transaction is allowed to be signed according to the
 /// deny config.
 pub fn check_transaction_for_signing(
@@ -32,6 +103,27 @@ pub fn check_transaction_for_signing(
     filter_config: &TransactionDenyConfig,
     package_store: &dyn BackingPackageStore,
 ) -> SuiResult {
+    check_transaction_for_signing_with_rule(
+        tx_data,
+        tx_signatures,
+        input_object_kinds,
+        receiving_objects,
+        filter_config,
+        package_store,
+    )
+    .map_err(SuiError::from)
+}
+
+/// Same as [check_transaction_for_signing], but if the transaction is rejected, the error also
+/// identifies the rule of the deny config that rejected it.
+pub fn check_transaction_for_signing_with_rule(
+    tx_data: &TransactionData,
+    tx_signatures: &[GenericSignature],
+    input_object_kinds: &[InputObjectKind],
+    receiving_objects: &[ObjectRef],
+    filter_config: &TransactionDenyConfig,
+    package_store: &dyn BackingPackageStore,
+) -> DenyCheckResult {
     check_disabled_features(filter_config, tx_data, tx_signatures)?;
 
     check_signers(filter_config, tx_data)?;
@@ -48,13 +140,15 @@ pub fn check_transaction_for_signing(
 fn check_receiving_objects(
     filter_config: &TransactionDenyConfig,
     receiving_objects: &[ObjectRef],
-) -> SuiResult {
+) -> DenyCheckResult {
     deny_if_true!(
+        DenyRule::ReceivingObjectsDisabled,
         filter_config.receiving_objects_disabled() && !receiving_objects.is_empty(),
         "Receiving objects is temporarily disabled".to_string()
     );
     for (id, _, _) in receiving_objects {
         deny_if_true!(
+            DenyRule::ObjectDenied,
             filter_config.get_object_deny_set().contains(id),
             format!("Access to object {:?} is temporarily disabled", id)
         );
@@ -66,8 +160,9 @@ fn check_disabled_features(
     filter_config: &TransactionDenyConfig,
     tx_data: &TransactionData,
     tx_signatures: &[GenericSignature],
-) -> SuiResult {
+) -> DenyCheckResult {
     deny_if_true!(
+        DenyRule::UserTransactionDisabled,
         filter_config.user_transaction_disabled(),
         "Transaction signing is temporarily disabled"
     );
@@ -75,10 +170,12 @@ fn check_disabled_features(
     tx_signatures.iter().try_for_each(|s| {
         if let GenericSignature::ZkLoginAuthenticator(z) = s {
             deny_if_true!(
+                DenyRule::ZkLoginSigDisabled,
                 filter_config.zklogin_sig_disabled(),
                 "zkLogin authenticator is temporarily disabled"
             );
             deny_if_true!(
+                DenyRule::ZkLoginProviderDisabled,
                 filter_config.zklogin_disabled_providers().contains(
                     &OIDCProvider::from_iss(z.get_iss())
                         .map_err(|_| SuiError::UnexpectedMessage(z.get_iss().to_string()))?
@@ -96,10 +193,12 @@ fn check_disabled_features(
 
     for command in tx_data.kind().iter_commands() {
         deny_if_true!(
+            DenyRule::PackagePublishDisabled,
             filter_config.package_publish_disabled() && matches!(command, Command::Publish(..)),
             "Package publish is temporarily disabled"
         );
         deny_if_true!(
+            DenyRule::PackageUpgradeDisabled,
             filter_config.package_upgrade_disabled() && matches!(command, Command::Upgrade(..)),
             "Package upgrade is temporarily disabled"
         );
@@ -107,13 +206,17 @@ fn check_disabled_features(
     Ok(())
 }
 
-fn check_signers(filter_config: &TransactionDenyConfig, tx_data: &TransactionData) -> SuiResult {
+fn check_signers(
+    filter_config: &TransactionDenyConfig,
+    tx_data: &TransactionData,
+) -> DenyCheckResult {
     let deny_map = filter_config.get_address_deny_set();
     if deny_map.is_empty() {
         return Ok(());
     }
     for signer in tx_data.signers() {
         deny_if_true!(
+            DenyRule::AddressDenied,
             deny_map.contains(&signer),
             format!(
                 "Access to account address {:?} is temporarily disabled",
@@ -127,7 +230,7 @@ fn check_signers(filter_config: &TransactionDenyConfig, tx_data: &TransactionDat
 fn check_input_objects(
     filter_config: &TransactionDenyConfig,
     input_object_kinds: &[InputObjectKind],
-) -> SuiResult {
+) -> DenyCheckResult {
     let deny_map = filter_config.get_object_deny_set();
     let shared_object_disabled = filter_config.shared_object_disabled();
     if deny_map.is_empty() && !shared_object_disabled {
@@ -137,10 +240,12 @@ fn check_input_objects(
     for input_object_kind in input_object_kinds {
         let id = input_object_kind.object_id();
         deny_if_true!(
+            DenyRule::ObjectDenied,
             deny_map.contains(&id),
             format!("Access to input object {:?} is temporarily disabled", id)
         );
         deny_if_true!(
+            DenyRule::SharedObjectDisabled,
             shared_object_disabled && input_object_kind.is_shared_object(),
             "Usage of shared object in transactions is temporarily disabled"
         );
@@ -152,7 +257,7 @@ fn check_package_dependencies(
     filter_config: &TransactionDenyConfig,
     tx_data: &TransactionData,
     package_store: &dyn BackingPackageStore,
-) -> SuiResult {
+) -> DenyCheckResult {
     let deny_map = filter_config.get_package_deny_set();
     if deny_map.is_empty() {
         return Ok(());
@@ -203,6 +308,7 @@ fn check_package_dependencies(
     }
     for dep in dependencies {
         deny_if_true!(
+            DenyRule::PackageDenied,
             deny_map.contains(&dep),
             format!("Access to package {:?} is temporarily disabled", dep)
         );
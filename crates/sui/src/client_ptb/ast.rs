@@ -38,6 +38,10 @@ pub const DRY_RUN: &str = "dry-run";
 pub const DEV_INSPECT: &str = "dev-inspect";
 pub const SERIALIZE_UNSIGNED: &str = "serialize-unsigned-transaction";
 pub const SERIALIZE_SIGNED: &str = "serialize-signed-transaction";
+pub const INCLUDE: &str = "include";
+pub const PARAM: &str = "param";
+pub const REPEAT: &str = "repeat";
+pub const END_REPEAT: &str = "end-repeat";
 
 // Types
 pub const U8: &str = "u8";
@@ -78,6 +82,10 @@ pub const COMMANDS: &[&str] = &[
     DEV_INSPECT,
     SERIALIZE_UNSIGNED,
     SERIALIZE_SIGNED,
+    INCLUDE,
+    PARAM,
+    REPEAT,
+    END_REPEAT,
 ];
 
 pub fn is_keyword(s: &str) -> bool {
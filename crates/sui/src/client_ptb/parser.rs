@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::iter::Peekable;
+use std::{collections::BTreeMap, iter::Peekable};
 
 use move_core_types::parsing::{
     address::{NumericalAddress, ParsedAddress},
@@ -25,6 +25,9 @@ use super::{
     token::{Lexeme, Token},
 };
 
+/// The most commands that a single `--repeat` block can expand to.
+pub(crate) const MAX_REPEAT_COMMANDS: usize = 1024;
+
 /// Parse a program
 pub struct ProgramParser<'a, I: Iterator<Item = &'a str>> {
     tokens: Peekable<Lexer<'a, I>>,
@@ -44,6 +47,19 @@ struct ProgramParsingState {
     dev_inspect_set: bool,
     gas_object_id: Option<Spanned<ObjectID>>,
     gas_budget: Option<Spanned<u64>>,
+    params: BTreeMap<String, Spanned<Argument>>,
+    repeats: Vec<RepeatBlock>,
+}
+
+/// A `--repeat` block whose `--end-repeat` has not been reached yet.
+struct RepeatBlock {
+    span: Span,
+    /// The variable bound to each value in turn, or `None` if the block failed to parse, in which
+    /// case its commands are dropped.
+    variable: Option<Spanned<String>>,
+    values: Vec<Spanned<Argument>>,
+    /// Index of the first parsed command in the body of the block.
+    start: usize,
 }
 
 impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
@@ -67,6 +83,8 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                 dev_inspect_set: false,
                 gas_object_id: None,
                 gas_budget: None,
+                params: BTreeMap::new(),
+                repeats: Vec::new(),
             },
         })
     }
@@ -135,6 +153,24 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                     }
                 }
 
+                L(T::Command, A::PARAM) => try_!(self.parse_param()),
+                L(T::Command, A::REPEAT) => match self.parse_repeat(sp) {
+                    Ok(block) => self.state.repeats.push(block),
+                    Err(err) => {
+                        self.state.errors.push(err);
+                        self.fast_forward_to_next_command();
+                        // Still open the block, so that its `--end-repeat` is not reported as
+                        // unmatched.
+                        self.state.repeats.push(RepeatBlock {
+                            span: sp,
+                            variable: None,
+                            values: vec![],
+                            start: self.state.parsed.len(),
+                        });
+                    }
+                },
+                L(T::Command, A::END_REPEAT) => try_!(self.end_repeat(sp)),
+
                 L(T::Command, A::TRANSFER_OBJECTS) => command!(self.parse_transfer_objects()),
                 L(T::Command, A::SPLIT_COINS) => command!(self.parse_split_coins()),
                 L(T::Command, A::MERGE_COINS) => command!(self.parse_merge_coins()),
@@ -196,6 +232,14 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                 .push(err!(sp, "Trailing {tok} found after the last command",));
         }
 
+        for block in std::mem::take(&mut self.state.repeats) {
+            self.state.errors.push(err!(
+                block.span => help: { "Close the block with --{}", A::END_REPEAT },
+                "Unclosed --{} block",
+                A::REPEAT,
+            ));
+        }
+
         if self.state.errors.is_empty() {
             Ok((
                 A::Program {
@@ -371,6 +415,123 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
         })
     }
 
+    /// Parse a param command. Parameters that already have a value keep it, so a value given on
+    /// the command line overrides the default given in an included file.
+    /// The expected format is: `--param <name> (<value>)?`
+    fn parse_param(&mut self) -> PTBResult<()> {
+        use Lexeme as L;
+        let sp!(sp, L(_, contents)) = self.expect(Token::Ident)?;
+        if is_keyword(contents) {
+            error!(sp => help: {
+                "Parameter names cannot be {}.",
+                all_keywords()
+            },
+            "Expected a parameter name but found reserved word '{contents}'.");
+        }
+
+        if self.is_repeat_variable(contents) {
+            error!(
+                sp,
+                "Parameter '{contents}' shadows a --{} variable",
+                A::REPEAT
+            );
+        }
+
+        if self.peek().value.is_command_end() {
+            if !self.state.params.contains_key(contents) {
+                error!(sp => help: {
+                    "Supply a value with '--{} {contents} <VALUE>' before this point, e.g. on the \
+                     command line.",
+                    A::PARAM
+                },
+                "Missing value for parameter '{contents}'");
+            }
+        } else {
+            let value = self.parse_argument()?;
+            self.state
+                .params
+                .entry(contents.to_owned())
+                .or_insert(value);
+        }
+
+        Ok(())
+    }
+
+    /// Parse the start of a repeat block. Its body is every command up to the matching
+    /// `--end-repeat`.
+    /// The expected format is: `--repeat <variable> [<value>, ...]`
+    fn parse_repeat(&mut self, sp: Span) -> PTBResult<RepeatBlock> {
+        use Lexeme as L;
+        let sp!(var_sp, L(_, contents)) = self.expect(Token::Ident)?;
+        if is_keyword(contents) {
+            error!(var_sp => help: {
+                "Variable names cannot be {}.",
+                all_keywords()
+            },
+            "Expected a variable name but found reserved word '{contents}'.");
+        }
+
+        if self.state.params.contains_key(contents) || self.is_repeat_variable(contents) {
+            error!(
+                var_sp,
+                "Variable '{contents}' shadows an outer parameter or variable"
+            );
+        }
+
+        let values = self.parse_array()?;
+        Ok(RepeatBlock {
+            span: sp.widen(values.span),
+            variable: Some(var_sp.wrap(contents.to_owned())),
+            values: values.value,
+            start: self.state.parsed.len(),
+        })
+    }
+
+    /// Close the innermost repeat block, replacing the commands in its body with a copy for each
+    /// of its values.
+    /// The expected format is: `--end-repeat`
+    fn end_repeat(&mut self, sp: Span) -> PTBResult<()> {
+        let Some(block) = self.state.repeats.pop() else {
+            error!(
+                sp,
+                "Found --{} without a matching --{}",
+                A::END_REPEAT,
+                A::REPEAT
+            );
+        };
+
+        let body = self.state.parsed.split_off(block.start);
+        let Some(variable) = block.variable else {
+            return Ok(());
+        };
+
+        let expanded = body.len() * block.values.len();
+        if expanded > MAX_REPEAT_COMMANDS {
+            error!(block.span => help: {
+                "Repeat blocks can expand to at most {MAX_REPEAT_COMMANDS} commands."
+            },
+            "Block expands to {expanded} commands");
+        }
+
+        for sp!(_, value) in &block.values {
+            for command in &body {
+                let mut command = command.clone();
+                substitute_command(&mut command.value, &variable.value, value)?;
+                self.state.parsed.push(command);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether `name` is the variable of an open repeat block.
+    fn is_repeat_variable(&self, name: &str) -> bool {
+        self.state
+            .repeats
+            .iter()
+            .any(|block| matches!(&block.variable, Some(sp!(_, v)) if v == name))
+    }
+
     /// Parse a gas specifier.
     /// The expected format is: `--gas-coin <address>`
     fn parse_gas_specifier(&mut self) -> PTBResult<Spanned<ObjectID>> {
@@ -438,7 +599,13 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
                 self.parse_array()?.map(V::Vector).widen_span(sp)
             }
 
-            L(T::Ident, _) => self.parse_variable()?,
+            L(T::Ident, name) => {
+                let sp!(sp, mut arg) = self.parse_variable()?;
+                if let Some(sp!(_, param)) = self.state.params.get(name) {
+                    substitute(&mut arg, name, param)?;
+                }
+                sp.wrap(arg)
+            }
 
             L(T::String, contents) => {
                 self.bump();
@@ -735,6 +902,77 @@ impl<'a, I: Iterator<Item = &'a str>> ProgramParser<'a, I> {
     }
 }
 
+/// Replace uses of variable `name` in the arguments of `command` with `value`.
+fn substitute_command(
+    command: &mut ParsedPTBCommand,
+    name: &str,
+    value: &Argument,
+) -> PTBResult<()> {
+    use ParsedPTBCommand as C;
+
+    let args: Vec<&mut Spanned<Argument>> = match command {
+        C::TransferObjects(objs, to) => objs.value.iter_mut().chain(Some(to)).collect(),
+        C::SplitCoins(coin, amounts) => Some(coin).into_iter().chain(&mut amounts.value).collect(),
+        C::MergeCoins(coin, coins) => Some(coin).into_iter().chain(&mut coins.value).collect(),
+        C::MakeMoveVec(_, elems) => elems.value.iter_mut().collect(),
+        C::MoveCall(_, _, args) => args.iter_mut().collect(),
+        C::Assign(var, assigned) => {
+            if var.value == name {
+                error!(
+                    var.span,
+                    "Cannot assign to --{} variable '{name}'",
+                    A::REPEAT
+                );
+            }
+            assigned.iter_mut().collect()
+        }
+        C::Upgrade(_, cap) => vec![cap],
+        C::Publish(_) | C::WarnShadows | C::Preview => vec![],
+    };
+
+    for arg in args {
+        substitute(&mut arg.value, name, value)?;
+    }
+
+    Ok(())
+}
+
+/// Replace uses of variable `name` in `arg` with `value`. Field accesses on `name` are resolved
+/// against `value`, which must itself be a variable.
+fn substitute(arg: &mut Argument, name: &str, value: &Argument) -> PTBResult<()> {
+    match arg {
+        Argument::Identifier(ident) if ident == name => *arg = value.clone(),
+
+        Argument::VariableAccess(ident, fields) if ident.value == name => {
+            *arg = match value {
+                Argument::Identifier(v) => {
+                    Argument::VariableAccess(ident.span.wrap(v.clone()), fields.clone())
+                }
+                Argument::VariableAccess(v, prefix) => Argument::VariableAccess(
+                    v.clone(),
+                    prefix.iter().chain(fields.iter()).cloned().collect(),
+                ),
+                _ => {
+                    let sp = ident.span.widen_opt(fields.last().map(|f| f.span));
+                    error!(sp, "Cannot access a field of '{value}'");
+                }
+            }
+        }
+
+        Argument::Vector(elems) => {
+            for elem in elems {
+                substitute(&mut elem.value, name, value)?;
+            }
+        }
+
+        Argument::Option(sp!(_, Some(inner))) => substitute(inner, name, value)?,
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1023,4 +1261,102 @@ mod tests {
         }
         insta::assert_debug_snapshot!(parsed);
     }
+
+    #[test]
+    fn test_parse_repeat() {
+        let input = "--repeat x [1, 2, 3] --split-coins gas [x] --assign c --end-repeat";
+        let mut x = shlex::split(input).unwrap();
+        x.push("--gas-budget 1".to_owned());
+        let parser = ProgramParser::new(x.iter().map(|x| x.as_str())).unwrap();
+        let (program, _) = parser.parse().unwrap();
+
+        let amounts: Vec<_> = program
+            .commands
+            .iter()
+            .filter_map(|sp!(_, cmd)| match cmd {
+                ParsedPTBCommand::SplitCoins(_, sp!(_, amounts)) => match &amounts[..] {
+                    [sp!(_, Argument::InferredNum(n))] => Some(n.to_string()),
+                    _ => panic!("Unexpected split amounts: {amounts:?}"),
+                },
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(program.commands.len(), 6);
+        assert_eq!(amounts, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_parse_nested_repeat() {
+        let input = "--repeat x [a, b] --repeat y [x, c] --transfer-objects [y.0] x --end-repeat \
+                     --end-repeat";
+        let mut x = shlex::split(input).unwrap();
+        x.push("--gas-budget 1".to_owned());
+        let parser = ProgramParser::new(x.iter().map(|x| x.as_str())).unwrap();
+        let (program, _) = parser.parse().unwrap();
+
+        let transfers: Vec<_> = program
+            .commands
+            .iter()
+            .map(|sp!(_, cmd)| match cmd {
+                ParsedPTBCommand::TransferObjects(sp!(_, objs), sp!(_, to)) => {
+                    format!("{} -> {to}", objs[0].value)
+                }
+                _ => panic!("Unexpected command: {cmd:?}"),
+            })
+            .collect();
+
+        assert_eq!(
+            transfers,
+            vec!["a.0 -> a", "c.0 -> a", "b.0 -> b", "c.0 -> b"]
+        );
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let input = "--param amount 5 --param amount 6 --param amount --split-coins gas [amount] \
+                     --gas-budget amount";
+        let x = shlex::split(input).unwrap();
+        let parser = ProgramParser::new(x.iter().map(|x| x.as_str())).unwrap();
+        let (program, meta) = parser.parse().unwrap();
+
+        assert_eq!(program.commands.len(), 1);
+        let sp!(_, ParsedPTBCommand::SplitCoins(_, sp!(_, amounts))) = &program.commands[0] else {
+            panic!("Expected split-coins, got {:?}", program.commands[0]);
+        };
+        assert!(matches!(&amounts[..], [sp!(_, Argument::InferredNum(n))] if n.to_string() == "5"));
+        assert_eq!(meta.gas_budget.map(|b| b.value), Some(5));
+    }
+
+    #[test]
+    fn test_parse_scripting_invalid() {
+        let too_many = (0..=MAX_REPEAT_COMMANDS)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let inputs = vec![
+            // Params
+            "--param a".to_owned(),
+            "--param none 1".to_owned(),
+            "--param".to_owned(),
+            "--param a 1 --assign b a.0".to_owned(),
+            // Repeat
+            "--end-repeat".to_owned(),
+            "--repeat x [1]".to_owned(),
+            "--repeat x 1 --end-repeat".to_owned(),
+            "--repeat gas [1] --end-repeat".to_owned(),
+            "--repeat x [1] --assign x 1 --end-repeat".to_owned(),
+            "--param x 1 --repeat x [1] --end-repeat".to_owned(),
+            "--repeat x [1] --repeat x [2] --end-repeat --end-repeat".to_owned(),
+            "--repeat x [1] --param x 2 --end-repeat".to_owned(),
+            "--repeat x [1, 2] --assign y x.0 --end-repeat".to_owned(),
+            format!("--repeat x [{too_many}] --assign y x --end-repeat"),
+        ];
+        for input in inputs {
+            let mut x = shlex::split(&input).unwrap();
+            x.push("--gas-budget 1".to_owned());
+            let parser = ProgramParser::new(x.iter().map(|x| x.as_str())).unwrap();
+            assert!(parser.parse().is_err(), "Expected {input:?} to fail");
+        }
+    }
 }
@@ -4,20 +4,27 @@
 use crate::{
     client_commands::{dry_run_or_execute_or_serialize, Opts, OptsWithGas, SuiClientCommandResult},
     client_ptb::{
-        ast::{ParsedProgram, Program},
+        ast::{ParsedProgram, Program, INCLUDE},
         builder::PTBBuilder,
-        error::{build_error_reports, PTBError},
+        error::{
+            build_error_report, build_error_reports, build_named_error_report, PTBError, Span,
+        },
         token::{Lexeme, Token},
     },
     displays::Pretty,
-    sp,
+    err, sp,
 };
 
-use super::{ast::ProgramMetadata, lexer::Lexer, parser::ProgramParser};
-use anyhow::{anyhow, ensure, Error};
+use super::{
+    ast::ProgramMetadata,
+    lexer::Lexer,
+    parser::{ProgramParser, MAX_REPEAT_COMMANDS},
+};
+use anyhow::{anyhow, bail, ensure, Context, Error};
 use clap::{arg, Args, ValueHint};
 use move_core_types::account_address::AccountAddress;
 use serde::Serialize;
+use std::path::{Path, PathBuf};
 use sui_json_rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
 use sui_keys::keystore::AccountKeystore;
 use sui_sdk::{wallet_context::WalletContext, SuiClient};
@@ -27,6 +34,9 @@ use sui_types::{
     transaction::{ProgrammableTransaction, TransactionKind},
 };
 
+/// How deeply `--include` commands can be nested.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
 #[derive(Clone, Debug, Args)]
 #[clap(disable_help_flag = true)]
 pub struct PTB {
@@ -53,10 +63,21 @@ impl PTB {
             ptb_description().print_help().unwrap();
             return Ok(());
         }
-        let source_string = to_source_string(self.args.clone());
+        let expanded = match expand_includes(self.args.clone()) {
+            Err(errors) => {
+                let suffix = if errors.len() > 1 { "s" } else { "" };
+                let rendered = build_error_reports(&to_source_string(self.args), errors);
+                eprintln!("Encountered error{suffix} when including PTB files:");
+                for e in rendered.iter() {
+                    eprintln!("{:?}", e);
+                }
+                anyhow::bail!("Could not build PTB due to previous error{suffix}");
+            }
+            Ok(expanded) => expanded,
+        };
 
         // Tokenize once to detect help flags
-        let tokens = self.args.iter().map(|s| s.as_str());
+        let tokens = expanded.args.iter().map(|s| s.as_str());
         for sp!(_, lexeme) in Lexer::new(tokens.clone()).into_iter().flatten() {
             match lexeme {
                 Lexeme(Token::Command, "help") => return Ok(ptb_description().print_long_help()?),
@@ -73,7 +94,7 @@ impl PTB {
         {
             Err(errors) => {
                 let suffix = if errors.len() > 1 { "s" } else { "" };
-                let rendered = build_error_reports(&source_string, errors);
+                let rendered = expanded.render_errors(errors);
                 eprintln!("Encountered error{suffix} when parsing PTB:");
                 for e in rendered.iter() {
                     eprintln!("{:?}", e);
@@ -107,7 +128,7 @@ impl PTB {
         if !warnings.is_empty() {
             let suffix = if warnings.len() > 1 { "s" } else { "" };
             eprintln!("Warning{suffix} produced when building PTB:");
-            let rendered = build_error_reports(&source_string, warnings);
+            let rendered = expanded.render_errors(warnings);
             for e in rendered.iter() {
                 eprintln!("{:?}", e);
             }
@@ -116,7 +137,7 @@ impl PTB {
             Err(errors) => {
                 let suffix = if errors.len() > 1 { "s" } else { "" };
                 eprintln!("Encountered error{suffix} when building PTB:");
-                let rendered = build_error_reports(&source_string, errors);
+                let rendered = expanded.render_errors(errors);
                 for e in rendered.iter() {
                     eprintln!("{:?}", e);
                 }
@@ -273,6 +294,261 @@ pub fn to_source_string(strings: Vec<String>) -> String {
     string
 }
 
+/// The shell tokens of a PTB after expanding its includes, along with where each token came from,
+/// so that errors can be rendered against the command line or the file the token was written in.
+pub struct ExpandedArgs {
+    pub args: Vec<String>,
+    /// The command line, followed by the contents of each included file.
+    sources: Vec<Source>,
+    /// The index in `sources` and the span in that source of each token in `args`.
+    origins: Vec<(usize, Span)>,
+}
+
+struct Source {
+    /// The path of an included file, or `None` for the command line.
+    name: Option<String>,
+    contents: String,
+}
+
+impl ExpandedArgs {
+    /// Render errors with spans in the source string of `args` (see [to_source_string]) against
+    /// the command line or included file that the tokens they point at came from.
+    pub fn render_errors(&self, errors: Vec<PTBError>) -> Vec<miette::Report> {
+        errors
+            .into_iter()
+            .map(|error| {
+                let (source, span) = self.locate(error.span);
+                let Source { name, contents } = &self.sources[source];
+                let error = PTBError { span, ..error };
+                match name {
+                    Some(name) => build_named_error_report(name, contents, error),
+                    None => build_error_report(contents, error),
+                }
+            })
+            .collect()
+    }
+
+    /// Map a span in the source string of `args` to the source its tokens came from, and the span
+    /// in that source. Spans that cover tokens from several sources are cut off at the end of the
+    /// first source's tokens.
+    fn locate(&self, span: Span) -> (usize, Span) {
+        let Some((first, first_offset)) = self.token_at(span.start) else {
+            return (0, span);
+        };
+        let (last, last_offset) = self
+            .token_at(span.end.saturating_sub(1).max(span.start))
+            .filter(|(last, _)| self.origins[*last].0 == self.origins[first].0)
+            .unwrap_or((first, first_offset));
+
+        let (source, first_span) = self.origins[first];
+        let (_, last_span) = self.origins[last];
+
+        // Offsets within a token can only be mapped if it was written without quotes or escapes.
+        let exact = |i: usize, sp: Span| sp.end - sp.start == self.args[i].len();
+        let start = if exact(first, first_span) {
+            first_span.start + (span.start - first_offset).min(self.args[first].len())
+        } else {
+            first_span.start
+        };
+        let end = if exact(last, last_span) {
+            last_span.start
+                + span
+                    .end
+                    .saturating_sub(last_offset)
+                    .min(self.args[last].len())
+        } else {
+            last_span.end
+        };
+
+        (
+            source,
+            Span {
+                start,
+                end: end.max(start),
+            },
+        )
+    }
+
+    /// The index of the token at `offset` in the source string of `args`, and the offset that the
+    /// token starts at. Offsets between or after tokens belong to the token before them.
+    fn token_at(&self, offset: usize) -> Option<(usize, usize)> {
+        let mut start = 0;
+        let mut found = None;
+        for (i, arg) in self.args.iter().enumerate() {
+            if start > offset {
+                break;
+            }
+            found = Some((i, start));
+            start += arg.len() + 1;
+        }
+        found
+    }
+}
+
+/// Replace each `--include <file>` in a vector of shell tokens with the shell tokens in that file,
+/// recursively. Paths in included files are relative to the file that includes them. Errors point
+/// at the `--include` in `args` that they come from, so that they can be rendered against the
+/// source string of `args`.
+pub fn expand_includes(args: Vec<String>) -> Result<ExpandedArgs, Vec<PTBError>> {
+    let mut expanded = ExpandedArgs {
+        args: vec![],
+        sources: vec![Source {
+            name: None,
+            contents: to_source_string(args.clone()),
+        }],
+        origins: vec![],
+    };
+    let mut errors = vec![];
+
+    // Tracks the offset of each token in the source string, which separates tokens by a single
+    // character.
+    let mut offset = 0;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let start = offset;
+        offset += arg.len() + 1;
+
+        if arg != format!("--{INCLUDE}") {
+            let sp = Span {
+                start,
+                end: start + arg.len(),
+            };
+            expanded.args.push(arg);
+            expanded.origins.push((0, sp));
+            continue;
+        }
+
+        let Some(path) = args.next() else {
+            let sp = Span {
+                start,
+                end: start + arg.len(),
+            };
+            errors.push(err!(sp, "Expected a file path after --{INCLUDE}"));
+            break;
+        };
+
+        let sp = Span {
+            start,
+            end: offset + path.len(),
+        };
+        offset += path.len() + 1;
+
+        if let Err(e) = include_file(Path::new(&path), &mut vec![], &mut expanded) {
+            errors.push(err!(sp, "{e:#}"));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(expanded)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Append the shell tokens in the file at `path` to `expanded`, expanding its own includes.
+/// `including` holds the files that are currently being included, to detect cycles.
+fn include_file(
+    path: &Path,
+    including: &mut Vec<PathBuf>,
+    expanded: &mut ExpandedArgs,
+) -> anyhow::Result<()> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Cannot include {}", path.display()))?;
+
+    if including.contains(&path) {
+        bail!("{} includes itself", path.display());
+    }
+
+    if including.len() >= MAX_INCLUDE_DEPTH {
+        bail!("Includes are nested more than {MAX_INCLUDE_DEPTH} deep");
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Cannot include {}", path.display()))?;
+    let Some(file_tokens) = split_with_spans(&contents) else {
+        bail!("Cannot include {}: unbalanced quotes", path.display());
+    };
+
+    let source = expanded.sources.len();
+    expanded.sources.push(Source {
+        name: Some(path.display().to_string()),
+        contents,
+    });
+
+    let dir = path.parent().map(Path::to_owned).unwrap_or_default();
+    including.push(path);
+
+    let mut file_tokens = file_tokens.into_iter();
+    while let Some((token, sp)) = file_tokens.next() {
+        if token != format!("--{INCLUDE}") {
+            expanded.args.push(token);
+            expanded.origins.push((source, sp));
+            continue;
+        }
+
+        let Some((nested, _)) = file_tokens.next() else {
+            bail!(
+                "Expected a file path after --{INCLUDE} in {}",
+                including.last().unwrap().display()
+            );
+        };
+
+        include_file(&dir.join(nested), including, expanded)?;
+    }
+
+    including.pop();
+    Ok(())
+}
+
+/// Split `contents` into shell tokens, like `shlex::split`, along with the span of each token in
+/// `contents`. Returns `None` if `contents` cannot be split, e.g. because of unbalanced quotes.
+fn split_with_spans(contents: &str) -> Option<Vec<(String, Span)>> {
+    let bytes = contents.as_bytes();
+    let is_space = |c: u8| matches!(c, b' ' | b'\t' | b'\n');
+    let mut tokens = vec![];
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if is_space(bytes[i]) {
+            i += 1;
+            continue;
+        }
+
+        // Comments run to the end of the line, but only when they start a token.
+        if bytes[i] == b'#' {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        let start = i;
+        let mut quote = None;
+        while i < bytes.len() {
+            match (quote, bytes[i]) {
+                (None, c) if is_space(c) => break,
+                (None, c @ (b'\'' | b'"')) => quote = Some(c),
+                (None | Some(b'"'), b'\\') => i += 1,
+                (Some(q), c) if c == q => quote = None,
+                _ => {}
+            }
+            i += 1;
+        }
+        let end = i.min(bytes.len());
+
+        // The token's value is left to `shlex`, so that quotes and escapes are handled the same
+        // way as on the command line.
+        let mut value = shlex::split(&contents[start..end])?;
+        if value.len() != 1 {
+            return None;
+        }
+        tokens.push((value.remove(0), Span { start, end }));
+    }
+
+    Some(tokens)
+}
+
 pub fn ptb_description() -> clap::Command {
     clap::Command::new("sui client ptb")
         .about(
@@ -420,4 +696,177 @@ pub fn ptb_description() -> clap::Command {
             --"json"
             "Return command outputs in json format."
         ))
+        .arg(arg!(
+            --"include" <PTB_FILE>
+            "Include the commands in a file, as if they had been passed on the command line."
+        ).long_help(
+            "Include the commands in a file, as if they had been passed on the command line. \
+            Paths to files included by an included file are relative to that file.\
+            \n\nExamples:\
+            \n --include split_and_transfer.ptb"
+        ).value_hint(ValueHint::FilePath))
+        .arg(arg!(
+            --"param" <PARAM>
+            "Set a parameter, which is replaced by its value wherever it is used."
+        ).long_help(
+            "Set a parameter, which is replaced by its value wherever it is used. Unlike \
+            variables, parameters are substituted when the PTB is parsed, so they can be used for \
+            any value, including gas budgets and the values of --repeat blocks. A parameter that \
+            is already set keeps its first value, so values passed on the command line override \
+            the defaults in included files. If only a name is supplied, the parameter must \
+            already be set.\
+            \n\nExamples:\
+            \n --param amount 1000 --include split_and_transfer.ptb\
+            \n --param amount # in split_and_transfer.ptb, requires amount to be set\
+            \n --split-coins gas [amount]"
+        )
+        .value_names(["NAME", "VALUE"]))
+        .arg(arg!(
+            --"repeat" <REPEAT>
+            "Repeat the commands up to the next --end-repeat once for each value in an array."
+        ).long_help(format!(
+            "Repeat the commands up to the next --end-repeat once for each value in an array, \
+            with the variable bound to that value. Blocks can be nested, and can expand to at \
+            most {MAX_REPEAT_COMMANDS} commands.\
+            \n\nExamples:\
+            \n --repeat to [@0x1, @0x2]\
+            \n --split-coins gas [1000]\
+            \n --assign coins\
+            \n --transfer-objects [coins.0] to\
+            \n --end-repeat"
+        ))
+        .value_names(["NAME", "[VALUES]"]))
+        .arg(arg!(
+            --"end-repeat"
+            "End the innermost --repeat block."
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(input: &str) -> Vec<String> {
+        shlex::split(input).unwrap()
+    }
+
+    #[test]
+    fn test_expand_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+
+        std::fs::write(
+            dir.path().join("outer.ptb"),
+            "# Split a coin and transfer it\n--param amount 1\n--include nested/inner.ptb\n",
+        )
+        .unwrap();
+        std::fs::write(
+            nested.join("inner.ptb"),
+            "--split-coins gas [amount]\n--assign coins\n--transfer-objects [coins.0] @0x1\n",
+        )
+        .unwrap();
+
+        let outer = dir.path().join("outer.ptb");
+        let input = format!(
+            "--param amount 5 --include {} --gas-budget 1",
+            outer.display()
+        );
+        let expanded = expand_includes(args(&input)).unwrap();
+
+        assert_eq!(
+            expanded.args,
+            args(
+                "--param amount 5 --param amount 1 --split-coins gas [amount] --assign coins \
+                 --transfer-objects [coins.0] @0x1 --gas-budget 1"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_expand_includes_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let cycle = dir.path().join("cycle.ptb");
+        std::fs::write(&cycle, "--include cycle.ptb").unwrap();
+        let quotes = dir.path().join("quotes.ptb");
+        std::fs::write(&quotes, "--assign a \"b").unwrap();
+        let missing = dir.path().join("missing.ptb");
+
+        for input in [
+            "--include".to_owned(),
+            format!("--include {}", missing.display()),
+            format!("--include {}", cycle.display()),
+            format!("--include {}", quotes.display()),
+        ] {
+            let errors = expand_includes(args(&input)).unwrap_err();
+            assert_eq!(errors.len(), 1, "Expected one error for {input:?}");
+            assert_eq!(errors[0].span.start, 0, "Unexpected span for {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_expand_includes_source_map() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner = dir.path().join("inner.ptb");
+        let contents = "# Comment\n--assign a '@0x1'\n--frobnicate coins\n";
+        std::fs::write(&inner, contents).unwrap();
+
+        let input = format!("--assign b 2 --include {} --gas-budget 1", inner.display());
+        let expanded = expand_includes(args(&input)).unwrap();
+        let source = to_source_string(expanded.args.clone());
+
+        // Tokens from the command line map back to the command line
+        let start = source.find("--gas-budget").unwrap();
+        let (file, sp) = expanded.locate(Span {
+            start,
+            end: start + "--gas-budget".len(),
+        });
+        assert!(expanded.sources[file].name.is_none());
+        assert_eq!(
+            &expanded.sources[file].contents[sp.start..sp.end],
+            "--gas-budget"
+        );
+
+        // Errors in an included file are rendered against that file
+        let errors = PTB::parse_ptb_commands(expanded.args.clone()).unwrap_err();
+        let (file, sp) = expanded.locate(errors[0].span);
+        let Source { name, contents } = &expanded.sources[file];
+        assert_eq!(
+            name.as_deref(),
+            Some(&*inner.canonicalize().unwrap().display().to_string())
+        );
+        assert!(contents[sp.start..sp.end].contains("frobnicate"));
+
+        // Quoted tokens map to the whole token
+        let start = source.find("@0x1").unwrap();
+        let (_, sp) = expanded.locate(Span {
+            start,
+            end: start + "@0x1".len(),
+        });
+        assert_eq!(&contents[sp.start..sp.end], "'@0x1'");
+    }
+
+    #[test]
+    fn test_split_with_spans() {
+        let contents = "--assign a \"b c\" # comment\n--split-coins gas [1,\\\n 2] 'x'#y";
+        let tokens = split_with_spans(contents).unwrap();
+        let values: Vec<_> = tokens.iter().map(|(v, _)| v.clone()).collect();
+        assert_eq!(values, shlex::split(contents).unwrap());
+        for (value, sp) in tokens {
+            assert_eq!(shlex::split(&contents[sp.start..sp.end]).unwrap(), [value]);
+        }
+
+        assert!(split_with_spans("--assign a \"b").is_none());
+        assert!(split_with_spans("--assign a 'b").is_none());
+    }
+
+    #[test]
+    fn test_expand_includes_span() {
+        let input = "--assign a 1 --include missing.ptb --assign b 2";
+        let source = to_source_string(args(input));
+        let errors = expand_includes(args(input)).unwrap_err();
+
+        let sp = errors[0].span;
+        assert_eq!(&source[sp.start..sp.end], "--include missing.ptb");
+    }
 }
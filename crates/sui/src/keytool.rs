@@ -24,6 +24,7 @@ use fastcrypto_zkp::bn254::zk_login::{fetch_jwks, OIDCProvider, ZkLoginInputs};
 use fastcrypto_zkp::bn254::zk_login::{JwkId, JWK};
 use fastcrypto_zkp::bn254::zk_login_api::ZkLoginEnv;
 use im::hashmap::HashMap as ImHashMap;
+use inquire::Password;
 use json_to_table::{json_to_table, Orientation};
 use num_bigint::BigUint;
 use rand::rngs::StdRng;
@@ -36,6 +37,7 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use sui_keys::encrypted_keystore::{EncryptedFileKeystore, KdfParams, KEYSTORE_PASSPHRASE_ENV};
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
@@ -102,6 +104,14 @@ pub enum KeyToolCommand {
         #[clap(long, default_value = "0")]
         cur_epoch: u64,
     },
+    /// Encrypt the keys and aliases of the keystore into a new encrypted keystore at `output`. The
+    /// passphrase is read from `SUI_KEYSTORE_PASSPHRASE`, or prompted for if it is not set. The
+    /// plaintext keystore is left untouched, so that it can be removed once the encrypted keystore
+    /// has been checked.
+    EncryptKeystore {
+        #[clap(long)]
+        output: PathBuf,
+    },
     /// Generate a new keypair with key scheme flag {ed25519 | secp256k1 | secp256r1}
     /// with optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or
     /// m/54'/784'/0'/0/0 for secp256k1 or m/74'/784'/0'/0/0 for secp256r1. Word
@@ -190,6 +200,10 @@ pub enum KeyToolCommand {
         threshold: ThresholdUnit,
     },
 
+    /// Create a new, empty encrypted keystore at the provided file path. The passphrase is read from
+    /// `SUI_KEYSTORE_PASSPHRASE`, or prompted for if it is not set.
+    NewEncryptedKeystore { path: PathBuf },
+
     /// Read the content at the provided file path. The accepted format can be
     /// [enum SuiKeyPair] (Base64 encoded of 33-byte `flag || privkey`) or `type AuthorityKeyPair`
     /// (Base64 encoded `privkey`). It prints its Base64 encoded public key and the key scheme flag.
@@ -342,6 +356,13 @@ pub struct Key {
     peer_id: Option<String>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedKeystoreOutput {
+    path: PathBuf,
+    addresses: Vec<SuiAddress>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportedKey {
@@ -461,6 +482,7 @@ pub enum CommandOutput {
     Convert(ConvertOutput),
     DecodeMultiSig(DecodedMultiSigOutput),
     DecodeOrVerifyTx(DecodeOrVerifyTxOutput),
+    EncryptedKeystore(EncryptedKeystoreOutput),
     Error(String),
     Generate(Key),
     Import(Key),
@@ -587,6 +609,27 @@ impl KeyToolCommand {
                     }
                 }
             }
+            KeyToolCommand::EncryptKeystore { output } => {
+                let Keystore::File(file) = keystore else {
+                    return Err(anyhow!("Only file based keystores can be encrypted"));
+                };
+                let path = file
+                    .path()
+                    .ok_or_else(|| anyhow!("Keystore has no file to encrypt"))?
+                    .to_path_buf();
+                let passphrase = read_new_passphrase()?;
+                let encrypted = EncryptedFileKeystore::migrate_from_file(
+                    &path,
+                    &output,
+                    &passphrase,
+                    KdfParams::default(),
+                )?;
+                CommandOutput::EncryptedKeystore(EncryptedKeystoreOutput {
+                    path: output,
+                    addresses: encrypted.addresses(),
+                })
+            }
+
             KeyToolCommand::Generate {
                 key_scheme,
                 derivation_path,
@@ -795,6 +838,16 @@ impl KeyToolCommand {
                 )
             }
 
+            KeyToolCommand::NewEncryptedKeystore { path } => {
+                let passphrase = read_new_passphrase()?;
+                let encrypted =
+                    EncryptedFileKeystore::create(&path, &passphrase, KdfParams::default())?;
+                CommandOutput::EncryptedKeystore(EncryptedKeystoreOutput {
+                    path,
+                    addresses: encrypted.addresses(),
+                })
+            }
+
             KeyToolCommand::Show { file } => {
                 let res = read_keypair_from_file(&file);
                 match res {
@@ -1400,6 +1453,19 @@ impl Debug for CommandOutput {
     }
 }
 
+/// Read the passphrase for a new encrypted keystore from `SUI_KEYSTORE_PASSPHRASE`, or prompt for it,
+/// with confirmation, if it is not set.
+fn read_new_passphrase() -> Result<String, anyhow::Error> {
+    let passphrase = match std::env::var(KEYSTORE_PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => Password::new("Passphrase for the encrypted keystore:").prompt()?,
+    };
+    if passphrase.is_empty() {
+        return Err(anyhow!("The keystore passphrase cannot be empty"));
+    }
+    Ok(passphrase)
+}
+
 /// Converts legacy formatted private key to 33 bytes bech32 encoded private key or vice versa.
 /// It can handle:
 /// 1) Hex encoded 32 byte private key (assumes scheme is Ed25519), this is the legacy wallet format
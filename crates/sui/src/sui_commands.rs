@@ -43,6 +43,7 @@ use sui_graphql_rpc::{
     test_infra::cluster::start_graphql_server_with_fn_rpc,
 };
 
+use sui_keys::encrypted_keystore::KEYSTORE_PASSPHRASE_ENV;
 use sui_keys::keypair_file::read_key;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_move::{self, execute_move_command};
@@ -440,7 +441,8 @@ impl SuiCommand {
             SuiCommand::Console { config } => {
                 let config = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
                 prompt_if_no_config(&config, false).await?;
-                let context = WalletContext::new(&config, None, None)?;
+                let mut context = WalletContext::new(&config, None, None)?;
+                unlock_keystore_from_env(&mut context)?;
                 if let Ok(client) = context.get_client().await {
                     if let Err(e) = client.check_api_version() {
                         eprintln!("{}", format!("[warning] {e}").yellow().bold());
@@ -458,6 +460,7 @@ impl SuiCommand {
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 if let Some(cmd) = cmd {
                     let mut context = WalletContext::new(&config_path, None, None)?;
+                    unlock_keystore_from_env(&mut context)?;
                     if let Ok(client) = context.get_client().await {
                         if let Err(e) = client.check_api_version() {
                             eprintln!("{}", format!("[warning] {e}").yellow().bold());
@@ -481,6 +484,7 @@ impl SuiCommand {
                 let config_path = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 let mut context = WalletContext::new(&config_path, None, None)?;
+                unlock_keystore_from_env(&mut context)?;
                 if let Some(cmd) = cmd {
                     if let Ok(client) = context.get_client().await {
                         if let Err(e) = client.check_api_version() {
@@ -1209,6 +1213,21 @@ async fn genesis(
     Ok(())
 }
 
+/// Unlock the wallet's keystore with the passphrase in `SUI_KEYSTORE_PASSPHRASE`, if it is set and
+/// the keystore is encrypted.
+fn unlock_keystore_from_env(context: &mut WalletContext) -> Result<(), anyhow::Error> {
+    if !context.is_locked() {
+        return Ok(());
+    }
+
+    if let Ok(passphrase) = std::env::var(KEYSTORE_PASSPHRASE_ENV) {
+        context
+            .unlock(&passphrase)
+            .with_context(|| format!("Cannot unlock keystore with {KEYSTORE_PASSPHRASE_ENV}"))?;
+    }
+    Ok(())
+}
+
 async fn prompt_if_no_config(
     wallet_conf_path: &Path,
     accept_defaults: bool,
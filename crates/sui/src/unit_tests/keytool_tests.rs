@@ -20,6 +20,7 @@ use rand::rngs::StdRng;
 use rand::SeedableRng;
 use shared_crypto::intent::Intent;
 use shared_crypto::intent::IntentScope;
+use sui_keys::encrypted_keystore::{EncryptedFileKeystore, KEYSTORE_PASSPHRASE_ENV};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
 use sui_types::base_types::ObjectDigest;
 use sui_types::base_types::ObjectID;
@@ -206,6 +207,63 @@ async fn test_load_keystore_err() {
     assert!(FileBasedKeystore::new(&path2).is_err());
 }
 
+#[test]
+async fn test_encrypt_keystore() -> Result<(), anyhow::Error> {
+    std::env::set_var(KEYSTORE_PASSPHRASE_ENV, "passphrase");
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("sui.keystore");
+    let encrypted_path = temp_dir.path().join("sui.keystore.enc");
+
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&path)?);
+    keystore.add_key(
+        Some("alias".to_string()),
+        SuiKeyPair::Ed25519(get_key_pair().1),
+    )?;
+
+    let output = KeyToolCommand::EncryptKeystore {
+        output: encrypted_path.clone(),
+    }
+    .execute(&mut keystore)
+    .await?;
+    let CommandOutput::EncryptedKeystore(output) = output else {
+        panic!("Expected an encrypted keystore output");
+    };
+    assert_eq!(keystore.addresses(), output.addresses);
+
+    // The encrypted keystore holds the same keys and aliases, under the passphrase
+    let mut encrypted = EncryptedFileKeystore::new(&encrypted_path)?;
+    encrypted.unlock("passphrase")?;
+    for address in keystore.addresses() {
+        assert_eq!(
+            keystore.get_alias_by_address(&address)?,
+            encrypted.get_alias_by_address(&address)?
+        );
+        assert_eq!(
+            keystore.get_key(&address)?.encode_base64(),
+            encrypted.get_key(&address)?.encode_base64()
+        );
+    }
+
+    // An existing keystore is never overwritten
+    assert!(KeyToolCommand::NewEncryptedKeystore {
+        path: encrypted_path
+    }
+    .execute(&mut keystore)
+    .await
+    .is_err());
+
+    let new_path = temp_dir.path().join("new.keystore.enc");
+    KeyToolCommand::NewEncryptedKeystore {
+        path: new_path.clone(),
+    }
+    .execute(&mut keystore)
+    .await?;
+    let mut encrypted = EncryptedFileKeystore::new(&new_path)?;
+    encrypted.unlock("passphrase")?;
+    assert!(encrypted.addresses().is_empty());
+    Ok(())
+}
+
 #[test]
 async fn test_private_keys_import_export() -> Result<(), anyhow::Error> {
     // private key in Bech32, private key in Hex, private key in Base64, derived Sui address in Hex
@@ -382,6 +382,30 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 }
             }
 
+            /// Opens a set of tables as a secondary instance of the DB at `primary_path`, for
+            /// reading the primary's data as of the time of opening while it is in use by another
+            /// process. Writes to the tables fail. If `with_secondary_path` is not set, the
+            /// secondary instance keeps its files in a temporary directory.
+            #[allow(unused_parens)]
+            pub fn open_tables_secondary(
+                primary_path: std::path::PathBuf,
+                with_secondary_path: Option<std::path::PathBuf>,
+                metric_conf: typed_store::rocks::MetricConf,
+                global_db_options_override: Option<typed_store::rocksdb::Options>,
+            ) -> Self {
+                let secondary_path = with_secondary_path.unwrap_or_else(|| {
+                    tempfile::tempdir()
+                        .expect("Failed to open temporary directory")
+                        .into_path()
+                });
+                let inner = #intermediate_db_map_struct_name::open_tables_impl(primary_path, Some(secondary_path), false, metric_conf, global_db_options_override, None, false);
+                Self {
+                    #(
+                        #field_names: inner.#field_names,
+                    )*
+                }
+            }
+
             /// Opens a set of tables in transactional read-write mode
             /// Only one process is allowed to do this at a time
             /// `global_db_options_override` apply to the whole DB
@@ -487,6 +511,31 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                 })
             }
 
+            /// Export key-value pairs in the given table as JSON, passing each pair to `f`. Bounds
+            /// are keys encoded as JSON, the lower bound is inclusive and the upper bound exclusive.
+            /// Returns the number of pairs exported
+            /// Tables must be opened in read only mode using `open_tables_read_only`
+            pub fn export_json(
+                &self,
+                cf_name: &str,
+                lower_bound: Option<&str>,
+                upper_bound: Option<&str>,
+                f: &mut dyn FnMut(typed_store::serde_json::Value, typed_store::serde_json::Value) -> eyre::Result<()>,
+            ) -> eyre::Result<usize> {
+                let table_name = Self::cf_name_to_table_name(cf_name)?;
+
+                Ok(match table_name {
+                    #(
+                        stringify!(#field_names) => {
+                            typed_store::traits::Map::try_catch_up_with_primary(&self.#field_names)?;
+                            self.#field_names.export_json(lower_bound, upper_bound, f)?
+                        }
+                    )*
+
+                    _ => eyre::bail!("No such table name: {}", table_name),
+                })
+            }
+
             /// Get key value sizes from the db
             /// Tables must be opened in read only mode using `open_tables_read_only`
             pub fn table_summary(&self, table_name: &str) -> eyre::Result<typed_store::traits::TableSummary> {
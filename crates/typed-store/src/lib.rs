@@ -10,6 +10,8 @@
 
 // Re-export rocksdb so that consumers can use the version of rocksdb via typed-store
 pub use rocksdb;
+// Re-export serde_json so that tables derived with `DBMapUtils` can be exported as JSON
+pub use serde_json;
 
 pub mod traits;
 pub use traits::Map;
@@ -1225,6 +1225,29 @@ impl<K, V> DBMap<K, V> {
         })
     }
 
+    /// Passes every key-value pair with a key in `[lower_bound, upper_bound)` to `f`, with both
+    /// encoded as JSON. Bounds are keys encoded as JSON. Returns the number of pairs visited.
+    pub fn export_json(
+        &self,
+        lower_bound: Option<&str>,
+        upper_bound: Option<&str>,
+        f: &mut dyn FnMut(serde_json::Value, serde_json::Value) -> eyre::Result<()>,
+    ) -> eyre::Result<usize>
+    where
+        K: Serialize + DeserializeOwned,
+        V: Serialize + DeserializeOwned,
+    {
+        let lower_bound = lower_bound.map(serde_json::from_str::<K>).transpose()?;
+        let upper_bound = upper_bound.map(serde_json::from_str::<K>).transpose()?;
+        let mut count = 0;
+        for item in self.safe_iter_with_bounds(lower_bound, upper_bound) {
+            let (key, value) = item?;
+            f(serde_json::to_value(&key)?, serde_json::to_value(&value)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
     // Creates metrics and context for tracking an iterator usage and performance.
     fn create_iter_context(
         &self,
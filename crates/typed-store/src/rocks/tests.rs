@@ -816,6 +816,46 @@ async fn test_iter_with_bounds(
     assert_eq!(vec![(49, "49".to_string())], db_iter.collect::<Vec<_>>());
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_export_json(#[values(true, false)] is_transactional: bool) {
+    let db = open_map(temp_dir(), None, is_transactional);
+    for i in 1..10 {
+        db.insert(&(i, format!("key{i}")), &vec![i; 2]).unwrap();
+    }
+
+    let mut exported = vec![];
+    let count = db
+        .export_json(Some(r#"[3, "key3"]"#), Some(r#"[5, ""]"#), &mut |k, v| {
+            exported.push((k, v));
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(
+        exported,
+        vec![
+            (serde_json::json!([3, "key3"]), serde_json::json!([3, 3])),
+            (serde_json::json!([4, "key4"]), serde_json::json!([4, 4])),
+        ]
+    );
+
+    // Errors from the callback stop the export
+    let mut visited = 0;
+    assert!(db
+        .export_json(None, None, &mut |_, _| {
+            visited += 1;
+            eyre::bail!("stop")
+        })
+        .is_err());
+    assert_eq!(visited, 1);
+
+    // Bounds must be valid keys
+    assert!(db
+        .export_json(Some("not a key"), None, &mut |_, _| Ok(()))
+        .is_err());
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_range_iter(